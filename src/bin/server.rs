@@ -8,7 +8,7 @@ use tidis::{
 };
 
 use async_std::net::TcpListener;
-use async_tls::TlsAcceptor;
+use async_tls::{TlsAcceptor, TlsConnector};
 use std::fs;
 use std::process::exit;
 use std::sync::Arc;
@@ -92,6 +92,7 @@ pub async fn main() -> tidis::Result<()> {
     let mut listener = None;
     let mut tls_listener = None;
     let mut tls_acceptor: Option<TlsAcceptor> = None;
+    let mut peer_tls_connector: Option<TlsConnector> = None;
     if port != "0" {
         println!("Tidis Server Listen on: {}:{}", &listen_addr, port);
         listener = Some(TcpListener::bind(&format!("{}:{}", &listen_addr, port)).await?);
@@ -112,9 +113,23 @@ pub async fn main() -> tidis::Result<()> {
         )?;
         // tls_acceptor = Some(TlsAcceptor::from(tls_config));
         tls_acceptor = Some(TlsAcceptor::from(Arc::new(tls_config)));
+
+        // the other instances only serve TLS as well
+        if listener.is_none() {
+            let peer_tls_config =
+                utils::load_client_config(tls_cert_file, tls_key_file, tls_ca_cert_file)?;
+            peer_tls_connector = Some(TlsConnector::from(Arc::new(peer_tls_config)));
+        }
     }
 
-    server::run(listener, tls_listener, tls_acceptor, signal::ctrl_c()).await;
+    server::run(
+        listener,
+        tls_listener,
+        tls_acceptor,
+        peer_tls_connector,
+        signal::ctrl_c(),
+    )
+    .await;
 
     Ok(())
 }
@@ -1,16 +1,23 @@
 use std::sync::{Arc, RwLock};
 
+use bytes::Bytes;
+use crc::{Crc, CRC_16_XMODEM};
 use hex::ToHex;
 use sha1::{Digest, Sha1};
 
 use crate::{
+    peer::Peers,
+    tikv::errors::RTError,
     utils::{resp_array, resp_bulk, resp_int},
     Frame,
 };
 
+const CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
+
 #[derive(Debug, Clone)]
 pub struct Cluster {
     nodes: Arc<RwLock<Vec<Node>>>,
+    peers: Peers,
 }
 
 #[derive(Debug, Clone)]
@@ -25,15 +32,16 @@ pub struct Node {
 }
 
 impl Cluster {
-    pub fn new(nodes: &[Node]) -> Cluster {
+    pub fn new(nodes: &[Node], peers: Peers) -> Cluster {
         Cluster {
             nodes: Arc::new(RwLock::new(nodes.to_owned().to_vec())),
+            peers,
         }
     }
 
-    pub fn build_myself(addr: &str) -> Self {
+    pub fn build_myself(addr: &str, peers: Peers) -> Self {
         let addrs = vec![addr.to_owned()];
-        Self::build_from_meta(&addrs, addr)
+        Self::build_from_meta(&addrs, addr, peers)
     }
 
     fn build_nodes_from_addrs(addrs: &[String], my_addr: &str) -> Vec<Node> {
@@ -83,9 +91,9 @@ impl Cluster {
         nodes
     }
 
-    fn build_from_meta(addrs: &[String], my_addr: &str) -> Self {
+    fn build_from_meta(addrs: &[String], my_addr: &str, peers: Peers) -> Self {
         let nodes = Self::build_nodes_from_addrs(addrs, my_addr);
-        Self::new(&nodes)
+        Self::new(&nodes, peers)
     }
 
     pub fn update_topo(&mut self, addrs: &[String], my_addr: &str) {
@@ -187,4 +195,64 @@ impl Cluster {
             .unwrap();
         (myself.slot_start, myself.slot_end)
     }
+
+    /// Returns the addresses of all the other nodes
+    pub fn peer_addrs(&self) -> Vec<String> {
+        let nodes_guard = self.nodes.read().unwrap();
+        nodes_guard
+            .iter()
+            .filter(|node| node.flags.is_none())
+            .map(|node| format!("{}:{}", node.ip, node.port))
+            .collect()
+    }
+
+    pub fn peers(&self) -> &Peers {
+        &self.peers
+    }
+
+    /// Forward a published message to all the other nodes in background.
+    pub fn forward_to_peers(&self, channel: Bytes, message: Bytes) {
+        self.peers.forward(self.peer_addrs(), channel, message);
+    }
+
+    /// Returns true if the slot of the user key is owned by myself
+    pub fn myself_owns_key(&self, user_key: &[u8]) -> bool {
+        let (slot_range_left, slot_range_right) = self.myself_owned_slots();
+        let slot = key_hash_slot(user_key);
+        slot >= slot_range_left && slot <= slot_range_right
+    }
+
+    /// Returns the `MOVED` error redirecting cluster clients to the owner of
+    /// the slot of the user key, or `None` if the slot is owned by myself.
+    pub fn moved_error(&self, user_key: &[u8]) -> Option<RTError> {
+        let slot = key_hash_slot(user_key);
+        let nodes_guard = self.nodes.read().unwrap();
+        nodes_guard
+            .iter()
+            .find(|node| node.slot_start <= slot && slot <= node.slot_end)
+            .filter(|node| node.flags.is_none())
+            .map(|node| RTError::Owned(format!("MOVED {} {}:{}", slot, node.ip, node.port)))
+    }
+}
+
+/// crc16 to user key with hashtag `{}` support
+pub fn key_hash_slot(user_key: &[u8]) -> usize {
+    // check if user key contains valid hashtag
+    let mut left_tag_idx = usize::MAX;
+    let mut right_tag_idx = usize::MAX;
+    for (idx, byte) in user_key.iter().enumerate() {
+        if byte == &b'{' {
+            left_tag_idx = idx;
+        }
+        if left_tag_idx != usize::MAX && byte == &b'}' {
+            right_tag_idx = idx;
+            break;
+        }
+    }
+    if right_tag_idx != usize::MAX && right_tag_idx - left_tag_idx > 1 {
+        // we have a valid hashtag, do crc16 to string to the content in hashtag
+        (CRC16.checksum(&user_key[left_tag_idx + 1..right_tag_idx]) & 0x3FFF).into()
+    } else {
+        (CRC16.checksum(user_key) & 0x3FFF).into()
+    }
 }
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Append the value at the end of the string stored at key.
+#[derive(Debug, Clone)]
+pub struct Append {
+    key: Bytes,
+    value: Bytes,
+    valid: bool,
+}
+
+impl Append {
+    pub fn new(key: &Bytes, value: &Bytes) -> Append {
+        Append {
+            key: key.clone(),
+            value: value.clone(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Append> {
+        let key = parse.next_bytes()?;
+        let value = parse.next_bytes()?;
+        Ok(Append::new(&key, &value))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Append> {
+        if argv.len() != 2 {
+            return Ok(Append::new_invalid());
+        }
+        Ok(Append::new(&argv[0], &argv[1]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.append(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn append(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_append(&self.key, &self.value)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Append {
+    fn new_invalid() -> Append {
+        Append {
+            key: Bytes::new(),
+            value: Bytes::new(),
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
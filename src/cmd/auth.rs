@@ -40,4 +40,8 @@ impl Invalid for Auth {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
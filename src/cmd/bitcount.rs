@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::bitmap::BitmapCommandCtx;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Count the set bits in the string value stored at key.
+#[derive(Debug, Clone)]
+pub struct Bitcount {
+    key: Bytes,
+    /// inclusive start and end, and whether they are bit indexes
+    range: Option<(i64, i64, bool)>,
+    valid: bool,
+}
+
+impl Bitcount {
+    pub fn new(key: &Bytes) -> Bitcount {
+        Bitcount {
+            key: key.clone(),
+            range: None,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// BITCOUNT key [start end [BYTE | BIT]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bitcount> {
+        let key = parse.next_bytes()?;
+
+        let mut options = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => options.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Bitcount::new_invalid()),
+            }
+        }
+
+        Ok(Bitcount::new(&key).with_options(&options))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Bitcount> {
+        if argv.is_empty() {
+            return Ok(Bitcount::new_invalid());
+        }
+        Ok(Bitcount::new(&argv[0]).with_options(&argv[1..]))
+    }
+
+    fn with_options(mut self, options: &[Bytes]) -> Bitcount {
+        let (start, end, unit) = match options {
+            [] => return self,
+            [start, end] => (start, end, None),
+            [start, end, unit] => (start, end, Some(unit)),
+            _ => return Bitcount::new_invalid(),
+        };
+        let start = String::from_utf8_lossy(start).parse::<i64>();
+        let end = String::from_utf8_lossy(end).parse::<i64>();
+        let is_bit = match unit.map(|unit| parse_bit_unit(unit)) {
+            None => false,
+            Some(Some(is_bit)) => is_bit,
+            Some(None) => return Bitcount::new_invalid(),
+        };
+        match (start, end) {
+            (Ok(start), Ok(end)) => self.range = Some((start, end, is_bit)),
+            _ => return Bitcount::new_invalid(),
+        }
+        self
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitcount(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn bitcount(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            BitmapCommandCtx::new(txn)
+                .do_async_txnkv_bitcount(&self.key, self.range)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+/// Parse the BYTE or BIT unit of a range, true for BIT.
+pub(crate) fn parse_bit_unit(unit: &[u8]) -> Option<bool> {
+    match String::from_utf8_lossy(unit).to_uppercase().as_str() {
+        "BYTE" => Some(false),
+        "BIT" => Some(true),
+        _ => None,
+    }
+}
+
+impl Invalid for Bitcount {
+    fn new_invalid() -> Bitcount {
+        Bitcount {
+            key: Bytes::new(),
+            range: None,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
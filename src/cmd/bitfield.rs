@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::bitmap::{BitfieldOp, BitfieldOverflow, BitfieldType, BitmapCommandCtx};
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::STRING_MAX_SIZE;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Treat the string value stored at key as an array of integers of arbitrary
+/// width, also used by BITFIELD_RO which only accepts GET.
+#[derive(Debug, Clone)]
+pub struct Bitfield {
+    key: Bytes,
+    ops: Vec<BitfieldOp>,
+    valid: bool,
+}
+
+impl Bitfield {
+    pub fn new(key: &Bytes) -> Bitfield {
+        Bitfield {
+            key: key.clone(),
+            ops: vec![],
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// BITFIELD key [GET encoding offset | [OVERFLOW WRAP | SAT | FAIL]
+    ///   SET encoding offset value | INCRBY encoding offset increment ...]
+    /// BITFIELD_RO key [GET encoding offset ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse, readonly: bool) -> crate::Result<Bitfield> {
+        let key = parse.next_bytes()?;
+
+        let mut options = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => options.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Bitfield::new_invalid()),
+            }
+        }
+
+        Ok(Bitfield::new(&key).with_options(&options, readonly))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes], readonly: bool) -> crate::Result<Bitfield> {
+        if argv.is_empty() {
+            return Ok(Bitfield::new_invalid());
+        }
+        Ok(Bitfield::new(&argv[0]).with_options(&argv[1..], readonly))
+    }
+
+    fn with_options(mut self, options: &[Bytes], readonly: bool) -> Bitfield {
+        let mut overflow = BitfieldOverflow::Wrap;
+        let mut idx = 0;
+        while idx < options.len() {
+            let name = String::from_utf8_lossy(&options[idx]).to_uppercase();
+            if name == "OVERFLOW" && !readonly {
+                overflow = match options
+                    .get(idx + 1)
+                    .map(|o| String::from_utf8_lossy(o).to_uppercase())
+                {
+                    Some(o) if o == "WRAP" => BitfieldOverflow::Wrap,
+                    Some(o) if o == "SAT" => BitfieldOverflow::Sat,
+                    Some(o) if o == "FAIL" => BitfieldOverflow::Fail,
+                    _ => return Bitfield::new_invalid(),
+                };
+                idx += 2;
+                continue;
+            }
+
+            let argc = match name.as_str() {
+                "GET" => 2,
+                "SET" | "INCRBY" if !readonly => 3,
+                _ => return Bitfield::new_invalid(),
+            };
+            if idx + argc >= options.len() {
+                return Bitfield::new_invalid();
+            }
+            let ty = match parse_type(&options[idx + 1]) {
+                Some(ty) => ty,
+                None => return Bitfield::new_invalid(),
+            };
+            let offset = match parse_offset(&options[idx + 2], ty) {
+                Some(offset) => offset,
+                None => return Bitfield::new_invalid(),
+            };
+            let op = if argc == 2 {
+                BitfieldOp::Get(ty, offset)
+            } else {
+                let value = match String::from_utf8_lossy(&options[idx + 3]).parse::<i64>() {
+                    Ok(value) => value,
+                    Err(_) => return Bitfield::new_invalid(),
+                };
+                if name == "SET" {
+                    BitfieldOp::Set(ty, offset, value, overflow)
+                } else {
+                    BitfieldOp::Incrby(ty, offset, value, overflow)
+                }
+            };
+            self.ops.push(op);
+            idx += argc + 1;
+        }
+        self
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitfield(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn bitfield(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            BitmapCommandCtx::new(txn)
+                .do_async_txnkv_bitfield(&self.key, &self.ops)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+/// Parse an encoding like i8 or u16, u64 is not supported as the values are
+/// returned as signed integers.
+fn parse_type(ty: &[u8]) -> Option<BitfieldType> {
+    let signed = match ty.first() {
+        Some(b'i') | Some(b'I') => true,
+        Some(b'u') | Some(b'U') => false,
+        _ => return None,
+    };
+    let bits = String::from_utf8_lossy(&ty[1..]).parse::<u32>().ok()?;
+    let max_bits = if signed { 64 } else { 63 };
+    (1..=max_bits)
+        .contains(&bits)
+        .then_some(BitfieldType { signed, bits })
+}
+
+/// Parse a bit offset, or a multiple of the type width if prefixed by `#`.
+fn parse_offset(offset: &[u8], ty: BitfieldType) -> Option<u64> {
+    let offset = match offset.strip_prefix(b"#") {
+        Some(n) => String::from_utf8_lossy(n)
+            .parse::<u64>()
+            .ok()?
+            .checked_mul(ty.bits as u64)?,
+        None => String::from_utf8_lossy(offset).parse::<u64>().ok()?,
+    };
+    // the last bit of the field must be inside a 512MB string
+    (offset.checked_add(ty.bits as u64 - 1)? / 8 < STRING_MAX_SIZE as u64).then_some(offset)
+}
+
+impl Invalid for Bitfield {
+    fn new_invalid() -> Bitfield {
+        Bitfield {
+            key: Bytes::new(),
+            ops: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
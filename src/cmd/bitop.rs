@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::bitmap::{BitOperation, BitmapCommandCtx};
+use crate::tikv::errors::{
+    AsyncResult, REDIS_BITOP_NOT_SINGLE_SOURCE_ERR, REDIS_NOT_SUPPORTED_ERR,
+};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Perform a bitwise operation between strings and store the result in the
+/// destination key.
+#[derive(Debug, Clone)]
+pub struct Bitop {
+    op: BitOperation,
+    dest: Bytes,
+    keys: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Bitop {
+    pub fn new(op: &[u8], dest: &Bytes, keys: &[Bytes]) -> Bitop {
+        let op = match String::from_utf8_lossy(op).to_uppercase().as_str() {
+            "AND" => BitOperation::And,
+            "OR" => BitOperation::Or,
+            "XOR" => BitOperation::Xor,
+            "NOT" => BitOperation::Not,
+            _ => return Bitop::new_invalid(),
+        };
+        if keys.is_empty() {
+            return Bitop::new_invalid();
+        }
+        Bitop {
+            op,
+            dest: dest.clone(),
+            keys: keys.to_vec(),
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// BITOP AND | OR | XOR | NOT destkey key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bitop> {
+        let op = parse.next_bytes()?;
+        let dest = parse.next_bytes()?;
+
+        let mut keys = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Bitop::new_invalid()),
+            }
+        }
+
+        Ok(Bitop::new(&op, &dest, &keys))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Bitop> {
+        if argv.len() < 3 {
+            return Ok(Bitop::new_invalid());
+        }
+        Ok(Bitop::new(&argv[0], &argv[1], &argv[2..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitop(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn bitop(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if self.op == BitOperation::Not && self.keys.len() != 1 {
+            return Ok(resp_err(REDIS_BITOP_NOT_SINGLE_SOURCE_ERR));
+        }
+        if is_use_txn_api() {
+            BitmapCommandCtx::new(txn)
+                .do_async_txnkv_bitop(self.op, &self.dest, &self.keys)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Bitop {
+    fn new_invalid() -> Bitop {
+        Bitop {
+            op: BitOperation::And,
+            dest: Bytes::new(),
+            keys: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
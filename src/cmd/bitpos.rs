@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use crate::cmd::bitcount::parse_bit_unit;
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::bitmap::BitmapCommandCtx;
+use crate::tikv::errors::{AsyncResult, REDIS_BIT_ARGUMENT_ERR, REDIS_NOT_SUPPORTED_ERR};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the position of the first bit set to 1 or 0 in the string value
+/// stored at key.
+#[derive(Debug, Clone)]
+pub struct Bitpos {
+    key: Bytes,
+    bit: Option<bool>,
+    start: i64,
+    end: Option<i64>,
+    is_bit: bool,
+    valid: bool,
+}
+
+impl Bitpos {
+    pub fn new(key: &Bytes, bit: &[u8]) -> Bitpos {
+        Bitpos {
+            key: key.clone(),
+            bit: match bit {
+                b"0" => Some(false),
+                b"1" => Some(true),
+                _ => None,
+            },
+            start: 0,
+            end: None,
+            is_bit: false,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// BITPOS key bit [start [end [BYTE | BIT]]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bitpos> {
+        let key = parse.next_bytes()?;
+        let bit = parse.next_bytes()?;
+
+        let mut options = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => options.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Bitpos::new_invalid()),
+            }
+        }
+
+        Ok(Bitpos::new(&key, &bit).with_options(&options))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Bitpos> {
+        if argv.len() < 2 {
+            return Ok(Bitpos::new_invalid());
+        }
+        Ok(Bitpos::new(&argv[0], &argv[1]).with_options(&argv[2..]))
+    }
+
+    fn with_options(mut self, options: &[Bytes]) -> Bitpos {
+        if options.len() > 3 {
+            return Bitpos::new_invalid();
+        }
+        if let Some(start) = options.first() {
+            match String::from_utf8_lossy(start).parse::<i64>() {
+                Ok(start) => self.start = start,
+                Err(_) => return Bitpos::new_invalid(),
+            }
+        }
+        if let Some(end) = options.get(1) {
+            match String::from_utf8_lossy(end).parse::<i64>() {
+                Ok(end) => self.end = Some(end),
+                Err(_) => return Bitpos::new_invalid(),
+            }
+        }
+        if let Some(unit) = options.get(2) {
+            match parse_bit_unit(unit) {
+                Some(is_bit) => self.is_bit = is_bit,
+                None => return Bitpos::new_invalid(),
+            }
+        }
+        self
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitpos(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn bitpos(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let bit = match self.bit {
+            Some(bit) => bit,
+            None => return Ok(resp_err(REDIS_BIT_ARGUMENT_ERR)),
+        };
+        if is_use_txn_api() {
+            BitmapCommandCtx::new(txn)
+                .do_async_txnkv_bitpos(&self.key, bit, self.start, self.end, self.is_bit)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Bitpos {
+    fn new_invalid() -> Bitpos {
+        Bitpos {
+            key: Bytes::new(),
+            bit: None,
+            start: 0,
+            end: None,
+            is_bit: false,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
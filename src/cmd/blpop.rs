@@ -0,0 +1,247 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::blocking_poll_interval_or_default;
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::metrics::BLOCKED_CLIENTS_GAUGE;
+use crate::tikv::client::any_key_exists;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::list::ListCommandCtx;
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_invalid_arguments, resp_nil_array};
+use crate::{Connection, Db, Frame, Shutdown};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use futures::future::select_all;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::select;
+use tokio::sync::{broadcast, Mutex};
+use tokio::time::{self, Duration, Instant};
+
+/// Pop an element from the first non-empty list of the keys, blocks the
+/// connection until an element is pushed to one of the lists if they are all
+/// empty.
+#[derive(Debug, Clone)]
+pub struct Blpop {
+    keys: Vec<Bytes>,
+    timeout: f64,
+    valid: bool,
+}
+
+impl Blpop {
+    pub fn new(keys: Vec<Bytes>, timeout: f64) -> Blpop {
+        Blpop {
+            keys,
+            timeout,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// BLPOP key [key ...] timeout
+    /// BRPOP key [key ...] timeout
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Blpop> {
+        let mut keys = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Blpop::new_invalid()),
+            }
+        }
+
+        let timeout = match keys.pop().and_then(|arg| parse_timeout(&arg)) {
+            Some(timeout) if !keys.is_empty() => timeout,
+            _ => return Ok(Blpop::new_invalid()),
+        };
+
+        Ok(Blpop::new(keys, timeout))
+    }
+
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        shutdown: &mut Shutdown,
+        op_left: bool,
+    ) -> crate::Result<()> {
+        let response = block_for_keys(db, &self.keys, self.timeout, dst, shutdown, || {
+            self.try_pop(None, op_left)
+        })
+        .await?;
+        let response = match response {
+            Some(response) => response,
+            // the connection is closed without reply
+            None if shutdown.is_shutdown() || dst.is_closed() => return Ok(()),
+            None => resp_nil_array(),
+        };
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Pop without blocking, as the command does inside a transaction.
+    pub async fn blpop(
+        &self,
+        txn: Option<Arc<Mutex<Transaction>>>,
+        op_left: bool,
+    ) -> AsyncResult<Frame> {
+        Ok(self
+            .try_pop(txn, op_left)
+            .await?
+            .unwrap_or_else(resp_nil_array))
+    }
+
+    async fn try_pop(
+        &self,
+        txn: Option<Arc<Mutex<Transaction>>>,
+        op_left: bool,
+    ) -> AsyncResult<Option<Frame>> {
+        if !self.valid {
+            return Ok(Some(resp_invalid_arguments()));
+        }
+        if !is_use_txn_api() {
+            return Ok(Some(resp_err(REDIS_NOT_SUPPORTED_ERR)));
+        }
+
+        match ListCommandCtx::new(txn)
+            .do_async_txnkv_mpop(&self.keys, op_left, 1)
+            .await
+        {
+            Ok(Some((key, mut values))) => Ok(Some(resp_array(vec![
+                resp_bulk(key.to_vec()),
+                values.remove(0),
+            ]))),
+            Ok(None) => Ok(None),
+            Err(e) => Ok(Some(resp_err(e))),
+        }
+    }
+}
+
+impl Invalid for Blpop {
+    fn new_invalid() -> Blpop {
+        Blpop {
+            keys: vec![],
+            timeout: 0.0,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
+
+/// Parse the timeout in seconds of the blocking commands, which must be a non
+/// negative float.
+pub(crate) fn parse_timeout(arg: &[u8]) -> Option<f64> {
+    match String::from_utf8_lossy(arg).parse::<f64>() {
+        Ok(timeout) if timeout.is_finite() && timeout >= 0.0 => Some(timeout),
+        _ => None,
+    }
+}
+
+/// Call `attempt` until it returns a reply, blocking on the keys in between.
+///
+/// The client is woken up at once when the keys are modified on this
+/// instance, the keys modified by other instances are noticed by reading
+/// their meta keys every `blocking_poll_interval` milliseconds, `attempt` is
+/// only called again once one of them exists. Returns `None`
+/// when `timeout` in seconds elapsed, zero blocks indefinitely, or the
+/// connection is shut down or closed by the client, which is told by
+/// `Connection::is_closed`.
+pub(crate) async fn block_for_keys<F, Fut>(
+    db: &Db,
+    keys: &[Bytes],
+    timeout: f64,
+    dst: &mut Connection,
+    shutdown: &mut Shutdown,
+    mut attempt: F,
+) -> AsyncResult<Option<Frame>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = AsyncResult<Option<Frame>>>,
+{
+    // watch the keys before the first attempt, so pushes in between are not missed
+    let mut blocked = BlockedKeys::watch(db, keys);
+    let deadline = if timeout > 0.0 {
+        Some(Instant::now() + Duration::from_secs_f64(timeout))
+    } else {
+        None
+    };
+    let poll_interval = Duration::from_millis(blocking_poll_interval_or_default());
+    let mut flushed = false;
+    let mut ready = true;
+
+    loop {
+        if ready {
+            match attempt().await {
+                Ok(None) => {}
+                result => return result,
+            }
+        }
+
+        // the replies of the commands pipelined before this one are left in
+        // the write buffer by `write_frame`, send them before blocking
+        if !flushed {
+            dst.flush().await?;
+            flushed = true;
+        }
+
+        let woken = select_all(blocked.receivers.iter_mut().map(|rx| Box::pin(rx.recv())));
+        select! {
+            _ = woken => ready = true,
+            _ = time::sleep(poll_interval) => ready = any_key_exists(keys).await?,
+            _ = time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                return Ok(None);
+            }
+            _ = shutdown.recv() => return Ok(None),
+            _ = dst.closed() => return Ok(None),
+        }
+    }
+}
+
+/// The keys a client is blocked on, they are unwatched once dropped, even if
+/// the blocked command is cancelled.
+struct BlockedKeys<'a> {
+    db: &'a Db,
+    keys: &'a [Bytes],
+    receivers: Vec<broadcast::Receiver<()>>,
+}
+
+impl<'a> BlockedKeys<'a> {
+    fn watch(db: &'a Db, keys: &'a [Bytes]) -> Self {
+        BLOCKED_CLIENTS_GAUGE.inc();
+        BlockedKeys {
+            db,
+            keys,
+            receivers: keys.iter().map(|key| db.watch_key(key)).collect(),
+        }
+    }
+}
+
+impl Drop for BlockedKeys<'_> {
+    fn drop(&mut self) {
+        BLOCKED_CLIENTS_GAUGE.dec();
+        // the receivers are dropped first, so the keys are not watched anymore
+        self.receivers.clear();
+        self.db.unwatch_keys(self.keys);
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use crate::cmd::blpop::{block_for_keys, parse_timeout};
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_invalid_arguments, resp_nil_array};
+use crate::{Connection, Db, Frame, Shutdown};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Pop the member with the lowest or highest score from the first non-empty
+/// sorted set of the keys, blocks the connection until a member is added to
+/// one of the sorted sets if they are all empty.
+#[derive(Debug, Clone)]
+pub struct Bzpop {
+    keys: Vec<Bytes>,
+    timeout: f64,
+    valid: bool,
+}
+
+impl Bzpop {
+    pub fn new(keys: Vec<Bytes>, timeout: f64) -> Bzpop {
+        Bzpop {
+            keys,
+            timeout,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// BZPOPMIN key [key ...] timeout
+    /// BZPOPMAX key [key ...] timeout
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bzpop> {
+        let mut keys = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Bzpop::new_invalid()),
+            }
+        }
+
+        let timeout = match keys.pop().and_then(|arg| parse_timeout(&arg)) {
+            Some(timeout) if !keys.is_empty() => timeout,
+            _ => return Ok(Bzpop::new_invalid()),
+        };
+
+        Ok(Bzpop::new(keys, timeout))
+    }
+
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        shutdown: &mut Shutdown,
+        from_min: bool,
+    ) -> crate::Result<()> {
+        let response = block_for_keys(db, &self.keys, self.timeout, dst, shutdown, || {
+            self.try_pop(None, from_min)
+        })
+        .await?;
+        let response = match response {
+            Some(response) => response,
+            // the connection is closed without reply
+            None if shutdown.is_shutdown() || dst.is_closed() => return Ok(()),
+            None => resp_nil_array(),
+        };
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Pop without blocking, as the command does inside a transaction.
+    pub async fn bzpop(
+        &self,
+        txn: Option<Arc<Mutex<Transaction>>>,
+        from_min: bool,
+    ) -> AsyncResult<Frame> {
+        Ok(self
+            .try_pop(txn, from_min)
+            .await?
+            .unwrap_or_else(resp_nil_array))
+    }
+
+    async fn try_pop(
+        &self,
+        txn: Option<Arc<Mutex<Transaction>>>,
+        from_min: bool,
+    ) -> AsyncResult<Option<Frame>> {
+        if !self.valid {
+            return Ok(Some(resp_invalid_arguments()));
+        }
+        if !is_use_txn_api() {
+            return Ok(Some(resp_err(REDIS_NOT_SUPPORTED_ERR)));
+        }
+
+        match ZsetCommandCtx::new(txn)
+            .do_async_txnkv_mzpop(&self.keys, from_min, 1)
+            .await
+        {
+            Ok(Some((key, values))) => {
+                let mut resp = vec![resp_bulk(key.to_vec())];
+                resp.extend(values);
+                Ok(Some(resp_array(resp)))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Ok(Some(resp_err(e))),
+        }
+    }
+}
+
+impl Invalid for Bzpop {
+    fn new_invalid() -> Bzpop {
+        Bzpop {
+            keys: vec![],
+            timeout: 0.0,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -3,7 +3,7 @@ use crate::cmd::Invalid;
 use crate::config::LOGGER;
 use crate::tikv::errors::REDIS_UNKNOWN_SUBCOMMAND;
 use crate::utils::{resp_err, resp_invalid_arguments};
-use crate::{Connection, Parse};
+use crate::{Connection, Frame, Parse};
 use slog::debug;
 
 #[derive(Debug, Clone)]
@@ -27,17 +27,7 @@ impl Cluster {
     }
 
     pub(crate) async fn apply(self, topo: &Topo, dst: &mut Connection) -> crate::Result<()> {
-        if !self.valid {
-            dst.write_frame(&resp_invalid_arguments()).await?;
-            return Ok(());
-        }
-
-        let response = match self.subcommand.to_uppercase().as_str() {
-            "INFO" => topo.cluster_info(),
-            "SLOTS" => topo.cluster_slots(),
-            "NODES" => topo.cluster_nodes(),
-            _ => resp_err(REDIS_UNKNOWN_SUBCOMMAND),
-        };
+        let response = self.cluster(topo);
 
         debug!(
             LOGGER,
@@ -51,6 +41,19 @@ impl Cluster {
 
         Ok(())
     }
+
+    pub(crate) fn cluster(&self, topo: &Topo) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+
+        match self.subcommand.to_uppercase().as_str() {
+            "INFO" => topo.cluster_info(),
+            "SLOTS" => topo.cluster_slots(),
+            "NODES" => topo.cluster_nodes(),
+            _ => resp_err(REDIS_UNKNOWN_SUBCOMMAND),
+        }
+    }
 }
 
 impl Invalid for Cluster {
@@ -60,4 +63,8 @@ impl Invalid for Cluster {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -16,33 +16,33 @@ use super::Invalid;
 
 #[derive(Debug, Clone)]
 pub struct Type {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Type {
-    pub fn new(key: impl ToString) -> Type {
+    pub fn new(key: &Bytes) -> Type {
         Type {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Type> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
-        Ok(Type::new(key))
+        Ok(Type::new(&key))
     }
 
     pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Type> {
         if argv.len() != 1 {
             return Ok(Type::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         Ok(Type::new(key))
     }
 
@@ -82,8 +82,12 @@ impl Type {
 impl Invalid for Type {
     fn new_invalid() -> Type {
         Type {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::cmd::{Invalid, Parse};
+use crate::cmd::{Invalid, Parse, ParseError};
 use crate::config::is_use_txn_api;
 use crate::tikv::config::ConfigCommandCtx;
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
@@ -16,6 +16,7 @@ use tokio::sync::Mutex;
 pub struct Config {
     key: String,
     field: String,
+    value: Option<String>,
     valid: bool,
 }
 
@@ -24,6 +25,7 @@ impl Config {
         Config {
             field: field.to_owned(),
             key: key.to_owned(),
+            value: None,
             valid: true,
         }
     }
@@ -39,17 +41,27 @@ impl Config {
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Config> {
         let key = parse.next_string()?;
         let field = parse.next_string()?;
-        Ok(Config::new(&key, &field))
+        let mut config = Config::new(&key, &field);
+        match parse.next_string() {
+            Ok(value) => config.value = Some(value),
+            Err(ParseError::EndOfStream) => {}
+            Err(_) => return Ok(Config::new_invalid()),
+        }
+        Ok(config)
     }
 
     pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Config> {
-        if argv.len() != 2 {
+        if argv.len() != 2 && argv.len() != 3 {
             return Ok(Config::new_invalid());
         }
-        Ok(Config::new(
+        let mut config = Config::new(
             &String::from_utf8_lossy(&argv[0]),
             &String::from_utf8_lossy(&argv[1]),
-        ))
+        );
+        config.value = argv
+            .get(2)
+            .map(|value| String::from_utf8_lossy(value).to_string());
+        Ok(config)
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -74,9 +86,12 @@ impl Config {
             return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
         }
 
-        ConfigCommandCtx::new(txn)
-            .do_async_rawkv_get(&self.key, &self.field)
-            .await
+        let ctx = ConfigCommandCtx::new(txn);
+        match (self.key.to_lowercase().as_str(), &self.value) {
+            ("set", Some(value)) => ctx.do_async_rawkv_set(&self.field, value).await,
+            ("set", None) | (_, Some(_)) => Ok(resp_invalid_arguments()),
+            _ => ctx.do_async_rawkv_get(&self.key, &self.field).await,
+        }
 
         // match self.key.to_lowercase().as_str() {
         //     "get" => match self.field.to_lowercase().as_str() {
@@ -100,7 +115,12 @@ impl Invalid for Config {
         Config {
             field: "".to_owned(),
             key: "".to_owned(),
+            value: None,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
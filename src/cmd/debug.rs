@@ -3,7 +3,7 @@ use crate::config::LOGGER;
 use crate::tikv::errors::REDIS_NOT_SUPPORTED_DEBUG_SUB_COMMAND_ERR;
 use crate::tikv::{start_profiler, stop_profiler};
 use crate::utils::{resp_err, resp_invalid_arguments, resp_ok};
-use crate::{Connection, Parse};
+use crate::{Connection, Frame, Parse};
 use slog::debug;
 
 #[derive(Debug, Clone)]
@@ -27,22 +27,7 @@ impl Debug {
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
-        if !self.valid {
-            dst.write_frame(&resp_invalid_arguments()).await?;
-            return Ok(());
-        }
-
-        let response = match self.subcommand.to_lowercase().as_str() {
-            "profiler_start" => {
-                start_profiler();
-                resp_ok()
-            }
-            "profiler_stop" => {
-                stop_profiler();
-                resp_ok()
-            }
-            _ => resp_err(REDIS_NOT_SUPPORTED_DEBUG_SUB_COMMAND_ERR),
-        };
+        let response = self.debug();
 
         debug!(
             LOGGER,
@@ -56,6 +41,24 @@ impl Debug {
 
         Ok(())
     }
+
+    pub(crate) fn debug(&self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+
+        match self.subcommand.to_lowercase().as_str() {
+            "profiler_start" => {
+                start_profiler();
+                resp_ok()
+            }
+            "profiler_stop" => {
+                stop_profiler();
+                resp_ok()
+            }
+            _ => resp_err(REDIS_NOT_SUPPORTED_DEBUG_SUB_COMMAND_ERR),
+        }
+    }
 }
 
 impl Invalid for Debug {
@@ -65,4 +68,8 @@ impl Invalid for Debug {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,23 +15,23 @@ use super::Invalid;
 
 #[derive(Debug, Clone)]
 pub struct Del {
-    keys: Vec<String>,
+    keys: Vec<Bytes>,
     valid: bool,
 }
 
 impl Del {
     /// Get the keys
-    pub fn keys(&self) -> &Vec<String> {
+    pub fn keys(&self) -> &Vec<Bytes> {
         &self.keys
     }
 
-    pub fn add_key(&mut self, key: String) {
+    pub fn add_key(&mut self, key: Bytes) {
         self.keys.push(key);
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Del> {
         let mut del = Del::default();
-        while let Ok(key) = parse.next_string() {
+        while let Ok(key) = parse.next_bytes() {
             del.add_key(key);
         }
 
@@ -46,10 +46,7 @@ impl Del {
             });
         }
         Ok(Del {
-            keys: argv
-                .iter()
-                .map(|x| String::from_utf8_lossy(x).to_string())
-                .collect::<Vec<String>>(),
+            keys: argv.to_vec(),
             valid: true,
         })
     }
@@ -101,4 +98,8 @@ impl Invalid for Del {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
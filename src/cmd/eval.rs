@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::config::is_use_txn_api;
 use crate::db::Db;
+use crate::notify::defer_events;
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::get_txn_client;
 use crate::tikv::lua::LuaCommandCtx;
@@ -10,6 +11,7 @@ use crate::{Connection, Frame, Parse};
 
 use bytes::Bytes;
 use mlua::Lua;
+use tikv_client::Transaction;
 use tokio::sync::Mutex;
 
 use crate::cmd::Invalid;
@@ -20,7 +22,7 @@ use slog::debug;
 pub struct Eval {
     script: String,
     numkeys: i64,
-    keys: Vec<String>,
+    keys: Vec<Bytes>,
     args: Vec<Bytes>,
     valid: bool,
 }
@@ -37,11 +39,11 @@ impl Eval {
     }
 
     /// Get the key
-    pub fn keys(&self) -> &Vec<String> {
+    pub fn keys(&self) -> &Vec<Bytes> {
         &self.keys
     }
 
-    pub fn add_key(&mut self, key: String) {
+    pub fn add_key(&mut self, key: Bytes) {
         self.keys.push(key);
     }
 
@@ -58,7 +60,7 @@ impl Eval {
         let mut eval = Eval::new(&script, numkeys);
 
         for _ in 0..eval.numkeys {
-            if let Ok(key) = parse.next_string() {
+            if let Ok(key) = parse.next_bytes() {
                 eval.add_key(key);
             } else {
                 break;
@@ -79,7 +81,7 @@ impl Eval {
         db: &Db,
         lua: &Option<Lua>,
     ) -> crate::Result<()> {
-        let response = self.eval(is_sha, db, lua).await?;
+        let response = self.eval(is_sha, db, lua, None).await?;
 
         debug!(
             LOGGER,
@@ -94,7 +96,15 @@ impl Eval {
         Ok(())
     }
 
-    async fn eval(&self, is_sha: bool, db: &Db, lua: &Option<Lua>) -> AsyncResult<Frame> {
+    /// Run the script in `txn` if given, which is left to the caller to
+    /// commit, otherwise in a new transaction of its own.
+    pub(crate) async fn eval(
+        &self,
+        is_sha: bool,
+        db: &Db,
+        lua: &Option<Lua>,
+        txn: Option<Arc<Mutex<Transaction>>>,
+    ) -> AsyncResult<Frame> {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
@@ -103,6 +113,18 @@ impl Eval {
             return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
         }
 
+        if let Some(txn) = txn {
+            let ctx = LuaCommandCtx::new(Some(txn), lua);
+            let resp = if is_sha {
+                ctx.do_async_evalsha(&self.script, db, &self.keys, &self.args)
+                    .await
+            } else {
+                ctx.do_async_eval(&self.script, db, &self.keys, &self.args)
+                    .await
+            };
+            return Ok(resp.unwrap_or_else(resp_err));
+        }
+
         // create new txn
         let client = get_txn_client()?;
         let txn = client.begin().await?;
@@ -110,16 +132,21 @@ impl Eval {
 
         let ctx = LuaCommandCtx::new(Some(txn_rc.clone()), lua);
 
-        let resp = if is_sha {
-            ctx.do_async_evalsha(&self.script, db, &self.keys, &self.args)
-                .await
-        } else {
-            ctx.do_async_eval(&self.script, db, &self.keys, &self.args)
-                .await
-        };
+        // the events of the script are only published once committed
+        let (resp, events) = defer_events(async {
+            if is_sha {
+                ctx.do_async_evalsha(&self.script, db, &self.keys, &self.args)
+                    .await
+            } else {
+                ctx.do_async_eval(&self.script, db, &self.keys, &self.args)
+                    .await
+            }
+        })
+        .await;
         match resp {
             Ok(r) => {
                 txn_rc.lock().await.commit().await?;
+                events.emit();
                 Ok(r)
             }
             Err(e) => {
@@ -140,4 +167,8 @@ impl Invalid for Eval {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,24 +15,24 @@ use slog::debug;
 
 #[derive(Debug, Clone)]
 pub struct Exists {
-    keys: Vec<String>,
+    keys: Vec<Bytes>,
     valid: bool,
 }
 
 impl Exists {
     /// Get the keys
-    pub fn keys(&self) -> &Vec<String> {
+    pub fn keys(&self) -> &Vec<Bytes> {
         &self.keys
     }
 
-    pub fn add_key(&mut self, key: String) {
+    pub fn add_key(&mut self, key: Bytes) {
         self.keys.push(key)
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Exists> {
         let mut exists = Exists::default();
 
-        while let Ok(key) = parse.next_string() {
+        while let Ok(key) = parse.next_bytes() {
             exists.add_key(key);
         }
 
@@ -47,10 +47,7 @@ impl Exists {
             });
         }
         Ok(Exists {
-            keys: argv
-                .iter()
-                .map(|x| String::from_utf8_lossy(x).to_string())
-                .collect::<Vec<String>>(),
+            keys: argv.to_vec(),
             valid: true,
         })
     }
@@ -103,4 +100,8 @@ impl Invalid for Exists {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -14,22 +14,22 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Expire {
-    key: String,
+    key: Bytes,
     seconds: i64,
     valid: bool,
 }
 
 impl Expire {
-    pub fn new(key: impl ToString, seconds: i64) -> Expire {
+    pub fn new(key: &Bytes, seconds: i64) -> Expire {
         Expire {
-            key: key.to_string(),
+            key: key.clone(),
             seconds,
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
@@ -38,7 +38,7 @@ impl Expire {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Expire> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let seconds = parse.next_int()?;
 
         Ok(Expire {
@@ -52,7 +52,7 @@ impl Expire {
         if argv.len() != 2 {
             return Ok(Expire::new_invalid());
         }
-        let key = String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         match String::from_utf8_lossy(&argv[1]).parse::<i64>() {
             Ok(v) => Ok(Expire::new(key, v)),
             Err(_) => Ok(Expire::new_invalid()),
@@ -111,9 +111,13 @@ impl Expire {
 impl Invalid for Expire {
     fn new_invalid() -> Expire {
         Expire {
-            key: "".to_owned(),
+            key: Bytes::new(),
             seconds: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
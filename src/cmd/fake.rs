@@ -59,7 +59,7 @@ impl Fake {
         Ok(())
     }
 
-    async fn do_apply(
+    pub(crate) async fn do_apply(
         self,
         command: &str,
         cur_client: Arc<Mutex<Client>>,
@@ -258,4 +258,8 @@ impl Invalid for Fake {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
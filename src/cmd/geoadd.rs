@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::geo::{geo_coord_is_valid, geohash_encode_score};
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Add the members with their coordinates to the sorted set stored at key.
+#[derive(Debug, Clone)]
+pub struct Geoadd {
+    key: Bytes,
+    members: Vec<Bytes>,
+    coords: Vec<(f64, f64)>,
+    exists: Option<bool>,
+    changed_only: bool,
+    valid: bool,
+}
+
+impl Geoadd {
+    pub fn new(key: &Bytes, args: &[Bytes]) -> Geoadd {
+        let mut geoadd = Geoadd {
+            key: key.clone(),
+            members: vec![],
+            coords: vec![],
+            exists: None,
+            changed_only: false,
+            valid: true,
+        };
+
+        let mut idx = 0;
+        while idx < args.len() {
+            match String::from_utf8_lossy(&args[idx]).to_uppercase().as_str() {
+                "NX" => geoadd.exists = Some(false),
+                "XX" => geoadd.exists = Some(true),
+                "CH" => geoadd.changed_only = true,
+                _ => break,
+            }
+            idx += 1;
+        }
+
+        let triples = args[idx..].chunks_exact(3);
+        if idx == args.len() || !triples.remainder().is_empty() {
+            return Geoadd::new_invalid();
+        }
+        for triple in triples {
+            let lon = String::from_utf8_lossy(&triple[0]).parse::<f64>();
+            let lat = String::from_utf8_lossy(&triple[1]).parse::<f64>();
+            match (lon, lat) {
+                (Ok(lon), Ok(lat)) => {
+                    geoadd.coords.push((lon, lat));
+                    geoadd.members.push(triple[2].clone());
+                }
+                _ => return Geoadd::new_invalid(),
+            }
+        }
+        geoadd
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GEOADD key [NX | XX] [CH] longitude latitude member [longitude latitude member ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Geoadd> {
+        let key = parse.next_bytes()?;
+
+        let mut args = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => args.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Geoadd::new_invalid()),
+            }
+        }
+
+        Ok(Geoadd::new(&key, &args))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Geoadd> {
+        if argv.is_empty() {
+            return Ok(Geoadd::new_invalid());
+        }
+        Ok(Geoadd::new(&argv[0], &argv[1..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.geoadd(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn geoadd(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if let Some((lon, lat)) = self
+            .coords
+            .iter()
+            .find(|(lon, lat)| !geo_coord_is_valid(*lon, *lat))
+        {
+            return Ok(resp_err(RTError::Owned(format!(
+                "ERR invalid longitude,latitude pair {:.6},{:.6}",
+                lon, lat
+            ))));
+        }
+        if is_use_txn_api() {
+            let scores = self
+                .coords
+                .iter()
+                .map(|(lon, lat)| geohash_encode_score(*lon, *lat))
+                .collect();
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zadd(
+                    &self.key,
+                    &self.members,
+                    &scores,
+                    self.exists,
+                    self.changed_only,
+                    false,
+                )
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Geoadd {
+    fn new_invalid() -> Geoadd {
+        Geoadd {
+            key: Bytes::new(),
+            members: vec![],
+            coords: vec![],
+            exists: None,
+            changed_only: false,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
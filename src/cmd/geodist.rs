@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_GEO_UNSUPPORTED_UNIT_ERR, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::geo::GeoCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the meters of a distance unit.
+pub(crate) fn parse_geo_unit(unit: &[u8]) -> Option<f64> {
+    match String::from_utf8_lossy(unit).to_lowercase().as_str() {
+        "m" => Some(1.0),
+        "km" => Some(1000.0),
+        "ft" => Some(0.3048),
+        "mi" => Some(1609.34),
+        _ => None,
+    }
+}
+
+/// Return the distance between two members of the sorted set stored at key.
+#[derive(Debug, Clone)]
+pub struct Geodist {
+    key: Bytes,
+    member1: Bytes,
+    member2: Bytes,
+    unit: Option<f64>,
+    valid: bool,
+}
+
+impl Geodist {
+    pub fn new(key: &Bytes, member1: &Bytes, member2: &Bytes, unit: Option<&Bytes>) -> Geodist {
+        Geodist {
+            key: key.clone(),
+            member1: member1.clone(),
+            member2: member2.clone(),
+            unit: match unit {
+                Some(unit) => parse_geo_unit(unit),
+                None => Some(1.0),
+            },
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GEODIST key member1 member2 [M | KM | FT | MI]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Geodist> {
+        let key = parse.next_bytes()?;
+        let member1 = parse.next_bytes()?;
+        let member2 = parse.next_bytes()?;
+        let unit = parse.next_bytes().ok();
+        if !parse.check_finish() {
+            return Ok(Geodist::new_invalid());
+        }
+        Ok(Geodist::new(&key, &member1, &member2, unit.as_ref()))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Geodist> {
+        if argv.len() != 3 && argv.len() != 4 {
+            return Ok(Geodist::new_invalid());
+        }
+        Ok(Geodist::new(&argv[0], &argv[1], &argv[2], argv.get(3)))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.geodist(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn geodist(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let unit = match self.unit {
+            Some(unit) => unit,
+            None => return Ok(resp_err(REDIS_GEO_UNSUPPORTED_UNIT_ERR)),
+        };
+        if is_use_txn_api() {
+            GeoCommandCtx::new(txn)
+                .do_async_txnkv_geodist(&self.key, &self.member1, &self.member2, unit)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Geodist {
+    fn new_invalid() -> Geodist {
+        Geodist {
+            key: Bytes::new(),
+            member1: Bytes::new(),
+            member2: Bytes::new(),
+            unit: None,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::geo::GeoCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the coordinates of the members of the sorted set stored at key.
+#[derive(Debug, Clone)]
+pub struct Geopos {
+    key: Bytes,
+    members: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Geopos {
+    pub fn new(key: &Bytes, members: &[Bytes]) -> Geopos {
+        Geopos {
+            key: key.clone(),
+            members: members.to_vec(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GEOPOS key [member [member ...]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Geopos> {
+        let key = parse.next_bytes()?;
+
+        let mut members = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(member) => members.push(member),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Geopos::new_invalid()),
+            }
+        }
+
+        Ok(Geopos::new(&key, &members))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Geopos> {
+        if argv.is_empty() {
+            return Ok(Geopos::new_invalid());
+        }
+        Ok(Geopos::new(&argv[0], &argv[1..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.geopos(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn geopos(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            GeoCommandCtx::new(txn)
+                .do_async_txnkv_geopos(&self.key, &self.members)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Geopos {
+    fn new_invalid() -> Geopos {
+        Geopos {
+            key: Bytes::new(),
+            members: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -0,0 +1,341 @@
+use std::sync::Arc;
+
+use crate::cmd::geodist::parse_geo_unit;
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_GEO_UNSUPPORTED_UNIT_ERR, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::geo::{geo_coord_is_valid, GeoCommandCtx, GeoOrigin, GeoSearchQuery, GeoShape};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+fn parse_f64(arg: &[u8]) -> Option<f64> {
+    String::from_utf8_lossy(arg)
+        .parse::<f64>()
+        .ok()
+        .filter(|v| !v.is_nan())
+}
+
+/// Return the members of the sorted set stored at key which are inside the
+/// given area.
+#[derive(Debug, Clone)]
+pub struct Geosearch {
+    key: Bytes,
+    query: GeoSearchQuery,
+    unit_valid: bool,
+    valid: bool,
+}
+
+impl Geosearch {
+    pub fn new(key: &Bytes, args: &[Bytes]) -> Geosearch {
+        let mut origin = None;
+        let mut shape = None;
+        let mut unit = None;
+        let mut unit_valid = true;
+        let mut query = GeoSearchQuery {
+            origin: GeoOrigin::Coord(0.0, 0.0),
+            shape: GeoShape::Radius(0.0),
+            unit: 1.0,
+            asc: None,
+            count: None,
+            any: false,
+            with_coord: false,
+            with_dist: false,
+            with_hash: false,
+        };
+
+        let mut idx = 0;
+        while idx < args.len() {
+            let left = args.len() - idx - 1;
+            match String::from_utf8_lossy(&args[idx]).to_uppercase().as_str() {
+                "FROMMEMBER" if left >= 1 && origin.is_none() => {
+                    origin = Some(GeoOrigin::Member(args[idx + 1].clone()));
+                    idx += 1;
+                }
+                "FROMLONLAT" if left >= 2 && origin.is_none() => {
+                    match (parse_f64(&args[idx + 1]), parse_f64(&args[idx + 2])) {
+                        (Some(lon), Some(lat)) if geo_coord_is_valid(lon, lat) => {
+                            origin = Some(GeoOrigin::Coord(lon, lat));
+                        }
+                        _ => return Geosearch::new_invalid(),
+                    }
+                    idx += 2;
+                }
+                "BYRADIUS" if left >= 2 && shape.is_none() => {
+                    match parse_f64(&args[idx + 1]) {
+                        Some(radius) if radius >= 0.0 => shape = Some(GeoShape::Radius(radius)),
+                        _ => return Geosearch::new_invalid(),
+                    }
+                    unit = Some(args[idx + 2].clone());
+                    idx += 2;
+                }
+                "BYBOX" if left >= 3 && shape.is_none() => {
+                    match (parse_f64(&args[idx + 1]), parse_f64(&args[idx + 2])) {
+                        (Some(width), Some(height)) if width >= 0.0 && height >= 0.0 => {
+                            shape = Some(GeoShape::Box(width, height));
+                        }
+                        _ => return Geosearch::new_invalid(),
+                    }
+                    unit = Some(args[idx + 3].clone());
+                    idx += 3;
+                }
+                "ASC" => query.asc = Some(true),
+                "DESC" => query.asc = Some(false),
+                "COUNT" if left >= 1 => {
+                    match String::from_utf8_lossy(&args[idx + 1]).parse::<u64>() {
+                        Ok(count) if count > 0 => query.count = Some(count),
+                        _ => return Geosearch::new_invalid(),
+                    }
+                    idx += 1;
+                    if idx + 1 < args.len()
+                        && String::from_utf8_lossy(&args[idx + 1]).to_uppercase() == "ANY"
+                    {
+                        query.any = true;
+                        idx += 1;
+                    }
+                }
+                "WITHCOORD" => query.with_coord = true,
+                "WITHDIST" => query.with_dist = true,
+                "WITHHASH" => query.with_hash = true,
+                _ => return Geosearch::new_invalid(),
+            }
+            idx += 1;
+        }
+
+        match (origin, shape, unit) {
+            (Some(origin), Some(shape), Some(unit)) => {
+                query.origin = origin;
+                match parse_geo_unit(&unit) {
+                    Some(unit) => {
+                        query.unit = unit;
+                        query.shape = match shape {
+                            GeoShape::Radius(radius) => GeoShape::Radius(radius * unit),
+                            GeoShape::Box(width, height) => {
+                                GeoShape::Box(width * unit, height * unit)
+                            }
+                        };
+                    }
+                    None => unit_valid = false,
+                }
+            }
+            _ => return Geosearch::new_invalid(),
+        }
+
+        Geosearch {
+            key: key.clone(),
+            query,
+            unit_valid,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GEOSEARCH key <FROMMEMBER member | FROMLONLAT longitude latitude>
+    ///   <BYRADIUS radius <M | KM | FT | MI> | BYBOX width height <M | KM | FT | MI>>
+    ///   [ASC | DESC] [COUNT count [ANY]] [WITHCOORD] [WITHDIST] [WITHHASH]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Geosearch> {
+        let key = parse.next_bytes()?;
+
+        let mut args = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => args.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Geosearch::new_invalid()),
+            }
+        }
+
+        Ok(Geosearch::new(&key, &args))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Geosearch> {
+        if argv.is_empty() {
+            return Ok(Geosearch::new_invalid());
+        }
+        Ok(Geosearch::new(&argv[0], &argv[1..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.geosearch(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn geosearch(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if !self.unit_valid {
+            return Ok(resp_err(REDIS_GEO_UNSUPPORTED_UNIT_ERR));
+        }
+        if is_use_txn_api() {
+            GeoCommandCtx::new(txn)
+                .do_async_txnkv_geosearch(&self.key, &self.query)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Geosearch {
+    fn new_invalid() -> Geosearch {
+        Geosearch {
+            key: Bytes::new(),
+            query: GeoSearchQuery {
+                origin: GeoOrigin::Coord(0.0, 0.0),
+                shape: GeoShape::Radius(0.0),
+                unit: 1.0,
+                asc: None,
+                count: None,
+                any: false,
+                with_coord: false,
+                with_dist: false,
+                with_hash: false,
+            },
+            unit_valid: true,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
+
+/// Store the members found by GEOSEARCH in the destination key.
+#[derive(Debug, Clone)]
+pub struct Geosearchstore {
+    dest: Bytes,
+    search: Geosearch,
+    store_dist: bool,
+}
+
+impl Geosearchstore {
+    pub fn new(dest: &Bytes, src: &Bytes, args: &[Bytes]) -> Geosearchstore {
+        let mut store_dist = false;
+        let args: Vec<Bytes> = args
+            .iter()
+            .filter(|arg| {
+                let is_store_dist = String::from_utf8_lossy(arg).to_uppercase() == "STOREDIST";
+                store_dist |= is_store_dist;
+                !is_store_dist
+            })
+            .cloned()
+            .collect();
+        let search = Geosearch::new(src, &args);
+        let query = &search.query;
+        // the stored members can't carry the extra information
+        if query.with_coord || query.with_dist || query.with_hash {
+            return Geosearchstore::new_invalid();
+        }
+        Geosearchstore {
+            dest: dest.clone(),
+            search,
+            store_dist,
+        }
+    }
+
+    pub fn keys(&self) -> Vec<Bytes> {
+        vec![self.dest.clone(), self.search.key.clone()]
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GEOSEARCHSTORE destination source <FROMMEMBER member | FROMLONLAT longitude latitude>
+    ///   <BYRADIUS radius <M | KM | FT | MI> | BYBOX width height <M | KM | FT | MI>>
+    ///   [ASC | DESC] [COUNT count [ANY]] [STOREDIST]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Geosearchstore> {
+        let dest = parse.next_bytes()?;
+        let src = parse.next_bytes()?;
+
+        let mut args = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => args.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Geosearchstore::new_invalid()),
+            }
+        }
+
+        Ok(Geosearchstore::new(&dest, &src, &args))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Geosearchstore> {
+        if argv.len() < 2 {
+            return Ok(Geosearchstore::new_invalid());
+        }
+        Ok(Geosearchstore::new(&argv[0], &argv[1], &argv[2..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.geosearchstore(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn geosearchstore(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.search.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if !self.search.unit_valid {
+            return Ok(resp_err(REDIS_GEO_UNSUPPORTED_UNIT_ERR));
+        }
+        if is_use_txn_api() {
+            GeoCommandCtx::new(txn)
+                .do_async_txnkv_geosearchstore(
+                    &self.dest,
+                    &self.search.key,
+                    &self.search.query,
+                    self.store_dist,
+                )
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Geosearchstore {
+    fn new_invalid() -> Geosearchstore {
+        Geosearchstore {
+            dest: Bytes::new(),
+            search: Geosearch::new_invalid(),
+            store_dist: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.search.valid
+    }
+}
@@ -22,24 +22,29 @@ use super::Invalid;
 #[derive(Debug, Clone)]
 pub struct Get {
     /// Name of the key to get
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Get {
     /// Create a new `Get` command which fetches `key`.
-    pub fn new(key: impl ToString) -> Get {
+    pub fn new(key: &Bytes) -> Get {
         Get {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
+    /// The key to read when the command is grouped with other pipelined reads
+    pub(crate) fn batch_keys(&self) -> Option<&[Bytes]> {
+        self.valid.then_some(std::slice::from_ref(&self.key))
+    }
+
     /// Parse a `Get` instance from a received frame.
     ///
     /// The `Parse` argument provides a cursor-like API to read fields from the
@@ -64,7 +69,7 @@ impl Get {
         // The `GET` string has already been consumed. The next value is the
         // name of the key to get. If the next value is not a string or the
         // input is fully consumed, then an error is returned.
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         Ok(Get { key, valid: true })
     }
@@ -73,7 +78,7 @@ impl Get {
         if argv.len() != 1 {
             return Ok(Get::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         Ok(Get::new(key))
     }
 
@@ -118,8 +123,12 @@ impl Get {
 impl Invalid for Get {
     fn new_invalid() -> Get {
         Get {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
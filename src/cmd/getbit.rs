@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use crate::cmd::setbit::parse_bit_offset;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::bitmap::BitmapCommandCtx;
+use crate::tikv::errors::{
+    AsyncResult, REDIS_BIT_OFFSET_OUT_OF_RANGE_ERR, REDIS_NOT_SUPPORTED_ERR,
+};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the bit value at offset in the string value stored at key.
+#[derive(Debug, Clone)]
+pub struct Getbit {
+    key: Bytes,
+    offset: Option<u64>,
+    valid: bool,
+}
+
+impl Getbit {
+    pub fn new(key: &Bytes, offset: &[u8]) -> Getbit {
+        Getbit {
+            key: key.clone(),
+            offset: parse_bit_offset(offset),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GETBIT key offset
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Getbit> {
+        let key = parse.next_bytes()?;
+        let offset = parse.next_bytes()?;
+        Ok(Getbit::new(&key, &offset))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Getbit> {
+        if argv.len() != 2 {
+            return Ok(Getbit::new_invalid());
+        }
+        Ok(Getbit::new(&argv[0], &argv[1]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.getbit(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getbit(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let offset = match self.offset {
+            Some(offset) => offset,
+            None => return Ok(resp_err(REDIS_BIT_OFFSET_OUT_OF_RANGE_ERR)),
+        };
+        if is_use_txn_api() {
+            BitmapCommandCtx::new(txn)
+                .do_async_txnkv_getbit(&self.key, offset)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Getbit {
+    fn new_invalid() -> Getbit {
+        Getbit {
+            key: Bytes::new(),
+            offset: None,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get the value of key and delete the key.
+#[derive(Debug, Clone)]
+pub struct Getdel {
+    key: Bytes,
+    valid: bool,
+}
+
+impl Getdel {
+    pub fn new(key: &Bytes) -> Getdel {
+        Getdel {
+            key: key.clone(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Getdel> {
+        let key = parse.next_bytes()?;
+        Ok(Getdel::new(&key))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Getdel> {
+        if argv.len() != 1 {
+            return Ok(Getdel::new_invalid());
+        }
+        Ok(Getdel::new(&argv[0]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.getdel(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getdel(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_getdel(&self.key)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Getdel {
+    fn new_invalid() -> Getdel {
+        Getdel {
+            key: Bytes::new(),
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
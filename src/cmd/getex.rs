@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments, timestamp_from_ttl};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get the value of key and optionally set its expiration.
+#[derive(Debug, Clone)]
+pub struct Getex {
+    key: Bytes,
+    /// relative expire time in milliseconds
+    expire: Option<i64>,
+    /// Unix time in milliseconds to expire the key at
+    expire_at: Option<i64>,
+    persist: bool,
+    valid: bool,
+}
+
+impl Getex {
+    pub fn new(key: &Bytes) -> Getex {
+        Getex {
+            key: key.clone(),
+            expire: None,
+            expire_at: None,
+            persist: false,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GETEX key [EX seconds|PX milliseconds|EXAT timestamp|PXAT timestamp|PERSIST]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Getex> {
+        let key = parse.next_bytes()?;
+
+        let mut options = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => options.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Getex::new_invalid()),
+            }
+        }
+
+        Ok(Getex::new(&key).with_options(&options))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Getex> {
+        if argv.is_empty() {
+            return Ok(Getex::new_invalid());
+        }
+        Ok(Getex::new(&argv[0]).with_options(&argv[1..]))
+    }
+
+    fn with_options(mut self, options: &[Bytes]) -> Getex {
+        match options {
+            [] => {}
+            [option] if String::from_utf8_lossy(option).to_uppercase() == "PERSIST" => {
+                self.persist = true;
+            }
+            [option, value] => {
+                let value = match String::from_utf8_lossy(value).parse::<i64>() {
+                    Ok(v) if v > 0 => v,
+                    _ => return Getex::new_invalid(),
+                };
+                match String::from_utf8_lossy(option).to_uppercase().as_str() {
+                    "EX" => self.expire = value.checked_mul(1000),
+                    "PX" => self.expire = Some(value),
+                    "EXAT" => self.expire_at = value.checked_mul(1000),
+                    "PXAT" => self.expire_at = Some(value),
+                    _ => return Getex::new_invalid(),
+                }
+                if self.expire.is_none() && self.expire_at.is_none() {
+                    return Getex::new_invalid();
+                }
+            }
+            _ => return Getex::new_invalid(),
+        }
+        self
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.getex(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getex(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let timestamp = match (self.expire, self.expire_at) {
+            _ if self.persist => Some(0),
+            (Some(ttl), _) => Some(timestamp_from_ttl(ttl as u64)),
+            (_, Some(ts)) => Some(ts as u64),
+            _ => None,
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_getex(&self.key, timestamp)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Getex {
+    fn new_invalid() -> Getex {
+        Getex {
+            key: Bytes::new(),
+            expire: None,
+            expire_at: None,
+            persist: false,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
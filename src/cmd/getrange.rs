@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the substring of the string value stored at key, also used by SUBSTR.
+#[derive(Debug, Clone)]
+pub struct Getrange {
+    key: Bytes,
+    start: i64,
+    end: i64,
+    valid: bool,
+}
+
+impl Getrange {
+    pub fn new(key: &Bytes, start: i64, end: i64) -> Getrange {
+        Getrange {
+            key: key.clone(),
+            start,
+            end,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GETRANGE key start end
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Getrange> {
+        let key = parse.next_bytes()?;
+        let start = parse.next_int()?;
+        let end = parse.next_int()?;
+        Ok(Getrange::new(&key, start, end))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Getrange> {
+        if argv.len() != 3 {
+            return Ok(Getrange::new_invalid());
+        }
+        let start = String::from_utf8_lossy(&argv[1]).parse::<i64>();
+        let end = String::from_utf8_lossy(&argv[2]).parse::<i64>();
+        match (start, end) {
+            (Ok(start), Ok(end)) => Ok(Getrange::new(&argv[0], start, end)),
+            _ => Ok(Getrange::new_invalid()),
+        }
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.getrange(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getrange(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_getrange(&self.key, self.start, self.end)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Getrange {
+    fn new_invalid() -> Getrange {
+        Getrange {
+            key: Bytes::new(),
+            start: 0,
+            end: 0,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -15,32 +15,32 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Hdel {
-    key: String,
-    fields: Vec<String>,
+    key: Bytes,
+    fields: Vec<Bytes>,
     valid: bool,
 }
 
 impl Hdel {
-    pub fn new(key: &str) -> Hdel {
+    pub fn new(key: &Bytes) -> Hdel {
         Hdel {
             fields: vec![],
-            key: key.to_owned(),
+            key: key.clone(),
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn add_field(&mut self, field: &str) {
-        self.fields.push(field.to_owned());
+    pub fn add_field(&mut self, field: &Bytes) {
+        self.fields.push(field.clone());
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hdel> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut hdel = Hdel::new(&key);
-        while let Ok(f) = parse.next_string() {
+        while let Ok(f) = parse.next_bytes() {
             hdel.add_field(&f);
         }
         Ok(hdel)
@@ -50,9 +50,9 @@ impl Hdel {
         if argv.len() < 2 {
             return Ok(Hdel::new_invalid());
         }
-        let mut hdel = Hdel::new(&String::from_utf8_lossy(&argv[0]));
+        let mut hdel = Hdel::new(&argv[0]);
         for arg in &argv[1..] {
-            hdel.add_field(&String::from_utf8_lossy(arg));
+            hdel.add_field(arg);
         }
         Ok(hdel)
     }
@@ -89,8 +89,12 @@ impl Invalid for Hdel {
     fn new_invalid() -> Hdel {
         Hdel {
             fields: vec![],
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -0,0 +1,99 @@
+use crate::cmd::Invalid;
+use crate::{Parse, ParseError};
+
+/// Switch to a different protocol, optionally authenticating and setting the
+/// connection's name, or provide a contextual client report.
+///
+/// HELLO is handled by the connection handler directly, as it changes the
+/// connection state, like AUTH does.
+#[derive(Debug, Clone)]
+pub struct Hello {
+    protover: Option<i64>,
+    auth: Option<(String, String)>,
+    setname: Option<String>,
+    valid: bool,
+}
+
+impl Hello {
+    pub fn new(
+        protover: Option<i64>,
+        auth: Option<(String, String)>,
+        setname: Option<String>,
+    ) -> Hello {
+        Hello {
+            protover,
+            auth,
+            setname,
+            valid: true,
+        }
+    }
+
+    pub fn protover(&self) -> Option<i64> {
+        self.protover
+    }
+
+    /// Returns the `(username, password)` pair of the AUTH option
+    pub fn auth(&self) -> Option<&(String, String)> {
+        self.auth.as_ref()
+    }
+
+    pub fn setname(&self) -> Option<&str> {
+        self.setname.as_deref()
+    }
+
+    pub fn valid(&self) -> bool {
+        self.valid
+    }
+
+    /// Parse a `Hello` instance from a received frame.
+    ///
+    /// # Format
+    ///
+    /// ```text
+    /// HELLO [protover [AUTH username password] [SETNAME clientname]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hello> {
+        let protover = match parse.next_int() {
+            Ok(v) => v,
+            Err(ParseError::EndOfStream) => return Ok(Hello::new(None, None, None)),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut auth = None;
+        let mut setname = None;
+        loop {
+            match parse.next_string() {
+                Ok(opt) => match opt.to_uppercase().as_str() {
+                    "AUTH" => {
+                        let username = parse.next_string()?;
+                        let password = parse.next_string()?;
+                        auth = Some((username, password));
+                    }
+                    "SETNAME" => {
+                        setname = Some(parse.next_string()?);
+                    }
+                    _ => return Ok(Hello::new_invalid()),
+                },
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(Hello::new(Some(protover), auth, setname))
+    }
+}
+
+impl Invalid for Hello {
+    fn new_invalid() -> Hello {
+        Hello {
+            protover: None,
+            auth: None,
+            setname: None,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -15,31 +15,31 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Hexists {
-    key: String,
-    field: String,
+    key: Bytes,
+    field: Bytes,
     valid: bool,
 }
 
 impl Hexists {
-    pub fn new(key: &str, field: &str) -> Hexists {
+    pub fn new(key: &Bytes, field: &Bytes) -> Hexists {
         Hexists {
-            field: field.to_owned(),
-            key: key.to_owned(),
+            field: field.clone(),
+            key: key.clone(),
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn field(&self) -> &str {
+    pub fn field(&self) -> &Bytes {
         &self.field
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hexists> {
-        let key = parse.next_string()?;
-        let field = parse.next_string()?;
+        let key = parse.next_bytes()?;
+        let field = parse.next_bytes()?;
         Ok(Hexists::new(&key, &field))
     }
 
@@ -47,10 +47,7 @@ impl Hexists {
         if argv.len() != 2 {
             return Ok(Hexists::new_invalid());
         }
-        Ok(Hexists::new(
-            &String::from_utf8_lossy(&argv[0]),
-            &String::from_utf8_lossy(&argv[1]),
-        ))
+        Ok(Hexists::new(&argv[0], &argv[1]))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -84,9 +81,13 @@ impl Hexists {
 impl Invalid for Hexists {
     fn new_invalid() -> Hexists {
         Hexists {
-            field: "".to_owned(),
-            key: "".to_owned(),
+            field: Bytes::new(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -14,31 +14,31 @@ use tikv_client::Transaction;
 use tokio::sync::Mutex;
 #[derive(Debug, Clone)]
 pub struct Hget {
-    key: String,
-    field: String,
+    key: Bytes,
+    field: Bytes,
     valid: bool,
 }
 
 impl Hget {
-    pub fn new(key: &str, field: &str) -> Hget {
+    pub fn new(key: &Bytes, field: &Bytes) -> Hget {
         Hget {
-            field: field.to_owned(),
-            key: key.to_owned(),
+            field: field.clone(),
+            key: key.clone(),
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn field(&self) -> &str {
+    pub fn field(&self) -> &Bytes {
         &self.field
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hget> {
-        let key = parse.next_string()?;
-        let field = parse.next_string()?;
+        let key = parse.next_bytes()?;
+        let field = parse.next_bytes()?;
         Ok(Hget::new(&key, &field))
     }
 
@@ -46,10 +46,7 @@ impl Hget {
         if argv.len() != 2 {
             return Ok(Hget::new_invalid());
         }
-        Ok(Hget::new(
-            &String::from_utf8_lossy(&argv[0]),
-            &String::from_utf8_lossy(&argv[1]),
-        ))
+        Ok(Hget::new(&argv[0], &argv[1]))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -83,9 +80,13 @@ impl Hget {
 impl Invalid for Hget {
     fn new_invalid() -> Hget {
         Hget {
-            field: "".to_owned(),
-            key: "".to_owned(),
+            field: Bytes::new(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,29 +15,29 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Hgetall {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Hgetall {
-    pub fn new(key: &str) -> Hgetall {
+    pub fn new(key: &Bytes) -> Hgetall {
         Hgetall {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hgetall> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         Ok(Hgetall { key, valid: true })
     }
 
@@ -45,7 +45,7 @@ impl Hgetall {
         if argv.len() != 1 {
             return Ok(Hgetall::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         Ok(Hgetall::new(key))
     }
 
@@ -71,6 +71,7 @@ impl Hgetall {
             HashCommandCtx::new(txn)
                 .do_async_txnkv_hgetall(&self.key, true, true)
                 .await
+                .map(Frame::into_map)
         } else {
             Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
         }
@@ -80,8 +81,12 @@ impl Hgetall {
 impl Invalid for Hgetall {
     fn new_invalid() -> Hgetall {
         Hgetall {
-            key: "".to_string(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
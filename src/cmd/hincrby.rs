@@ -15,42 +15,42 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Hincrby {
-    key: String,
-    field: String,
+    key: Bytes,
+    field: Bytes,
     step: i64,
     valid: bool,
 }
 
 impl Hincrby {
-    pub fn new(key: &str, field: &str, step: i64) -> Hincrby {
+    pub fn new(key: &Bytes, field: &Bytes, step: i64) -> Hincrby {
         Hincrby {
-            key: key.to_string(),
-            field: field.to_string(),
+            key: key.clone(),
+            field: field.clone(),
             step,
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn field(&self) -> &str {
+    pub fn field(&self) -> &Bytes {
         &self.field
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
-    pub fn set_field(&mut self, field: &str) {
-        self.field = field.to_owned();
+    pub fn set_field(&mut self, field: &Bytes) {
+        self.field = field.clone();
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hincrby> {
-        let key = parse.next_string()?;
-        let field = parse.next_string()?;
+        let key = parse.next_bytes()?;
+        let field = parse.next_bytes()?;
         let step = parse.next_int()?;
         Ok(Hincrby {
             key,
@@ -64,8 +64,8 @@ impl Hincrby {
         if argv.len() != 3 {
             return Ok(Hincrby::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
-        let field = &String::from_utf8_lossy(&argv[1]);
+        let key = &argv[0];
+        let field = &argv[1];
         let step = String::from_utf8_lossy(&argv[2]).parse::<i64>();
         match step {
             Ok(v) => Ok(Hincrby::new(key, field, v)),
@@ -104,10 +104,14 @@ impl Hincrby {
 impl Invalid for Hincrby {
     fn new_invalid() -> Hincrby {
         Hincrby {
-            key: "".to_string(),
-            field: "".to_string(),
+            key: Bytes::new(),
+            field: Bytes::new(),
             step: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
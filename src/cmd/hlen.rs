@@ -15,29 +15,29 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Hlen {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Hlen {
-    pub fn new(key: &str) -> Hlen {
+    pub fn new(key: &Bytes) -> Hlen {
         Hlen {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hlen> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         Ok(Hlen { key, valid: true })
     }
 
@@ -45,7 +45,7 @@ impl Hlen {
         if argv.len() != 1 {
             return Ok(Hlen::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         Ok(Hlen::new(key))
     }
 
@@ -80,8 +80,12 @@ impl Hlen {
 impl Invalid for Hlen {
     fn new_invalid() -> Hlen {
         Hlen {
-            key: "".to_string(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
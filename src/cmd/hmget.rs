@@ -15,36 +15,36 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Hmget {
-    key: String,
-    fields: Vec<String>,
+    key: Bytes,
+    fields: Vec<Bytes>,
     valid: bool,
 }
 
 impl Hmget {
-    pub fn new(key: &str) -> Hmget {
+    pub fn new(key: &Bytes) -> Hmget {
         Hmget {
-            key: key.to_owned(),
+            key: key.clone(),
             fields: vec![],
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn fields(&self) -> &Vec<String> {
+    pub fn fields(&self) -> &Vec<Bytes> {
         &self.fields
     }
 
-    pub fn add_field(&mut self, field: &str) {
-        self.fields.push(field.to_string());
+    pub fn add_field(&mut self, field: &Bytes) {
+        self.fields.push(field.clone());
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hmget> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut hmget = Hmget::new(&key);
-        while let Ok(field) = parse.next_string() {
+        while let Ok(field) = parse.next_bytes() {
             hmget.add_field(&field);
         }
         Ok(hmget)
@@ -54,10 +54,10 @@ impl Hmget {
         if argv.len() < 2 {
             return Ok(Hmget::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let mut hmget = Hmget::new(key);
         for arg in &argv[1..argv.len()] {
-            hmget.add_field(&String::from_utf8_lossy(arg));
+            hmget.add_field(arg);
         }
         Ok(hmget)
     }
@@ -93,9 +93,13 @@ impl Hmget {
 impl Invalid for Hmget {
     fn new_invalid() -> Hmget {
         Hmget {
-            key: "".to_owned(),
+            key: Bytes::new(),
             fields: vec![],
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,19 +15,19 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Hset {
-    key: String,
+    key: Bytes,
     field_and_value: Vec<KvPair>,
     valid: bool,
 }
 
 impl Hset {
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
     /// Get the field and value pairs
@@ -42,12 +42,12 @@ impl Hset {
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hset> {
         let mut hset = Hset::default();
 
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         hset.set_key(&key);
 
-        while let Ok(field) = parse.next_string() {
+        while let Ok(field) = parse.next_bytes() {
             if let Ok(value) = parse.next_bytes() {
-                let kv = KvPair::new(field, value.to_vec());
+                let kv = KvPair::new(field.to_vec(), value.to_vec());
                 hset.add_field_value(kv);
             } else {
                 return Err("protocol error".into());
@@ -60,14 +60,14 @@ impl Hset {
         if argv.len() % 2 != 1 {
             return Ok(Hset::new_invalid());
         }
-        let key = String::from_utf8_lossy(&argv[0]).to_string();
+        let key = argv[0].clone();
         let mut hset = Hset::default();
         hset.set_key(&key);
 
         for idx in (1..argv.len()).step_by(2) {
-            let field = String::from_utf8_lossy(&argv[idx]);
+            let field = argv[idx].to_vec();
             let value = argv[idx + 1].clone();
-            let kv = KvPair::new(field.to_string(), value);
+            let kv = KvPair::new(field, value);
             hset.add_field_value(kv);
         }
         Ok(hset)
@@ -116,7 +116,7 @@ impl Default for Hset {
     fn default() -> Self {
         Hset {
             field_and_value: vec![],
-            key: String::new(),
+            key: Bytes::new(),
             valid: true,
         }
     }
@@ -126,8 +126,12 @@ impl Invalid for Hset {
     fn new_invalid() -> Hset {
         Hset {
             field_and_value: vec![],
-            key: String::new(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,39 +15,39 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Hstrlen {
-    key: String,
-    field: String,
+    key: Bytes,
+    field: Bytes,
     valid: bool,
 }
 
 impl Hstrlen {
-    pub fn new(key: &str, field: &str) -> Hstrlen {
+    pub fn new(key: &Bytes, field: &Bytes) -> Hstrlen {
         Hstrlen {
-            field: field.to_owned(),
-            key: key.to_owned(),
+            field: field.clone(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     pub fn new_invalid() -> Hstrlen {
         Hstrlen {
-            field: "".to_owned(),
-            key: "".to_owned(),
+            field: Bytes::new(),
+            key: Bytes::new(),
             valid: false,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn field(&self) -> &str {
+    pub fn field(&self) -> &Bytes {
         &self.field
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hstrlen> {
-        let key = parse.next_string()?;
-        let field = parse.next_string()?;
+        let key = parse.next_bytes()?;
+        let field = parse.next_bytes()?;
         Ok(Hstrlen::new(&key, &field))
     }
 
@@ -55,10 +55,7 @@ impl Hstrlen {
         if argv.len() != 2 {
             return Ok(Hstrlen::new_invalid());
         }
-        Ok(Hstrlen::new(
-            &String::from_utf8_lossy(&argv[0]),
-            &String::from_utf8_lossy(&argv[1]),
-        ))
+        Ok(Hstrlen::new(&argv[0], &argv[1]))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -92,9 +89,13 @@ impl Hstrlen {
 impl Invalid for Hstrlen {
     fn new_invalid() -> Hstrlen {
         Hstrlen {
-            field: "".to_owned(),
-            key: "".to_owned(),
+            field: Bytes::new(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Increment the floating point number stored at key by the specified increment.
+#[derive(Debug, Clone)]
+pub struct IncrByFloat {
+    key: Bytes,
+    step: f64,
+    valid: bool,
+}
+
+impl IncrByFloat {
+    pub fn new(key: &Bytes, step: f64) -> IncrByFloat {
+        IncrByFloat {
+            key: key.clone(),
+            step,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// INCRBYFLOAT key increment
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<IncrByFloat> {
+        let key = parse.next_bytes()?;
+        let step = parse.next_string()?.parse::<f64>()?;
+        Ok(IncrByFloat::new(&key, step))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<IncrByFloat> {
+        if argv.len() != 2 {
+            return Ok(IncrByFloat::new_invalid());
+        }
+        match String::from_utf8_lossy(&argv[1]).parse::<f64>() {
+            Ok(step) => Ok(IncrByFloat::new(&argv[0], step)),
+            Err(_) => Ok(IncrByFloat::new_invalid()),
+        }
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.incr_by_float(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn incr_by_float(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid || self.step.is_nan() {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_incr_float(&self.key, self.step)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for IncrByFloat {
+    fn new_invalid() -> IncrByFloat {
+        IncrByFloat {
+            key: Bytes::new(),
+            step: 0f64,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -15,26 +15,26 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct IncrDecr {
-    key: String,
+    key: Bytes,
     step: i64,
     valid: bool,
 }
 
 impl IncrDecr {
-    pub fn new(key: impl ToString, step: i64) -> IncrDecr {
+    pub fn new(key: &Bytes, step: i64) -> IncrDecr {
         IncrDecr {
-            key: key.to_string(),
+            key: key.clone(),
             step,
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse, single_step: bool) -> crate::Result<IncrDecr> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let step = if single_step { 1 } else { parse.next_int()? };
         Ok(IncrDecr {
             key,
@@ -47,7 +47,7 @@ impl IncrDecr {
         if (single_step && argv.len() != 1) || (!single_step && argv.len() != 2) {
             return Ok(IncrDecr::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let step = if single_step {
             Ok(1)
         } else {
@@ -107,9 +107,13 @@ impl IncrDecr {
 impl Invalid for IncrDecr {
     fn new_invalid() -> IncrDecr {
         IncrDecr {
-            key: "".to_owned(),
+            key: Bytes::new(),
             step: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
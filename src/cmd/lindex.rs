@@ -15,26 +15,26 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Lindex {
-    key: String,
+    key: Bytes,
     idx: i64,
     valid: bool,
 }
 
 impl Lindex {
-    pub fn new(key: &str, idx: i64) -> Lindex {
+    pub fn new(key: &Bytes, idx: i64) -> Lindex {
         Lindex {
-            key: key.to_owned(),
+            key: key.clone(),
             idx,
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lindex> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let idx = parse.next_int()?;
 
         Ok(Lindex {
@@ -48,7 +48,7 @@ impl Lindex {
         if argv.len() != 2 {
             return Ok(Lindex::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let idx = match String::from_utf8_lossy(&argv[1]).parse::<i64>() {
             Ok(v) => v,
             Err(_) => return Ok(Lindex::new_invalid()),
@@ -87,9 +87,13 @@ impl Lindex {
 impl Invalid for Lindex {
     fn new_invalid() -> Lindex {
         Lindex {
-            key: "".to_owned(),
+            key: Bytes::new(),
             idx: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -17,7 +17,7 @@ use super::Invalid;
 
 #[derive(Debug, Clone)]
 pub struct Linsert {
-    key: String,
+    key: Bytes,
     before_pivot: bool,
     pivot: Bytes,
     element: Bytes,
@@ -25,9 +25,9 @@ pub struct Linsert {
 }
 
 impl Linsert {
-    pub fn new(key: &str, before_pivot: bool, pivot: Bytes, element: Bytes) -> Linsert {
+    pub fn new(key: &Bytes, before_pivot: bool, pivot: Bytes, element: Bytes) -> Linsert {
         Linsert {
-            key: key.to_owned(),
+            key: key.clone(),
             before_pivot,
             pivot,
             element,
@@ -36,7 +36,7 @@ impl Linsert {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Linsert> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let pos = parse.next_string()?;
         let before_pivot = match pos.to_lowercase().as_str() {
             "before" => true,
@@ -61,7 +61,7 @@ impl Linsert {
         if argv.len() != 4 {
             return Ok(Linsert::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let before_pivot = match String::from_utf8_lossy(&argv[1]).to_lowercase().as_str() {
             "before" => true,
             "after" => false,
@@ -106,11 +106,15 @@ impl Linsert {
 impl Invalid for Linsert {
     fn new_invalid() -> Linsert {
         Linsert {
-            key: "".to_owned(),
+            key: Bytes::new(),
             before_pivot: false,
             pivot: Bytes::new(),
             element: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
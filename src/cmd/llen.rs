@@ -15,24 +15,24 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Llen {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Llen {
-    pub fn new(key: &str) -> Llen {
+    pub fn new(key: &Bytes) -> Llen {
         Llen {
-            key: key.to_owned(),
+            key: key.clone(),
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Llen> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         Ok(Llen { key, valid: true })
     }
@@ -41,7 +41,7 @@ impl Llen {
         if argv.len() != 1 {
             return Ok(Llen::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         Ok(Llen::new(key))
     }
 
@@ -76,8 +76,12 @@ impl Llen {
 impl Invalid for Llen {
     fn new_invalid() -> Llen {
         Llen {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
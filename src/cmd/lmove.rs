@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use crate::cmd::blpop::{block_for_keys, parse_timeout};
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::list::ListCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments, resp_nil};
+use crate::{Connection, Db, Frame, Shutdown};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Pop an element from the source list and push it to the destination list
+/// atomically, in one transaction.
+///
+/// The blocking variant, with a timeout, blocks the connection until the
+/// source list is not empty.
+#[derive(Debug, Clone)]
+pub struct Lmove {
+    source: Bytes,
+    destination: Bytes,
+    from_left: bool,
+    to_left: bool,
+    timeout: Option<f64>,
+    valid: bool,
+}
+
+impl Lmove {
+    pub fn new(
+        source: Bytes,
+        destination: Bytes,
+        from_left: bool,
+        to_left: bool,
+        timeout: Option<f64>,
+    ) -> Lmove {
+        Lmove {
+            source,
+            destination,
+            from_left,
+            to_left,
+            timeout,
+            valid: true,
+        }
+    }
+
+    pub fn source(&self) -> &Bytes {
+        &self.source
+    }
+
+    pub fn destination(&self) -> &Bytes {
+        &self.destination
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// LMOVE source destination LEFT|RIGHT LEFT|RIGHT
+    /// BLMOVE source destination LEFT|RIGHT LEFT|RIGHT timeout
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse, blocking: bool) -> crate::Result<Lmove> {
+        let source = parse.next_bytes()?;
+        let destination = parse.next_bytes()?;
+        let from_left = parse_direction(&parse.next_string()?);
+        let to_left = parse_direction(&parse.next_string()?);
+
+        let mut timeout = None;
+        if blocking {
+            match parse_timeout(&parse.next_bytes()?) {
+                Some(t) => timeout = Some(t),
+                None => return Ok(Lmove::new_invalid()),
+            }
+        }
+
+        match (from_left, to_left) {
+            (Some(from_left), Some(to_left)) => {
+                Ok(Lmove::new(source, destination, from_left, to_left, timeout))
+            }
+            _ => Ok(Lmove::new_invalid()),
+        }
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// RPOPLPUSH source destination
+    /// ```
+    pub(crate) fn parse_rpoplpush_frames(parse: &mut Parse) -> crate::Result<Lmove> {
+        let source = parse.next_bytes()?;
+        let destination = parse.next_bytes()?;
+        Ok(Lmove::new(source, destination, false, true, None))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Lmove> {
+        if argv.len() != 4 {
+            return Ok(Lmove::new_invalid());
+        }
+        let from_left = parse_direction(&String::from_utf8_lossy(&argv[2]));
+        let to_left = parse_direction(&String::from_utf8_lossy(&argv[3]));
+        match (from_left, to_left) {
+            (Some(from_left), Some(to_left)) => Ok(Lmove::new(
+                argv[0].clone(),
+                argv[1].clone(),
+                from_left,
+                to_left,
+                None,
+            )),
+            _ => Ok(Lmove::new_invalid()),
+        }
+    }
+
+    pub(crate) fn parse_rpoplpush_argv(argv: &[Bytes]) -> crate::Result<Lmove> {
+        if argv.len() != 2 {
+            return Ok(Lmove::new_invalid());
+        }
+        Ok(Lmove::new(
+            argv[0].clone(),
+            argv[1].clone(),
+            false,
+            true,
+            None,
+        ))
+    }
+
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        shutdown: &mut Shutdown,
+    ) -> crate::Result<()> {
+        let response = match self.timeout {
+            Some(timeout) => {
+                let keys = [self.source.clone()];
+                block_for_keys(db, &keys, timeout, dst, shutdown, || self.try_move(None)).await?
+            }
+            None => self.try_move(None).await?,
+        };
+        let response = match response {
+            Some(response) => response,
+            // the connection is closed without reply
+            None if shutdown.is_shutdown() || dst.is_closed() => return Ok(()),
+            None => resp_nil(),
+        };
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Move without blocking, which is also what the blocking variant does
+    /// inside a transaction.
+    pub async fn lmove(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        Ok(self.try_move(txn).await?.unwrap_or_else(resp_nil))
+    }
+
+    async fn try_move(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Option<Frame>> {
+        if !self.valid {
+            return Ok(Some(resp_invalid_arguments()));
+        }
+        if !is_use_txn_api() {
+            return Ok(Some(resp_err(REDIS_NOT_SUPPORTED_ERR)));
+        }
+
+        match ListCommandCtx::new(txn)
+            .do_async_txnkv_lmove(
+                &self.source,
+                &self.destination,
+                self.from_left,
+                self.to_left,
+            )
+            .await
+        {
+            Ok(v) => Ok(v),
+            Err(e) => Ok(Some(resp_err(e))),
+        }
+    }
+}
+
+impl Invalid for Lmove {
+    fn new_invalid() -> Lmove {
+        Lmove {
+            source: Bytes::new(),
+            destination: Bytes::new(),
+            from_left: false,
+            to_left: false,
+            timeout: None,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
+
+/// `LEFT` or `RIGHT`, returns if it is the left side of the list.
+fn parse_direction(arg: &str) -> Option<bool> {
+    match arg.to_uppercase().as_str() {
+        "LEFT" => Some(true),
+        "RIGHT" => Some(false),
+        _ => None,
+    }
+}
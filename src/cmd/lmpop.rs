@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use crate::cmd::blpop::{block_for_keys, parse_timeout};
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::list::ListCommandCtx;
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_invalid_arguments, resp_nil_array};
+use crate::{Connection, Db, Frame, Shutdown};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Pop elements from the first non-empty list of the keys.
+///
+/// The blocking variant, with a timeout, blocks the connection until one of
+/// the lists is not empty.
+#[derive(Debug, Clone)]
+pub struct Lmpop {
+    keys: Vec<Bytes>,
+    op_left: bool,
+    count: i64,
+    timeout: Option<f64>,
+    valid: bool,
+}
+
+impl Lmpop {
+    pub fn new(keys: Vec<Bytes>, op_left: bool, count: i64, timeout: Option<f64>) -> Lmpop {
+        Lmpop {
+            keys,
+            op_left,
+            count,
+            timeout,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// LMPOP numkeys key [key ...] LEFT|RIGHT [COUNT count]
+    /// BLMPOP timeout numkeys key [key ...] LEFT|RIGHT [COUNT count]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse, blocking: bool) -> crate::Result<Lmpop> {
+        let mut timeout = None;
+        if blocking {
+            match parse_timeout(&parse.next_bytes()?) {
+                Some(t) => timeout = Some(t),
+                None => return Ok(Lmpop::new_invalid()),
+            }
+        }
+
+        let numkeys = match parse.next_int() {
+            Ok(n) if n > 0 => n,
+            _ => return Ok(Lmpop::new_invalid()),
+        };
+        let mut keys = Vec::with_capacity(numkeys as usize);
+        for _ in 0..numkeys {
+            keys.push(parse.next_bytes()?);
+        }
+
+        let op_left = match parse.next_string()?.to_uppercase().as_str() {
+            "LEFT" => true,
+            "RIGHT" => false,
+            _ => return Ok(Lmpop::new_invalid()),
+        };
+
+        let mut count = 1;
+        if let Ok(arg) = parse.next_string() {
+            match (arg.to_uppercase().as_str(), parse.next_int()) {
+                ("COUNT", Ok(n)) if n > 0 => count = n,
+                _ => return Ok(Lmpop::new_invalid()),
+            }
+        }
+
+        Ok(Lmpop::new(keys, op_left, count, timeout))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Lmpop> {
+        let numkeys = match argv
+            .first()
+            .map(|n| String::from_utf8_lossy(n).parse::<usize>())
+        {
+            Some(Ok(n)) if n > 0 && argv.len() >= n + 2 => n,
+            _ => return Ok(Lmpop::new_invalid()),
+        };
+        let keys = argv[1..=numkeys].to_vec();
+
+        let op_left = match String::from_utf8_lossy(&argv[numkeys + 1])
+            .to_uppercase()
+            .as_str()
+        {
+            "LEFT" => true,
+            "RIGHT" => false,
+            _ => return Ok(Lmpop::new_invalid()),
+        };
+
+        let mut count = 1;
+        match &argv[numkeys + 2..] {
+            [] => {}
+            [arg, n] if arg.eq_ignore_ascii_case(b"COUNT") => {
+                match String::from_utf8_lossy(n).parse::<i64>() {
+                    Ok(n) if n > 0 => count = n,
+                    _ => return Ok(Lmpop::new_invalid()),
+                }
+            }
+            _ => return Ok(Lmpop::new_invalid()),
+        }
+
+        Ok(Lmpop::new(keys, op_left, count, None))
+    }
+
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        shutdown: &mut Shutdown,
+    ) -> crate::Result<()> {
+        let response = match self.timeout {
+            Some(timeout) => {
+                block_for_keys(db, &self.keys, timeout, dst, shutdown, || {
+                    self.try_pop(None)
+                })
+                .await?
+            }
+            None => self.try_pop(None).await?,
+        };
+        let response = match response {
+            Some(response) => response,
+            // the connection is closed without reply
+            None if shutdown.is_shutdown() || dst.is_closed() => return Ok(()),
+            None => resp_nil_array(),
+        };
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Pop without blocking, which is also what the blocking variant does
+    /// inside a transaction.
+    pub async fn lmpop(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        Ok(self.try_pop(txn).await?.unwrap_or_else(resp_nil_array))
+    }
+
+    async fn try_pop(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Option<Frame>> {
+        if !self.valid {
+            return Ok(Some(resp_invalid_arguments()));
+        }
+        if !is_use_txn_api() {
+            return Ok(Some(resp_err(REDIS_NOT_SUPPORTED_ERR)));
+        }
+
+        match ListCommandCtx::new(txn)
+            .do_async_txnkv_mpop(&self.keys, self.op_left, self.count)
+            .await
+        {
+            Ok(Some((key, values))) => Ok(Some(resp_array(vec![
+                resp_bulk(key.to_vec()),
+                resp_array(values),
+            ]))),
+            Ok(None) => Ok(None),
+            Err(e) => Ok(Some(resp_err(e))),
+        }
+    }
+}
+
+impl Invalid for Lmpop {
+    fn new_invalid() -> Lmpop {
+        Lmpop {
+            keys: vec![],
+            op_left: false,
+            count: 0,
+            timeout: None,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
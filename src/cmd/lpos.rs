@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::list::ListCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the positions of the element in the list.
+#[derive(Debug, Clone)]
+pub struct Lpos {
+    key: Bytes,
+    element: Bytes,
+    rank: i64,
+    count: Option<i64>,
+    maxlen: i64,
+    valid: bool,
+}
+
+impl Lpos {
+    pub fn new(key: &Bytes, element: &Bytes) -> Lpos {
+        Lpos {
+            key: key.clone(),
+            element: element.clone(),
+            rank: 1,
+            count: None,
+            maxlen: 0,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// LPOS key element [RANK rank] [COUNT num-matches] [MAXLEN len]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lpos> {
+        let key = parse.next_bytes()?;
+        let element = parse.next_bytes()?;
+
+        let mut options = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => options.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Lpos::new_invalid()),
+            }
+        }
+
+        Ok(Lpos::new(&key, &element).with_options(&options))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Lpos> {
+        if argv.len() < 2 {
+            return Ok(Lpos::new_invalid());
+        }
+        Ok(Lpos::new(&argv[0], &argv[1]).with_options(&argv[2..]))
+    }
+
+    fn with_options(mut self, options: &[Bytes]) -> Lpos {
+        for option in options.chunks(2) {
+            let (name, value) = match option {
+                [name, value] => (name, String::from_utf8_lossy(value).parse::<i64>()),
+                _ => return Lpos::new_invalid(),
+            };
+            let value = match value {
+                Ok(v) => v,
+                Err(_) => return Lpos::new_invalid(),
+            };
+            match String::from_utf8_lossy(name).to_uppercase().as_str() {
+                // zero rank is meaningless, and negative rank scans from the tail
+                "RANK" if value != 0 && value != i64::MIN => self.rank = value,
+                "COUNT" if value >= 0 => self.count = Some(value),
+                "MAXLEN" if value >= 0 => self.maxlen = value,
+                _ => return Lpos::new_invalid(),
+            }
+        }
+        self
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.lpos(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn lpos(self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            ListCommandCtx::new(txn)
+                .do_async_txnkv_lpos(
+                    &self.key,
+                    &self.element,
+                    self.rank,
+                    self.count.map(|c| c as u64),
+                    self.maxlen as u64,
+                )
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Lpos {
+    fn new_invalid() -> Lpos {
+        Lpos {
+            key: Bytes::new(),
+            element: Bytes::new(),
+            rank: 1,
+            count: None,
+            maxlen: 0,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -15,28 +15,28 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Lrange {
-    key: String,
+    key: Bytes,
     left: i64,
     right: i64,
     valid: bool,
 }
 
 impl Lrange {
-    pub fn new(key: &str, left: i64, right: i64) -> Lrange {
+    pub fn new(key: &Bytes, left: i64, right: i64) -> Lrange {
         Lrange {
-            key: key.to_owned(),
+            key: key.clone(),
             left,
             right,
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lrange> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let left = parse.next_int()?;
         let right = parse.next_int()?;
 
@@ -52,7 +52,7 @@ impl Lrange {
         if argv.len() != 3 {
             return Ok(Lrange::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let left = match String::from_utf8_lossy(&argv[1]).parse::<i64>() {
             Ok(v) => v,
             Err(_) => return Ok(Lrange::new_invalid()),
@@ -96,10 +96,14 @@ impl Lrange {
 impl Invalid for Lrange {
     fn new_invalid() -> Lrange {
         Lrange {
-            key: "".to_owned(),
+            key: Bytes::new(),
             left: 0,
             right: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
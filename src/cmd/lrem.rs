@@ -17,16 +17,16 @@ use super::Invalid;
 
 #[derive(Debug, Clone)]
 pub struct Lrem {
-    key: String,
+    key: Bytes,
     count: i64,
     element: Bytes,
     valid: bool,
 }
 
 impl Lrem {
-    pub fn new(key: &str, count: i64, element: Bytes) -> Lrem {
+    pub fn new(key: &Bytes, count: i64, element: Bytes) -> Lrem {
         Lrem {
-            key: key.to_owned(),
+            key: key.clone(),
             count,
             element,
             valid: true,
@@ -34,7 +34,7 @@ impl Lrem {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lrem> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let count = parse.next_int()?;
         let element = parse.next_bytes()?;
 
@@ -50,7 +50,7 @@ impl Lrem {
         if argv.len() != 3 {
             return Ok(Lrem::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let count = String::from_utf8_lossy(&argv[1]).parse::<i64>()?;
 
         let element = argv[2].clone();
@@ -94,10 +94,14 @@ impl Lrem {
 impl Invalid for Lrem {
     fn new_invalid() -> Lrem {
         Lrem {
-            key: "".to_owned(),
+            key: Bytes::new(),
             count: 0,
             element: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
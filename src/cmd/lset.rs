@@ -15,28 +15,28 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Lset {
-    key: String,
+    key: Bytes,
     idx: i64,
     element: Bytes,
     valid: bool,
 }
 
 impl Lset {
-    pub fn new(key: &str, idx: i64, ele: Bytes) -> Lset {
+    pub fn new(key: &Bytes, idx: i64, ele: Bytes) -> Lset {
         Lset {
-            key: key.to_owned(),
+            key: key.clone(),
             idx,
             element: ele,
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lset> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let idx = parse.next_int()?;
         let element = parse.next_bytes()?;
 
@@ -52,7 +52,7 @@ impl Lset {
         if argv.len() != 3 {
             return Ok(Lset::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let idx = match String::from_utf8_lossy(&argv[1]).parse::<i64>() {
             Ok(v) => v,
             Err(_) => return Ok(Lset::new_invalid()),
@@ -92,10 +92,14 @@ impl Lset {
 impl Invalid for Lset {
     fn new_invalid() -> Lset {
         Lset {
-            key: "".to_owned(),
+            key: Bytes::new(),
             idx: 0,
             element: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,28 +15,28 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Ltrim {
-    key: String,
+    key: Bytes,
     start: i64,
     end: i64,
     valid: bool,
 }
 
 impl Ltrim {
-    pub fn new(key: &str, start: i64, end: i64) -> Ltrim {
+    pub fn new(key: &Bytes, start: i64, end: i64) -> Ltrim {
         Ltrim {
-            key: key.to_owned(),
+            key: key.clone(),
             start,
             end,
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Ltrim> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let start = parse.next_int()?;
         let end = parse.next_int()?;
 
@@ -52,7 +52,7 @@ impl Ltrim {
         if argv.len() != 3 {
             return Ok(Ltrim::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let start = match String::from_utf8_lossy(&argv[1]).parse::<i64>() {
             Ok(v) => v,
             Err(_) => return Ok(Ltrim::new_invalid()),
@@ -96,10 +96,14 @@ impl Ltrim {
 impl Invalid for Ltrim {
     fn new_invalid() -> Ltrim {
         Ltrim {
-            key: "".to_owned(),
+            key: Bytes::new(),
             start: 0,
             end: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
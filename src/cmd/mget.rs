@@ -20,27 +20,32 @@ use tokio::sync::Mutex;
 #[derive(Debug, Clone)]
 pub struct Mget {
     /// Name of the keys to get
-    keys: Vec<String>,
+    keys: Vec<Bytes>,
     valid: bool,
 }
 
 impl Mget {
     /// Get the keys
-    pub fn keys(&self) -> &Vec<String> {
+    pub fn keys(&self) -> &Vec<Bytes> {
         &self.keys
     }
 
-    pub fn add_key(&mut self, key: String) {
+    pub fn add_key(&mut self, key: Bytes) {
         self.keys.push(key);
     }
 
+    /// The keys to read when the command is grouped with other pipelined reads
+    pub(crate) fn batch_keys(&self) -> Option<&[Bytes]> {
+        self.valid.then_some(self.keys.as_slice())
+    }
+
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Mget> {
         // The `MGET` string has already been consumed. The next value is the
         // name of the key to get. If the next value is not a string or the
         // input is fully consumed, then an error is returned.
         let mut mget = Mget::default();
 
-        while let Ok(key) = parse.next_string() {
+        while let Ok(key) = parse.next_bytes() {
             mget.add_key(key);
         }
 
@@ -53,7 +58,7 @@ impl Mget {
         }
         let mut mget = Mget::default();
         for arg in argv {
-            mget.add_key(String::from_utf8_lossy(arg).to_string());
+            mget.add_key(arg.clone());
         }
         Ok(mget)
     }
@@ -108,4 +113,8 @@ impl Invalid for Mget {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
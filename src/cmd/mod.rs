@@ -31,6 +31,66 @@ pub use setnx::SetNX;
 mod setex;
 pub use setex::SetEX;
 
+mod append;
+pub use append::Append;
+
+mod getrange;
+pub use getrange::Getrange;
+
+mod setrange;
+pub use setrange::Setrange;
+
+mod getdel;
+pub use getdel::Getdel;
+
+mod getex;
+pub use getex::Getex;
+
+mod incrbyfloat;
+pub use incrbyfloat::IncrByFloat;
+
+mod setbit;
+pub use setbit::Setbit;
+
+mod getbit;
+pub use getbit::Getbit;
+
+mod bitcount;
+pub use bitcount::Bitcount;
+
+mod bitpos;
+pub use bitpos::Bitpos;
+
+mod bitop;
+pub use bitop::Bitop;
+
+mod bitfield;
+pub use bitfield::Bitfield;
+
+mod pfadd;
+pub use pfadd::Pfadd;
+
+mod pfcount;
+pub use pfcount::Pfcount;
+
+mod pfmerge;
+pub use pfmerge::Pfmerge;
+
+mod geoadd;
+pub use geoadd::Geoadd;
+
+mod geopos;
+pub use geopos::Geopos;
+
+mod geodist;
+pub use geodist::Geodist;
+
+mod geohash;
+pub use geohash::Geohash;
+
+mod geosearch;
+pub use geosearch::{Geosearch, Geosearchstore};
+
 mod ttl;
 pub use ttl::TTL;
 
@@ -38,7 +98,10 @@ mod cmdtype;
 pub use cmdtype::Type;
 
 mod subscribe;
-pub use subscribe::{Subscribe, Unsubscribe};
+pub use subscribe::{Subscribe, SubscribeKind, Unsubscribe};
+
+mod pubsub;
+pub use pubsub::Pubsub;
 
 mod ping;
 pub use ping::Ping;
@@ -103,6 +166,9 @@ pub use llen::Llen;
 mod lindex;
 pub use lindex::Lindex;
 
+mod lpos;
+pub use lpos::Lpos;
+
 mod lset;
 pub use lset::Lset;
 
@@ -115,6 +181,15 @@ pub use lrem::Lrem;
 mod linsert;
 pub use linsert::Linsert;
 
+mod blpop;
+pub use blpop::Blpop;
+
+mod lmpop;
+pub use lmpop::Lmpop;
+
+mod lmove;
+pub use lmove::Lmove;
+
 mod eval;
 pub use eval::Eval;
 
@@ -139,6 +214,18 @@ pub use srandmember::Srandmember;
 mod spop;
 pub use spop::Spop;
 
+mod sinter;
+pub use sinter::Sinter;
+
+mod sinterstore;
+pub use sinterstore::Sinterstore;
+
+mod sintercard;
+pub use sintercard::Sintercard;
+
+mod smove;
+pub use smove::Smove;
+
 mod srem;
 pub use srem::Srem;
 
@@ -175,12 +262,36 @@ pub use zcount::Zcount;
 mod zpop;
 pub use zpop::Zpop;
 
+mod bzpop;
+pub use bzpop::Bzpop;
+
+mod zmpop;
+pub use zmpop::Zmpop;
+
 mod zrank;
 pub use zrank::Zrank;
 
 mod zincrby;
 pub use zincrby::Zincrby;
 
+mod zunion;
+pub use zunion::Zunion;
+
+mod zunionstore;
+pub use zunionstore::Zunionstore;
+
+mod zintercard;
+pub use zintercard::Zintercard;
+
+mod zrangebylex;
+pub use zrangebylex::Zrangebylex;
+
+mod zlexcount;
+pub use zlexcount::Zlexcount;
+
+mod zremrangebylex;
+pub use zremrangebylex::Zremrangebylex;
+
 mod script;
 pub use script::script_clear_killed;
 pub use script::script_interuptted;
@@ -193,6 +304,9 @@ pub use unknown::Unknown;
 mod auth;
 pub use auth::Auth;
 
+mod hello;
+pub use hello::Hello;
+
 mod debug;
 pub use debug::Debug;
 
@@ -208,15 +322,22 @@ pub use fake::Fake;
 mod multi;
 pub use multi::Multi;
 
+mod watch;
+pub use watch::Watch;
+
 mod scan;
 pub use scan::Scan;
 
 use crate::client::Client;
+use crate::tikv::set::SetOperation;
 use crate::{cluster::Cluster as Topo, Connection, Db, Frame, Parse, ParseError, Shutdown};
 
 /// All commands should be implement new_invalid() for invalid check
 pub trait Invalid {
     fn new_invalid() -> Self;
+
+    /// Returns false if the arguments failed to parse
+    fn is_valid(&self) -> bool;
 }
 
 fn transform_parse<T: Invalid>(parse_res: crate::Result<T>, parse: &mut Parse) -> T {
@@ -241,12 +362,22 @@ pub enum Command {
     Get(Get),
     Mget(Mget),
     Publish(Publish),
+    PublishLocal(Publish),
     Set(Set),
     SetNX(SetNX),
     SetEX(SetEX),
+    Psetex(SetEX),
+    Getset(Set),
     Mset(Mset),
+    Msetnx(Mset),
     Subscribe(Subscribe),
     Unsubscribe(Unsubscribe),
+    Psubscribe(Subscribe),
+    Punsubscribe(Unsubscribe),
+    Ssubscribe(Subscribe),
+    Sunsubscribe(Unsubscribe),
+    Spublish(Publish),
+    Pubsub(Pubsub),
     Ping(Ping),
     Config(Config),
     Type(Type),
@@ -263,6 +394,35 @@ pub enum Command {
     IncrBy(IncrDecr),
     DecrBy(IncrDecr),
     Strlen(Strlen),
+    IncrByFloat(IncrByFloat),
+    Append(Append),
+    Getrange(Getrange),
+    Substr(Getrange),
+    Setrange(Setrange),
+    Getdel(Getdel),
+    Getex(Getex),
+
+    // bitmap
+    Setbit(Setbit),
+    Getbit(Getbit),
+    Bitcount(Bitcount),
+    Bitpos(Bitpos),
+    Bitop(Bitop),
+    Bitfield(Bitfield),
+    BitfieldRo(Bitfield),
+
+    // hyperloglog
+    Pfadd(Pfadd),
+    Pfcount(Pfcount),
+    Pfmerge(Pfmerge),
+
+    // geo
+    Geoadd(Geoadd),
+    Geopos(Geopos),
+    Geodist(Geodist),
+    Geohash(Geohash),
+    Geosearch(Geosearch),
+    Geosearchstore(Geosearchstore),
 
     // hash
     Hset(Hset),
@@ -281,15 +441,25 @@ pub enum Command {
     // list
     Lpush(Push),
     Rpush(Push),
+    Lpushx(Push),
+    Rpushx(Push),
     Lpop(Pop),
     Rpop(Pop),
     Lrange(Lrange),
     Llen(Llen),
     Lindex(Lindex),
+    Lpos(Lpos),
     Lset(Lset),
     Ltrim(Ltrim),
     Lrem(Lrem),
     Linsert(Linsert),
+    Blpop(Blpop),
+    Brpop(Blpop),
+    Lmpop(Lmpop),
+    Blmpop(Lmpop),
+    Lmove(Lmove),
+    Rpoplpush(Lmove),
+    Blmove(Lmove),
     // set
     Sadd(Sadd),
     Scard(Scard),
@@ -299,6 +469,14 @@ pub enum Command {
     Srandmember(Srandmember),
     Spop(Spop),
     Srem(Srem),
+    Sinter(Sinter),
+    Sunion(Sinter),
+    Sdiff(Sinter),
+    Sinterstore(Sinterstore),
+    Sunionstore(Sinterstore),
+    Sdiffstore(Sinterstore),
+    Sintercard(Sintercard),
+    Smove(Smove),
     // sorted set
     Zadd(Zadd),
     Zcard(Zcard),
@@ -313,8 +491,22 @@ pub enum Command {
     Zcount(Zcount),
     Zpopmin(Zpop),
     Zpopmax(Zpop),
+    Bzpopmin(Bzpop),
+    Bzpopmax(Bzpop),
+    Bzmpop(Zmpop),
     Zrank(Zrank),
     Zincryby(Zincrby),
+    Zunion(Zunion),
+    Zinter(Zunion),
+    Zdiff(Zunion),
+    Zunionstore(Zunionstore),
+    Zinterstore(Zunionstore),
+    Zdiffstore(Zunionstore),
+    Zintercard(Zintercard),
+    Zrangebylex(Zrangebylex),
+    Zrevrangebylex(Zrangebylex),
+    Zlexcount(Zlexcount),
+    Zremrangebylex(Zremrangebylex),
 
     // scripts
     Eval(Eval),
@@ -322,6 +514,7 @@ pub enum Command {
     Script(Script),
 
     Auth(Auth),
+    Hello(Hello),
     Debug(Debug),
 
     Cluster(Cluster),
@@ -334,6 +527,8 @@ pub enum Command {
     Multi(Multi),
     Exec(Multi),
     Discard(Multi),
+    Watch(Watch),
+    Unwatch(Watch),
 
     Scan(Scan),
     // Xscan command is same as scan, for testing purpose, avoid some client decoding the response
@@ -372,9 +567,24 @@ impl Command {
                 Publish::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "publishlocal" => Command::PublishLocal(transform_parse(
+                Publish::parse_local_frames(&mut parse),
+                &mut parse,
+            )),
             "set" => Command::Set(transform_parse(Set::parse_frames(&mut parse), &mut parse)),
             "setnx" => Command::SetNX(transform_parse(SetNX::parse_frames(&mut parse), &mut parse)),
-            "setex" => Command::SetEX(transform_parse(SetEX::parse_frames(&mut parse), &mut parse)),
+            "setex" => Command::SetEX(transform_parse(
+                SetEX::parse_frames(&mut parse, false),
+                &mut parse,
+            )),
+            "psetex" => Command::Psetex(transform_parse(
+                SetEX::parse_frames(&mut parse, true),
+                &mut parse,
+            )),
+            "getset" => Command::Getset(transform_parse(
+                Set::parse_getset_frames(&mut parse),
+                &mut parse,
+            )),
             "subscribe" => Command::Subscribe(transform_parse(
                 Subscribe::parse_frames(&mut parse),
                 &mut parse,
@@ -383,6 +593,30 @@ impl Command {
                 Unsubscribe::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "psubscribe" => Command::Psubscribe(transform_parse(
+                Subscribe::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "punsubscribe" => Command::Punsubscribe(transform_parse(
+                Unsubscribe::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "ssubscribe" => Command::Ssubscribe(transform_parse(
+                Subscribe::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "sunsubscribe" => Command::Sunsubscribe(transform_parse(
+                Unsubscribe::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "spublish" => Command::Spublish(transform_parse(
+                Publish::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "pubsub" => Command::Pubsub(transform_parse(
+                Pubsub::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "ping" => Command::Ping(transform_parse(Ping::parse_frames(&mut parse), &mut parse)),
             "config" => Command::Config(transform_parse(
                 Config::parse_frames(&mut parse),
@@ -392,6 +626,9 @@ impl Command {
             "type" => Command::Type(transform_parse(Type::parse_frames(&mut parse), &mut parse)),
             "mget" => Command::Mget(transform_parse(Mget::parse_frames(&mut parse), &mut parse)),
             "mset" => Command::Mset(transform_parse(Mset::parse_frames(&mut parse), &mut parse)),
+            "msetnx" => {
+                Command::Msetnx(transform_parse(Mset::parse_frames(&mut parse), &mut parse))
+            }
             "ttl" => Command::TTL(transform_parse(TTL::parse_frames(&mut parse), &mut parse)),
             "pttl" => Command::PTTL(transform_parse(TTL::parse_frames(&mut parse), &mut parse)),
             "expire" => Command::Expire(transform_parse(
@@ -438,6 +675,89 @@ impl Command {
                 Strlen::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "incrbyfloat" => Command::IncrByFloat(transform_parse(
+                IncrByFloat::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "append" => Command::Append(transform_parse(
+                Append::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "getrange" => Command::Getrange(transform_parse(
+                Getrange::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "substr" => Command::Substr(transform_parse(
+                Getrange::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "setrange" => Command::Setrange(transform_parse(
+                Setrange::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "getdel" => Command::Getdel(transform_parse(
+                Getdel::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "getex" => Command::Getex(transform_parse(Getex::parse_frames(&mut parse), &mut parse)),
+            "setbit" => Command::Setbit(transform_parse(
+                Setbit::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "getbit" => Command::Getbit(transform_parse(
+                Getbit::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "bitcount" => Command::Bitcount(transform_parse(
+                Bitcount::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "bitpos" => Command::Bitpos(transform_parse(
+                Bitpos::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "bitop" => Command::Bitop(transform_parse(Bitop::parse_frames(&mut parse), &mut parse)),
+            "bitfield" => Command::Bitfield(transform_parse(
+                Bitfield::parse_frames(&mut parse, false),
+                &mut parse,
+            )),
+            "bitfield_ro" => Command::BitfieldRo(transform_parse(
+                Bitfield::parse_frames(&mut parse, true),
+                &mut parse,
+            )),
+            "pfadd" => Command::Pfadd(transform_parse(Pfadd::parse_frames(&mut parse), &mut parse)),
+            "pfcount" => Command::Pfcount(transform_parse(
+                Pfcount::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "pfmerge" => Command::Pfmerge(transform_parse(
+                Pfmerge::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "geoadd" => Command::Geoadd(transform_parse(
+                Geoadd::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "geopos" => Command::Geopos(transform_parse(
+                Geopos::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "geodist" => Command::Geodist(transform_parse(
+                Geodist::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "geohash" => Command::Geohash(transform_parse(
+                Geohash::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "geosearch" => Command::Geosearch(transform_parse(
+                Geosearch::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "geosearchstore" => Command::Geosearchstore(transform_parse(
+                Geosearchstore::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "hset" => Command::Hset(transform_parse(Hset::parse_frames(&mut parse), &mut parse)),
             "hsetnx" => {
                 Command::Hsetnx(transform_parse(Hset::parse_frames(&mut parse), &mut parse))
@@ -467,6 +787,12 @@ impl Command {
             )),
             "lpush" => Command::Lpush(transform_parse(Push::parse_frames(&mut parse), &mut parse)),
             "rpush" => Command::Rpush(transform_parse(Push::parse_frames(&mut parse), &mut parse)),
+            "lpushx" => {
+                Command::Lpushx(transform_parse(Push::parse_frames(&mut parse), &mut parse))
+            }
+            "rpushx" => {
+                Command::Rpushx(transform_parse(Push::parse_frames(&mut parse), &mut parse))
+            }
             "lpop" => Command::Lpop(transform_parse(Pop::parse_frames(&mut parse), &mut parse)),
             "rpop" => Command::Rpop(transform_parse(Pop::parse_frames(&mut parse), &mut parse)),
             "lrange" => Command::Lrange(transform_parse(
@@ -478,6 +804,7 @@ impl Command {
                 Lindex::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "lpos" => Command::Lpos(transform_parse(Lpos::parse_frames(&mut parse), &mut parse)),
             "lset" => Command::Lset(transform_parse(Lset::parse_frames(&mut parse), &mut parse)),
             "ltrim" => Command::Ltrim(transform_parse(Ltrim::parse_frames(&mut parse), &mut parse)),
             "lrem" => Command::Lrem(transform_parse(Lrem::parse_frames(&mut parse), &mut parse)),
@@ -485,6 +812,28 @@ impl Command {
                 Linsert::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "blpop" => Command::Blpop(transform_parse(Blpop::parse_frames(&mut parse), &mut parse)),
+            "brpop" => Command::Brpop(transform_parse(Blpop::parse_frames(&mut parse), &mut parse)),
+            "lmpop" => Command::Lmpop(transform_parse(
+                Lmpop::parse_frames(&mut parse, false),
+                &mut parse,
+            )),
+            "blmpop" => Command::Blmpop(transform_parse(
+                Lmpop::parse_frames(&mut parse, true),
+                &mut parse,
+            )),
+            "lmove" => Command::Lmove(transform_parse(
+                Lmove::parse_frames(&mut parse, false),
+                &mut parse,
+            )),
+            "rpoplpush" => Command::Rpoplpush(transform_parse(
+                Lmove::parse_rpoplpush_frames(&mut parse),
+                &mut parse,
+            )),
+            "blmove" => Command::Blmove(transform_parse(
+                Lmove::parse_frames(&mut parse, true),
+                &mut parse,
+            )),
             "eval" => Command::Eval(transform_parse(Eval::parse_frames(&mut parse), &mut parse)),
             "evalsha" => {
                 Command::Evalsha(transform_parse(Eval::parse_frames(&mut parse), &mut parse))
@@ -512,6 +861,35 @@ impl Command {
                 &mut parse,
             )),
             "spop" => Command::Spop(transform_parse(Spop::parse_frames(&mut parse), &mut parse)),
+            "sinter" => Command::Sinter(transform_parse(
+                Sinter::parse_frames(&mut parse, SetOperation::Inter),
+                &mut parse,
+            )),
+            "sunion" => Command::Sunion(transform_parse(
+                Sinter::parse_frames(&mut parse, SetOperation::Union),
+                &mut parse,
+            )),
+            "sdiff" => Command::Sdiff(transform_parse(
+                Sinter::parse_frames(&mut parse, SetOperation::Diff),
+                &mut parse,
+            )),
+            "sinterstore" => Command::Sinterstore(transform_parse(
+                Sinterstore::parse_frames(&mut parse, SetOperation::Inter),
+                &mut parse,
+            )),
+            "sunionstore" => Command::Sunionstore(transform_parse(
+                Sinterstore::parse_frames(&mut parse, SetOperation::Union),
+                &mut parse,
+            )),
+            "sdiffstore" => Command::Sdiffstore(transform_parse(
+                Sinterstore::parse_frames(&mut parse, SetOperation::Diff),
+                &mut parse,
+            )),
+            "sintercard" => Command::Sintercard(transform_parse(
+                Sintercard::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "smove" => Command::Smove(transform_parse(Smove::parse_frames(&mut parse), &mut parse)),
             "srem" => Command::Srem(transform_parse(Srem::parse_frames(&mut parse), &mut parse)),
             "zadd" => Command::Zadd(transform_parse(Zadd::parse_frames(&mut parse), &mut parse)),
             "zcard" => Command::Zcard(transform_parse(Zcard::parse_frames(&mut parse), &mut parse)),
@@ -554,12 +932,67 @@ impl Command {
             "zpopmax" => {
                 Command::Zpopmax(transform_parse(Zpop::parse_frames(&mut parse), &mut parse))
             }
+            "bzpopmin" => {
+                Command::Bzpopmin(transform_parse(Bzpop::parse_frames(&mut parse), &mut parse))
+            }
+            "bzpopmax" => {
+                Command::Bzpopmax(transform_parse(Bzpop::parse_frames(&mut parse), &mut parse))
+            }
+            "bzmpop" => Command::Bzmpop(transform_parse(
+                Zmpop::parse_frames(&mut parse, true),
+                &mut parse,
+            )),
             "zrank" => Command::Zrank(transform_parse(Zrank::parse_frames(&mut parse), &mut parse)),
             "zincrby" => Command::Zincryby(transform_parse(
                 Zincrby::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "zunion" => Command::Zunion(transform_parse(
+                Zunion::parse_frames(&mut parse, SetOperation::Union),
+                &mut parse,
+            )),
+            "zinter" => Command::Zinter(transform_parse(
+                Zunion::parse_frames(&mut parse, SetOperation::Inter),
+                &mut parse,
+            )),
+            "zdiff" => Command::Zdiff(transform_parse(
+                Zunion::parse_frames(&mut parse, SetOperation::Diff),
+                &mut parse,
+            )),
+            "zunionstore" => Command::Zunionstore(transform_parse(
+                Zunionstore::parse_frames(&mut parse, SetOperation::Union),
+                &mut parse,
+            )),
+            "zinterstore" => Command::Zinterstore(transform_parse(
+                Zunionstore::parse_frames(&mut parse, SetOperation::Inter),
+                &mut parse,
+            )),
+            "zdiffstore" => Command::Zdiffstore(transform_parse(
+                Zunionstore::parse_frames(&mut parse, SetOperation::Diff),
+                &mut parse,
+            )),
+            "zintercard" => Command::Zintercard(transform_parse(
+                Zintercard::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "zrangebylex" => Command::Zrangebylex(transform_parse(
+                Zrangebylex::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "zrevrangebylex" => Command::Zrevrangebylex(transform_parse(
+                Zrangebylex::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "zlexcount" => Command::Zlexcount(transform_parse(
+                Zlexcount::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "zremrangebylex" => Command::Zremrangebylex(transform_parse(
+                Zremrangebylex::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "auth" => Command::Auth(transform_parse(Auth::parse_frames(&mut parse), &mut parse)),
+            "hello" => Command::Hello(transform_parse(Hello::parse_frames(&mut parse), &mut parse)),
             "debug" => Command::Debug(transform_parse(Debug::parse_frames(&mut parse), &mut parse)),
             "cluster" => Command::Cluster(transform_parse(
                 Cluster::parse_frames(&mut parse),
@@ -584,6 +1017,8 @@ impl Command {
             "multi" => Command::Multi(Multi::new()),
             "exec" => Command::Exec(Multi::new()),
             "discard" => Command::Discard(Multi::new()),
+            "watch" => Command::Watch(transform_parse(Watch::parse_frames(&mut parse), &mut parse)),
+            "unwatch" => Command::Unwatch(Watch::default()),
             "scan" => Command::Scan(transform_parse(Scan::parse_frames(&mut parse), &mut parse)),
             "xscan" => Command::Scan(transform_parse(Scan::parse_frames(&mut parse), &mut parse)),
             _ => {
@@ -614,15 +1049,41 @@ impl Command {
             "incrby" => Command::IncrBy(IncrDecr::parse_argv(argv, false)?),
             "decrby" => Command::DecrBy(IncrDecr::parse_argv(argv, false)?),
             "strlen" => Command::Strlen(Strlen::parse_argv(argv)?),
+            "incrbyfloat" => Command::IncrByFloat(IncrByFloat::parse_argv(argv)?),
+            "append" => Command::Append(Append::parse_argv(argv)?),
+            "getrange" => Command::Getrange(Getrange::parse_argv(argv)?),
+            "substr" => Command::Substr(Getrange::parse_argv(argv)?),
+            "setrange" => Command::Setrange(Setrange::parse_argv(argv)?),
+            "getdel" => Command::Getdel(Getdel::parse_argv(argv)?),
+            "getex" => Command::Getex(Getex::parse_argv(argv)?),
+            "setbit" => Command::Setbit(Setbit::parse_argv(argv)?),
+            "getbit" => Command::Getbit(Getbit::parse_argv(argv)?),
+            "bitcount" => Command::Bitcount(Bitcount::parse_argv(argv)?),
+            "bitpos" => Command::Bitpos(Bitpos::parse_argv(argv)?),
+            "bitop" => Command::Bitop(Bitop::parse_argv(argv)?),
+            "bitfield" => Command::Bitfield(Bitfield::parse_argv(argv, false)?),
+            "bitfield_ro" => Command::BitfieldRo(Bitfield::parse_argv(argv, true)?),
+            "pfadd" => Command::Pfadd(Pfadd::parse_argv(argv)?),
+            "pfcount" => Command::Pfcount(Pfcount::parse_argv(argv)?),
+            "pfmerge" => Command::Pfmerge(Pfmerge::parse_argv(argv)?),
+            "geoadd" => Command::Geoadd(Geoadd::parse_argv(argv)?),
+            "geopos" => Command::Geopos(Geopos::parse_argv(argv)?),
+            "geodist" => Command::Geodist(Geodist::parse_argv(argv)?),
+            "geohash" => Command::Geohash(Geohash::parse_argv(argv)?),
+            "geosearch" => Command::Geosearch(Geosearch::parse_argv(argv)?),
+            "geosearchstore" => Command::Geosearchstore(Geosearchstore::parse_argv(argv)?),
             "del" => Command::Del(Del::parse_argv(argv)?),
             "type" => Command::Type(Type::parse_argv(argv)?),
             "exists" => Command::Exists(Exists::parse_argv(argv)?),
             "get" => Command::Get(Get::parse_argv(argv)?),
             "set" => Command::Set(Set::parse_argv(argv)?),
             "setnx" => Command::SetNX(SetNX::parse_argv(argv)?),
-            "setex" => Command::SetEX(SetEX::parse_argv(argv)?),
+            "setex" => Command::SetEX(SetEX::parse_argv(argv, false)?),
+            "psetex" => Command::Psetex(SetEX::parse_argv(argv, true)?),
+            "getset" => Command::Getset(Set::parse_getset_argv(argv)?),
             "mget" => Command::Mget(Mget::parse_argv(argv)?),
             "mset" => Command::Mset(Mset::parse_argv(argv)?),
+            "msetnx" => Command::Msetnx(Mset::parse_argv(argv)?),
             "ttl" => Command::TTL(TTL::parse_argv(argv)?),
             "pttl" => Command::PTTL(TTL::parse_argv(argv)?),
             "expire" => Command::Expire(Expire::parse_argv(argv)?),
@@ -645,15 +1106,21 @@ impl Command {
             "hstrlen" => Command::Hstrlen(Hstrlen::parse_argv(argv)?),
             "lpush" => Command::Lpush(Push::parse_argv(argv)?),
             "rpush" => Command::Rpush(Push::parse_argv(argv)?),
+            "lpushx" => Command::Lpushx(Push::parse_argv(argv)?),
+            "rpushx" => Command::Rpushx(Push::parse_argv(argv)?),
             "lpop" => Command::Lpop(Pop::parse_argv(argv)?),
             "rpop" => Command::Rpop(Pop::parse_argv(argv)?),
             "lrange" => Command::Lrange(Lrange::parse_argv(argv)?),
             "llen" => Command::Llen(Llen::parse_argv(argv)?),
             "lindex" => Command::Lindex(Lindex::parse_argv(argv)?),
+            "lpos" => Command::Lpos(Lpos::parse_argv(argv)?),
             "lset" => Command::Lset(Lset::parse_argv(argv)?),
             "ltrim" => Command::Ltrim(Ltrim::parse_argv(argv)?),
             "lrem" => Command::Lrem(Lrem::parse_argv(argv)?),
             "linsert" => Command::Linsert(Linsert::parse_argv(argv)?),
+            "lmpop" => Command::Lmpop(Lmpop::parse_argv(argv)?),
+            "lmove" => Command::Lmove(Lmove::parse_argv(argv)?),
+            "rpoplpush" => Command::Rpoplpush(Lmove::parse_rpoplpush_argv(argv)?),
             "sadd" => Command::Sadd(Sadd::parse_argv(argv)?),
             "scard" => Command::Scard(Scard::parse_argv(argv)?),
             "sismember" => Command::Sismember(Sismember::parse_argv(argv)?),
@@ -661,6 +1128,18 @@ impl Command {
             "smembers" => Command::Smembers(Smembers::parse_argv(argv)?),
             "srandmember" => Command::Srandmember(Srandmember::parse_argv(argv)?),
             "spop" => Command::Spop(Spop::parse_argv(argv)?),
+            "sinter" => Command::Sinter(Sinter::parse_argv(argv, SetOperation::Inter)?),
+            "sunion" => Command::Sunion(Sinter::parse_argv(argv, SetOperation::Union)?),
+            "sdiff" => Command::Sdiff(Sinter::parse_argv(argv, SetOperation::Diff)?),
+            "sinterstore" => {
+                Command::Sinterstore(Sinterstore::parse_argv(argv, SetOperation::Inter)?)
+            }
+            "sunionstore" => {
+                Command::Sunionstore(Sinterstore::parse_argv(argv, SetOperation::Union)?)
+            }
+            "sdiffstore" => Command::Sdiffstore(Sinterstore::parse_argv(argv, SetOperation::Diff)?),
+            "sintercard" => Command::Sintercard(Sintercard::parse_argv(argv)?),
+            "smove" => Command::Smove(Smove::parse_argv(argv)?),
             "srem" => Command::Srem(Srem::parse_argv(argv)?),
             "zadd" => Command::Zadd(Zadd::parse_argv(argv)?),
             "zcard" => Command::Zcard(Zcard::parse_argv(argv)?),
@@ -677,6 +1156,21 @@ impl Command {
             "zpopmax" => Command::Zpopmax(Zpop::parse_argv(argv)?),
             "zrank" => Command::Zrank(Zrank::parse_argv(argv)?),
             "zincrby" => Command::Zincryby(Zincrby::parse_argv(argv)?),
+            "zunion" => Command::Zunion(Zunion::parse_argv(argv, SetOperation::Union)?),
+            "zinter" => Command::Zinter(Zunion::parse_argv(argv, SetOperation::Inter)?),
+            "zdiff" => Command::Zdiff(Zunion::parse_argv(argv, SetOperation::Diff)?),
+            "zunionstore" => {
+                Command::Zunionstore(Zunionstore::parse_argv(argv, SetOperation::Union)?)
+            }
+            "zinterstore" => {
+                Command::Zinterstore(Zunionstore::parse_argv(argv, SetOperation::Inter)?)
+            }
+            "zdiffstore" => Command::Zdiffstore(Zunionstore::parse_argv(argv, SetOperation::Diff)?),
+            "zintercard" => Command::Zintercard(Zintercard::parse_argv(argv)?),
+            "zrangebylex" => Command::Zrangebylex(Zrangebylex::parse_argv(argv)?),
+            "zrevrangebylex" => Command::Zrevrangebylex(Zrangebylex::parse_argv(argv)?),
+            "zlexcount" => Command::Zlexcount(Zlexcount::parse_argv(argv)?),
+            "zremrangebylex" => Command::Zremrangebylex(Zremrangebylex::parse_argv(argv)?),
             "scan" => Command::Scan(Scan::parse_argv(argv)?),
             "xscan" => Command::Scan(Scan::parse_argv(argv)?),
             _ => {
@@ -709,16 +1203,33 @@ impl Command {
         match self {
             Del(cmd) => cmd.apply(dst).await,
             Get(cmd) => cmd.apply(dst).await,
-            Publish(cmd) => cmd.apply(db, dst).await,
+            Publish(cmd) => cmd.apply(db, topo, dst, false).await,
+            PublishLocal(cmd) => cmd.apply(db, topo, dst, true).await,
+            Spublish(cmd) => cmd.apply_shard(db, topo, dst).await,
+            Pubsub(cmd) => cmd.apply(db, dst).await,
             Set(cmd) => cmd.apply(dst).await,
             SetNX(cmd) => cmd.apply(dst).await,
             SetEX(cmd) => cmd.apply(dst).await,
-            Subscribe(cmd) => cmd.apply(db, dst, shutdown).await,
+            Psetex(cmd) => cmd.apply(dst).await,
+            Getset(cmd) => cmd.apply(dst).await,
+            Subscribe(cmd) => {
+                cmd.apply(db, topo, dst, shutdown, SubscribeKind::Channel)
+                    .await
+            }
+            Psubscribe(cmd) => {
+                cmd.apply(db, topo, dst, shutdown, SubscribeKind::Pattern)
+                    .await
+            }
+            Ssubscribe(cmd) => {
+                cmd.apply(db, topo, dst, shutdown, SubscribeKind::Shard)
+                    .await
+            }
             Ping(cmd) => cmd.apply(dst).await,
             Config(cmd) => cmd.apply(dst).await,
             Type(cmd) => cmd.apply(dst).await,
             Mget(cmd) => cmd.apply(dst).await,
-            Mset(cmd) => cmd.apply(dst).await,
+            Mset(cmd) => cmd.apply(dst, false).await,
+            Msetnx(cmd) => cmd.apply(dst, true).await,
             TTL(cmd) => cmd.apply(dst, false).await,
             PTTL(cmd) => cmd.apply(dst, true).await,
             Expire(cmd) => cmd.apply(dst, false, false).await,
@@ -732,6 +1243,29 @@ impl Command {
             IncrBy(cmd) => cmd.apply(dst, true).await,
             DecrBy(cmd) => cmd.apply(dst, false).await,
             Strlen(cmd) => cmd.apply(dst).await,
+            IncrByFloat(cmd) => cmd.apply(dst).await,
+            Append(cmd) => cmd.apply(dst).await,
+            Getrange(cmd) => cmd.apply(dst).await,
+            Substr(cmd) => cmd.apply(dst).await,
+            Setrange(cmd) => cmd.apply(dst).await,
+            Getdel(cmd) => cmd.apply(dst).await,
+            Getex(cmd) => cmd.apply(dst).await,
+            Setbit(cmd) => cmd.apply(dst).await,
+            Getbit(cmd) => cmd.apply(dst).await,
+            Bitcount(cmd) => cmd.apply(dst).await,
+            Bitpos(cmd) => cmd.apply(dst).await,
+            Bitop(cmd) => cmd.apply(dst).await,
+            Bitfield(cmd) => cmd.apply(dst).await,
+            BitfieldRo(cmd) => cmd.apply(dst).await,
+            Pfadd(cmd) => cmd.apply(dst).await,
+            Pfcount(cmd) => cmd.apply(dst).await,
+            Pfmerge(cmd) => cmd.apply(dst).await,
+            Geoadd(cmd) => cmd.apply(dst).await,
+            Geopos(cmd) => cmd.apply(dst).await,
+            Geodist(cmd) => cmd.apply(dst).await,
+            Geohash(cmd) => cmd.apply(dst).await,
+            Geosearch(cmd) => cmd.apply(dst).await,
+            Geosearchstore(cmd) => cmd.apply(dst).await,
             Hset(cmd) => cmd.apply(dst, false, false).await,
             Hmset(cmd) => cmd.apply(dst, true, false).await,
             Hsetnx(cmd) => cmd.apply(dst, false, true).await,
@@ -745,17 +1279,27 @@ impl Command {
             Hincrby(cmd) => cmd.apply(dst).await,
             Hexists(cmd) => cmd.apply(dst).await,
             Hstrlen(cmd) => cmd.apply(dst).await,
-            Lpush(cmd) => cmd.apply(dst, true).await,
-            Rpush(cmd) => cmd.apply(dst, false).await,
+            Lpush(cmd) => cmd.apply(dst, true, false).await,
+            Rpush(cmd) => cmd.apply(dst, false, false).await,
+            Lpushx(cmd) => cmd.apply(dst, true, true).await,
+            Rpushx(cmd) => cmd.apply(dst, false, true).await,
             Lpop(cmd) => cmd.apply(dst, true).await,
             Rpop(cmd) => cmd.apply(dst, false).await,
             Lrange(cmd) => cmd.apply(dst).await,
             Llen(cmd) => cmd.apply(dst).await,
             Lindex(cmd) => cmd.apply(dst).await,
+            Lpos(cmd) => cmd.apply(dst).await,
             Lset(cmd) => cmd.apply(dst).await,
             Ltrim(cmd) => cmd.apply(dst).await,
             Lrem(cmd) => cmd.apply(dst).await,
             Linsert(cmd) => cmd.apply(dst).await,
+            Blpop(cmd) => cmd.apply(db, dst, shutdown, true).await,
+            Brpop(cmd) => cmd.apply(db, dst, shutdown, false).await,
+            Lmpop(cmd) => cmd.apply(db, dst, shutdown).await,
+            Blmpop(cmd) => cmd.apply(db, dst, shutdown).await,
+            Lmove(cmd) => cmd.apply(db, dst, shutdown).await,
+            Rpoplpush(cmd) => cmd.apply(db, dst, shutdown).await,
+            Blmove(cmd) => cmd.apply(db, dst, shutdown).await,
             Eval(cmd) => cmd.apply(dst, false, db, lua).await,
             Evalsha(cmd) => cmd.apply(dst, true, db, lua).await,
             Script(cmd) => cmd.apply(dst, db).await,
@@ -766,6 +1310,14 @@ impl Command {
             Smembers(cmd) => cmd.apply(dst).await,
             Srandmember(cmd) => cmd.apply(dst).await,
             Spop(cmd) => cmd.apply(dst).await,
+            Sinter(cmd) => cmd.apply(dst).await,
+            Sunion(cmd) => cmd.apply(dst).await,
+            Sdiff(cmd) => cmd.apply(dst).await,
+            Sinterstore(cmd) => cmd.apply(dst).await,
+            Sunionstore(cmd) => cmd.apply(dst).await,
+            Sdiffstore(cmd) => cmd.apply(dst).await,
+            Sintercard(cmd) => cmd.apply(dst).await,
+            Smove(cmd) => cmd.apply(dst).await,
             Srem(cmd) => cmd.apply(dst).await,
             Zadd(cmd) => cmd.apply(dst).await,
             Zcard(cmd) => cmd.apply(dst).await,
@@ -780,8 +1332,22 @@ impl Command {
             Zcount(cmd) => cmd.apply(dst).await,
             Zpopmin(cmd) => cmd.apply(dst, true).await,
             Zpopmax(cmd) => cmd.apply(dst, false).await,
+            Bzpopmin(cmd) => cmd.apply(db, dst, shutdown, true).await,
+            Bzpopmax(cmd) => cmd.apply(db, dst, shutdown, false).await,
+            Bzmpop(cmd) => cmd.apply(db, dst, shutdown).await,
             Zrank(cmd) => cmd.apply(dst).await,
             Zincryby(cmd) => cmd.apply(dst).await,
+            Zunion(cmd) => cmd.apply(dst).await,
+            Zinter(cmd) => cmd.apply(dst).await,
+            Zdiff(cmd) => cmd.apply(dst).await,
+            Zunionstore(cmd) => cmd.apply(dst).await,
+            Zinterstore(cmd) => cmd.apply(dst).await,
+            Zdiffstore(cmd) => cmd.apply(dst).await,
+            Zintercard(cmd) => cmd.apply(dst).await,
+            Zrangebylex(cmd) => cmd.apply(dst, false).await,
+            Zrevrangebylex(cmd) => cmd.apply(dst, true).await,
+            Zlexcount(cmd) => cmd.apply(dst).await,
+            Zremrangebylex(cmd) => cmd.apply(dst).await,
             Debug(cmd) => cmd.apply(dst).await,
             Cluster(cmd) => cmd.apply(topo, dst).await,
             ReadWrite(cmd) => cmd.apply("readwrite", dst, cur_client, clients).await,
@@ -793,30 +1359,232 @@ impl Command {
             Xscan(cmd) => cmd.apply(dst).await,
 
             Unknown(cmd) => cmd.apply(dst).await,
-            // `Unsubscribe` cannot be applied. It may only be received from the
-            // context of a `Subscribe` command.
-            Unsubscribe(_) => Err("`Unsubscribe` is unsupported in this context".into()),
+            // Out of the context of a `Subscribe` command, there is nothing to
+            // unsubscribe from.
+            Unsubscribe(cmd) => cmd.apply(dst, SubscribeKind::Channel).await,
+            Punsubscribe(cmd) => cmd.apply(dst, SubscribeKind::Pattern).await,
+            Sunsubscribe(cmd) => cmd.apply(dst, SubscribeKind::Shard).await,
 
             _ => Ok(()),
         }
     }
 
+    /// Returns the keys read by the command if it can be grouped with other
+    /// pipelined reads into one snapshot `batch_get`.
+    pub(crate) fn batch_read_keys(&self) -> Option<&[Bytes]> {
+        match self {
+            Command::Get(cmd) => cmd.batch_keys(),
+            Command::Mget(cmd) => cmd.batch_keys(),
+            _ => None,
+        }
+    }
+
+    /// Build the reply of a grouped read command from the `GET` replies of
+    /// its keys.
+    pub(crate) fn batch_read_reply(&self, values: Vec<Frame>) -> Frame {
+        match self {
+            // MGET replies nil for keys not holding a string
+            Command::Mget(_) => Frame::Array(
+                values
+                    .into_iter()
+                    .map(|v| match v {
+                        Frame::ErrorString(_) | Frame::ErrorOwned(_) => Frame::Null,
+                        v => v,
+                    })
+                    .collect(),
+            ),
+            _ => values.into_iter().next().unwrap_or(Frame::Null),
+        }
+    }
+
+    /// Returns false if the arguments of the command failed to parse, such
+    /// commands are rejected when queued in a transaction.
+    pub(crate) fn is_valid(&self) -> bool {
+        match self {
+            Command::Del(cmd) => cmd.is_valid(),
+            Command::Get(cmd) => cmd.is_valid(),
+            Command::Mget(cmd) => cmd.is_valid(),
+            Command::Publish(cmd) => cmd.is_valid(),
+            Command::PublishLocal(cmd) => cmd.is_valid(),
+            Command::Set(cmd) => cmd.is_valid(),
+            Command::SetNX(cmd) => cmd.is_valid(),
+            Command::SetEX(cmd) => cmd.is_valid(),
+            Command::Psetex(cmd) => cmd.is_valid(),
+            Command::Getset(cmd) => cmd.is_valid(),
+            Command::Mset(cmd) => cmd.is_valid(),
+            Command::Msetnx(cmd) => cmd.is_valid(),
+            Command::Subscribe(cmd) => cmd.is_valid(),
+            Command::Unsubscribe(cmd) => cmd.is_valid(),
+            Command::Psubscribe(cmd) => cmd.is_valid(),
+            Command::Punsubscribe(cmd) => cmd.is_valid(),
+            Command::Ssubscribe(cmd) => cmd.is_valid(),
+            Command::Sunsubscribe(cmd) => cmd.is_valid(),
+            Command::Spublish(cmd) => cmd.is_valid(),
+            Command::Pubsub(cmd) => cmd.is_valid(),
+            Command::Ping(cmd) => cmd.is_valid(),
+            Command::Config(cmd) => cmd.is_valid(),
+            Command::Type(cmd) => cmd.is_valid(),
+            Command::TTL(cmd) => cmd.is_valid(),
+            Command::PTTL(cmd) => cmd.is_valid(),
+            Command::Expire(cmd) => cmd.is_valid(),
+            Command::ExpireAt(cmd) => cmd.is_valid(),
+            Command::Pexpire(cmd) => cmd.is_valid(),
+            Command::PexpireAt(cmd) => cmd.is_valid(),
+            Command::Persist(cmd) => cmd.is_valid(),
+            Command::Exists(cmd) => cmd.is_valid(),
+            Command::Incr(cmd) => cmd.is_valid(),
+            Command::Decr(cmd) => cmd.is_valid(),
+            Command::IncrBy(cmd) => cmd.is_valid(),
+            Command::DecrBy(cmd) => cmd.is_valid(),
+            Command::Strlen(cmd) => cmd.is_valid(),
+            Command::IncrByFloat(cmd) => cmd.is_valid(),
+            Command::Append(cmd) => cmd.is_valid(),
+            Command::Getrange(cmd) => cmd.is_valid(),
+            Command::Substr(cmd) => cmd.is_valid(),
+            Command::Setrange(cmd) => cmd.is_valid(),
+            Command::Getdel(cmd) => cmd.is_valid(),
+            Command::Getex(cmd) => cmd.is_valid(),
+            Command::Setbit(cmd) => cmd.is_valid(),
+            Command::Getbit(cmd) => cmd.is_valid(),
+            Command::Bitcount(cmd) => cmd.is_valid(),
+            Command::Bitpos(cmd) => cmd.is_valid(),
+            Command::Bitop(cmd) => cmd.is_valid(),
+            Command::Bitfield(cmd) => cmd.is_valid(),
+            Command::BitfieldRo(cmd) => cmd.is_valid(),
+            Command::Pfadd(cmd) => cmd.is_valid(),
+            Command::Pfcount(cmd) => cmd.is_valid(),
+            Command::Pfmerge(cmd) => cmd.is_valid(),
+            Command::Geoadd(cmd) => cmd.is_valid(),
+            Command::Geopos(cmd) => cmd.is_valid(),
+            Command::Geodist(cmd) => cmd.is_valid(),
+            Command::Geohash(cmd) => cmd.is_valid(),
+            Command::Geosearch(cmd) => cmd.is_valid(),
+            Command::Geosearchstore(cmd) => cmd.is_valid(),
+            Command::Hset(cmd) => cmd.is_valid(),
+            Command::Hmset(cmd) => cmd.is_valid(),
+            Command::Hsetnx(cmd) => cmd.is_valid(),
+            Command::Hget(cmd) => cmd.is_valid(),
+            Command::Hmget(cmd) => cmd.is_valid(),
+            Command::Hlen(cmd) => cmd.is_valid(),
+            Command::Hgetall(cmd) => cmd.is_valid(),
+            Command::Hdel(cmd) => cmd.is_valid(),
+            Command::Hkeys(cmd) => cmd.is_valid(),
+            Command::Hvals(cmd) => cmd.is_valid(),
+            Command::Hincrby(cmd) => cmd.is_valid(),
+            Command::Hexists(cmd) => cmd.is_valid(),
+            Command::Hstrlen(cmd) => cmd.is_valid(),
+            Command::Lpush(cmd) => cmd.is_valid(),
+            Command::Rpush(cmd) => cmd.is_valid(),
+            Command::Lpushx(cmd) => cmd.is_valid(),
+            Command::Rpushx(cmd) => cmd.is_valid(),
+            Command::Lpop(cmd) => cmd.is_valid(),
+            Command::Rpop(cmd) => cmd.is_valid(),
+            Command::Lrange(cmd) => cmd.is_valid(),
+            Command::Llen(cmd) => cmd.is_valid(),
+            Command::Lindex(cmd) => cmd.is_valid(),
+            Command::Lpos(cmd) => cmd.is_valid(),
+            Command::Lset(cmd) => cmd.is_valid(),
+            Command::Ltrim(cmd) => cmd.is_valid(),
+            Command::Lrem(cmd) => cmd.is_valid(),
+            Command::Linsert(cmd) => cmd.is_valid(),
+            Command::Blpop(cmd) => cmd.is_valid(),
+            Command::Brpop(cmd) => cmd.is_valid(),
+            Command::Lmpop(cmd) => cmd.is_valid(),
+            Command::Blmpop(cmd) => cmd.is_valid(),
+            Command::Lmove(cmd) => cmd.is_valid(),
+            Command::Rpoplpush(cmd) => cmd.is_valid(),
+            Command::Blmove(cmd) => cmd.is_valid(),
+            Command::Sadd(cmd) => cmd.is_valid(),
+            Command::Scard(cmd) => cmd.is_valid(),
+            Command::Sismember(cmd) => cmd.is_valid(),
+            Command::Smismember(cmd) => cmd.is_valid(),
+            Command::Smembers(cmd) => cmd.is_valid(),
+            Command::Srandmember(cmd) => cmd.is_valid(),
+            Command::Spop(cmd) => cmd.is_valid(),
+            Command::Sinter(cmd) => cmd.is_valid(),
+            Command::Sunion(cmd) => cmd.is_valid(),
+            Command::Sdiff(cmd) => cmd.is_valid(),
+            Command::Sinterstore(cmd) => cmd.is_valid(),
+            Command::Sunionstore(cmd) => cmd.is_valid(),
+            Command::Sdiffstore(cmd) => cmd.is_valid(),
+            Command::Sintercard(cmd) => cmd.is_valid(),
+            Command::Smove(cmd) => cmd.is_valid(),
+            Command::Srem(cmd) => cmd.is_valid(),
+            Command::Zadd(cmd) => cmd.is_valid(),
+            Command::Zcard(cmd) => cmd.is_valid(),
+            Command::Zscore(cmd) => cmd.is_valid(),
+            Command::Zrem(cmd) => cmd.is_valid(),
+            Command::Zremrangebyscore(cmd) => cmd.is_valid(),
+            Command::Zremrangebyrank(cmd) => cmd.is_valid(),
+            Command::Zrange(cmd) => cmd.is_valid(),
+            Command::Zrevrange(cmd) => cmd.is_valid(),
+            Command::Zrangebyscore(cmd) => cmd.is_valid(),
+            Command::Zrevrangebyscore(cmd) => cmd.is_valid(),
+            Command::Zcount(cmd) => cmd.is_valid(),
+            Command::Zpopmin(cmd) => cmd.is_valid(),
+            Command::Zpopmax(cmd) => cmd.is_valid(),
+            Command::Bzpopmin(cmd) => cmd.is_valid(),
+            Command::Bzpopmax(cmd) => cmd.is_valid(),
+            Command::Bzmpop(cmd) => cmd.is_valid(),
+            Command::Zrank(cmd) => cmd.is_valid(),
+            Command::Zincryby(cmd) => cmd.is_valid(),
+            Command::Zunion(cmd) => cmd.is_valid(),
+            Command::Zinter(cmd) => cmd.is_valid(),
+            Command::Zdiff(cmd) => cmd.is_valid(),
+            Command::Zunionstore(cmd) => cmd.is_valid(),
+            Command::Zinterstore(cmd) => cmd.is_valid(),
+            Command::Zdiffstore(cmd) => cmd.is_valid(),
+            Command::Zintercard(cmd) => cmd.is_valid(),
+            Command::Zrangebylex(cmd) => cmd.is_valid(),
+            Command::Zrevrangebylex(cmd) => cmd.is_valid(),
+            Command::Zlexcount(cmd) => cmd.is_valid(),
+            Command::Zremrangebylex(cmd) => cmd.is_valid(),
+            Command::Eval(cmd) => cmd.is_valid(),
+            Command::Evalsha(cmd) => cmd.is_valid(),
+            Command::Script(cmd) => cmd.is_valid(),
+            Command::Auth(cmd) => cmd.is_valid(),
+            Command::Hello(cmd) => cmd.is_valid(),
+            Command::Debug(cmd) => cmd.is_valid(),
+            Command::Cluster(cmd) => cmd.is_valid(),
+            Command::ReadWrite(cmd) => cmd.is_valid(),
+            Command::ReadOnly(cmd) => cmd.is_valid(),
+            Command::Client(cmd) => cmd.is_valid(),
+            Command::Info(cmd) => cmd.is_valid(),
+            Command::Watch(cmd) => cmd.is_valid(),
+            Command::Unwatch(cmd) => cmd.is_valid(),
+            Command::Scan(cmd) => cmd.is_valid(),
+            Command::Xscan(cmd) => cmd.is_valid(),
+            Command::Multi(_) | Command::Exec(_) | Command::Discard(_) => true,
+            Command::Unknown(_) => false,
+        }
+    }
+
     /// Returns the command name
     pub(crate) fn get_name(&self) -> &str {
         match self {
             Command::Del(_) => "del",
             Command::Get(_) => "get",
             Command::Publish(_) => "pub",
+            Command::PublishLocal(_) => "publishlocal",
             Command::Set(_) => "set",
             Command::SetNX(_) => "setnx",
             Command::SetEX(_) => "setex",
+            Command::Psetex(_) => "psetex",
+            Command::Getset(_) => "getset",
             Command::Subscribe(_) => "subscribe",
             Command::Unsubscribe(_) => "unsubscribe",
+            Command::Psubscribe(_) => "psubscribe",
+            Command::Punsubscribe(_) => "punsubscribe",
+            Command::Ssubscribe(_) => "ssubscribe",
+            Command::Sunsubscribe(_) => "sunsubscribe",
+            Command::Spublish(_) => "spublish",
+            Command::Pubsub(_) => "pubsub",
             Command::Ping(_) => "ping",
             Command::Config(_) => "config",
             Command::Type(_) => "type",
             Command::Mget(_) => "mget",
             Command::Mset(_) => "mset",
+            Command::Msetnx(_) => "msetnx",
             Command::TTL(_) => "ttl",
             Command::PTTL(_) => "pttl",
             Command::Expire(_) => "expire",
@@ -830,6 +1598,29 @@ impl Command {
             Command::IncrBy(_) => "incrby",
             Command::DecrBy(_) => "decrby",
             Command::Strlen(_) => "strlen",
+            Command::IncrByFloat(_) => "incrbyfloat",
+            Command::Append(_) => "append",
+            Command::Getrange(_) => "getrange",
+            Command::Substr(_) => "substr",
+            Command::Setrange(_) => "setrange",
+            Command::Getdel(_) => "getdel",
+            Command::Getex(_) => "getex",
+            Command::Setbit(_) => "setbit",
+            Command::Getbit(_) => "getbit",
+            Command::Bitcount(_) => "bitcount",
+            Command::Bitpos(_) => "bitpos",
+            Command::Bitop(_) => "bitop",
+            Command::Bitfield(_) => "bitfield",
+            Command::BitfieldRo(_) => "bitfield_ro",
+            Command::Pfadd(_) => "pfadd",
+            Command::Pfcount(_) => "pfcount",
+            Command::Pfmerge(_) => "pfmerge",
+            Command::Geoadd(_) => "geoadd",
+            Command::Geopos(_) => "geopos",
+            Command::Geodist(_) => "geodist",
+            Command::Geohash(_) => "geohash",
+            Command::Geosearch(_) => "geosearch",
+            Command::Geosearchstore(_) => "geosearchstore",
             Command::Hset(_) => "hset",
             Command::Hmset(_) => "hmset",
             Command::Hsetnx(_) => "hsetnx",
@@ -845,15 +1636,25 @@ impl Command {
             Command::Hstrlen(_) => "hstrlen",
             Command::Lpush(_) => "lpush",
             Command::Rpush(_) => "rpush",
+            Command::Lpushx(_) => "lpushx",
+            Command::Rpushx(_) => "rpushx",
             Command::Lpop(_) => "lpop",
             Command::Rpop(_) => "rpop",
             Command::Lrange(_) => "lrange",
             Command::Llen(_) => "llen",
             Command::Lindex(_) => "lindex",
+            Command::Lpos(_) => "lpos",
             Command::Lset(_) => "lset",
             Command::Ltrim(_) => "ltrim",
             Command::Lrem(_) => "lrem",
             Command::Linsert(_) => "linsert",
+            Command::Blpop(_) => "blpop",
+            Command::Brpop(_) => "brpop",
+            Command::Lmpop(_) => "lmpop",
+            Command::Blmpop(_) => "blmpop",
+            Command::Lmove(_) => "lmove",
+            Command::Rpoplpush(_) => "rpoplpush",
+            Command::Blmove(_) => "blmove",
             Command::Eval(_) => "eval",
             Command::Evalsha(_) => "evalsha",
             Command::Script(_) => "script",
@@ -864,6 +1665,14 @@ impl Command {
             Command::Smembers(_) => "smembers",
             Command::Srandmember(_) => "srandmember",
             Command::Spop(_) => "spop",
+            Command::Sinter(_) => "sinter",
+            Command::Sunion(_) => "sunion",
+            Command::Sdiff(_) => "sdiff",
+            Command::Sinterstore(_) => "sinterstore",
+            Command::Sunionstore(_) => "sunionstore",
+            Command::Sdiffstore(_) => "sdiffstore",
+            Command::Sintercard(_) => "sintercard",
+            Command::Smove(_) => "smove",
             Command::Srem(_) => "srem",
             Command::Zadd(_) => "zadd",
             Command::Zcard(_) => "zcard",
@@ -878,9 +1687,24 @@ impl Command {
             Command::Zcount(_) => "zcount",
             Command::Zpopmin(_) => "zpopmin",
             Command::Zpopmax(_) => "zpopmax",
+            Command::Bzpopmin(_) => "bzpopmin",
+            Command::Bzpopmax(_) => "bzpopmax",
+            Command::Bzmpop(_) => "bzmpop",
             Command::Zrank(_) => "zrank",
             Command::Zincryby(_) => "zincrby",
+            Command::Zunion(_) => "zunion",
+            Command::Zinter(_) => "zinter",
+            Command::Zdiff(_) => "zdiff",
+            Command::Zunionstore(_) => "zunionstore",
+            Command::Zinterstore(_) => "zinterstore",
+            Command::Zdiffstore(_) => "zdiffstore",
+            Command::Zintercard(_) => "zintercard",
+            Command::Zrangebylex(_) => "zrangebylex",
+            Command::Zrevrangebylex(_) => "zrevrangebylex",
+            Command::Zlexcount(_) => "zlexcount",
+            Command::Zremrangebylex(_) => "zremrangebylex",
             Command::Auth(_) => "auth",
+            Command::Hello(_) => "hello",
             Command::Debug(_) => "debug",
             Command::Cluster(_) => "cluster",
             Command::ReadWrite(_) => "readwrite",
@@ -890,6 +1714,8 @@ impl Command {
             Command::Multi(_) => "multi",
             Command::Exec(_) => "exec",
             Command::Discard(_) => "discard",
+            Command::Watch(_) => "watch",
+            Command::Unwatch(_) => "unwatch",
             Command::Scan(_) => "scan",
             Command::Xscan(_) => "xscan",
             Command::Unknown(cmd) => cmd.get_name(),
@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
 use crate::config::is_use_txn_api;
-use crate::tikv::errors::AsyncResult;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::string::StringCommandCtx;
 use crate::tikv::KEY_ENCODER;
-use crate::utils::resp_invalid_arguments;
+use crate::utils::{resp_err, resp_invalid_arguments};
 use crate::{Connection, Frame, Parse};
 use tikv_client::{KvPair, Transaction};
 use tokio::sync::Mutex;
@@ -16,7 +16,7 @@ use slog::debug;
 
 #[derive(Debug, Clone)]
 pub struct Mset {
-    keys: Vec<String>,
+    keys: Vec<Bytes>,
     vals: Vec<Bytes>,
     valid: bool,
 }
@@ -31,7 +31,7 @@ impl Mset {
     }
 
     /// Get the keys
-    pub fn keys(&self) -> &Vec<String> {
+    pub fn keys(&self) -> &Vec<Bytes> {
         &self.keys
     }
 
@@ -39,7 +39,7 @@ impl Mset {
         &self.vals
     }
 
-    pub fn add_key(&mut self, key: String) {
+    pub fn add_key(&mut self, key: Bytes) {
         self.keys.push(key);
     }
 
@@ -50,7 +50,7 @@ impl Mset {
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Mset> {
         let mut mset = Mset::default();
 
-        while let Ok(key) = parse.next_string() {
+        while let Ok(key) = parse.next_bytes() {
             mset.add_key(key);
             if let Ok(val) = parse.next_bytes() {
                 mset.add_val(val);
@@ -68,14 +68,19 @@ impl Mset {
         }
         let mut mset = Mset::default();
         for idx in (0..argv.len()).step_by(2) {
-            mset.add_key(String::from_utf8_lossy(&argv[idx]).to_string());
+            mset.add_key(argv[idx].clone());
             mset.add_val(argv[idx + 1].clone());
         }
         Ok(mset)
     }
 
-    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
-        let response = self.batch_put(None).await.unwrap_or_else(Into::into);
+    pub(crate) async fn apply(self, dst: &mut Connection, nx: bool) -> crate::Result<()> {
+        let response = if nx {
+            self.batch_put_not_exists(None).await
+        } else {
+            self.batch_put(None).await
+        }
+        .unwrap_or_else(Into::into);
 
         debug!(
             LOGGER,
@@ -118,6 +123,23 @@ impl Mset {
                 .await
         }
     }
+
+    /// Set the keys only if none of them exists, as MSETNX.
+    pub async fn batch_put_not_exists(
+        &self,
+        txn: Option<Arc<Mutex<Transaction>>>,
+    ) -> AsyncResult<Frame> {
+        if !self.valid || self.keys.is_empty() {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_batch_put_not_exists(&self.keys, &self.vals)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
 }
 
 impl Default for Mset {
@@ -139,4 +161,8 @@ impl Invalid for Mset {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -1,13 +1,25 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use slog::{debug, error};
 use tokio::sync::Mutex;
 
+use bytes::Bytes;
+use mlua::Lua;
+use tikv_client::{Timestamp, Transaction};
+
 use crate::{
+    client::Client,
+    cluster::Cluster as Topo,
     config::LOGGER,
-    tikv::{errors::REDIS_EXEC_ERR, get_txn_client},
-    utils::{resp_array, resp_err, resp_invalid_arguments, resp_nil},
-    Command, Connection, Frame,
+    notify::defer_events,
+    tikv::{
+        client::watched_keys_modified,
+        errors::{RTError, REDIS_NOT_ALLOWED_IN_MULTI_ERR},
+        get_txn_client,
+    },
+    utils::{resp_array, resp_err, resp_nil_array, resp_ok},
+    Command, Connection, Db, Frame,
 };
 
 #[derive(Debug, Clone)]
@@ -18,16 +30,84 @@ impl Multi {
         Multi {}
     }
 
-    pub async fn exec(self, dst: &mut Connection, cmds: Vec<Command>) -> crate::Result<()> {
-        let mut resp_arr = Vec::with_capacity(cmds.len());
-
+    /// Run the queued commands in one transaction.
+    ///
+    /// Commands failing at runtime, such as `WRONGTYPE`, reply an error in
+    /// their slot of the reply array and don't stop the others, the
+    /// transaction is only rolled back if the storage itself fails.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn exec(
+        self,
+        dst: &mut Connection,
+        cmds: Vec<Command>,
+        watched_keys: Vec<(Bytes, Timestamp)>,
+        db: &Db,
+        topo: &Topo,
+        cur_client: Arc<Mutex<Client>>,
+        clients: Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>,
+        lua: &Option<Lua>,
+    ) -> crate::Result<()> {
         // create new txn
         let client = get_txn_client()?;
-        let txn = client.begin().await?;
-        let txn_rc = Some(Arc::new(Mutex::new(txn)));
+        let txn = Arc::new(Mutex::new(client.begin().await?));
 
-        let mut response = resp_nil();
-        let mut abort_on_error = false;
+        // abort the transaction with a null reply if any watched key changed
+        if !watched_keys.is_empty() {
+            let response = match watched_keys_modified(&watched_keys, txn.clone()).await {
+                Ok(false) => None,
+                Ok(true) => Some(resp_nil_array()),
+                Err(e) => Some(resp_err(e)),
+            };
+            if let Some(response) = response {
+                txn.lock().await.rollback().await?;
+                debug!(
+                    LOGGER,
+                    "res, {} -> {}, {:?}",
+                    dst.local_addr(),
+                    dst.peer_addr(),
+                    response
+                );
+                dst.write_frame(&response).await?;
+                return Ok(());
+            }
+        }
+
+        // the events of the commands are only published once committed
+        let (result, events) = defer_events(Self::run_queued(
+            cmds, txn, db, topo, cur_client, clients, lua,
+        ))
+        .await;
+        let (response, committed) = result?;
+        if committed {
+            events.emit();
+        }
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+        Ok(())
+    }
+
+    /// Run the queued commands in `txn` and commit it, returns the reply of
+    /// `EXEC` and whether the transaction is committed.
+    async fn run_queued(
+        cmds: Vec<Command>,
+        txn: Arc<Mutex<Transaction>>,
+        db: &Db,
+        topo: &Topo,
+        cur_client: Arc<Mutex<Client>>,
+        clients: Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>,
+        lua: &Option<Lua>,
+    ) -> crate::Result<(Frame, bool)> {
+        let mut resp_arr = Vec::with_capacity(cmds.len());
+        let txn_rc = Some(txn);
+
+        let mut failure = None;
 
         for cmd in cmds {
             let result = match cmd {
@@ -36,14 +116,40 @@ impl Multi {
                 Command::Decr(mut cmd) => cmd.incr_by(txn_rc.clone(), false).await,
                 Command::DecrBy(mut cmd) => cmd.incr_by(txn_rc.clone(), false).await,
                 Command::Strlen(cmd) => cmd.strlen(txn_rc.clone()).await,
+                Command::IncrByFloat(cmd) => cmd.incr_by_float(txn_rc.clone()).await,
+                Command::Append(cmd) => cmd.append(txn_rc.clone()).await,
+                Command::Getrange(cmd) => cmd.getrange(txn_rc.clone()).await,
+                Command::Substr(cmd) => cmd.getrange(txn_rc.clone()).await,
+                Command::Setrange(cmd) => cmd.setrange(txn_rc.clone()).await,
+                Command::Getdel(cmd) => cmd.getdel(txn_rc.clone()).await,
+                Command::Getex(cmd) => cmd.getex(txn_rc.clone()).await,
+                Command::Setbit(cmd) => cmd.setbit(txn_rc.clone()).await,
+                Command::Getbit(cmd) => cmd.getbit(txn_rc.clone()).await,
+                Command::Bitcount(cmd) => cmd.bitcount(txn_rc.clone()).await,
+                Command::Bitpos(cmd) => cmd.bitpos(txn_rc.clone()).await,
+                Command::Bitop(cmd) => cmd.bitop(txn_rc.clone()).await,
+                Command::Bitfield(cmd) => cmd.bitfield(txn_rc.clone()).await,
+                Command::BitfieldRo(cmd) => cmd.bitfield(txn_rc.clone()).await,
+                Command::Pfadd(cmd) => cmd.pfadd(txn_rc.clone()).await,
+                Command::Pfcount(cmd) => cmd.pfcount(txn_rc.clone()).await,
+                Command::Pfmerge(cmd) => cmd.pfmerge(txn_rc.clone()).await,
+                Command::Geoadd(cmd) => cmd.geoadd(txn_rc.clone()).await,
+                Command::Geopos(cmd) => cmd.geopos(txn_rc.clone()).await,
+                Command::Geodist(cmd) => cmd.geodist(txn_rc.clone()).await,
+                Command::Geohash(cmd) => cmd.geohash(txn_rc.clone()).await,
+                Command::Geosearch(cmd) => cmd.geosearch(txn_rc.clone()).await,
+                Command::Geosearchstore(cmd) => cmd.geosearchstore(txn_rc.clone()).await,
                 Command::Del(cmd) => cmd.del(txn_rc.clone()).await,
                 Command::Exists(cmd) => cmd.exists(txn_rc.clone()).await,
                 Command::Get(cmd) => cmd.get(txn_rc.clone()).await,
                 Command::Set(cmd) => cmd.set(txn_rc.clone()).await,
                 Command::SetNX(cmd) => cmd.put_not_exists(txn_rc.clone()).await,
                 Command::SetEX(cmd) => cmd.setex(txn_rc.clone()).await,
+                Command::Psetex(cmd) => cmd.setex(txn_rc.clone()).await,
+                Command::Getset(cmd) => cmd.set(txn_rc.clone()).await,
                 Command::Mget(cmd) => cmd.batch_get(txn_rc.clone()).await,
                 Command::Mset(cmd) => cmd.batch_put(txn_rc.clone()).await,
+                Command::Msetnx(cmd) => cmd.batch_put_not_exists(txn_rc.clone()).await,
                 Command::Type(cmd) => cmd.cmd_type(txn_rc.clone()).await,
                 Command::TTL(cmd) => cmd.ttl(false, txn_rc.clone()).await,
                 Command::PTTL(cmd) => cmd.ttl(true, txn_rc.clone()).await,
@@ -65,17 +171,27 @@ impl Multi {
                 Command::Hincrby(cmd) => cmd.hincrby(txn_rc.clone()).await,
                 Command::Hexists(cmd) => cmd.hexists(txn_rc.clone()).await,
                 Command::Hstrlen(cmd) => cmd.hstrlen(txn_rc.clone()).await,
-                Command::Lpush(cmd) => cmd.push(txn_rc.clone(), true).await,
-                Command::Rpush(cmd) => cmd.push(txn_rc.clone(), false).await,
+                Command::Lpush(cmd) => cmd.push(txn_rc.clone(), true, false).await,
+                Command::Rpush(cmd) => cmd.push(txn_rc.clone(), false, false).await,
+                Command::Lpushx(cmd) => cmd.push(txn_rc.clone(), true, true).await,
+                Command::Rpushx(cmd) => cmd.push(txn_rc.clone(), false, true).await,
                 Command::Lpop(cmd) => cmd.pop(txn_rc.clone(), true).await,
                 Command::Rpop(cmd) => cmd.pop(txn_rc.clone(), false).await,
                 Command::Lrange(cmd) => cmd.lrange(txn_rc.clone()).await,
                 Command::Llen(cmd) => cmd.llen(txn_rc.clone()).await,
                 Command::Lindex(cmd) => cmd.lindex(txn_rc.clone()).await,
+                Command::Lpos(cmd) => cmd.lpos(txn_rc.clone()).await,
                 Command::Lset(cmd) => cmd.lset(txn_rc.clone()).await,
                 Command::Ltrim(cmd) => cmd.ltrim(txn_rc.clone()).await,
                 Command::Lrem(cmd) => cmd.lrem(txn_rc.clone()).await,
                 Command::Linsert(cmd) => cmd.linsert(txn_rc.clone()).await,
+                Command::Blpop(cmd) => cmd.blpop(txn_rc.clone(), true).await,
+                Command::Brpop(cmd) => cmd.blpop(txn_rc.clone(), false).await,
+                Command::Lmpop(cmd) => cmd.lmpop(txn_rc.clone()).await,
+                Command::Blmpop(cmd) => cmd.lmpop(txn_rc.clone()).await,
+                Command::Lmove(cmd) => cmd.lmove(txn_rc.clone()).await,
+                Command::Rpoplpush(cmd) => cmd.lmove(txn_rc.clone()).await,
+                Command::Blmove(cmd) => cmd.lmove(txn_rc.clone()).await,
                 Command::Sadd(cmd) => cmd.sadd(txn_rc.clone()).await,
                 Command::Scard(cmd) => cmd.scard(txn_rc.clone()).await,
                 Command::Sismember(cmd) => cmd.sismember(txn_rc.clone()).await,
@@ -83,6 +199,14 @@ impl Multi {
                 Command::Smembers(cmd) => cmd.smembers(txn_rc.clone()).await,
                 Command::Srandmember(cmd) => cmd.srandmember(txn_rc.clone()).await,
                 Command::Spop(cmd) => cmd.spop(txn_rc.clone()).await,
+                Command::Sinter(cmd) => cmd.sinter(txn_rc.clone()).await,
+                Command::Sunion(cmd) => cmd.sinter(txn_rc.clone()).await,
+                Command::Sdiff(cmd) => cmd.sinter(txn_rc.clone()).await,
+                Command::Sinterstore(cmd) => cmd.sinterstore(txn_rc.clone()).await,
+                Command::Sunionstore(cmd) => cmd.sinterstore(txn_rc.clone()).await,
+                Command::Sdiffstore(cmd) => cmd.sinterstore(txn_rc.clone()).await,
+                Command::Sintercard(cmd) => cmd.sintercard(txn_rc.clone()).await,
+                Command::Smove(cmd) => cmd.smove(txn_rc.clone()).await,
                 Command::Srem(cmd) => cmd.srem(txn_rc.clone()).await,
                 Command::Zadd(cmd) => cmd.zadd(txn_rc.clone()).await,
                 Command::Zcard(cmd) => cmd.zcard(txn_rc.clone()).await,
@@ -97,48 +221,75 @@ impl Multi {
                 Command::Zcount(cmd) => cmd.zcount(txn_rc.clone()).await,
                 Command::Zpopmin(cmd) => cmd.zpop(txn_rc.clone(), true).await,
                 Command::Zpopmax(cmd) => cmd.zpop(txn_rc.clone(), false).await,
+                Command::Bzpopmin(cmd) => cmd.bzpop(txn_rc.clone(), true).await,
+                Command::Bzpopmax(cmd) => cmd.bzpop(txn_rc.clone(), false).await,
+                Command::Bzmpop(cmd) => cmd.zmpop(txn_rc.clone()).await,
                 Command::Zrank(cmd) => cmd.zrank(txn_rc.clone()).await,
                 Command::Zincryby(cmd) => cmd.zincrby(txn_rc.clone()).await,
+                Command::Zunion(cmd) => cmd.zunion(txn_rc.clone()).await,
+                Command::Zinter(cmd) => cmd.zunion(txn_rc.clone()).await,
+                Command::Zdiff(cmd) => cmd.zunion(txn_rc.clone()).await,
+                Command::Zunionstore(cmd) => cmd.zunionstore(txn_rc.clone()).await,
+                Command::Zinterstore(cmd) => cmd.zunionstore(txn_rc.clone()).await,
+                Command::Zdiffstore(cmd) => cmd.zunionstore(txn_rc.clone()).await,
+                Command::Zintercard(cmd) => cmd.zintercard(txn_rc.clone()).await,
+                Command::Zrangebylex(cmd) => cmd.zrangebylex(txn_rc.clone(), false).await,
+                Command::Zrevrangebylex(cmd) => cmd.zrangebylex(txn_rc.clone(), true).await,
+                Command::Zlexcount(cmd) => cmd.zlexcount(txn_rc.clone()).await,
+                Command::Zremrangebylex(cmd) => cmd.zremrangebylex(txn_rc.clone()).await,
                 Command::Scan(cmd) => cmd.scan(txn_rc.clone()).await,
                 Command::Xscan(cmd) => cmd.scan(txn_rc.clone()).await,
-                _ => Ok(resp_invalid_arguments()),
+                Command::Eval(cmd) => cmd.eval(false, db, lua, txn_rc.clone()).await,
+                Command::Evalsha(cmd) => cmd.eval(true, db, lua, txn_rc.clone()).await,
+                Command::Script(cmd) => cmd.script(db).await,
+                Command::Config(cmd) => cmd.config(txn_rc.clone()).await,
+                Command::Ping(cmd) => Ok(cmd.ping()),
+                Command::Publish(cmd) => Ok(cmd.publish(db, topo, false)),
+                Command::PublishLocal(cmd) => Ok(cmd.publish(db, topo, true)),
+                Command::Spublish(cmd) => Ok(cmd.spublish(db, topo)),
+                Command::Pubsub(cmd) => Ok(cmd.pubsub(db)),
+                Command::Debug(cmd) => Ok(cmd.debug()),
+                Command::Cluster(cmd) => Ok(cmd.cluster(topo)),
+                Command::ReadWrite(cmd) => Ok(cmd
+                    .do_apply("readwrite", cur_client.clone(), clients.clone())
+                    .await),
+                Command::ReadOnly(cmd) => Ok(cmd
+                    .do_apply("readonly", cur_client.clone(), clients.clone())
+                    .await),
+                Command::Client(cmd) => Ok(cmd
+                    .do_apply("client", cur_client.clone(), clients.clone())
+                    .await),
+                Command::Info(cmd) => Ok(cmd
+                    .do_apply("info", cur_client.clone(), clients.clone())
+                    .await),
+                Command::Unwatch(_) => Ok(resp_ok()),
+                Command::Unknown(cmd) => Ok(cmd.unknown()),
+                // rejected when queued
+                _ => Ok(resp_err(REDIS_NOT_ALLOWED_IN_MULTI_ERR)),
             };
             match result {
-                Ok(resp) => {
-                    // check response error
-                    match resp {
-                        Frame::ErrorOwned(_) | Frame::ErrorString(_) => {
-                            response = resp_err(REDIS_EXEC_ERR);
-                            abort_on_error = true;
-                            break;
-                        }
-                        _ => resp_arr.push(resp),
-                    }
-                }
+                Ok(resp) => resp_arr.push(resp),
+                // a redis error of the command, rather than of the storage
+                Err(e @ (RTError::String(_) | RTError::Owned(_))) => resp_arr.push(resp_err(e)),
                 Err(e) => {
-                    error!(LOGGER, "EXECABORT {}", e);
-                    response = resp_err(REDIS_EXEC_ERR);
-                    abort_on_error = true;
+                    error!(LOGGER, "EXEC failed {}", e);
+                    failure = Some(e);
                     break;
                 }
             }
         }
 
-        if !abort_on_error {
-            response = resp_array(resp_arr);
-            txn_rc.unwrap().lock().await.commit().await?;
-        } else {
-            txn_rc.unwrap().lock().await.rollback().await?;
-        }
-
-        debug!(
-            LOGGER,
-            "res, {} -> {}, {:?}",
-            dst.local_addr(),
-            dst.peer_addr(),
-            response
-        );
-        dst.write_frame(&response).await?;
-        Ok(())
+        let txn = txn_rc.unwrap();
+        let result = match failure {
+            None => match txn.lock().await.commit().await {
+                Ok(_) => (resp_array(resp_arr), true),
+                Err(e) => (resp_err(e.into()), false),
+            },
+            Some(e) => {
+                txn.lock().await.rollback().await?;
+                (resp_err(e), false)
+            }
+        };
+        Ok(result)
     }
 }
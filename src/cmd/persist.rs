@@ -14,25 +14,25 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Persist {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Persist {
-    pub fn new(key: impl ToString) -> Persist {
+    pub fn new(key: &Bytes) -> Persist {
         Persist {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Persist> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         Ok(Persist { key, valid: true })
     }
@@ -42,7 +42,7 @@ impl Persist {
             return Ok(Persist::new_invalid());
         }
         Ok(Persist {
-            key: String::from_utf8_lossy(&argv[0]).to_string(),
+            key: argv[0].clone(),
             valid: true,
         })
     }
@@ -80,8 +80,12 @@ impl Persist {
 impl Invalid for Persist {
     fn new_invalid() -> Persist {
         Persist {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
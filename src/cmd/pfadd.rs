@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::hyperloglog::HyperLogLogCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Add the elements to the HyperLogLog stored at key.
+#[derive(Debug, Clone)]
+pub struct Pfadd {
+    key: Bytes,
+    elements: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Pfadd {
+    pub fn new(key: &Bytes, elements: &[Bytes]) -> Pfadd {
+        Pfadd {
+            key: key.clone(),
+            elements: elements.to_vec(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// PFADD key [element [element ...]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Pfadd> {
+        let key = parse.next_bytes()?;
+
+        let mut elements = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(element) => elements.push(element),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Pfadd::new_invalid()),
+            }
+        }
+
+        Ok(Pfadd::new(&key, &elements))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Pfadd> {
+        if argv.is_empty() {
+            return Ok(Pfadd::new_invalid());
+        }
+        Ok(Pfadd::new(&argv[0], &argv[1..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.pfadd(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn pfadd(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            HyperLogLogCommandCtx::new(txn)
+                .do_async_txnkv_pfadd(&self.key, &self.elements)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Pfadd {
+    fn new_invalid() -> Pfadd {
+        Pfadd {
+            key: Bytes::new(),
+            elements: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::hyperloglog::HyperLogLogCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the approximated cardinality of the union of the HyperLogLogs
+/// stored at the keys.
+#[derive(Debug, Clone)]
+pub struct Pfcount {
+    keys: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Pfcount {
+    pub fn new(keys: &[Bytes]) -> Pfcount {
+        Pfcount {
+            keys: keys.to_vec(),
+            valid: !keys.is_empty(),
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// PFCOUNT key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Pfcount> {
+        let mut keys = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Pfcount::new_invalid()),
+            }
+        }
+
+        Ok(Pfcount::new(&keys))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Pfcount> {
+        Ok(Pfcount::new(argv))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.pfcount(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn pfcount(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            HyperLogLogCommandCtx::new(txn)
+                .do_async_txnkv_pfcount(&self.keys)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Pfcount {
+    fn new_invalid() -> Pfcount {
+        Pfcount {
+            keys: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
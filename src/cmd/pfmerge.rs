@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::hyperloglog::HyperLogLogCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Merge the HyperLogLogs stored at the source keys into the destination key.
+#[derive(Debug, Clone)]
+pub struct Pfmerge {
+    dest: Bytes,
+    keys: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Pfmerge {
+    pub fn new(dest: &Bytes, keys: &[Bytes]) -> Pfmerge {
+        Pfmerge {
+            dest: dest.clone(),
+            keys: keys.to_vec(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.dest
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// PFMERGE destkey [sourcekey [sourcekey ...]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Pfmerge> {
+        let dest = parse.next_bytes()?;
+
+        let mut keys = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Pfmerge::new_invalid()),
+            }
+        }
+
+        Ok(Pfmerge::new(&dest, &keys))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Pfmerge> {
+        if argv.is_empty() {
+            return Ok(Pfmerge::new_invalid());
+        }
+        Ok(Pfmerge::new(&argv[0], &argv[1..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.pfmerge(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn pfmerge(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            HyperLogLogCommandCtx::new(txn)
+                .do_async_txnkv_pfmerge(&self.dest, &self.keys)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Pfmerge {
+    fn new_invalid() -> Pfmerge {
+        Pfmerge {
+            dest: Bytes::new(),
+            keys: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -56,15 +56,7 @@ impl Ping {
     /// The response is written to `dst`. This is called by the server in order
     /// to execute a received command.
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
-        if !self.valid {
-            dst.write_frame(&resp_invalid_arguments()).await?;
-            return Ok(());
-        }
-
-        let response = match self.msg {
-            None => Frame::Simple("PONG".to_string()),
-            Some(msg) => Frame::Bulk(Bytes::from(msg)),
-        };
+        let response = self.ping();
 
         debug!(
             LOGGER,
@@ -79,6 +71,17 @@ impl Ping {
 
         Ok(())
     }
+
+    pub(crate) fn ping(self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+
+        match self.msg {
+            None => Frame::Simple("PONG".to_string()),
+            Some(msg) => Frame::Bulk(Bytes::from(msg)),
+        }
+    }
 }
 
 impl Default for Ping {
@@ -97,4 +100,8 @@ impl Invalid for Ping {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,21 +15,21 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Pop {
-    key: String,
-    count: i64,
+    key: Bytes,
+    count: Option<i64>,
     valid: bool,
 }
 
 impl Pop {
-    pub fn new(key: &str, count: i64) -> Pop {
+    pub fn new(key: &Bytes, count: Option<i64>) -> Pop {
         Pop {
-            key: key.to_owned(),
+            key: key.clone(),
             count,
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
@@ -37,12 +37,12 @@ impl Pop {
         if argv.is_empty() || argv.len() > 2 {
             return Ok(Pop::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
-        let mut count = 1;
+        let key = &argv[0];
+        let mut count = None;
         if argv.len() == 2 {
             match String::from_utf8_lossy(&argv[1]).parse::<i64>() {
-                Ok(v) => count = v,
-                Err(_) => {
+                Ok(v) if v >= 0 => count = Some(v),
+                _ => {
                     return Ok(Pop::new_invalid());
                 }
             }
@@ -50,12 +50,21 @@ impl Pop {
         Ok(Pop::new(key, count))
     }
 
+    /// # Format
+    ///
+    /// ```text
+    /// LPOP key [count]
+    /// RPOP key [count]
+    /// ```
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Pop> {
-        let key = parse.next_string()?;
-        let mut count = 1;
+        let key = parse.next_bytes()?;
+        let mut count = None;
 
-        if let Ok(n) = parse.next_int() {
-            count = n;
+        if let Ok(arg) = parse.next_bytes() {
+            match String::from_utf8_lossy(&arg).parse::<i64>() {
+                Ok(n) if n >= 0 => count = Some(n),
+                _ => return Ok(Pop::new_invalid()),
+            }
         }
 
         let pop = Pop::new(&key, count);
@@ -98,9 +107,13 @@ impl Pop {
 impl Invalid for Pop {
     fn new_invalid() -> Pop {
         Pop {
-            key: "".to_owned(),
-            count: 0,
+            key: Bytes::new(),
+            count: None,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
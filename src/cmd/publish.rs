@@ -1,9 +1,10 @@
+use crate::cluster::Cluster as Topo;
 use crate::{Connection, Db, Frame, Parse};
 
 use bytes::Bytes;
 
-use super::Invalid;
-use crate::utils::resp_invalid_arguments;
+use super::{Invalid, Unknown};
+use crate::utils::{resp_err, resp_invalid_arguments};
 
 /// Posts a message to the given channel.
 ///
@@ -15,20 +16,24 @@ use crate::utils::resp_invalid_arguments;
 #[derive(Debug, Clone)]
 pub struct Publish {
     /// Name of the channel on which the message should be published.
-    channel: String,
+    channel: Bytes,
 
     /// The message to publish.
     message: Bytes,
+
+    /// The secret of the cluster carried by `PUBLISHLOCAL`.
+    secret: Bytes,
     valid: bool,
 }
 
 impl Publish {
     #[allow(dead_code)]
     /// Create a new `Publish` command which sends `message` on `channel`.
-    pub(crate) fn new(channel: impl ToString, message: Bytes) -> Publish {
+    pub(crate) fn new(channel: Bytes, message: Bytes) -> Publish {
         Publish {
-            channel: channel.to_string(),
+            channel,
             message,
+            secret: Bytes::new(),
             valid: true,
         }
     }
@@ -57,8 +62,8 @@ impl Publish {
         // The `PUBLISH` string has already been consumed. Extract the `channel`
         // and `message` values from the frame.
         //
-        // The `channel` must be a valid string.
-        let channel = parse.next_string()?;
+        // The `channel` is arbitrary bytes as well.
+        let channel = parse.next_bytes()?;
 
         // The `message` is arbitrary bytes.
         let message = parse.next_bytes()?;
@@ -66,18 +71,66 @@ impl Publish {
         Ok(Publish {
             channel,
             message,
+            secret: Bytes::new(),
             valid: true,
         })
     }
 
+    /// Parse a `Publish` instance forwarded by a peer.
+    ///
+    /// ```text
+    /// PUBLISHLOCAL secret channel message
+    /// ```
+    pub(crate) fn parse_local_frames(parse: &mut Parse) -> crate::Result<Publish> {
+        let secret = parse.next_bytes()?;
+        let mut publish = Publish::parse_frames(parse)?;
+        publish.secret = secret;
+        Ok(publish)
+    }
+
     /// Apply the `Publish` command to the specified `Db` instance.
     ///
     /// The response is written to `dst`. This is called by the server in order
     /// to execute a received command.
-    pub(crate) async fn apply(self, db: &Db, dst: &mut Connection) -> crate::Result<()> {
+    /// If `local` is set, which is the case of `PUBLISHLOCAL` forwarded by
+    /// another node, the message is not forwarded to the other nodes again.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        topo: &Topo,
+        dst: &mut Connection,
+        local: bool,
+    ) -> crate::Result<()> {
+        let response = self.publish(db, topo, local);
+
+        // Write the frame to the client.
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Apply the `Publish` command as `SPUBLISH` to a shard channel.
+    pub(crate) async fn apply_shard(
+        self,
+        db: &Db,
+        topo: &Topo,
+        dst: &mut Connection,
+    ) -> crate::Result<()> {
+        let response = self.spublish(db, topo);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub(crate) fn publish(self, db: &Db, topo: &Topo, local: bool) -> Frame {
+        // `PUBLISHLOCAL` is hidden from the clients, which don't know the secret
+        if local && !topo.peers().is_secret(&self.secret) {
+            return Unknown::new("publishlocal").unknown();
+        }
+
         if !self.valid {
-            dst.write_frame(&resp_invalid_arguments()).await?;
-            return Ok(());
+            return resp_invalid_arguments();
         }
 
         // The shared state contains the `tokio::sync::broadcast::Sender` for
@@ -89,16 +142,36 @@ impl Publish {
         // receive the message. Subscribers may drop before receiving the
         // message. Given this, `num_subscribers` should only be used as a
         // "hint".
-        let num_subscribers = db.publish(&self.channel, self.message);
+        let num_subscribers = db.publish(&self.channel, self.message.clone());
+
+        // Subscribers may be connected to any node of the cluster, so the
+        // message is forwarded to all the other nodes as well, in background
+        // rather than holding the client or its transaction. Only the local
+        // subscribers are counted, as redis cluster does.
+        if !local {
+            topo.forward_to_peers(self.channel, self.message);
+        }
 
         // The number of subscribers is returned as the response to the publish
         // request.
-        let response = Frame::Integer(num_subscribers as i64);
+        Frame::Integer(num_subscribers as i64)
+    }
 
-        // Write the frame to the client.
-        dst.write_frame(&response).await?;
+    /// Publish the message to a shard channel. The subscribers of a shard
+    /// channel are all connected to the owner of its slot, so the message is
+    /// only delivered locally and clients are redirected to the owner by
+    /// `MOVED` if it is not myself.
+    pub(crate) fn spublish(self, db: &Db, topo: &Topo) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
 
-        Ok(())
+        if let Some(e) = topo.moved_error(&self.channel) {
+            return resp_err(e);
+        }
+
+        let num_subscribers = db.spublish(&self.channel, self.message);
+        Frame::Integer(num_subscribers as i64)
     }
 
     #[allow(dead_code)]
@@ -109,7 +182,7 @@ impl Publish {
     pub(crate) fn into_frame(self) -> Frame {
         let mut frame = Frame::array();
         frame.push_bulk(Bytes::from("publish".as_bytes()));
-        frame.push_bulk(Bytes::from(self.channel.into_bytes()));
+        frame.push_bulk(self.channel);
         frame.push_bulk(self.message);
 
         frame
@@ -119,9 +192,14 @@ impl Publish {
 impl Invalid for Publish {
     fn new_invalid() -> Self {
         Publish {
-            channel: "".to_string(),
+            channel: Bytes::new(),
             message: Bytes::from(""),
+            secret: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
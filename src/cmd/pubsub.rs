@@ -0,0 +1,117 @@
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::tikv::errors::REDIS_UNKNOWN_SUBCOMMAND;
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_int, resp_invalid_arguments};
+use crate::{Connection, Db, Frame, Parse, ParseError};
+use bytes::Bytes;
+use slog::debug;
+
+/// Introspect the state of the pub/sub of this node.
+///
+/// Only the subscriptions of the clients connected to this node are reported,
+/// as Redis Cluster does.
+#[derive(Debug, Clone)]
+pub struct Pubsub {
+    subcommand: String,
+    args: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Pubsub {
+    pub fn new(subcommand: impl ToString, args: Vec<Bytes>) -> Pubsub {
+        Pubsub {
+            subcommand: subcommand.to_string(),
+            args,
+            valid: true,
+        }
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// PUBSUB CHANNELS [pattern]
+    /// PUBSUB NUMSUB [channel [channel ...]]
+    /// PUBSUB NUMPAT
+    /// PUBSUB SHARDCHANNELS [pattern]
+    /// PUBSUB SHARDNUMSUB [shardchannel [shardchannel ...]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Pubsub> {
+        let subcommand = parse.next_string()?;
+
+        let mut args = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => args.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Pubsub::new_invalid()),
+            }
+        }
+
+        Ok(Pubsub::new(subcommand, args))
+    }
+
+    pub(crate) async fn apply(self, db: &Db, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.pubsub(db);
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub(crate) fn pubsub(&self, db: &Db) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+
+        match self.subcommand.to_uppercase().as_str() {
+            "CHANNELS" if self.args.len() <= 1 => self.channels(db, false),
+            "SHARDCHANNELS" if self.args.len() <= 1 => self.channels(db, true),
+            "NUMSUB" => self.numsub(db, false),
+            "SHARDNUMSUB" => self.numsub(db, true),
+            "NUMPAT" if self.args.is_empty() => resp_int(db.pubsub_numpat() as i64),
+            _ => resp_err(REDIS_UNKNOWN_SUBCOMMAND),
+        }
+    }
+
+    fn channels(&self, db: &Db, shard: bool) -> Frame {
+        let pattern = self.args.first().map(|pattern| &pattern[..]);
+        let channels = db
+            .pubsub_channels(pattern, shard)
+            .into_iter()
+            .map(|channel| resp_bulk(channel.to_vec()))
+            .collect();
+        resp_array(channels)
+    }
+
+    fn numsub(&self, db: &Db, shard: bool) -> Frame {
+        let counts = db.pubsub_numsub(&self.args, shard);
+        let mut resp = Vec::with_capacity(self.args.len() * 2);
+        for (channel, count) in self.args.iter().zip(counts) {
+            resp.push(resp_bulk(channel.to_vec()));
+            resp.push(resp_int(count as i64));
+        }
+        resp_array(resp)
+    }
+}
+
+impl Invalid for Pubsub {
+    fn new_invalid() -> Pubsub {
+        Pubsub {
+            subcommand: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
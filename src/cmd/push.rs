@@ -15,21 +15,21 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Push {
-    key: String,
+    key: Bytes,
     items: Vec<Bytes>,
     valid: bool,
 }
 
 impl Push {
-    pub fn new(key: &str) -> Push {
+    pub fn new(key: &Bytes) -> Push {
         Push {
             items: vec![],
-            key: key.to_owned(),
+            key: key.clone(),
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
@@ -42,7 +42,7 @@ impl Push {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Push> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut push = Push::new(&key);
 
         while let Ok(item) = parse.next_bytes() {
@@ -56,7 +56,7 @@ impl Push {
         if argv.len() < 2 {
             return Ok(Push::new_invalid());
         }
-        let mut push = Push::new(&String::from_utf8_lossy(&argv[0]));
+        let mut push = Push::new(&argv[0]);
 
         for arg in &argv[1..] {
             push.add_item(arg.to_owned());
@@ -65,8 +65,15 @@ impl Push {
         Ok(push)
     }
 
-    pub(crate) async fn apply(self, dst: &mut Connection, op_left: bool) -> crate::Result<()> {
-        let response = self.push(None, op_left).await?;
+    /// Push the items, or do nothing if the list does not exist and `exists`
+    /// is set, as `LPUSHX` and `RPUSHX` do.
+    pub(crate) async fn apply(
+        self,
+        dst: &mut Connection,
+        op_left: bool,
+        exists: bool,
+    ) -> crate::Result<()> {
+        let response = self.push(None, op_left, exists).await?;
         debug!(
             LOGGER,
             "res, {} -> {}, {:?}",
@@ -83,13 +90,14 @@ impl Push {
         &self,
         txn: Option<Arc<Mutex<Transaction>>>,
         op_left: bool,
+        exists: bool,
     ) -> AsyncResult<Frame> {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
         if is_use_txn_api() {
             ListCommandCtx::new(txn)
-                .do_async_txnkv_push(&self.key, &self.items, op_left)
+                .do_async_txnkv_push(&self.key, &self.items, op_left, exists)
                 .await
         } else {
             Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
@@ -101,8 +109,12 @@ impl Invalid for Push {
     fn new_invalid() -> Push {
         Push {
             items: vec![],
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,37 +15,37 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Sadd {
-    key: String,
-    members: Vec<String>,
+    key: Bytes,
+    members: Vec<Bytes>,
     valid: bool,
 }
 
 impl Sadd {
-    pub fn new(key: &str) -> Sadd {
+    pub fn new(key: &Bytes) -> Sadd {
         Sadd {
-            key: key.to_string(),
+            key: key.clone(),
             members: vec![],
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
-    pub fn add_member(&mut self, member: &str) {
-        self.members.push(member.to_string());
+    pub fn add_member(&mut self, member: &Bytes) {
+        self.members.push(member.clone());
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Sadd> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut sadd = Sadd::new(&key);
-        while let Ok(member) = parse.next_string() {
+        while let Ok(member) = parse.next_bytes() {
             sadd.add_member(&member);
         }
         Ok(sadd)
@@ -55,10 +55,10 @@ impl Sadd {
         if argv.len() < 2 {
             return Ok(Sadd::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let mut sadd = Sadd::new(key);
         for arg in &argv[1..] {
-            sadd.add_member(&String::from_utf8_lossy(arg));
+            sadd.add_member(arg);
         }
         Ok(sadd)
     }
@@ -94,9 +94,13 @@ impl Sadd {
 impl Invalid for Sadd {
     fn new_invalid() -> Sadd {
         Sadd {
-            key: "".to_string(),
+            key: Bytes::new(),
             members: vec![],
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
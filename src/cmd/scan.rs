@@ -15,14 +15,14 @@ use crate::config::LOGGER;
 
 #[derive(Debug, Clone)]
 pub struct Scan {
-    start: String,
+    start: Bytes,
     count: i64,
     regex: String,
     valid: bool,
 }
 
 impl Scan {
-    pub fn new(start: String, count: i64, regex: String) -> Scan {
+    pub fn new(start: Bytes, count: i64, regex: String) -> Scan {
         Scan {
             start,
             count,
@@ -36,7 +36,7 @@ impl Scan {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Scan> {
-        let start = parse.next_string()?;
+        let start = parse.next_bytes()?;
         let mut count = 10;
         let mut regex = ".*?".to_owned();
         while let Ok(flag) = parse.next_string() {
@@ -64,7 +64,7 @@ impl Scan {
 
         let mut count = 10;
         let mut regex = ".*?".to_owned();
-        let start = String::from_utf8_lossy(&argv[0]);
+        let start = argv[0].clone();
         if argv.len() >= 3 {
             if argv[1].to_ascii_uppercase() == b"COUNT" {
                 if let Ok(c) = String::from_utf8_lossy(&argv[2]).parse::<i64>() {
@@ -93,7 +93,7 @@ impl Scan {
         }
 
         Ok(Scan {
-            start: start.to_string(),
+            start,
             count,
             regex,
             valid: true,
@@ -131,10 +131,14 @@ impl Scan {
 impl Invalid for Scan {
     fn new_invalid() -> Scan {
         Scan {
-            start: "".to_owned(),
+            start: Bytes::new(),
             count: 0,
             regex: "".to_owned(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,29 +15,29 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Scard {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Scard {
-    pub fn new(key: &str) -> Scard {
+    pub fn new(key: &Bytes) -> Scard {
         Scard {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Scard> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         Ok(Scard::new(&key))
     }
 
@@ -45,7 +45,7 @@ impl Scard {
         if argv.len() != 1 {
             return Ok(Scard::new_invalid());
         }
-        Ok(Scard::new(&String::from_utf8_lossy(&argv[0])))
+        Ok(Scard::new(&argv[0]))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -79,8 +79,12 @@ impl Scard {
 impl Invalid for Scard {
     fn new_invalid() -> Scard {
         Scard {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -148,4 +148,8 @@ impl Invalid for Script {
             valid: true,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
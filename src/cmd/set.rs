@@ -1,8 +1,8 @@
 use crate::cmd::{Parse, ParseError};
 use crate::config::is_use_txn_api;
-use crate::tikv::errors::AsyncResult;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::string::StringCommandCtx;
-use crate::utils::{resp_invalid_arguments, timestamp_from_ttl};
+use crate::utils::{resp_err, resp_invalid_arguments, timestamp_from_ttl};
 use crate::{Connection, Frame};
 
 use crate::config::LOGGER;
@@ -18,7 +18,7 @@ use super::Invalid;
 ///
 /// If `key` already holds a value, it is overwritten, regardless of its type.
 /// Any previous time to live associated with the key is discarded on successful
-/// SET operation.
+/// SET operation, unless KEEPTTL is given.
 ///
 /// # Options
 ///
@@ -26,10 +26,16 @@ use super::Invalid;
 ///
 /// * EX `seconds` -- Set the specified expire time, in seconds.
 /// * PX `milliseconds` -- Set the specified expire time, in milliseconds.
+/// * EXAT `timestamp` -- Set the specified Unix time the key will expire at, in seconds.
+/// * PXAT `timestamp` -- Set the specified Unix time the key will expire at, in milliseconds.
+/// * NX -- Only set the key if it does not already exist.
+/// * XX -- Only set the key if it already exists.
+/// * KEEPTTL -- Retain the time to live associated with the key.
+/// * GET -- Return the old string stored at key, or nil if key did not exist.
 #[derive(Debug, Clone)]
 pub struct Set {
     /// the lookup key
-    key: String,
+    key: Bytes,
 
     /// the value to be stored
     value: Bytes,
@@ -37,9 +43,21 @@ pub struct Set {
     /// When to expire the key
     expire: Option<i64>,
 
+    /// Unix time in milliseconds to expire the key at
+    expire_at: Option<i64>,
+
+    /// Retain the ttl of the old value
+    keep_ttl: bool,
+
     /// Set if key is not present
     nx: Option<bool>,
 
+    /// Set if key is present
+    xx: bool,
+
+    /// Reply the old value
+    get: bool,
+
     valid: bool,
 }
 
@@ -48,18 +66,29 @@ impl Set {
     ///
     /// If `expire` is `Some`, the value should expire after the specified
     /// duration.
-    pub fn new(key: impl ToString, value: Bytes, expire: Option<i64>) -> Set {
+    pub fn new(key: &Bytes, value: Bytes, expire: Option<i64>) -> Set {
         Set {
-            key: key.to_string(),
+            key: key.clone(),
             value,
             expire,
+            expire_at: None,
+            keep_ttl: false,
             nx: None,
+            xx: false,
+            get: false,
             valid: true,
         }
     }
 
+    /// Create a `GETSET` command, which is `SET key value GET`.
+    pub fn new_getset(key: &Bytes, value: Bytes) -> Set {
+        let mut set = Set::new(key, value, None);
+        set.get = true;
+        set
+    }
+
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
@@ -91,103 +120,87 @@ impl Set {
     /// Expects an array frame containing at least 3 entries.
     ///
     /// ```text
-    /// SET key value [EX seconds|PX milliseconds]
+    /// SET key value [NX|XX] [GET] [EX seconds|PX milliseconds|EXAT timestamp|PXAT timestamp|KEEPTTL]
     /// ```
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Set> {
-        use ParseError::EndOfStream;
-
         // Read the key to set. This is a required field
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         // Read the value to set. This is a required field.
         let value = parse.next_bytes()?;
 
-        // The expiration is optional. If nothing else follows, then it is
-        // `None`.
-        let mut expire = None;
-
-        let mut nx = None;
-
-        // Attempt to parse another string.
-        match parse.next_string() {
-            Ok(s) if s.to_uppercase() == "EX" => {
-                // An expiration is specified in seconds. The next value is an
-                // integer.
-                let secs = parse.next_int()?;
-                expire = Some(secs * 1000);
-            }
-            Ok(s) if s.to_uppercase() == "PX" => {
-                // An expiration is specified in milliseconds. The next value is
-                // an integer.
-                let ms = parse.next_int()?;
-                expire = Some(ms);
-            }
-            Ok(s) if s.to_uppercase() == "NX" => {
-                // Only set if key not present
-                nx = Some(true);
+        // The options are optional, collect them until the end of stream.
+        let mut options = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(option) => options.push(option),
+                Err(ParseError::EndOfStream) => break,
+                // All other errors are bubbled up, resulting in the connection
+                // being terminated.
+                Err(err) => return Err(err.into()),
             }
-            Ok(_) => return Err("currently `SET` only supports the expiration option".into()),
-            // The `EndOfStream` error indicates there is no further data to
-            // parse. In this case, it is a normal run time situation and
-            // indicates there are no specified `SET` options.
-            Err(EndOfStream) => {}
-            // All other errors are bubbled up, resulting in the connection
-            // being terminated.
-            Err(err) => return Err(err.into()),
         }
 
-        Ok(Set {
-            key,
-            value,
-            expire,
-            nx,
-            valid: true,
-        })
+        Ok(Set::new(&key, value, None).with_options(&options))
     }
 
-    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Set> {
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Set> {
         if argv.len() < 2 {
             return Ok(Set::new_invalid());
         }
-        let key = String::from_utf8_lossy(&argv[0]).to_string();
-        let value = argv[1].clone();
-        let mut expire = None;
-        let mut nx = None;
-        let mut idx = 2;
-        loop {
-            if idx >= argv.len() {
-                break;
-            }
-            let flag = String::from_utf8_lossy(&argv[idx]).to_uppercase();
-            if flag == "EX" {
-                idx += 1;
-                let secs = String::from_utf8_lossy(&argv[idx]).parse::<i64>();
-                if let Ok(v) = secs {
-                    expire = Some(v * 1000);
-                } else {
-                    return Ok(Set::new_invalid());
-                }
-            } else if flag == "PX" {
-                idx += 1;
-                let ms = String::from_utf8_lossy(&argv[idx]).parse::<i64>();
-                if let Ok(v) = ms {
-                    expire = Some(v);
+        Ok(Set::new(&argv[0], argv[1].clone(), None).with_options(&argv[2..]))
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// GETSET key value
+    /// ```
+    pub(crate) fn parse_getset_frames(parse: &mut Parse) -> crate::Result<Set> {
+        let key = parse.next_bytes()?;
+        let value = parse.next_bytes()?;
+        Ok(Set::new_getset(&key, value))
+    }
+
+    pub(crate) fn parse_getset_argv(argv: &[Bytes]) -> crate::Result<Set> {
+        if argv.len() != 2 {
+            return Ok(Set::new_invalid());
+        }
+        Ok(Set::new_getset(&argv[0], argv[1].clone()))
+    }
+
+    fn with_options(mut self, options: &[Bytes]) -> Set {
+        let mut iter = options.iter();
+        while let Some(option) = iter.next() {
+            let option = String::from_utf8_lossy(option).to_uppercase();
+            let has_expire = self.expire.is_some() || self.expire_at.is_some() || self.keep_ttl;
+            match option.as_str() {
+                "NX" if !self.xx => self.nx = Some(true),
+                "XX" if self.nx.is_none() => self.xx = true,
+                "GET" => self.get = true,
+                "KEEPTTL" if !has_expire => self.keep_ttl = true,
+                "EX" | "PX" | "EXAT" | "PXAT" if !has_expire => {
+                    let value = match iter
+                        .next()
+                        .map(|v| String::from_utf8_lossy(v).parse::<i64>())
+                    {
+                        Some(Ok(v)) if v > 0 => v,
+                        _ => return Set::new_invalid(),
+                    };
+                    match option.as_str() {
+                        "EX" => self.expire = value.checked_mul(1000),
+                        "PX" => self.expire = Some(value),
+                        "EXAT" => self.expire_at = value.checked_mul(1000),
+                        _ => self.expire_at = Some(value),
+                    }
+                    if self.expire.is_none() && self.expire_at.is_none() {
+                        return Set::new_invalid();
+                    }
                 }
-            } else if flag == "NX" {
-                nx = Some(true);
-            } else {
-                return Ok(Set::new_invalid());
+                _ => return Set::new_invalid(),
             }
-
-            idx += 1;
         }
-        Ok(Set {
-            key,
-            value,
-            expire,
-            nx,
-            valid: true,
-        })
+        self
     }
 
     /// Apply the `Set` command to the specified `Db` instance.
@@ -213,13 +226,38 @@ impl Set {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
+        // plain SET and SETNX go through the fast paths
+        let plain = !(self.xx || self.get || self.keep_ttl || self.expire_at.is_some());
         Ok(match self.nx {
-            Some(_) => self.put_not_exists(txn).await,
-            None => self.put(txn).await,
+            None if plain => self.put(txn).await,
+            Some(_) if plain && self.expire.is_none() => self.put_not_exists(txn).await,
+            _ => self.set_with_options(txn).await,
         }
         .unwrap_or_else(Into::into))
     }
 
+    async fn set_with_options(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !is_use_txn_api() {
+            return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
+        }
+        let timestamp = match (self.expire, self.expire_at) {
+            _ if self.keep_ttl => None,
+            (Some(ttl), _) => Some(timestamp_from_ttl(ttl as u64)),
+            (_, Some(ts)) => Some(ts as u64),
+            _ => Some(0),
+        };
+        StringCommandCtx::new(txn)
+            .do_async_txnkv_set(
+                &self.key,
+                &self.value,
+                timestamp,
+                self.nx.is_some(),
+                self.xx,
+                self.get,
+            )
+            .await
+    }
+
     async fn put_not_exists(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
         if is_use_txn_api() {
             StringCommandCtx::new(txn)
@@ -252,11 +290,19 @@ impl Set {
 impl Invalid for Set {
     fn new_invalid() -> Set {
         Set {
-            key: "".to_owned(),
+            key: Bytes::new(),
             value: Bytes::new(),
             expire: None,
+            expire_at: None,
+            keep_ttl: false,
             nx: None,
+            xx: false,
+            get: false,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
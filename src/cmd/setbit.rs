@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::bitmap::BitmapCommandCtx;
+use crate::tikv::errors::{
+    AsyncResult, REDIS_BIT_OFFSET_OUT_OF_RANGE_ERR, REDIS_BIT_VALUE_OUT_OF_RANGE_ERR,
+    REDIS_NOT_SUPPORTED_ERR,
+};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Set or clear the bit at offset in the string value stored at key.
+#[derive(Debug, Clone)]
+pub struct Setbit {
+    key: Bytes,
+    offset: Option<u64>,
+    on: Option<bool>,
+    valid: bool,
+}
+
+impl Setbit {
+    pub fn new(key: &Bytes, offset: &[u8], value: &[u8]) -> Setbit {
+        Setbit {
+            key: key.clone(),
+            offset: parse_bit_offset(offset),
+            on: match value {
+                b"0" => Some(false),
+                b"1" => Some(true),
+                _ => None,
+            },
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// SETBIT key offset value
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Setbit> {
+        let key = parse.next_bytes()?;
+        let offset = parse.next_bytes()?;
+        let value = parse.next_bytes()?;
+        Ok(Setbit::new(&key, &offset, &value))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Setbit> {
+        if argv.len() != 3 {
+            return Ok(Setbit::new_invalid());
+        }
+        Ok(Setbit::new(&argv[0], &argv[1], &argv[2]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.setbit(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn setbit(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let offset = match self.offset {
+            Some(offset) => offset,
+            None => return Ok(resp_err(REDIS_BIT_OFFSET_OUT_OF_RANGE_ERR)),
+        };
+        let on = match self.on {
+            Some(on) => on,
+            None => return Ok(resp_err(REDIS_BIT_VALUE_OUT_OF_RANGE_ERR)),
+        };
+        if is_use_txn_api() {
+            BitmapCommandCtx::new(txn)
+                .do_async_txnkv_setbit(&self.key, offset, on)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+/// Parse a bit offset, which must fit in a 512MB string.
+pub(crate) fn parse_bit_offset(offset: &[u8]) -> Option<u64> {
+    String::from_utf8_lossy(offset)
+        .parse::<u64>()
+        .ok()
+        .filter(|offset| *offset < 1 << 32)
+}
+
+impl Invalid for Setbit {
+    fn new_invalid() -> Setbit {
+        Setbit {
+            key: Bytes::new(),
+            offset: None,
+            on: None,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
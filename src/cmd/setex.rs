@@ -15,7 +15,7 @@ use tokio::sync::Mutex;
 #[derive(Debug, Clone)]
 pub struct SetEX {
     /// the lookup key
-    key: String,
+    key: Bytes,
 
     /// the value to be stored
     value: Bytes,
@@ -31,9 +31,9 @@ impl SetEX {
     ///
     /// If `expire` is `Some`, the value should expire after the specified
     /// duration.
-    pub fn new(key: impl ToString, value: Bytes, expire: i64) -> SetEX {
+    pub fn new(key: &Bytes, value: Bytes, expire: i64) -> SetEX {
         SetEX {
-            key: key.to_string(),
+            key: key.clone(),
             value,
             expire,
             valid: true,
@@ -42,7 +42,7 @@ impl SetEX {
 
     pub fn new_invalid() -> SetEX {
         SetEX {
-            key: "".to_owned(),
+            key: Bytes::new(),
             value: Bytes::new(),
             expire: 0,
             valid: false,
@@ -50,7 +50,7 @@ impl SetEX {
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
@@ -64,14 +64,15 @@ impl SetEX {
         self.expire
     }
 
-    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<SetEX> {
+    /// Parse SETEX, or PSETEX if `is_millis` is set.
+    pub(crate) fn parse_frames(parse: &mut Parse, is_millis: bool) -> crate::Result<SetEX> {
         // Read the key to set. This is a required field
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         // Read the ttl to set.
         let uexpire = parse.next_int()?;
 
-        let expire = uexpire * 1000;
+        let expire = if is_millis { uexpire } else { uexpire * 1000 };
 
         // Read the value to set. This is a required field.
         let value = parse.next_bytes()?;
@@ -84,16 +85,17 @@ impl SetEX {
         })
     }
 
-    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<SetEX> {
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>, is_millis: bool) -> crate::Result<SetEX> {
         if argv.len() != 3 {
             return Ok(SetEX::new_invalid());
         }
-        let key = String::from_utf8_lossy(&argv[0]).to_string();
+        let key = argv[0].clone();
         let expire = String::from_utf8_lossy(&argv[1]).parse::<i64>();
         let value = argv[2].clone();
 
         if let Ok(v) = expire {
-            return Ok(SetEX::new(key, value, v * 1000));
+            let expire = if is_millis { v } else { v * 1000 };
+            return Ok(SetEX::new(&key, value, expire));
         }
         Ok(SetEX::new_invalid())
     }
@@ -113,7 +115,7 @@ impl SetEX {
     }
 
     pub async fn setex(self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
-        if !self.valid {
+        if !self.valid || self.expire <= 0 {
             return Ok(resp_invalid_arguments());
         }
         if is_use_txn_api() {
@@ -130,10 +132,14 @@ impl SetEX {
 impl Invalid for SetEX {
     fn new_invalid() -> SetEX {
         SetEX {
-            key: "".to_owned(),
+            key: Bytes::new(),
             value: Bytes::new(),
             expire: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -28,7 +28,7 @@ use tokio::sync::Mutex;
 #[derive(Debug, Clone)]
 pub struct SetNX {
     /// the lookup key
-    key: String,
+    key: Bytes,
 
     /// the value to be stored
     value: Bytes,
@@ -41,9 +41,9 @@ impl SetNX {
     ///
     /// If `expire` is `Some`, the value should expire after the specified
     /// duration.
-    pub fn new(key: impl ToString, value: Bytes) -> SetNX {
+    pub fn new(key: &Bytes, value: Bytes) -> SetNX {
         SetNX {
-            key: key.to_string(),
+            key: key.clone(),
             value,
             valid: true,
         }
@@ -51,14 +51,14 @@ impl SetNX {
 
     pub fn new_invalid() -> SetNX {
         SetNX {
-            key: "".to_owned(),
+            key: Bytes::new(),
             value: Bytes::new(),
             valid: false,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
@@ -69,7 +69,7 @@ impl SetNX {
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<SetNX> {
         // Read the key to set. This is a required field
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         // Read the value to set. This is a required field.
         let value = parse.next_bytes()?;
@@ -85,7 +85,7 @@ impl SetNX {
         if argv.len() != 2 {
             return Ok(SetNX::new_invalid());
         }
-        let key = String::from_utf8_lossy(&argv[0]).to_string();
+        let key = argv[0].clone();
         let value = argv[1].clone();
 
         Ok(SetNX {
@@ -129,9 +129,13 @@ impl SetNX {
 impl Invalid for SetNX {
     fn new_invalid() -> SetNX {
         SetNX {
-            key: "".to_owned(),
+            key: Bytes::new(),
             value: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
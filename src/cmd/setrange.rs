@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR, REDIS_OFFSET_OUT_OF_RANGE_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Overwrite part of the string stored at key, starting at the specified offset.
+#[derive(Debug, Clone)]
+pub struct Setrange {
+    key: Bytes,
+    offset: i64,
+    value: Bytes,
+    valid: bool,
+}
+
+impl Setrange {
+    pub fn new(key: &Bytes, offset: i64, value: &Bytes) -> Setrange {
+        Setrange {
+            key: key.clone(),
+            offset,
+            value: value.clone(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// SETRANGE key offset value
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Setrange> {
+        let key = parse.next_bytes()?;
+        let offset = parse.next_int()?;
+        let value = parse.next_bytes()?;
+        Ok(Setrange::new(&key, offset, &value))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Setrange> {
+        if argv.len() != 3 {
+            return Ok(Setrange::new_invalid());
+        }
+        match String::from_utf8_lossy(&argv[1]).parse::<i64>() {
+            Ok(offset) => Ok(Setrange::new(&argv[0], offset, &argv[2])),
+            Err(_) => Ok(Setrange::new_invalid()),
+        }
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.setrange(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn setrange(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if self.offset < 0 {
+            return Ok(resp_err(REDIS_OFFSET_OUT_OF_RANGE_ERR));
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_setrange(&self.key, self.offset as usize, &self.value)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Setrange {
+    fn new_invalid() -> Setrange {
+        Setrange {
+            key: Bytes::new(),
+            offset: 0,
+            value: Bytes::new(),
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
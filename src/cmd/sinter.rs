@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::set::{SetCommandCtx, SetOperation};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the members of the intersection, union or difference of the sets,
+/// used by SINTER, SUNION and SDIFF.
+#[derive(Debug, Clone)]
+pub struct Sinter {
+    op: SetOperation,
+    keys: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Sinter {
+    pub fn new(op: SetOperation, keys: &[Bytes]) -> Sinter {
+        if keys.is_empty() {
+            return Sinter::new_invalid();
+        }
+        Sinter {
+            op,
+            keys: keys.to_vec(),
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// SINTER key [key ...]
+    /// SUNION key [key ...]
+    /// SDIFF key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse, op: SetOperation) -> crate::Result<Sinter> {
+        let mut keys = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Sinter::new_invalid()),
+            }
+        }
+
+        Ok(Sinter::new(op, &keys))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes], op: SetOperation) -> crate::Result<Sinter> {
+        Ok(Sinter::new(op, argv))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.sinter(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn sinter(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            SetCommandCtx::new(txn)
+                .do_async_txnkv_set_algebra(self.op, &self.keys)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Sinter {
+    fn new_invalid() -> Sinter {
+        Sinter {
+            op: SetOperation::Inter,
+            keys: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::set::SetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the cardinality of the intersection of the sets.
+#[derive(Debug, Clone)]
+pub struct Sintercard {
+    keys: Vec<Bytes>,
+    limit: usize,
+    valid: bool,
+}
+
+impl Sintercard {
+    pub fn new(args: &[Bytes]) -> Sintercard {
+        let numkeys = match args
+            .first()
+            .map(|n| String::from_utf8_lossy(n).parse::<usize>())
+        {
+            Some(Ok(n)) if n > 0 && args.len() > n => n,
+            _ => return Sintercard::new_invalid(),
+        };
+        let keys = args[1..=numkeys].to_vec();
+
+        let mut limit = 0;
+        match &args[numkeys + 1..] {
+            [] => {}
+            [arg, n] if arg.eq_ignore_ascii_case(b"LIMIT") => {
+                match String::from_utf8_lossy(n).parse::<usize>() {
+                    Ok(n) => limit = n,
+                    _ => return Sintercard::new_invalid(),
+                }
+            }
+            _ => return Sintercard::new_invalid(),
+        }
+
+        Sintercard {
+            keys,
+            limit,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// SINTERCARD numkeys key [key ...] [LIMIT limit]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Sintercard> {
+        let mut args = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => args.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Sintercard::new_invalid()),
+            }
+        }
+
+        Ok(Sintercard::new(&args))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Sintercard> {
+        Ok(Sintercard::new(argv))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.sintercard(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn sintercard(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            SetCommandCtx::new(txn)
+                .do_async_txnkv_sintercard(&self.keys, self.limit)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Sintercard {
+    fn new_invalid() -> Sintercard {
+        Sintercard {
+            keys: vec![],
+            limit: 0,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::set::{SetCommandCtx, SetOperation};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Store the intersection, union or difference of the sets in the
+/// destination key, used by SINTERSTORE, SUNIONSTORE and SDIFFSTORE.
+#[derive(Debug, Clone)]
+pub struct Sinterstore {
+    op: SetOperation,
+    dest: Bytes,
+    keys: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Sinterstore {
+    pub fn new(op: SetOperation, dest: &Bytes, keys: &[Bytes]) -> Sinterstore {
+        if keys.is_empty() {
+            return Sinterstore::new_invalid();
+        }
+        Sinterstore {
+            op,
+            dest: dest.clone(),
+            keys: keys.to_vec(),
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// SINTERSTORE destination key [key ...]
+    /// SUNIONSTORE destination key [key ...]
+    /// SDIFFSTORE destination key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse, op: SetOperation) -> crate::Result<Sinterstore> {
+        let dest = parse.next_bytes()?;
+
+        let mut keys = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Sinterstore::new_invalid()),
+            }
+        }
+
+        Ok(Sinterstore::new(op, &dest, &keys))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes], op: SetOperation) -> crate::Result<Sinterstore> {
+        if argv.len() < 2 {
+            return Ok(Sinterstore::new_invalid());
+        }
+        Ok(Sinterstore::new(op, &argv[0], &argv[1..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.sinterstore(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn sinterstore(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            SetCommandCtx::new(txn)
+                .do_async_txnkv_set_algebra_store(self.op, &self.dest, &self.keys)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Sinterstore {
+    fn new_invalid() -> Sinterstore {
+        Sinterstore {
+            op: SetOperation::Inter,
+            dest: Bytes::new(),
+            keys: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
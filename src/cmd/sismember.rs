@@ -15,23 +15,23 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Sismember {
-    key: String,
-    member: String,
+    key: Bytes,
+    member: Bytes,
     valid: bool,
 }
 
 impl Sismember {
-    pub fn new(key: &str, member: &str) -> Sismember {
+    pub fn new(key: &Bytes, member: &Bytes) -> Sismember {
         Sismember {
-            key: key.to_string(),
-            member: member.to_string(),
+            key: key.clone(),
+            member: member.clone(),
             valid: true,
         }
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Sismember> {
-        let key = parse.next_string()?;
-        let member = parse.next_string()?;
+        let key = parse.next_bytes()?;
+        let member = parse.next_bytes()?;
         Ok(Sismember {
             key,
             member,
@@ -43,10 +43,7 @@ impl Sismember {
         if argv.len() != 2 {
             return Ok(Sismember::new_invalid());
         }
-        Ok(Sismember::new(
-            &String::from_utf8_lossy(&argv[0]),
-            &String::from_utf8_lossy(&argv[1]),
-        ))
+        Ok(Sismember::new(&argv[0], &argv[1]))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -82,9 +79,13 @@ impl Sismember {
 impl Invalid for Sismember {
     fn new_invalid() -> Sismember {
         Sismember {
-            key: "".to_string(),
-            member: "".to_string(),
+            key: Bytes::new(),
+            member: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
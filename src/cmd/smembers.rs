@@ -15,40 +15,40 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Smembers {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Smembers {
-    pub fn new(key: &str) -> Smembers {
+    pub fn new(key: &Bytes) -> Smembers {
         Smembers {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Smembers> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         Ok(Smembers::new(&key))
     }
 
     pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Smembers> {
         if argv.len() != 1 {
             return Ok(Smembers {
-                key: "".to_owned(),
+                key: Bytes::new(),
                 valid: false,
             });
         }
-        Ok(Smembers::new(&String::from_utf8_lossy(&argv[0])))
+        Ok(Smembers::new(&argv[0]))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -82,8 +82,12 @@ impl Smembers {
 impl Invalid for Smembers {
     fn new_invalid() -> Smembers {
         Smembers {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
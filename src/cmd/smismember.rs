@@ -15,37 +15,37 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Smismember {
-    key: String,
-    members: Vec<String>,
+    key: Bytes,
+    members: Vec<Bytes>,
     valid: bool,
 }
 
 impl Smismember {
-    pub fn new(key: &str) -> Smismember {
+    pub fn new(key: &Bytes) -> Smismember {
         Smismember {
-            key: key.to_string(),
+            key: key.clone(),
             members: vec![],
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
-    pub fn add_member(&mut self, member: &str) {
-        self.members.push(member.to_string());
+    pub fn add_member(&mut self, member: &Bytes) {
+        self.members.push(member.clone());
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Smismember> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut smismember = Smismember::new(&key);
-        while let Ok(member) = parse.next_string() {
+        while let Ok(member) = parse.next_bytes() {
             smismember.add_member(&member);
         }
         Ok(smismember)
@@ -55,9 +55,9 @@ impl Smismember {
         if argv.len() < 2 {
             return Ok(Smismember::new_invalid());
         }
-        let mut s = Smismember::new(&String::from_utf8_lossy(&argv[0]));
+        let mut s = Smismember::new(&argv[0]);
         for arg in &argv[1..] {
-            s.add_member(&String::from_utf8_lossy(arg));
+            s.add_member(arg);
         }
         Ok(s)
     }
@@ -93,9 +93,13 @@ impl Smismember {
 impl Invalid for Smismember {
     fn new_invalid() -> Smismember {
         Smismember {
-            key: "".to_string(),
+            key: Bytes::new(),
             members: vec![],
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::set::SetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Move a member from the source set to the destination set.
+#[derive(Debug, Clone)]
+pub struct Smove {
+    src: Bytes,
+    dest: Bytes,
+    member: Bytes,
+    valid: bool,
+}
+
+impl Smove {
+    pub fn new(src: &Bytes, dest: &Bytes, member: &Bytes) -> Smove {
+        Smove {
+            src: src.clone(),
+            dest: dest.clone(),
+            member: member.clone(),
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> Vec<Bytes> {
+        vec![self.src.clone(), self.dest.clone()]
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// SMOVE source destination member
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Smove> {
+        let src = parse.next_bytes()?;
+        let dest = parse.next_bytes()?;
+        let member = parse.next_bytes()?;
+        Ok(Smove::new(&src, &dest, &member))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Smove> {
+        if argv.len() != 3 {
+            return Ok(Smove::new_invalid());
+        }
+        Ok(Smove::new(&argv[0], &argv[1], &argv[2]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.smove(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn smove(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            SetCommandCtx::new(txn)
+                .do_async_txnkv_smove(&self.src, &self.dest, &self.member)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Smove {
+    fn new_invalid() -> Smove {
+        Smove {
+            src: Bytes::new(),
+            dest: Bytes::new(),
+            member: Bytes::new(),
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
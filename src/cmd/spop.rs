@@ -15,22 +15,22 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Spop {
-    key: String,
+    key: Bytes,
     count: i64,
     valid: bool,
 }
 
 impl Spop {
-    pub fn new(key: &str, count: i64) -> Spop {
+    pub fn new(key: &Bytes, count: i64) -> Spop {
         Spop {
-            key: key.to_string(),
+            key: key.clone(),
             count,
             valid: true,
         }
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Spop> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         let mut count = 1;
         if let Ok(v) = parse.next_int() {
@@ -54,7 +54,7 @@ impl Spop {
                 Err(_) => return Ok(Spop::new_invalid()),
             }
         }
-        Ok(Spop::new(&String::from_utf8_lossy(&argv[0]), count))
+        Ok(Spop::new(&argv[0], count))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -88,9 +88,13 @@ impl Spop {
 impl Invalid for Spop {
     fn new_invalid() -> Spop {
         Spop {
-            key: "".to_string(),
+            key: Bytes::new(),
             count: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
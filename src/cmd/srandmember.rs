@@ -15,22 +15,22 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Srandmember {
-    key: String,
+    key: Bytes,
     count: Option<i64>,
     valid: bool,
 }
 
 impl Srandmember {
-    pub fn new(key: &str, count: Option<i64>) -> Srandmember {
+    pub fn new(key: &Bytes, count: Option<i64>) -> Srandmember {
         Srandmember {
-            key: key.to_string(),
+            key: key.clone(),
             count,
             valid: true,
         }
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Srandmember> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         let mut count = None;
         if let Ok(v) = parse.next_int() {
@@ -54,7 +54,7 @@ impl Srandmember {
                 Err(_) => return Ok(Srandmember::new_invalid()),
             }
         }
-        Ok(Srandmember::new(&String::from_utf8_lossy(&argv[0]), count))
+        Ok(Srandmember::new(&argv[0], count))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -105,9 +105,13 @@ impl Srandmember {
 impl Invalid for Srandmember {
     fn new_invalid() -> Srandmember {
         Srandmember {
-            key: "".to_string(),
+            key: Bytes::new(),
             count: None,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
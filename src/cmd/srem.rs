@@ -15,37 +15,37 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Srem {
-    key: String,
-    members: Vec<String>,
+    key: Bytes,
+    members: Vec<Bytes>,
     valid: bool,
 }
 
 impl Srem {
-    pub fn new(key: &str) -> Srem {
+    pub fn new(key: &Bytes) -> Srem {
         Srem {
-            key: key.to_string(),
+            key: key.clone(),
             members: vec![],
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
-    pub fn add_member(&mut self, member: &str) {
-        self.members.push(member.to_string());
+    pub fn add_member(&mut self, member: &Bytes) {
+        self.members.push(member.clone());
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Srem> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut srem = Srem::new(&key);
-        while let Ok(member) = parse.next_string() {
+        while let Ok(member) = parse.next_bytes() {
             srem.add_member(&member);
         }
         Ok(srem)
@@ -55,10 +55,10 @@ impl Srem {
         if argv.len() < 2 {
             return Ok(Srem::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let mut srem = Srem::new(key);
         for arg in &argv[1..] {
-            srem.add_member(&String::from_utf8_lossy(arg));
+            srem.add_member(arg);
         }
         Ok(srem)
     }
@@ -94,9 +94,13 @@ impl Srem {
 impl Invalid for Srem {
     fn new_invalid() -> Srem {
         Srem {
-            key: "".to_string(),
+            key: Bytes::new(),
             members: vec![],
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
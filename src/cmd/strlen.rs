@@ -15,24 +15,24 @@ use crate::config::is_use_txn_api;
 
 #[derive(Debug, Clone)]
 pub struct Strlen {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Strlen {
-    pub fn new(key: impl ToString) -> Strlen {
+    pub fn new(key: &Bytes) -> Strlen {
         Strlen {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Strlen> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         Ok(Strlen { key, valid: true })
     }
@@ -41,7 +41,7 @@ impl Strlen {
         if argv.len() != 1 {
             return Ok(Strlen::new_invalid());
         }
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         Ok(Strlen::new(key))
     }
 
@@ -81,8 +81,12 @@ impl Strlen {
 impl Invalid for Strlen {
     fn new_invalid() -> Strlen {
         Strlen {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
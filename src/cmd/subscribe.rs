@@ -1,21 +1,36 @@
+use crate::cluster::{key_hash_slot, Cluster as Topo};
 use crate::cmd::{Invalid, Parse, ParseError, Unknown};
+use crate::tikv::errors::{REDIS_CROSSSLOT_ERR, REDIS_SSUBSCRIBE_WRONG_ARGS_ERR};
 use crate::{Command, Connection, Db, Frame, Shutdown};
 
-use crate::utils::resp_invalid_arguments;
+use crate::utils::{resp_err, resp_invalid_arguments};
 use bytes::Bytes;
 use std::pin::Pin;
 use tokio::select;
 use tokio::sync::broadcast;
+use tokio::time::{self, Duration};
 use tokio_stream::{Stream, StreamExt, StreamMap};
 
+/// Interval to check if the slots of the subscribed shard channels are still
+/// owned by myself after topology changes.
+const SHARD_SLOT_CHECK_INTERVAL_MS: u64 = 1000;
+
+/// Kind of the subscriptions a `Subscribe` or `Unsubscribe` command applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscribeKind {
+    Channel,
+    Pattern,
+    Shard,
+}
+
 /// Subscribes the client to one or more channels.
 ///
 /// Once the client enters the subscribed state, it is not supposed to issue any
-/// other commands, except for additional SUBSCRIBE, PSUBSCRIBE, UNSUBSCRIBE,
-/// PUNSUBSCRIBE, PING and QUIT commands.
+/// other commands, except for additional SUBSCRIBE, PSUBSCRIBE, SSUBSCRIBE,
+/// UNSUBSCRIBE, PUNSUBSCRIBE, SUNSUBSCRIBE, PING and QUIT commands.
 #[derive(Debug, Clone)]
 pub struct Subscribe {
-    channels: Vec<String>,
+    channels: Vec<Bytes>,
     valid: bool,
 }
 
@@ -25,7 +40,7 @@ pub struct Subscribe {
 /// previously subscribed channels.
 #[derive(Clone, Debug)]
 pub struct Unsubscribe {
-    channels: Vec<String>,
+    channels: Vec<Bytes>,
     valid: bool,
 }
 
@@ -35,10 +50,71 @@ pub struct Unsubscribe {
 /// a trait object.
 type Messages = Pin<Box<dyn Stream<Item = Bytes> + Send>>;
 
+/// Stream of messages of a pattern, along with the channel they were
+/// published to.
+type PatternMessages = Pin<Box<dyn Stream<Item = (Bytes, Bytes)> + Send>>;
+
+/// Subscription set of a client in the subscribed state.
+struct Subscriptions {
+    db: Db,
+    channels: StreamMap<Bytes, Messages>,
+    patterns: StreamMap<Bytes, PatternMessages>,
+    shard_channels: StreamMap<Bytes, Messages>,
+    /// Channels to subscribe to on next loop of `Subscribe::apply`
+    subscribe_to: Vec<Bytes>,
+    /// Patterns to subscribe to on next loop of `Subscribe::apply`
+    psubscribe_to: Vec<Bytes>,
+    /// Shard channels to subscribe to on next loop of `Subscribe::apply`
+    ssubscribe_to: Vec<Bytes>,
+}
+
+impl Subscriptions {
+    fn new(db: &Db) -> Subscriptions {
+        Subscriptions {
+            db: db.clone(),
+            channels: StreamMap::new(),
+            patterns: StreamMap::new(),
+            shard_channels: StreamMap::new(),
+            subscribe_to: vec![],
+            psubscribe_to: vec![],
+            ssubscribe_to: vec![],
+        }
+    }
+
+    /// Number of subscriptions, channels and patterns together, as replied
+    /// to subscribe and unsubscribe requests.
+    fn len(&self) -> usize {
+        self.channels.len() + self.patterns.len()
+    }
+
+    /// Number of shard channels, which are counted apart from the others.
+    fn shard_len(&self) -> usize {
+        self.shard_channels.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+            && self.shard_len() == 0
+            && self.subscribe_to.is_empty()
+            && self.psubscribe_to.is_empty()
+            && self.ssubscribe_to.is_empty()
+    }
+}
+
+impl Drop for Subscriptions {
+    fn drop(&mut self) {
+        // Leave the broadcast channels before pruning them
+        self.channels.clear();
+        self.patterns.clear();
+        self.shard_channels.clear();
+        self.db.prune_pub_sub();
+    }
+}
+
 impl Subscribe {
     #[allow(dead_code)]
     /// Creates a new `Subscribe` command to listen on the specified channels.
-    pub(crate) fn new(channels: &[String]) -> Subscribe {
+    pub(crate) fn new(channels: &[Bytes]) -> Subscribe {
         Subscribe {
             channels: channels.to_vec(),
             valid: true,
@@ -74,13 +150,13 @@ impl Subscribe {
         //
         // Extract the first string. If there is none, the the frame is
         // malformed and the error is bubbled up.
-        let mut channels = vec![parse.next_string()?];
+        let mut channels = vec![parse.next_bytes()?];
 
         // Now, the remainder of the frame is consumed. Each value must be a
         // string or the frame is malformed. Once all values in the frame have
         // been consumed, the command is fully parsed.
         loop {
-            match parse.next_string() {
+            match parse.next_bytes() {
                 // A string has been consumed from the `parse`, push it into the
                 // list of channels to subscribe to.
                 Ok(s) => channels.push(s),
@@ -102,17 +178,28 @@ impl Subscribe {
     /// Apply the `Subscribe` command to the specified `Db` instance.
     ///
     /// This function is the entry point and includes the initial list of
-    /// channels to subscribe to. Additional `subscribe` and `unsubscribe`
-    /// commands may be received from the client and the list of subscriptions
-    /// are updated accordingly.
+    /// channels, glob patterns or shard channels according to `kind`, to
+    /// subscribe to. Additional subscribe and unsubscribe commands of all
+    /// kinds may be received from the client and the list of subscriptions
+    /// are updated accordingly. The client leaves the subscribed state once it
+    /// has unsubscribed from everything.
     ///
     /// [here]: https://redis.io/topics/pubsub
     pub(crate) async fn apply(
-        mut self,
+        self,
         db: &Db,
+        topo: &Topo,
         dst: &mut Connection,
         shutdown: &mut Shutdown,
+        kind: SubscribeKind,
     ) -> crate::Result<()> {
+        if kind == SubscribeKind::Shard {
+            if let Some(response) = check_shard_channels(&self.channels, topo) {
+                dst.write_frame(&response).await?;
+                return Ok(());
+            }
+        }
+
         if !self.valid {
             dst.write_frame(&resp_invalid_arguments()).await?;
             return Ok(());
@@ -127,26 +214,57 @@ impl Subscribe {
         // handle this, a `StreamMap` is used to track active subscriptions. The
         // `StreamMap` merges messages from individual broadcast channels as
         // they are received.
-        let mut subscriptions = StreamMap::new();
+        let mut subscriptions = Subscriptions::new(db);
+        match kind {
+            SubscribeKind::Channel => subscriptions.subscribe_to = self.channels,
+            SubscribeKind::Pattern => subscriptions.psubscribe_to = self.channels,
+            SubscribeKind::Shard => subscriptions.ssubscribe_to = self.channels,
+        }
+
+        let mut slot_check = time::interval(Duration::from_millis(SHARD_SLOT_CHECK_INTERVAL_MS));
+        // Set when the subscriptions may have changed
+        let mut changed = true;
 
         loop {
-            // `self.channels` is used to track additional channels to subscribe
-            // to. When new `SUBSCRIBE` commands are received during the
-            // execution of `apply`, the new channels are pushed onto this vec.
-            for channel_name in self.channels.drain(..) {
+            // `subscribe_to` and `psubscribe_to` are used to track additional
+            // channels and patterns to subscribe to. When new `SUBSCRIBE` or
+            // `PSUBSCRIBE` commands are received during the execution of
+            // `apply`, the new channels are pushed onto these vecs.
+            for channel_name in std::mem::take(&mut subscriptions.subscribe_to) {
                 subscribe_to_channel(channel_name, &mut subscriptions, db, dst).await?;
             }
+            for pattern in std::mem::take(&mut subscriptions.psubscribe_to) {
+                subscribe_to_pattern(pattern, &mut subscriptions, db, dst).await?;
+            }
+            for channel_name in std::mem::take(&mut subscriptions.ssubscribe_to) {
+                subscribe_to_shard_channel(channel_name, &mut subscriptions, db, dst).await?;
+            }
+            if changed {
+                db.prune_pub_sub();
+                changed = false;
+            }
 
             // Wait for one of the following to happen:
             //
             // - Receive a message from one of the subscribed channels.
+            // - Receive a message from one of the subscribed patterns.
+            // - Receive a message from one of the subscribed shard channels.
             // - Receive a subscribe or unsubscribe command from the client.
+            // - Time to check the slots of the shard channels.
             // - A server shutdown signal.
             select! {
                 // Receive messages from subscribed channels
-                Some((channel_name, msg)) = subscriptions.next() => {
+                Some((channel_name, msg)) = subscriptions.channels.next() => {
                     dst.write_frame(&make_message_frame(channel_name, msg)).await?;
                 }
+                // Receive messages from subscribed patterns
+                Some((pattern, (channel_name, msg))) = subscriptions.patterns.next() => {
+                    dst.write_frame(&make_pmessage_frame(pattern, channel_name, msg)).await?;
+                }
+                // Receive messages from subscribed shard channels
+                Some((channel_name, msg)) = subscriptions.shard_channels.next() => {
+                    dst.write_frame(&make_smessage_frame(channel_name, msg)).await?;
+                }
                 res = dst.read_frame() => {
                     let frame = match res? {
                         Some(frame) => frame,
@@ -154,12 +272,36 @@ impl Subscribe {
                         None => return Ok(())
                     };
 
-                    handle_command(
-                        frame,
-                        &mut self.channels,
-                        &mut subscriptions,
-                        dst,
-                    ).await?;
+                    handle_command(frame, &mut subscriptions, topo, dst).await?;
+                    changed = true;
+
+                    // Unsubscribed from everything, back to the normal state
+                    if subscriptions.is_empty() {
+                        return Ok(());
+                    }
+                }
+                _ = slot_check.tick(), if subscriptions.shard_len() > 0 => {
+                    // The slot has been moved to another node, unsubscribe the
+                    // client from the shard channel, so it could subscribe to
+                    // the new owner.
+                    let moved: Vec<Bytes> = subscriptions
+                        .shard_channels
+                        .keys()
+                        .filter(|channel_name| topo.moved_error(channel_name).is_some())
+                        .cloned()
+                        .collect();
+                    for channel_name in moved {
+                        subscriptions.shard_channels.remove(&channel_name);
+
+                        let response =
+                            make_sunsubscribe_frame(Some(channel_name), subscriptions.shard_len());
+                        dst.write_frame(&response).await?;
+                        changed = true;
+                    }
+
+                    if subscriptions.is_empty() {
+                        return Ok(());
+                    }
                 }
                 _ = shutdown.recv() => {
                     return Ok(());
@@ -177,15 +319,15 @@ impl Subscribe {
         let mut frame = Frame::array();
         frame.push_bulk(Bytes::from("subscribe".as_bytes()));
         for channel in self.channels {
-            frame.push_bulk(Bytes::from(channel.into_bytes()));
+            frame.push_bulk(channel);
         }
         frame
     }
 }
 
 async fn subscribe_to_channel(
-    channel_name: String,
-    subscriptions: &mut StreamMap<String, Messages>,
+    channel_name: Bytes,
+    subscriptions: &mut Subscriptions,
     db: &Db,
     dst: &mut Connection,
 ) -> crate::Result<()> {
@@ -204,7 +346,7 @@ async fn subscribe_to_channel(
     });
 
     // Track subscription in this client's subscription set.
-    subscriptions.insert(channel_name.clone(), rx);
+    subscriptions.channels.insert(channel_name.clone(), rx);
 
     // Respond with the successful subscription
     let response = make_subscribe_frame(channel_name, subscriptions.len());
@@ -213,43 +355,160 @@ async fn subscribe_to_channel(
     Ok(())
 }
 
+async fn subscribe_to_pattern(
+    pattern: Bytes,
+    subscriptions: &mut Subscriptions,
+    db: &Db,
+    dst: &mut Connection,
+) -> crate::Result<()> {
+    let mut rx = db.psubscribe(pattern.clone());
+
+    let rx = Box::pin(async_stream::stream! {
+        loop {
+            match rx.recv().await {
+                Ok(msg) => yield msg,
+                // If we lagged in consuming messages, just resume.
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(_) => break,
+            }
+        }
+    });
+
+    subscriptions.patterns.insert(pattern.clone(), rx);
+
+    let response = make_psubscribe_frame(pattern, subscriptions.len());
+    dst.write_frame(&response).await?;
+
+    Ok(())
+}
+
+async fn subscribe_to_shard_channel(
+    channel_name: Bytes,
+    subscriptions: &mut Subscriptions,
+    db: &Db,
+    dst: &mut Connection,
+) -> crate::Result<()> {
+    let mut rx = db.ssubscribe(channel_name.clone());
+
+    let rx = Box::pin(async_stream::stream! {
+        loop {
+            match rx.recv().await {
+                Ok(msg) => yield msg,
+                // If we lagged in consuming messages, just resume.
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(_) => break,
+            }
+        }
+    });
+
+    subscriptions
+        .shard_channels
+        .insert(channel_name.clone(), rx);
+
+    let response = make_ssubscribe_frame(channel_name, subscriptions.shard_len());
+    dst.write_frame(&response).await?;
+
+    Ok(())
+}
+
+/// Returns the error replied to `SSUBSCRIBE` if no shard channel is given, the
+/// shard channels are not all in the same slot, or the slot is not owned by
+/// myself.
+fn check_shard_channels(channels: &[Bytes], topo: &Topo) -> Option<Frame> {
+    let first = match channels.first() {
+        Some(channel_name) => channel_name,
+        None => return Some(resp_err(REDIS_SSUBSCRIBE_WRONG_ARGS_ERR)),
+    };
+    let slot = key_hash_slot(first);
+    if channels
+        .iter()
+        .any(|channel_name| key_hash_slot(channel_name) != slot)
+    {
+        return Some(resp_err(REDIS_CROSSSLOT_ERR));
+    }
+    topo.moved_error(first).map(resp_err)
+}
+
 /// Handle a command received while inside `Subscribe::apply`. Only subscribe
 /// and unsubscribe commands are permitted in this context.
 ///
-/// Any new subscriptions are appended to `subscribe_to` instead of modifying
-/// `subscriptions`.
+/// Any new subscriptions are appended to `subscribe_to` and `psubscribe_to`
+/// instead of modifying the stream maps.
 async fn handle_command(
     frame: Frame,
-    subscribe_to: &mut Vec<String>,
-    subscriptions: &mut StreamMap<String, Messages>,
+    subscriptions: &mut Subscriptions,
+    topo: &Topo,
     dst: &mut Connection,
 ) -> crate::Result<()> {
     // A command has been received from the client.
     //
-    // Only `SUBSCRIBE` and `UNSUBSCRIBE` commands are permitted
-    // in this context.
+    // Only the subscribe and unsubscribe commands are permitted in this
+    // context.
     match Command::from_frame(frame)? {
         Command::Subscribe(subscribe) => {
             // The `apply` method will subscribe to the channels we add to this
             // vector.
-            subscribe_to.extend(subscribe.channels.into_iter());
+            subscriptions.subscribe_to.extend(subscribe.channels);
         }
+        Command::Psubscribe(psubscribe) => {
+            subscriptions.psubscribe_to.extend(psubscribe.channels);
+        }
+        Command::Ssubscribe(ssubscribe) => match check_shard_channels(&ssubscribe.channels, topo) {
+            Some(response) => dst.write_frame(&response).await?,
+            None => subscriptions.ssubscribe_to.extend(ssubscribe.channels),
+        },
         Command::Unsubscribe(mut unsubscribe) => {
             // If no channels are specified, this requests unsubscribing from
             // **all** channels. To implement this, the `unsubscribe.channels`
             // vec is populated with the list of channels currently subscribed
             // to.
             if unsubscribe.channels.is_empty() {
-                unsubscribe.channels = subscriptions
-                    .keys()
-                    .map(|channel_name| channel_name.to_string())
-                    .collect();
+                unsubscribe.channels = subscriptions.channels.keys().cloned().collect();
+            }
+            if unsubscribe.channels.is_empty() {
+                let response = make_unsubscribe_frame(None, subscriptions.len());
+                dst.write_frame(&response).await?;
             }
 
             for channel_name in unsubscribe.channels {
-                subscriptions.remove(&channel_name);
+                subscriptions.channels.remove(&channel_name);
 
-                let response = make_unsubscribe_frame(channel_name, subscriptions.len());
+                let response = make_unsubscribe_frame(Some(channel_name), subscriptions.len());
+                dst.write_frame(&response).await?;
+            }
+        }
+        Command::Punsubscribe(mut punsubscribe) => {
+            // Unsubscribe from all patterns if none specified
+            if punsubscribe.channels.is_empty() {
+                punsubscribe.channels = subscriptions.patterns.keys().cloned().collect();
+            }
+            if punsubscribe.channels.is_empty() {
+                let response = make_punsubscribe_frame(None, subscriptions.len());
+                dst.write_frame(&response).await?;
+            }
+
+            for pattern in punsubscribe.channels {
+                subscriptions.patterns.remove(&pattern);
+
+                let response = make_punsubscribe_frame(Some(pattern), subscriptions.len());
+                dst.write_frame(&response).await?;
+            }
+        }
+        Command::Sunsubscribe(mut sunsubscribe) => {
+            // Unsubscribe from all shard channels if none specified
+            if sunsubscribe.channels.is_empty() {
+                sunsubscribe.channels = subscriptions.shard_channels.keys().cloned().collect();
+            }
+            if sunsubscribe.channels.is_empty() {
+                let response = make_sunsubscribe_frame(None, subscriptions.shard_len());
+                dst.write_frame(&response).await?;
+            }
+
+            for channel_name in sunsubscribe.channels {
+                subscriptions.shard_channels.remove(&channel_name);
+
+                let response =
+                    make_sunsubscribe_frame(Some(channel_name), subscriptions.shard_len());
                 dst.write_frame(&response).await?;
             }
         }
@@ -263,33 +522,93 @@ async fn handle_command(
 
 /// Creates the response to a subcribe request.
 ///
-/// All of these functions take the `channel_name` as a `String` instead of
-/// a `&str` since `Bytes::from` can reuse the allocation in the `String`, and
-/// taking a `&str` would require copying the data. This allows the caller to
-/// decide whether to clone the channel name or not.
-fn make_subscribe_frame(channel_name: String, num_subs: usize) -> Frame {
-    let mut response = Frame::array();
+/// All of these functions take the `channel_name` as `Bytes` instead of a
+/// `&[u8]` since it is pushed to the frame as is, and taking a `&[u8]` would
+/// require copying the data. This allows the caller to decide whether to
+/// clone the channel name or not.
+fn make_subscribe_frame(channel_name: Bytes, num_subs: usize) -> Frame {
+    let mut response = Frame::push();
     response.push_bulk(Bytes::from_static(b"subscribe"));
-    response.push_bulk(Bytes::from(channel_name));
+    response.push_bulk(channel_name);
     response.push_int(num_subs as i64);
     response
 }
 
-/// Creates the response to an unsubcribe request.
-fn make_unsubscribe_frame(channel_name: String, num_subs: usize) -> Frame {
-    let mut response = Frame::array();
-    response.push_bulk(Bytes::from_static(b"unsubscribe"));
-    response.push_bulk(Bytes::from(channel_name));
+/// Creates the response to a psubcribe request.
+fn make_psubscribe_frame(pattern: Bytes, num_subs: usize) -> Frame {
+    let mut response = Frame::push();
+    response.push_bulk(Bytes::from_static(b"psubscribe"));
+    response.push_bulk(pattern);
     response.push_int(num_subs as i64);
     response
 }
 
+/// Creates the response to a ssubcribe request.
+fn make_ssubscribe_frame(channel_name: Bytes, num_subs: usize) -> Frame {
+    let mut response = Frame::push();
+    response.push_bulk(Bytes::from_static(b"ssubscribe"));
+    response.push_bulk(channel_name);
+    response.push_int(num_subs as i64);
+    response
+}
+
+/// Creates the response to an unsubcribe request, the channel is nil if the
+/// client was not subscribed to any channel.
+fn make_unsubscribe_frame(channel_name: Option<Bytes>, num_subs: usize) -> Frame {
+    Frame::Push(vec![
+        Frame::Bulk(Bytes::from_static(b"unsubscribe")),
+        channel_name.map_or(Frame::Null, Frame::Bulk),
+        Frame::Integer(num_subs as i64),
+    ])
+}
+
+/// Creates the response to a punsubcribe request, the pattern is nil if the
+/// client was not subscribed to any pattern.
+fn make_punsubscribe_frame(pattern: Option<Bytes>, num_subs: usize) -> Frame {
+    Frame::Push(vec![
+        Frame::Bulk(Bytes::from_static(b"punsubscribe")),
+        pattern.map_or(Frame::Null, Frame::Bulk),
+        Frame::Integer(num_subs as i64),
+    ])
+}
+
 /// Creates a message informing the client about a new message on a channel that
 /// the client subscribes to.
-fn make_message_frame(channel_name: String, msg: Bytes) -> Frame {
-    let mut response = Frame::array();
+fn make_message_frame(channel_name: Bytes, msg: Bytes) -> Frame {
+    let mut response = Frame::push();
     response.push_bulk(Bytes::from_static(b"message"));
-    response.push_bulk(Bytes::from(channel_name));
+    response.push_bulk(channel_name);
+    response.push_bulk(msg);
+    response
+}
+
+/// Creates the response to a sunsubcribe request, the channel is nil if the
+/// client was not subscribed to any shard channel.
+fn make_sunsubscribe_frame(channel_name: Option<Bytes>, num_subs: usize) -> Frame {
+    Frame::Push(vec![
+        Frame::Bulk(Bytes::from_static(b"sunsubscribe")),
+        channel_name.map_or(Frame::Null, Frame::Bulk),
+        Frame::Integer(num_subs as i64),
+    ])
+}
+
+/// Creates a message informing the client about a new message on a shard
+/// channel that the client subscribes to.
+fn make_smessage_frame(channel_name: Bytes, msg: Bytes) -> Frame {
+    let mut response = Frame::push();
+    response.push_bulk(Bytes::from_static(b"smessage"));
+    response.push_bulk(channel_name);
+    response.push_bulk(msg);
+    response
+}
+
+/// Creates a message informing the client about a new message on a channel
+/// matching a pattern that the client subscribes to.
+fn make_pmessage_frame(pattern: Bytes, channel_name: Bytes, msg: Bytes) -> Frame {
+    let mut response = Frame::push();
+    response.push_bulk(Bytes::from_static(b"pmessage"));
+    response.push_bulk(pattern);
+    response.push_bulk(channel_name);
     response.push_bulk(msg);
     response
 }
@@ -297,7 +616,7 @@ fn make_message_frame(channel_name: String, msg: Bytes) -> Frame {
 impl Unsubscribe {
     #[allow(dead_code)]
     /// Create a new `Unsubscribe` command with the given `channels`.
-    pub(crate) fn new(channels: &[String]) -> Unsubscribe {
+    pub(crate) fn new(channels: &[Bytes]) -> Unsubscribe {
         Unsubscribe {
             channels: channels.to_vec(),
             valid: true,
@@ -334,7 +653,7 @@ impl Unsubscribe {
         // Once all values in the frame have been consumed, the command is fully
         // parsed.
         loop {
-            match parse.next_string() {
+            match parse.next_bytes() {
                 // A string has been consumed from the `parse`, push it into the
                 // list of channels to unsubscribe from.
                 Ok(s) => channels.push(s),
@@ -353,6 +672,32 @@ impl Unsubscribe {
         })
     }
 
+    /// Apply the `Unsubscribe` command out of the subscribed state, there is
+    /// nothing to unsubscribe from, so only the replies are sent.
+    pub(crate) async fn apply(
+        self,
+        dst: &mut Connection,
+        kind: SubscribeKind,
+    ) -> crate::Result<()> {
+        if !self.valid {
+            dst.write_frame(&resp_invalid_arguments()).await?;
+            return Ok(());
+        }
+
+        let make_frame = match kind {
+            SubscribeKind::Channel => make_unsubscribe_frame,
+            SubscribeKind::Pattern => make_punsubscribe_frame,
+            SubscribeKind::Shard => make_sunsubscribe_frame,
+        };
+        if self.channels.is_empty() {
+            dst.write_frame(&make_frame(None, 0)).await?;
+        }
+        for channel_name in self.channels {
+            dst.write_frame(&make_frame(Some(channel_name), 0)).await?;
+        }
+        Ok(())
+    }
+
     #[allow(dead_code)]
     /// Converts the command into an equivalent `Frame`.
     ///
@@ -367,7 +712,7 @@ impl Unsubscribe {
         frame.push_bulk(Bytes::from("unsubscribe".as_bytes()));
 
         for channel in self.channels {
-            frame.push_bulk(Bytes::from(channel.into_bytes()));
+            frame.push_bulk(channel);
         }
 
         frame
@@ -381,6 +726,10 @@ impl Invalid for Subscribe {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
 
 impl Invalid for Unsubscribe {
@@ -390,4 +739,8 @@ impl Invalid for Unsubscribe {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
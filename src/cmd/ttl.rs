@@ -14,25 +14,25 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct TTL {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl TTL {
-    pub fn new(key: impl ToString) -> TTL {
+    pub fn new(key: &Bytes) -> TTL {
         TTL {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<TTL> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         Ok(TTL { key, valid: true })
     }
@@ -42,7 +42,7 @@ impl TTL {
             return Ok(TTL::new_invalid());
         }
         Ok(TTL {
-            key: String::from_utf8_lossy(&argv[0]).to_string(),
+            key: argv[0].clone(),
             valid: true,
         })
     }
@@ -84,8 +84,12 @@ impl TTL {
 impl Invalid for TTL {
     fn new_invalid() -> TTL {
         TTL {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
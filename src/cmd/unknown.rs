@@ -27,7 +27,7 @@ impl Unknown {
     ///
     /// This usually means the command is not yet implemented.
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
-        let response = Frame::ErrorOwned(format!("ERR unknown command '{}'", self.command_name));
+        let response = self.unknown();
 
         debug!(
             LOGGER,
@@ -40,4 +40,8 @@ impl Unknown {
         dst.write_frame(&response).await?;
         Ok(())
     }
+
+    pub(crate) fn unknown(&self) -> Frame {
+        Frame::ErrorOwned(format!("ERR unknown command '{}'", self.command_name))
+    }
 }
@@ -0,0 +1,64 @@
+use bytes::Bytes;
+
+use crate::cmd::{Invalid, Parse};
+
+/// Marks the given keys to be watched for conditional execution of a
+/// transaction.
+///
+/// The watched keys are recorded on the connection with the timestamp of the
+/// `WATCH`, `EXEC` fails with a null reply if any of them has been modified
+/// after that. `UNWATCH` flushes all the previously watched keys.
+#[derive(Debug, Clone)]
+pub struct Watch {
+    keys: Vec<Bytes>,
+    valid: bool,
+}
+
+impl Watch {
+    /// Get the keys
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    pub fn add_key(&mut self, key: Bytes) {
+        self.keys.push(key);
+    }
+
+    pub fn valid(&self) -> bool {
+        self.valid
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Watch> {
+        let mut watch = Watch::default();
+        while let Ok(key) = parse.next_bytes() {
+            watch.add_key(key);
+        }
+        if watch.keys.is_empty() {
+            return Ok(Watch::new_invalid());
+        }
+
+        Ok(watch)
+    }
+}
+
+impl Default for Watch {
+    fn default() -> Self {
+        Watch {
+            keys: vec![],
+            valid: true,
+        }
+    }
+}
+
+impl Invalid for Watch {
+    fn new_invalid() -> Watch {
+        Watch {
+            keys: vec![],
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -15,8 +15,8 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zadd {
-    key: String,
-    members: Vec<String>,
+    key: Bytes,
+    members: Vec<Bytes>,
     scores: Vec<f64>,
     exists: Option<bool>,
     changed_only: bool,
@@ -24,9 +24,9 @@ pub struct Zadd {
 }
 
 impl Zadd {
-    pub fn new(key: &str) -> Zadd {
+    pub fn new(key: &Bytes) -> Zadd {
         Zadd {
-            key: key.to_string(),
+            key: key.clone(),
             members: vec![],
             scores: vec![],
             exists: None,
@@ -36,12 +36,12 @@ impl Zadd {
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
     pub fn set_exists(&mut self, exists: bool) {
@@ -52,8 +52,8 @@ impl Zadd {
         self.changed_only = changed_only;
     }
 
-    pub fn add_member(&mut self, member: &str) {
-        self.members.push(member.to_string());
+    pub fn add_member(&mut self, member: &Bytes) {
+        self.members.push(member.clone());
     }
 
     pub fn add_score(&mut self, score: f64) {
@@ -61,7 +61,7 @@ impl Zadd {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zadd> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut zadd = Zadd::new(&key);
         let mut first_score: Option<f64>;
 
@@ -113,10 +113,10 @@ impl Zadd {
                 first_score = None;
 
                 // parse next member
-                let member = parse.next_string()?;
+                let member = parse.next_bytes()?;
                 zadd.add_member(&member);
             } else if let Ok(str_score) = parse.next_string() {
-                let member = parse.next_string()?;
+                let member = parse.next_bytes()?;
                 let score = String::from_utf8_lossy(str_score.as_bytes()).parse::<f64>()?;
                 zadd.add_score(score);
                 zadd.add_member(&member);
@@ -132,7 +132,7 @@ impl Zadd {
         if argv.is_empty() {
             return Ok(Zadd::new_invalid());
         }
-        let mut zadd = Zadd::new(&String::from_utf8_lossy(&argv[0]));
+        let mut zadd = Zadd::new(&argv[0]);
         let mut first_score: Option<f64>;
 
         // try to parse the flag
@@ -190,7 +190,7 @@ impl Zadd {
                 if idx >= argv.len() {
                     return Ok(Zadd::new_invalid());
                 }
-                let member = &String::from_utf8_lossy(&argv[idx]);
+                let member = &argv[idx];
                 zadd.add_member(member);
             } else {
                 idx += 1;
@@ -202,7 +202,7 @@ impl Zadd {
                     if idx >= argv.len() {
                         return Ok(Zadd::new_invalid());
                     }
-                    let member = &String::from_utf8_lossy(&argv[idx]);
+                    let member = &argv[idx];
                     zadd.add_score(score);
                     zadd.add_member(member);
                 } else {
@@ -252,7 +252,7 @@ impl Zadd {
 impl Invalid for Zadd {
     fn new_invalid() -> Zadd {
         Zadd {
-            key: "".to_string(),
+            key: Bytes::new(),
             members: vec![],
             scores: vec![],
             exists: None,
@@ -260,4 +260,8 @@ impl Invalid for Zadd {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,29 +15,29 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zcard {
-    key: String,
+    key: Bytes,
     valid: bool,
 }
 
 impl Zcard {
-    pub fn new(key: &str) -> Zcard {
+    pub fn new(key: &Bytes) -> Zcard {
         Zcard {
-            key: key.to_string(),
+            key: key.clone(),
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zcard> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         Ok(Zcard { key, valid: true })
     }
 
@@ -45,7 +45,7 @@ impl Zcard {
         if argv.len() != 1 {
             return Ok(Zcard::new_invalid());
         }
-        Ok(Zcard::new(&String::from_utf8_lossy(&argv[0])))
+        Ok(Zcard::new(&argv[0]))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -79,8 +79,12 @@ impl Zcard {
 impl Invalid for Zcard {
     fn new_invalid() -> Zcard {
         Zcard {
-            key: "".to_owned(),
+            key: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,7 +15,7 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zcount {
-    key: String,
+    key: Bytes,
     min: f64,
     min_inclusive: bool,
     max: f64,
@@ -24,9 +24,15 @@ pub struct Zcount {
 }
 
 impl Zcount {
-    pub fn new(key: &str, min: f64, min_inclusive: bool, max: f64, max_inclusive: bool) -> Zcount {
+    pub fn new(
+        key: &Bytes,
+        min: f64,
+        min_inclusive: bool,
+        max: f64,
+        max_inclusive: bool,
+    ) -> Zcount {
         Zcount {
-            key: key.to_string(),
+            key: key.clone(),
             min,
             min_inclusive,
             max,
@@ -36,7 +42,7 @@ impl Zcount {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zcount> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut min_inclusive = true;
         let mut max_inclusive = true;
 
@@ -86,13 +92,7 @@ impl Zcount {
         }
         let max = String::from_utf8_lossy(&bmax).parse::<f64>().unwrap();
 
-        let z = Zcount::new(
-            &String::from_utf8_lossy(&argv[0]),
-            min,
-            min_inclusive,
-            max,
-            max_inclusive,
-        );
+        let z = Zcount::new(&argv[0], min, min_inclusive, max, max_inclusive);
         Ok(z)
     }
 
@@ -133,7 +133,7 @@ impl Zcount {
 impl Invalid for Zcount {
     fn new_invalid() -> Zcount {
         Zcount {
-            key: "".to_string(),
+            key: Bytes::new(),
             min: 0f64,
             min_inclusive: false,
             max: 0f64,
@@ -141,4 +141,8 @@ impl Invalid for Zcount {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
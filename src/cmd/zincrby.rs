@@ -15,26 +15,26 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zincrby {
-    key: String,
+    key: Bytes,
     step: f64,
-    member: String,
+    member: Bytes,
     valid: bool,
 }
 
 impl Zincrby {
-    pub fn new(key: &str, step: f64, member: &str) -> Zincrby {
+    pub fn new(key: &Bytes, step: f64, member: &Bytes) -> Zincrby {
         Zincrby {
-            key: key.to_string(),
+            key: key.clone(),
             step,
-            member: member.to_string(),
+            member: member.clone(),
             valid: true,
         }
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zincrby> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let step_byte = parse.next_bytes()?;
-        let member = parse.next_string()?;
+        let member = parse.next_bytes()?;
 
         let step = String::from_utf8_lossy(&step_byte).parse::<f64>()?;
 
@@ -46,9 +46,9 @@ impl Zincrby {
             return Ok(Zincrby::new_invalid());
         }
 
-        let key = &String::from_utf8_lossy(&argv[0]);
+        let key = &argv[0];
         let step = String::from_utf8_lossy(&argv[1]).parse::<f64>()?;
-        let member = &String::from_utf8_lossy(&argv[2]);
+        let member = &argv[2];
 
         Ok(Zincrby::new(key, step, member))
     }
@@ -87,10 +87,14 @@ impl Zincrby {
 impl Invalid for Zincrby {
     fn new_invalid() -> Zincrby {
         Zincrby {
-            key: "".to_string(),
-            member: "".to_string(),
+            key: Bytes::new(),
+            member: Bytes::new(),
             step: 0f64,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
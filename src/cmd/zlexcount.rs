@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use crate::cmd::zrangebylex::parse_lex_bound;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_INVALID_LEX_RANGE_ERR, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::zset::{LexBound, ZsetCommandCtx};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the number of members of the sorted set stored at key between min
+/// and max in lexicographical order.
+#[derive(Debug, Clone)]
+pub struct Zlexcount {
+    key: Bytes,
+    min: Option<LexBound>,
+    max: Option<LexBound>,
+    valid: bool,
+}
+
+impl Zlexcount {
+    pub fn new(key: &Bytes, min: &[u8], max: &[u8]) -> Zlexcount {
+        Zlexcount {
+            key: key.clone(),
+            min: parse_lex_bound(min),
+            max: parse_lex_bound(max),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// ZLEXCOUNT key min max
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zlexcount> {
+        let key = parse.next_bytes()?;
+        let min = parse.next_bytes()?;
+        let max = parse.next_bytes()?;
+        if !parse.check_finish() {
+            return Ok(Zlexcount::new_invalid());
+        }
+        Ok(Zlexcount::new(&key, &min, &max))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Zlexcount> {
+        if argv.len() != 3 {
+            return Ok(Zlexcount::new_invalid());
+        }
+        Ok(Zlexcount::new(&argv[0], &argv[1], &argv[2]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zlexcount(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zlexcount(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (min, max) = match (&self.min, &self.max) {
+            (Some(min), Some(max)) => (min.clone(), max.clone()),
+            _ => return Ok(resp_err(REDIS_INVALID_LEX_RANGE_ERR)),
+        };
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zlexcount(&self.key, min, max)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zlexcount {
+    fn new_invalid() -> Zlexcount {
+        Zlexcount {
+            key: Bytes::new(),
+            min: None,
+            max: None,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
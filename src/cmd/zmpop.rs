@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use crate::cmd::blpop::{block_for_keys, parse_timeout};
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_invalid_arguments, resp_nil_array};
+use crate::{Connection, Db, Frame, Shutdown};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Pop members from the first non-empty sorted set of the keys.
+#[derive(Debug, Clone)]
+pub struct Zmpop {
+    keys: Vec<Bytes>,
+    from_min: bool,
+    count: i64,
+    timeout: f64,
+    valid: bool,
+}
+
+impl Zmpop {
+    pub fn new(keys: Vec<Bytes>, from_min: bool, count: i64, timeout: f64) -> Zmpop {
+        Zmpop {
+            keys,
+            from_min,
+            count,
+            timeout,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// BZMPOP timeout numkeys key [key ...] MIN|MAX [COUNT count]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse, blocking: bool) -> crate::Result<Zmpop> {
+        let mut timeout = 0.0;
+        if blocking {
+            match parse_timeout(&parse.next_bytes()?) {
+                Some(t) => timeout = t,
+                None => return Ok(Zmpop::new_invalid()),
+            }
+        }
+
+        let numkeys = match parse.next_int() {
+            Ok(n) if n > 0 => n,
+            _ => return Ok(Zmpop::new_invalid()),
+        };
+        let mut keys = Vec::with_capacity(numkeys as usize);
+        for _ in 0..numkeys {
+            keys.push(parse.next_bytes()?);
+        }
+
+        let from_min = match parse.next_string()?.to_uppercase().as_str() {
+            "MIN" => true,
+            "MAX" => false,
+            _ => return Ok(Zmpop::new_invalid()),
+        };
+
+        let mut count = 1;
+        if let Ok(arg) = parse.next_string() {
+            match (arg.to_uppercase().as_str(), parse.next_int()) {
+                ("COUNT", Ok(n)) if n > 0 => count = n,
+                _ => return Ok(Zmpop::new_invalid()),
+            }
+        }
+
+        Ok(Zmpop::new(keys, from_min, count, timeout))
+    }
+
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        shutdown: &mut Shutdown,
+    ) -> crate::Result<()> {
+        let response = block_for_keys(db, &self.keys, self.timeout, dst, shutdown, || {
+            self.try_pop(None)
+        })
+        .await?;
+        let response = match response {
+            Some(response) => response,
+            // the connection is closed without reply
+            None if shutdown.is_shutdown() || dst.is_closed() => return Ok(()),
+            None => resp_nil_array(),
+        };
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Pop without blocking, as the command does inside a transaction.
+    pub async fn zmpop(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        Ok(self.try_pop(txn).await?.unwrap_or_else(resp_nil_array))
+    }
+
+    async fn try_pop(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Option<Frame>> {
+        if !self.valid {
+            return Ok(Some(resp_invalid_arguments()));
+        }
+        if !is_use_txn_api() {
+            return Ok(Some(resp_err(REDIS_NOT_SUPPORTED_ERR)));
+        }
+
+        match ZsetCommandCtx::new(txn)
+            .do_async_txnkv_mzpop(&self.keys, self.from_min, self.count as u64)
+            .await
+        {
+            Ok(Some((key, values))) => {
+                // reply the members along with their scores in pairs
+                let mut values = values.into_iter();
+                let mut members = vec![];
+                while let (Some(member), Some(score)) = (values.next(), values.next()) {
+                    members.push(resp_array(vec![member, score]));
+                }
+                Ok(Some(resp_array(vec![
+                    resp_bulk(key.to_vec()),
+                    resp_array(members),
+                ])))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Ok(Some(resp_err(e))),
+        }
+    }
+}
+
+impl Invalid for Zmpop {
+    fn new_invalid() -> Zmpop {
+        Zmpop {
+            keys: vec![],
+            from_min: false,
+            count: 0,
+            timeout: 0.0,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -15,22 +15,22 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zpop {
-    key: String,
+    key: Bytes,
     count: i64,
     valid: bool,
 }
 
 impl Zpop {
-    pub fn new(key: &str, count: i64) -> Zpop {
+    pub fn new(key: &Bytes, count: i64) -> Zpop {
         Zpop {
-            key: key.to_string(),
+            key: key.clone(),
             count,
             valid: true,
         }
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zpop> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         // default count is 1
         let mut count = 1;
         if let Ok(c) = parse.next_int() {
@@ -54,7 +54,7 @@ impl Zpop {
                 Err(_) => return Ok(Zpop::new_invalid()),
             }
         }
-        Ok(Zpop::new(&String::from_utf8_lossy(&argv[0]), count))
+        Ok(Zpop::new(&argv[0], count))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection, from_min: bool) -> crate::Result<()> {
@@ -92,9 +92,13 @@ impl Zpop {
 impl Invalid for Zpop {
     fn new_invalid() -> Zpop {
         Zpop {
-            key: "".to_string(),
+            key: Bytes::new(),
             count: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
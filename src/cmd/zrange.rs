@@ -4,7 +4,7 @@ use crate::cmd::{Invalid, Parse};
 use crate::config::is_use_txn_api;
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::zset::ZsetCommandCtx;
-use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::utils::{resp3_score_pairs, resp_err, resp_invalid_arguments};
 use crate::{Connection, Frame};
 
 use crate::config::LOGGER;
@@ -15,7 +15,7 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zrange {
-    key: String,
+    key: Bytes,
     min: i64,
     max: i64,
     withscores: bool,
@@ -24,9 +24,9 @@ pub struct Zrange {
 }
 
 impl Zrange {
-    pub fn new(key: &str, min: i64, max: i64, withscores: bool, reverse: bool) -> Zrange {
+    pub fn new(key: &Bytes, min: i64, max: i64, withscores: bool, reverse: bool) -> Zrange {
         Zrange {
-            key: key.to_string(),
+            key: key.clone(),
             min,
             max,
             withscores,
@@ -36,7 +36,7 @@ impl Zrange {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zrange> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         let min = parse.next_int()?;
         let max = parse.next_int()?;
@@ -96,19 +96,16 @@ impl Zrange {
                 _ => {}
             }
         }
-        let z = Zrange::new(
-            &String::from_utf8_lossy(&argv[0]),
-            min,
-            max,
-            withscores,
-            reverse,
-        );
+        let z = Zrange::new(&argv[0], min, max, withscores, reverse);
 
         Ok(z)
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
-        let response = self.zrange(None).await?;
+        let mut response = self.zrange(None).await?;
+        if self.withscores && dst.protocol() == 3 {
+            response = resp3_score_pairs(response);
+        }
         debug!(
             LOGGER,
             "res, {} -> {}, {:?}",
@@ -138,7 +135,7 @@ impl Zrange {
 impl Invalid for Zrange {
     fn new_invalid() -> Zrange {
         Zrange {
-            key: "".to_string(),
+            key: Bytes::new(),
             min: 0,
             max: 0,
             withscores: false,
@@ -146,4 +143,8 @@ impl Invalid for Zrange {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
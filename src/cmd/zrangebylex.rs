@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_INVALID_LEX_RANGE_ERR, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::zset::{LexBound, ZsetCommandCtx};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Parse a lex range item, which is `-`, `+`, or a member prefixed by `[`
+/// when inclusive and `(` when exclusive.
+pub(crate) fn parse_lex_bound(item: &[u8]) -> Option<LexBound> {
+    match item {
+        b"-" => Some(LexBound::Min),
+        b"+" => Some(LexBound::Max),
+        [b'[', member @ ..] => Some(LexBound::Inclusive(member.to_vec())),
+        [b'(', member @ ..] => Some(LexBound::Exclusive(member.to_vec())),
+        _ => None,
+    }
+}
+
+/// Return the members of the sorted set stored at key between min and max
+/// in lexicographical order, `ZREVRANGEBYLEX` takes max before min.
+#[derive(Debug, Clone)]
+pub struct Zrangebylex {
+    key: Bytes,
+    min: Option<LexBound>,
+    max: Option<LexBound>,
+    offset: i64,
+    count: i64,
+    valid: bool,
+}
+
+impl Zrangebylex {
+    pub fn new(args: &[Bytes]) -> Zrangebylex {
+        if args.len() < 3 {
+            return Zrangebylex::new_invalid();
+        }
+
+        let (mut offset, mut count) = (0, -1);
+        match &args[3..] {
+            [] => {}
+            [arg, o, c] if arg.eq_ignore_ascii_case(b"LIMIT") => {
+                match (
+                    String::from_utf8_lossy(o).parse::<i64>(),
+                    String::from_utf8_lossy(c).parse::<i64>(),
+                ) {
+                    (Ok(o), Ok(c)) => (offset, count) = (o, c),
+                    _ => return Zrangebylex::new_invalid(),
+                }
+            }
+            _ => return Zrangebylex::new_invalid(),
+        }
+
+        Zrangebylex {
+            key: args[0].clone(),
+            min: parse_lex_bound(&args[1]),
+            max: parse_lex_bound(&args[2]),
+            offset,
+            count,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &Bytes {
+        &self.key
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// ZRANGEBYLEX key min max [LIMIT offset count]
+    /// ZREVRANGEBYLEX key max min [LIMIT offset count]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zrangebylex> {
+        let mut args = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => args.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Zrangebylex::new_invalid()),
+            }
+        }
+
+        Ok(Zrangebylex::new(&args))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes]) -> crate::Result<Zrangebylex> {
+        Ok(Zrangebylex::new(argv))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection, reverse: bool) -> crate::Result<()> {
+        let response = self.zrangebylex(None, reverse).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zrangebylex(
+        &self,
+        txn: Option<Arc<Mutex<Transaction>>>,
+        reverse: bool,
+    ) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (min, max) = match (&self.min, &self.max) {
+            (Some(min), Some(max)) => (min.clone(), max.clone()),
+            _ => return Ok(resp_err(REDIS_INVALID_LEX_RANGE_ERR)),
+        };
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zrange_by_lex(&self.key, min, max, self.offset, self.count, reverse)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zrangebylex {
+    fn new_invalid() -> Zrangebylex {
+        Zrangebylex {
+            key: Bytes::new(),
+            min: None,
+            max: None,
+            offset: 0,
+            count: -1,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -4,7 +4,7 @@ use crate::cmd::{Invalid, Parse};
 use crate::config::is_use_txn_api;
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::zset::ZsetCommandCtx;
-use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::utils::{resp3_score_pairs, resp_err, resp_invalid_arguments};
 use crate::{Connection, Frame};
 
 use crate::config::LOGGER;
@@ -15,7 +15,7 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zrangebyscore {
-    key: String,
+    key: Bytes,
     min: f64,
     min_inclusive: bool,
     max: f64,
@@ -26,7 +26,7 @@ pub struct Zrangebyscore {
 
 impl Zrangebyscore {
     pub fn new(
-        key: &str,
+        key: &Bytes,
         min: f64,
         min_inclusive: bool,
         max: f64,
@@ -34,7 +34,7 @@ impl Zrangebyscore {
         withscores: bool,
     ) -> Zrangebyscore {
         Zrangebyscore {
-            key: key.to_string(),
+            key: key.clone(),
             min,
             min_inclusive,
             max,
@@ -45,7 +45,7 @@ impl Zrangebyscore {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zrangebyscore> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut min_inclusive = true;
         let mut max_inclusive = true;
 
@@ -155,20 +155,16 @@ impl Zrangebyscore {
             }
         }
 
-        let z = Zrangebyscore::new(
-            &String::from_utf8_lossy(&argv[0]),
-            min,
-            min_inclusive,
-            max,
-            max_inclusive,
-            withscores,
-        );
+        let z = Zrangebyscore::new(&argv[0], min, min_inclusive, max, max_inclusive, withscores);
 
         Ok(z)
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection, reverse: bool) -> crate::Result<()> {
-        let response = self.zrangebyscore(None, reverse).await?;
+        let mut response = self.zrangebyscore(None, reverse).await?;
+        if self.withscores && dst.protocol() == 3 {
+            response = resp3_score_pairs(response);
+        }
         debug!(
             LOGGER,
             "res, {} -> {}, {:?}",
@@ -210,7 +206,7 @@ impl Zrangebyscore {
 impl Invalid for Zrangebyscore {
     fn new_invalid() -> Zrangebyscore {
         Zrangebyscore {
-            key: "".to_string(),
+            key: Bytes::new(),
             min: 0f64,
             min_inclusive: false,
             max: 0f64,
@@ -219,4 +215,8 @@ impl Invalid for Zrangebyscore {
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,23 +15,23 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zrank {
-    key: String,
-    member: String,
+    key: Bytes,
+    member: Bytes,
     valid: bool,
 }
 
 impl Zrank {
-    pub fn new(key: &str, member: &str) -> Zrank {
+    pub fn new(key: &Bytes, member: &Bytes) -> Zrank {
         Zrank {
-            key: key.to_string(),
-            member: member.to_string(),
+            key: key.clone(),
+            member: member.clone(),
             valid: true,
         }
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zrank> {
-        let key = parse.next_string()?;
-        let member = parse.next_string()?;
+        let key = parse.next_bytes()?;
+        let member = parse.next_bytes()?;
 
         Ok(Zrank {
             key,
@@ -44,10 +44,7 @@ impl Zrank {
         if argv.len() != 2 {
             return Ok(Zrank::new_invalid());
         }
-        Ok(Zrank::new(
-            &String::from_utf8_lossy(&argv[0]),
-            &String::from_utf8_lossy(&argv[1]),
-        ))
+        Ok(Zrank::new(&argv[0], &argv[1]))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -81,9 +78,13 @@ impl Zrank {
 impl Invalid for Zrank {
     fn new_invalid() -> Zrank {
         Zrank {
-            key: "".to_string(),
-            member: "".to_string(),
+            key: Bytes::new(),
+            member: Bytes::new(),
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,39 +15,39 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zrem {
-    key: String,
-    members: Vec<String>,
+    key: Bytes,
+    members: Vec<Bytes>,
     valid: bool,
 }
 
 impl Zrem {
-    pub fn new(key: &str) -> Zrem {
+    pub fn new(key: &Bytes) -> Zrem {
         Zrem {
-            key: key.to_string(),
+            key: key.clone(),
             members: vec![],
             valid: true,
         }
     }
 
     /// Get the key
-    pub fn key(&self) -> &str {
+    pub fn key(&self) -> &Bytes {
         &self.key
     }
 
-    pub fn set_key(&mut self, key: &str) {
-        self.key = key.to_owned();
+    pub fn set_key(&mut self, key: &Bytes) {
+        self.key = key.clone();
     }
 
-    pub fn add_member(&mut self, member: &str) {
-        self.members.push(member.to_string());
+    pub fn add_member(&mut self, member: &Bytes) {
+        self.members.push(member.clone());
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zrem> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
         let mut zrem = Zrem::new(&key);
 
         // parse member
-        while let Ok(member) = parse.next_string() {
+        while let Ok(member) = parse.next_bytes() {
             zrem.add_member(&member);
         }
 
@@ -58,9 +58,9 @@ impl Zrem {
         if argv.len() < 2 {
             return Ok(Zrem::new_invalid());
         }
-        let mut zrem = Zrem::new(&String::from_utf8_lossy(&argv[0]));
+        let mut zrem = Zrem::new(&argv[0]);
         for arg in &argv[1..] {
-            zrem.add_member(&String::from_utf8_lossy(arg));
+            zrem.add_member(arg);
         }
         Ok(zrem)
     }
@@ -96,9 +96,13 @@ impl Zrem {
 impl Invalid for Zrem {
     fn new_invalid() -> Zrem {
         Zrem {
-            key: "".to_string(),
+            key: Bytes::new(),
             members: vec![],
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,16 +15,16 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zremrangebyrank {
-    key: String,
+    key: Bytes,
     min: i64,
     max: i64,
     valid: bool,
 }
 
 impl Zremrangebyrank {
-    pub fn new(key: &str, min: i64, max: i64) -> Zremrangebyrank {
+    pub fn new(key: &Bytes, min: i64, max: i64) -> Zremrangebyrank {
         Zremrangebyrank {
-            key: key.to_string(),
+            key: key.clone(),
             min,
             max,
             valid: true,
@@ -32,7 +32,7 @@ impl Zremrangebyrank {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zremrangebyrank> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         let min = parse.next_int()?;
         let max = parse.next_int()?;
@@ -56,11 +56,7 @@ impl Zremrangebyrank {
             Err(_) => return Ok(Zremrangebyrank::new_invalid()),
         };
 
-        Ok(Zremrangebyrank::new(
-            &String::from_utf8_lossy(&argv[0]),
-            min,
-            max,
-        ))
+        Ok(Zremrangebyrank::new(&argv[0], min, max))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -97,10 +93,14 @@ impl Zremrangebyrank {
 impl Invalid for Zremrangebyrank {
     fn new_invalid() -> Zremrangebyrank {
         Zremrangebyrank {
-            key: "".to_string(),
+            key: Bytes::new(),
             min: 0,
             max: 0,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
@@ -15,16 +15,16 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zremrangebyscore {
-    key: String,
+    key: Bytes,
     min: f64,
     max: f64,
     valid: bool,
 }
 
 impl Zremrangebyscore {
-    pub fn new(key: &str, min: f64, max: f64) -> Zremrangebyscore {
+    pub fn new(key: &Bytes, min: f64, max: f64) -> Zremrangebyscore {
         Zremrangebyscore {
-            key: key.to_string(),
+            key: key.clone(),
             min,
             max,
             valid: true,
@@ -32,7 +32,7 @@ impl Zremrangebyscore {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zremrangebyscore> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         // TODO support (/-inf/+inf
         let min = parse.next_string()?.parse::<f64>()?;
@@ -58,11 +58,7 @@ impl Zremrangebyscore {
             Err(_) => return Ok(Zremrangebyscore::new_invalid()),
         };
 
-        Ok(Zremrangebyscore::new(
-            &String::from_utf8_lossy(&argv[0]),
-            min,
-            max,
-        ))
+        Ok(Zremrangebyscore::new(&argv[0], min, max))
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -99,10 +95,14 @@ impl Zremrangebyscore {
 impl Invalid for Zremrangebyscore {
     fn new_invalid() -> Zremrangebyscore {
         Zremrangebyscore {
-            key: "".to_string(),
+            key: Bytes::new(),
             min: 0f64,
             max: 0f64,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
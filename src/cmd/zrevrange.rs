@@ -4,7 +4,7 @@ use crate::cmd::{Invalid, Parse};
 use crate::config::is_use_txn_api;
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::zset::ZsetCommandCtx;
-use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::utils::{resp3_score_pairs, resp_err, resp_invalid_arguments};
 use crate::{Connection, Frame};
 
 use crate::config::LOGGER;
@@ -15,7 +15,7 @@ use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
 pub struct Zrevrange {
-    key: String,
+    key: Bytes,
     min: i64,
     max: i64,
     withscores: bool,
@@ -23,9 +23,9 @@ pub struct Zrevrange {
 }
 
 impl Zrevrange {
-    pub fn new(key: &str, min: i64, max: i64, withscores: bool) -> Zrevrange {
+    pub fn new(key: &Bytes, min: i64, max: i64, withscores: bool) -> Zrevrange {
         Zrevrange {
-            key: key.to_string(),
+            key: key.clone(),
             min,
             max,
             withscores,
@@ -34,7 +34,7 @@ impl Zrevrange {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zrevrange> {
-        let key = parse.next_string()?;
+        let key = parse.next_bytes()?;
 
         let min = parse.next_int()?;
         let max = parse.next_int()?;
@@ -73,13 +73,16 @@ impl Zrevrange {
                 withscores = true;
             }
         }
-        let z = Zrevrange::new(&String::from_utf8_lossy(&argv[0]), min, max, withscores);
+        let z = Zrevrange::new(&argv[0], min, max, withscores);
 
         Ok(z)
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
-        let response = self.zrevrange(None).await?;
+        let mut response = self.zrevrange(None).await?;
+        if self.withscores && dst.protocol() == 3 {
+            response = resp3_score_pairs(response);
+        }
         debug!(
             LOGGER,
             "res, {} -> {}, {:?}",
@@ -109,11 +112,15 @@ impl Zrevrange {
 impl Invalid for Zrevrange {
     fn new_invalid() -> Zrevrange {
         Zrevrange {
-            key: "".to_string(),
+            key: Bytes::new(),
             min: 0,
             max: 0,
             withscores: false,
             valid: false,
         }
     }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
 }
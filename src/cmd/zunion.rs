@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::set::SetOperation;
+use crate::tikv::zset::{ZsetAggregate, ZsetCommandCtx};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return the union, intersection or difference of the sorted sets, used by
+/// ZUNION, ZINTER and ZDIFF.
+#[derive(Debug, Clone)]
+pub struct Zunion {
+    op: SetOperation,
+    keys: Vec<Bytes>,
+    weights: Vec<f64>,
+    aggregate: ZsetAggregate,
+    with_scores: bool,
+    valid: bool,
+}
+
+impl Zunion {
+    /// Parse the arguments after the destination key if `store` is set, the
+    /// WITHSCORES option is not allowed then.
+    pub fn new(op: SetOperation, args: &[Bytes], store: bool) -> Zunion {
+        let numkeys = match args
+            .first()
+            .map(|n| String::from_utf8_lossy(n).parse::<usize>())
+        {
+            Some(Ok(n)) if n > 0 && args.len() > n => n,
+            _ => return Zunion::new_invalid(),
+        };
+        let mut zunion = Zunion {
+            op,
+            keys: args[1..=numkeys].to_vec(),
+            weights: vec![1.0; numkeys],
+            aggregate: ZsetAggregate::Sum,
+            with_scores: false,
+            valid: true,
+        };
+
+        // ZDIFF takes the scores of the first key as they are
+        let combined = op != SetOperation::Diff;
+        let mut idx = numkeys + 1;
+        while idx < args.len() {
+            let left = args.len() - idx - 1;
+            match String::from_utf8_lossy(&args[idx]).to_uppercase().as_str() {
+                "WEIGHTS" if combined && left >= numkeys => {
+                    for (i, weight) in args[idx + 1..=idx + numkeys].iter().enumerate() {
+                        match String::from_utf8_lossy(weight).parse::<f64>() {
+                            Ok(weight) if !weight.is_nan() => zunion.weights[i] = weight,
+                            _ => return Zunion::new_invalid(),
+                        }
+                    }
+                    idx += numkeys;
+                }
+                "AGGREGATE" if combined && left >= 1 => {
+                    zunion.aggregate = match String::from_utf8_lossy(&args[idx + 1])
+                        .to_uppercase()
+                        .as_str()
+                    {
+                        "SUM" => ZsetAggregate::Sum,
+                        "MIN" => ZsetAggregate::Min,
+                        "MAX" => ZsetAggregate::Max,
+                        _ => return Zunion::new_invalid(),
+                    };
+                    idx += 1;
+                }
+                "WITHSCORES" if !store => zunion.with_scores = true,
+                _ => return Zunion::new_invalid(),
+            }
+            idx += 1;
+        }
+        zunion
+    }
+
+    pub fn keys(&self) -> &Vec<Bytes> {
+        &self.keys
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// ZUNION numkeys key [key ...] [WEIGHTS weight [weight ...]]
+    ///   [AGGREGATE SUM|MIN|MAX] [WITHSCORES]
+    /// ZINTER numkeys key [key ...] [WEIGHTS weight [weight ...]]
+    ///   [AGGREGATE SUM|MIN|MAX] [WITHSCORES]
+    /// ZDIFF numkeys key [key ...] [WITHSCORES]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse, op: SetOperation) -> crate::Result<Zunion> {
+        let mut args = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => args.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Zunion::new_invalid()),
+            }
+        }
+
+        Ok(Zunion::new(op, &args, false))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes], op: SetOperation) -> crate::Result<Zunion> {
+        Ok(Zunion::new(op, argv, false))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zunion(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zunion(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zset_algebra(
+                    self.op,
+                    &self.keys,
+                    &self.weights,
+                    self.aggregate,
+                    self.with_scores,
+                )
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+
+    /// Store the result in `dest` instead of returning it.
+    pub(crate) async fn zunionstore(
+        &self,
+        dest: &[u8],
+        txn: Option<Arc<Mutex<Transaction>>>,
+    ) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zset_algebra_store(
+                    self.op,
+                    dest,
+                    &self.keys,
+                    &self.weights,
+                    self.aggregate,
+                )
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zunion {
+    fn new_invalid() -> Zunion {
+        Zunion {
+            op: SetOperation::Union,
+            keys: vec![],
+            weights: vec![],
+            aggregate: ZsetAggregate::Sum,
+            with_scores: false,
+            valid: false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.valid
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use crate::cmd::zunion::Zunion;
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::tikv::errors::AsyncResult;
+use crate::tikv::set::SetOperation;
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Store the union, intersection or difference of the sorted sets in the
+/// destination key, used by ZUNIONSTORE, ZINTERSTORE and ZDIFFSTORE.
+#[derive(Debug, Clone)]
+pub struct Zunionstore {
+    dest: Bytes,
+    zunion: Zunion,
+}
+
+impl Zunionstore {
+    pub fn new(op: SetOperation, dest: &Bytes, args: &[Bytes]) -> Zunionstore {
+        Zunionstore {
+            dest: dest.clone(),
+            zunion: Zunion::new(op, args, true),
+        }
+    }
+
+    pub fn dest(&self) -> &Bytes {
+        &self.dest
+    }
+
+    /// # Format
+    ///
+    /// ```text
+    /// ZUNIONSTORE destination numkeys key [key ...] [WEIGHTS weight [weight ...]]
+    ///   [AGGREGATE SUM|MIN|MAX]
+    /// ZINTERSTORE destination numkeys key [key ...] [WEIGHTS weight [weight ...]]
+    ///   [AGGREGATE SUM|MIN|MAX]
+    /// ZDIFFSTORE destination numkeys key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse, op: SetOperation) -> crate::Result<Zunionstore> {
+        let dest = parse.next_bytes()?;
+
+        let mut args = vec![];
+        loop {
+            match parse.next_bytes() {
+                Ok(arg) => args.push(arg),
+                Err(ParseError::EndOfStream) => break,
+                Err(_) => return Ok(Zunionstore::new_invalid()),
+            }
+        }
+
+        Ok(Zunionstore::new(op, &dest, &args))
+    }
+
+    pub(crate) fn parse_argv(argv: &[Bytes], op: SetOperation) -> crate::Result<Zunionstore> {
+        if argv.is_empty() {
+            return Ok(Zunionstore::new_invalid());
+        }
+        Ok(Zunionstore::new(op, &argv[0], &argv[1..]))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zunionstore(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zunionstore(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        self.zunion.zunionstore(&self.dest, txn).await
+    }
+}
+
+impl Invalid for Zunionstore {
+    fn new_invalid() -> Zunionstore {
+        Zunionstore {
+            dest: Bytes::new(),
+            zunion: Zunion::new_invalid(),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.zunion.is_valid()
+    }
+}
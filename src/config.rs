@@ -65,6 +65,7 @@ struct Server {
     cluster_topology_interval: Option<u64>,
     cluster_topology_expire: Option<u64>,
     meta_key_number: Option<u16>,
+    notify_keyspace_events: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -96,9 +97,6 @@ struct Backend {
     txn_lock_backoff_delay_ms: Option<u64>,
     txn_lock_backoff_delay_attemps: Option<u32>,
 
-    cmd_lrem_length_limit: Option<u32>,
-    cmd_linsert_length_limit: Option<u32>,
-
     async_deletion_enabled: Option<bool>,
 
     async_gc_worker_number: Option<usize>,
@@ -109,11 +107,19 @@ struct Backend {
     async_del_hash_threshold: Option<u32>,
     async_del_set_threshold: Option<u32>,
     async_del_zset_threshold: Option<u32>,
+    async_del_string_threshold: Option<u32>,
 
     async_expire_list_threshold: Option<u32>,
     async_expire_hash_threshold: Option<u32>,
     async_expire_set_threshold: Option<u32>,
     async_expire_zset_threshold: Option<u32>,
+    async_expire_string_threshold: Option<u32>,
+
+    active_expire_enabled: Option<bool>,
+    active_expire_interval: Option<u64>,
+    active_expire_keys_per_round: Option<u32>,
+
+    blocking_poll_interval: Option<u64>,
 }
 
 // Config
@@ -130,6 +136,15 @@ pub fn is_auth_enabled() -> bool {
     false
 }
 
+pub fn auth_password() -> Option<String> {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            return c.server.password.clone();
+        }
+    }
+    None
+}
+
 // return false only if auth is enabled and password mismatch
 pub fn is_auth_matched(password: &str) -> bool {
     unsafe {
@@ -361,6 +376,19 @@ pub fn config_meta_key_number_or_default() -> u16 {
     100
 }
 
+pub fn config_notify_keyspace_events_or_default() -> String {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(s) = c.server.notify_keyspace_events.clone() {
+                return s;
+            }
+        }
+    }
+
+    // keyspace notifications disabled by default
+    "".to_owned()
+}
+
 fn log_level_str() -> String {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
@@ -509,34 +537,25 @@ pub fn txn_lock_backoff_delay_attemps() -> u32 {
     2
 }
 
-pub fn cmd_lrem_length_limit_or_default() -> u32 {
+pub fn async_del_list_threshold_or_default() -> u32 {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.cmd_lrem_length_limit {
+            if let Some(b) = c.backend.async_del_list_threshold {
                 return b;
             }
         }
     }
-    // default lrem length no limit
-    0
-}
-
-pub fn cmd_linsert_length_limit_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.cmd_linsert_length_limit {
-                return b;
-            }
-        }
+    if async_deletion_enabled_or_default() {
+        1000
+    } else {
+        u32::MAX
     }
-    // default linsert length no limit
-    0
 }
 
-pub fn async_del_list_threshold_or_default() -> u32 {
+pub fn async_del_hash_threshold_or_default() -> u32 {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_del_list_threshold {
+            if let Some(b) = c.backend.async_del_hash_threshold {
                 return b;
             }
         }
@@ -548,10 +567,10 @@ pub fn async_del_list_threshold_or_default() -> u32 {
     }
 }
 
-pub fn async_del_hash_threshold_or_default() -> u32 {
+pub fn async_del_set_threshold_or_default() -> u32 {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_del_hash_threshold {
+            if let Some(b) = c.backend.async_del_set_threshold {
                 return b;
             }
         }
@@ -563,10 +582,10 @@ pub fn async_del_hash_threshold_or_default() -> u32 {
     }
 }
 
-pub fn async_del_set_threshold_or_default() -> u32 {
+pub fn async_del_zset_threshold_or_default() -> u32 {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_del_set_threshold {
+            if let Some(b) = c.backend.async_del_zset_threshold {
                 return b;
             }
         }
@@ -578,10 +597,10 @@ pub fn async_del_set_threshold_or_default() -> u32 {
     }
 }
 
-pub fn async_del_zset_threshold_or_default() -> u32 {
+pub fn async_del_string_threshold_or_default() -> u32 {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_del_zset_threshold {
+            if let Some(b) = c.backend.async_del_string_threshold {
                 return b;
             }
         }
@@ -653,6 +672,21 @@ pub fn async_expire_zset_threshold_or_default() -> u32 {
     }
 }
 
+pub fn async_expire_string_threshold_or_default() -> u32 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.async_expire_string_threshold {
+                return b;
+            }
+        }
+    }
+    if async_deletion_enabled_or_default() {
+        1000
+    } else {
+        u32::MAX
+    }
+}
+
 pub fn async_deletion_enabled_or_default() -> bool {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
@@ -701,6 +735,55 @@ pub fn async_gc_worker_queue_size_or_default() -> usize {
     100000
 }
 
+pub fn active_expire_enabled_or_default() -> bool {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.active_expire_enabled {
+                return b;
+            }
+        }
+    }
+    // default active expire enabled
+    true
+}
+
+pub fn active_expire_interval_or_default() -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.active_expire_interval {
+                return b;
+            }
+        }
+    }
+    // default active expire interval in ms
+    1000
+}
+
+pub fn active_expire_keys_per_round_or_default() -> u32 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.active_expire_keys_per_round {
+                return b;
+            }
+        }
+    }
+    // default max number of expired keys deleted in one round
+    1000
+}
+
+pub fn blocking_poll_interval_or_default() -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.blocking_poll_interval {
+                return b;
+            }
+        }
+    }
+    // default interval in ms to check the keys of blocked clients, which may
+    // be changed by other instances
+    100
+}
+
 pub fn backend_timeout_or_default() -> u64 {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
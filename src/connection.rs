@@ -3,11 +3,16 @@ use crate::metrics::{DATA_TRAFFIC_IN, DATA_TRAFFIC_OUT};
 
 use async_std::io::{BufReader, BufWriter, WriteExt};
 use async_std::net::TcpStream;
-use async_tls::server::TlsStream;
 use bytes::{Buf, BytesMut};
-use futures::AsyncReadExt;
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite};
+use std::fmt::Debug;
 use std::io::{self, Cursor};
 
+/// A TLS stream, either accepted from a client or connected to a peer.
+trait TlsStream: AsyncRead + AsyncWrite + Send + Unpin + Debug {}
+
+impl<S: AsyncRead + AsyncWrite + Send + Unpin + Debug> TlsStream for S {}
+
 /// Send and receive `Frame` values from a remote peer.
 ///
 /// When implementing networking protocols, a message on that protocol is
@@ -30,14 +35,20 @@ pub struct Connection {
     w: Option<BufWriter<TcpStream>>,
     r: Option<BufReader<TcpStream>>,
 
-    tls_w: Option<BufWriter<futures::io::WriteHalf<TlsStream<TcpStream>>>>,
-    tls_r: Option<BufReader<futures::io::ReadHalf<TlsStream<TcpStream>>>>,
+    tls_w: Option<BufWriter<futures::io::WriteHalf<Box<dyn TlsStream>>>>,
+    tls_r: Option<BufReader<futures::io::ReadHalf<Box<dyn TlsStream>>>>,
 
     local_addr: String,
     peer_addr: String,
 
+    // The protocol version negotiated by `HELLO`, 2 by default.
+    protocol: u8,
+
     // The buffer for reading frames.
     buffer: BytesMut,
+
+    // Set when the peer closed the connection.
+    eof: bool,
 }
 
 impl Connection {
@@ -48,6 +59,7 @@ impl Connection {
             tls: false,
             local_addr: socket.local_addr().unwrap().to_string(),
             peer_addr: socket.peer_addr().unwrap().to_string(),
+            protocol: 2,
 
             w: Some(BufWriter::new(socket.clone())),
             r: Some(BufReader::new(socket)),
@@ -59,19 +71,21 @@ impl Connection {
             // value to their specific use case. There is a high likelihood that
             // a larger read buffer will work better.
             buffer: BytesMut::with_capacity(32 * 1024),
+            eof: false,
         }
     }
 
-    pub fn new_tls(
-        local_addr: &str,
-        peer_addr: &str,
-        tls_stream: TlsStream<TcpStream>,
-    ) -> Connection {
+    pub fn new_tls<S>(local_addr: &str, peer_addr: &str, tls_stream: S) -> Connection
+    where
+        S: AsyncRead + AsyncWrite + Send + Unpin + Debug + 'static,
+    {
+        let tls_stream: Box<dyn TlsStream> = Box::new(tls_stream);
         let (tls_r, tls_w) = tls_stream.split();
         Connection {
             tls: true,
             local_addr: local_addr.to_string(),
             peer_addr: peer_addr.to_string(),
+            protocol: 2,
 
             w: None,
             r: None,
@@ -79,6 +93,7 @@ impl Connection {
             tls_w: Some(BufWriter::new(tls_w)),
             tls_r: Some(BufReader::new(tls_r)),
             buffer: BytesMut::with_capacity(32 * 1024),
+            eof: false,
         }
     }
 
@@ -90,6 +105,15 @@ impl Connection {
         &self.peer_addr
     }
 
+    /// The RESP protocol version used to encode replies
+    pub fn protocol(&self) -> u8 {
+        self.protocol
+    }
+
+    pub fn set_protocol(&mut self, protocol: u8) {
+        self.protocol = protocol;
+    }
+
     async fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
         if self.tls {
             self.tls_w.as_mut().unwrap().write_all(buf).await?;
@@ -100,7 +124,7 @@ impl Connection {
         Ok(())
     }
 
-    async fn flush(&mut self) -> io::Result<()> {
+    pub async fn flush(&mut self) -> io::Result<()> {
         if self.tls {
             self.tls_w.as_mut().unwrap().flush().await?;
         } else {
@@ -137,6 +161,11 @@ impl Connection {
                 return Ok(Some(frame));
             }
 
+            // Replies of pipelined requests are held back until all of the
+            // buffered requests are served, send them out before waiting for
+            // the peer.
+            self.flush().await?;
+
             // There is not enough buffered data to read a frame. Attempt to
             // read more data from the socket.
             //
@@ -161,6 +190,39 @@ impl Connection {
         }
     }
 
+    /// Wait until the peer closes the connection, used to notice a client
+    /// leaving while it is blocked. The requests received in the meantime are
+    /// buffered to be read by `read_frame` later.
+    pub async fn closed(&mut self) -> io::Result<()> {
+        let mut buf = vec![0; 1024];
+        loop {
+            let len = self.read(&mut buf).await?;
+            if 0 == len {
+                self.eof = true;
+                return Ok(());
+            }
+            self.buffer.extend_from_slice(&buf[..len]);
+        }
+    }
+
+    /// Returns true once `closed` noticed the peer closed the connection.
+    pub fn is_closed(&self) -> bool {
+        self.eof
+    }
+
+    /// Read a `Frame` value which has already been buffered, without waiting
+    /// on the underlying stream.
+    ///
+    /// This is used to look ahead at pipelined requests. `None` is returned if
+    /// the buffer doesn't hold a complete frame.
+    pub fn try_read_frame(&mut self) -> crate::Result<Option<Frame>> {
+        let (frame, len) = self.parse_frame()?;
+        if frame.is_some() {
+            DATA_TRAFFIC_IN.inc_by(len as u64);
+        }
+        Ok(frame)
+    }
+
     /// Tries to parse a frame from the buffer. If the buffer contains enough
     /// data, the frame is returned and the data removed from the buffer. If not
     /// enough data has been buffered yet, `Ok(None)` is returned. If the
@@ -168,6 +230,25 @@ impl Connection {
     fn parse_frame(&mut self) -> crate::Result<(Option<Frame>, usize)> {
         use frame::Error::Incomplete;
 
+        // Inline commands, such as a `PING` typed over telnet or sent by a
+        // health checker, don't start with a RESP type byte. Empty inline
+        // lines are skipped like redis does.
+        while let Some(&first) = self.buffer.first() {
+            if !Frame::is_inline(first) {
+                break;
+            }
+            match Frame::parse_inline(&self.buffer[..]) {
+                Ok((frame, len)) => {
+                    self.buffer.advance(len);
+                    if frame.is_some() {
+                        return Ok((frame, len));
+                    }
+                }
+                Err(Incomplete) => return Ok((None, 0)),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
         // Cursor is used to track the "current" location in the
         // buffer. Cursor also implements `Buf` from the `bytes` crate
         // which provides a number of helpful utilities for working
@@ -228,51 +309,25 @@ impl Connection {
 
     /// Write a single `Frame` value to the underlying stream.
     ///
-    /// The `Frame` value is written to the socket using the various `write_*`
-    /// functions provided by `AsyncWrite`. Calling these functions directly on
-    /// a `TcpStream` is **not** advised, as this will result in a large number of
-    /// syscalls. However, it is fine to call these functions on a *buffered*
-    /// write stream. The data will be written to the buffer. Once the buffer is
-    /// full, it is flushed to the underlying socket.
+    /// The `Frame` is first encoded into an in-memory buffer, and the buffer is
+    /// then written to the socket with a single call to `write_all`. Encoding
+    /// is done synchronously, this allows nested frames of any depth without
+    /// the need for recursive async functions.
+    ///
+    /// RESP3 only frames are downgraded to their RESP2 equivalent if the peer
+    /// did not switch to protocol 3 with `HELLO`.
+    ///
+    /// If the peer has pipelined more requests, the frame is left in the write
+    /// buffer so that all the replies of the pipeline are sent with one write.
+    /// The write buffer is flushed by `read_frame` before it waits on the
+    /// socket.
     pub async fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
-        // Arrays are encoded by encoding each entry. All other frame types are
-        // considered literals. For now, this is not able to encode
-        // recursive frame structures. See below for more details.
-        match frame {
-            Frame::Array(val) => {
-                // Encode the frame type prefix. For an array, it is `*`.
-                self.write_all(b"*").await?;
-
-                // Encode the length of the array.
-                self.write_decimal(val.len() as i64).await?;
-
-                // Iterate and encode each entry in the array.
-                for entry in &**val {
-                    // TODO make this to be recursive
-                    // we need nested array response only for `cluster slots` command for now
-                    match entry {
-                        Frame::Array(val) => {
-                            self.write_all(b"*").await?;
-                            self.write_decimal(val.len() as i64).await?;
-                            for entry in &**val {
-                                match entry {
-                                    Frame::Array(val) => {
-                                        self.write_all(b"*").await?;
-                                        self.write_decimal(val.len() as i64).await?;
-                                        for entry in &**val {
-                                            self.write_value(entry).await?;
-                                        }
-                                    }
-                                    _ => self.write_value(entry).await?,
-                                }
-                            }
-                        }
-                        _ => self.write_value(entry).await?,
-                    }
-                }
-            }
-            // The frame type is a literal. Encode the value directly.
-            _ => self.write_value(frame).await?,
+        let mut buf = Vec::with_capacity(64);
+        encode_frame(&mut buf, frame, self.protocol == 3);
+        self.write_all(&buf).await?;
+
+        if !self.buffer.is_empty() {
+            return Ok(());
         }
 
         // Ensure the encoded frame is written to the socket. The calls above
@@ -280,63 +335,128 @@ impl Connection {
         // remaining contents of the buffer to the socket.
         self.flush().await
     }
+}
 
-    /// Write a frame literal to the stream
-    async fn write_value(&mut self, frame: &Frame) -> io::Result<()> {
-        match frame {
-            Frame::Simple(val) => {
-                self.write_all(b"+").await?;
-                self.write_all(val.as_bytes()).await?;
-                self.write_all(b"\r\n").await?;
+/// Encode a frame into `buf`, using RESP3 types only if `resp3` is set.
+fn encode_frame(buf: &mut Vec<u8>, frame: &Frame, resp3: bool) {
+    match frame {
+        Frame::Simple(val) => {
+            buf.push(b'+');
+            buf.extend_from_slice(val.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+        Frame::ErrorString(val) => {
+            buf.push(b'-');
+            buf.extend_from_slice(val.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+        Frame::ErrorOwned(val) => {
+            buf.push(b'-');
+            buf.extend_from_slice(val.as_bytes());
+            buf.extend_from_slice(b"\r\n");
+        }
+        Frame::Integer(val) => {
+            buf.push(b':');
+            encode_decimal(buf, *val);
+        }
+        Frame::Null => {
+            if resp3 {
+                buf.extend_from_slice(b"_\r\n");
+            } else {
+                buf.extend_from_slice(b"$-1\r\n");
+            }
+        }
+        Frame::NullArray => {
+            if resp3 {
+                buf.extend_from_slice(b"_\r\n");
+            } else {
+                buf.extend_from_slice(b"*-1\r\n");
+            }
+        }
+        Frame::Bulk(val) => encode_blob(buf, b'$', val),
+        Frame::Array(val) => encode_aggregate(buf, b'*', val, resp3),
+        Frame::Set(val) => {
+            let prefix = if resp3 { b'~' } else { b'*' };
+            encode_aggregate(buf, prefix, val, resp3);
+        }
+        Frame::Push(val) => {
+            let prefix = if resp3 { b'>' } else { b'*' };
+            encode_aggregate(buf, prefix, val, resp3);
+        }
+        Frame::Map(pairs) => {
+            // RESP2 has no map type, the map is flatten into an array of
+            // `[key, value, key, value ...]`
+            if resp3 {
+                buf.push(b'%');
+                encode_decimal(buf, pairs.len() as i64);
+            } else {
+                buf.push(b'*');
+                encode_decimal(buf, (pairs.len() * 2) as i64);
             }
-            Frame::ErrorString(val) => {
-                self.write_all(b"-").await?;
-                self.write_all(val.as_bytes()).await?;
-                self.write_all(b"\r\n").await?;
+            for (key, value) in pairs {
+                encode_frame(buf, key, resp3);
+                encode_frame(buf, value, resp3);
             }
-            Frame::ErrorOwned(val) => {
-                self.write_all(b"-").await?;
-                self.write_all(val.as_bytes()).await?;
-                self.write_all(b"\r\n").await?;
+        }
+        Frame::Double(val) => {
+            let val = Frame::format_double(*val);
+            if resp3 {
+                buf.push(b',');
+                buf.extend_from_slice(val.as_bytes());
+                buf.extend_from_slice(b"\r\n");
+            } else {
+                encode_blob(buf, b'$', val.as_bytes());
             }
-            Frame::Integer(val) => {
-                self.write_all(b":").await?;
-                self.write_decimal(*val).await?;
+        }
+        Frame::Boolean(val) => {
+            if resp3 {
+                buf.extend_from_slice(if *val { b"#t\r\n" } else { b"#f\r\n" });
+            } else {
+                buf.push(b':');
+                encode_decimal(buf, *val as i64);
             }
-            Frame::Null => {
-                self.write_all(b"$-1\r\n").await?;
+        }
+        Frame::BigNumber(val) => {
+            if resp3 {
+                buf.push(b'(');
+                buf.extend_from_slice(val.as_bytes());
+                buf.extend_from_slice(b"\r\n");
+            } else {
+                encode_blob(buf, b'$', val.as_bytes());
             }
-            Frame::Bulk(val) => {
-                let len = val.len();
-
-                self.write_all(b"$").await?;
-                self.write_decimal(len as i64).await?;
-                self.write_all(val).await?;
-                self.write_all(b"\r\n").await?;
+        }
+        Frame::Verbatim(format, val) => {
+            if resp3 {
+                buf.push(b'=');
+                encode_decimal(buf, (val.len() + 4) as i64);
+                buf.extend_from_slice(format.as_bytes());
+                buf.push(b':');
+                buf.extend_from_slice(val);
+                buf.extend_from_slice(b"\r\n");
+            } else {
+                encode_blob(buf, b'$', val);
             }
-            // Encoding an `Array` from within a value cannot be done using a
-            // recursive strategy. In general, async fns do not support
-            // recursion.
-            // We do support at most 3 nested array response in up-level for now.
-            Frame::Array(_val) => unreachable!(),
         }
-
-        Ok(())
     }
+}
 
-    /// Write a decimal frame to the stream
-    async fn write_decimal(&mut self, val: i64) -> io::Result<()> {
-        use std::io::Write;
-
-        // Convert the value to a string
-        let mut buf = [0u8; 20];
-        let mut buf = Cursor::new(&mut buf[..]);
-        write!(&mut buf, "{}", val)?;
+fn encode_aggregate(buf: &mut Vec<u8>, prefix: u8, entries: &[Frame], resp3: bool) {
+    buf.push(prefix);
+    encode_decimal(buf, entries.len() as i64);
+    for entry in entries {
+        encode_frame(buf, entry, resp3);
+    }
+}
 
-        let pos = buf.position() as usize;
-        self.write_all(&buf.get_ref()[..pos]).await?;
-        self.write_all(b"\r\n").await?;
+fn encode_blob(buf: &mut Vec<u8>, prefix: u8, val: &[u8]) {
+    buf.push(prefix);
+    encode_decimal(buf, val.len() as i64);
+    buf.extend_from_slice(val);
+    buf.extend_from_slice(b"\r\n");
+}
 
-        Ok(())
-    }
+/// Write a decimal followed by `\r\n` to the buffer
+fn encode_decimal(buf: &mut Vec<u8>, val: i64) {
+    buf.extend_from_slice(val.to_string().as_bytes());
+    buf.extend_from_slice(b"\r\n");
 }
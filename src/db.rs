@@ -2,6 +2,8 @@ use tokio::sync::{broadcast, Notify};
 use tokio::time::{self, Duration, Instant};
 
 use crate::config::LOGGER;
+use crate::metrics::{PUBSUB_CHANNELS_GAUGE, PUBSUB_SUBSCRIPTIONS_GAUGE};
+use crate::utils::glob_match;
 use bytes::Bytes;
 use slog::debug;
 use std::collections::{BTreeMap, HashMap};
@@ -66,7 +68,19 @@ struct State {
 
     scripts: HashMap<String, Bytes>,
 
-    pub_sub: HashMap<String, broadcast::Sender<Bytes>>,
+    pub_sub: HashMap<Bytes, broadcast::Sender<Bytes>>,
+
+    /// Broadcast channels of the glob pattern subscriptions, messages are
+    /// sent along with the channel they were published to.
+    pattern_pub_sub: HashMap<Bytes, broadcast::Sender<(Bytes, Bytes)>>,
+
+    /// Broadcast channels of the sharded pub/sub, which is a namespace apart
+    /// from the one of `pub_sub`.
+    shard_pub_sub: HashMap<Bytes, broadcast::Sender<Bytes>>,
+
+    /// Wakes up the clients blocked on a key when an element is pushed to it
+    /// on this instance.
+    blocking_keys: HashMap<Bytes, broadcast::Sender<()>>,
 
     /// Tracks key TTLs.
     ///
@@ -138,6 +152,9 @@ impl Db {
                 entries: HashMap::new(),
                 scripts: HashMap::new(),
                 pub_sub: HashMap::new(),
+                pattern_pub_sub: HashMap::new(),
+                shard_pub_sub: HashMap::new(),
+                blocking_keys: HashMap::new(),
                 expirations: BTreeMap::new(),
                 next_id: 0,
                 shutdown: false,
@@ -255,7 +272,7 @@ impl Db {
     /// The returned `Receiver` is used to receive values broadcast by `PUBLISH`
     /// commands.
     #[allow(clippy::significant_drop_in_scrutinee)]
-    pub(crate) fn subscribe(&self, key: String) -> broadcast::Receiver<Bytes> {
+    pub(crate) fn subscribe(&self, key: Bytes) -> broadcast::Receiver<Bytes> {
         use std::collections::hash_map::Entry;
 
         // Acquire the mutex
@@ -284,23 +301,205 @@ impl Db {
         }
     }
 
+    /// Returns a `Receiver` for the requested glob pattern.
+    ///
+    /// The returned `Receiver` is used to receive the values broadcast by
+    /// `PUBLISH` to any channel matching the pattern, along with the channel.
+    #[allow(clippy::significant_drop_in_scrutinee)]
+    pub(crate) fn psubscribe(&self, pattern: Bytes) -> broadcast::Receiver<(Bytes, Bytes)> {
+        use std::collections::hash_map::Entry;
+
+        let mut state = self.shared.state.lock().unwrap();
+
+        match state.pattern_pub_sub.entry(pattern) {
+            Entry::Occupied(e) => e.get().subscribe(),
+            Entry::Vacant(e) => {
+                let (tx, rx) = broadcast::channel(1024);
+                e.insert(tx);
+                rx
+            }
+        }
+    }
+
     /// Publish a message to the channel. Returns the number of subscribers
-    /// listening on the channel.
-    pub(crate) fn publish(&self, key: &str, value: Bytes) -> usize {
+    /// listening on the channel, including those of the matching patterns.
+    pub(crate) fn publish(&self, key: &Bytes, value: Bytes) -> usize {
         let state = self.shared.state.lock().unwrap();
 
-        state
+        let num_subscribers = state
             .pub_sub
             .get(key)
             // On a successful message send on the broadcast channel, the number
             // of subscribers is returned. An error indicates there are no
             // receivers, in which case, `0` should be returned.
-            .map(|tx| tx.send(value).unwrap_or(0))
+            .map(|tx| tx.send(value.clone()).unwrap_or(0))
             // If there is no entry for the channel key, then there are no
             // subscribers. In this case, return `0`.
+            .unwrap_or(0);
+
+        let num_pattern_subscribers: usize = state
+            .pattern_pub_sub
+            .iter()
+            .filter(|(pattern, _)| glob_match(pattern, key))
+            .map(|(_, tx)| tx.send((key.clone(), value.clone())).unwrap_or(0))
+            .sum();
+
+        num_subscribers + num_pattern_subscribers
+    }
+
+    /// Returns a `Receiver` for the requested shard channel.
+    ///
+    /// The returned `Receiver` is used to receive values broadcast by
+    /// `SPUBLISH` commands.
+    #[allow(clippy::significant_drop_in_scrutinee)]
+    pub(crate) fn ssubscribe(&self, key: Bytes) -> broadcast::Receiver<Bytes> {
+        use std::collections::hash_map::Entry;
+
+        let mut state = self.shared.state.lock().unwrap();
+
+        match state.shard_pub_sub.entry(key) {
+            Entry::Occupied(e) => e.get().subscribe(),
+            Entry::Vacant(e) => {
+                let (tx, rx) = broadcast::channel(1024);
+                e.insert(tx);
+                rx
+            }
+        }
+    }
+
+    /// Publish a message to the shard channel. Returns the number of
+    /// subscribers listening on the shard channel.
+    pub(crate) fn spublish(&self, key: &[u8], value: Bytes) -> usize {
+        let state = self.shared.state.lock().unwrap();
+
+        state
+            .shard_pub_sub
+            .get(key)
+            .map(|tx| tx.send(value).unwrap_or(0))
             .unwrap_or(0)
     }
 
+    /// Returns the channels having subscribers, which match the glob pattern
+    /// if given. Channels of the sharded pub/sub are returned if `shard` is set.
+    pub(crate) fn pubsub_channels(&self, pattern: Option<&[u8]>, shard: bool) -> Vec<Bytes> {
+        let state = self.shared.state.lock().unwrap();
+        let pub_sub = if shard {
+            &state.shard_pub_sub
+        } else {
+            &state.pub_sub
+        };
+
+        pub_sub
+            .iter()
+            .filter(|(_, tx)| tx.receiver_count() > 0)
+            .filter(|(channel, _)| match pattern {
+                Some(pattern) => glob_match(pattern, channel),
+                None => true,
+            })
+            .map(|(channel, _)| channel.clone())
+            .collect()
+    }
+
+    /// Returns the number of subscribers of each channel, patterns are not
+    /// counted. Channels of the sharded pub/sub are counted if `shard` is set.
+    pub(crate) fn pubsub_numsub(&self, channels: &[Bytes], shard: bool) -> Vec<usize> {
+        let state = self.shared.state.lock().unwrap();
+        let pub_sub = if shard {
+            &state.shard_pub_sub
+        } else {
+            &state.pub_sub
+        };
+
+        channels
+            .iter()
+            .map(|channel| pub_sub.get(channel).map_or(0, |tx| tx.receiver_count()))
+            .collect()
+    }
+
+    /// Returns the number of patterns having subscribers.
+    pub(crate) fn pubsub_numpat(&self) -> usize {
+        let state = self.shared.state.lock().unwrap();
+        state
+            .pattern_pub_sub
+            .values()
+            .filter(|tx| tx.receiver_count() > 0)
+            .count()
+    }
+
+    /// Remove the broadcast channels all subscribers have left, and refresh
+    /// the pub/sub metrics. Called when subscriptions of a client change.
+    pub(crate) fn prune_pub_sub(&self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.pub_sub.retain(|_, tx| tx.receiver_count() > 0);
+        state
+            .pattern_pub_sub
+            .retain(|_, tx| tx.receiver_count() > 0);
+        state.shard_pub_sub.retain(|_, tx| tx.receiver_count() > 0);
+
+        let stats = [
+            (
+                "channel",
+                state.pub_sub.len(),
+                receiver_count(&state.pub_sub),
+            ),
+            (
+                "pattern",
+                state.pattern_pub_sub.len(),
+                receiver_count(&state.pattern_pub_sub),
+            ),
+            (
+                "shard",
+                state.shard_pub_sub.len(),
+                receiver_count(&state.shard_pub_sub),
+            ),
+        ];
+        drop(state);
+
+        for (kind, num_channels, num_subscriptions) in stats {
+            PUBSUB_CHANNELS_GAUGE
+                .with_label_values(&[kind])
+                .set(num_channels as i64);
+            PUBSUB_SUBSCRIPTIONS_GAUGE
+                .with_label_values(&[kind])
+                .set(num_subscriptions as i64);
+        }
+    }
+
+    /// Returns a `Receiver` which is woken up when the key is ready, the
+    /// client should check the key again then.
+    pub(crate) fn watch_key(&self, key: &Bytes) -> broadcast::Receiver<()> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.blocking_keys.get(key) {
+            Some(tx) => tx.subscribe(),
+            None => {
+                let (tx, rx) = broadcast::channel(1);
+                state.blocking_keys.insert(key.clone(), tx);
+                rx
+            }
+        }
+    }
+
+    /// Remove the wakeup channels of the keys no client is blocked on anymore,
+    /// called after the receivers of `watch_key` are dropped.
+    pub(crate) fn unwatch_keys(&self, keys: &[Bytes]) {
+        let mut state = self.shared.state.lock().unwrap();
+        for key in keys {
+            if let Some(tx) = state.blocking_keys.get(key) {
+                if tx.receiver_count() == 0 {
+                    state.blocking_keys.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Wake up the clients blocked on the key.
+    pub(crate) fn signal_key_ready(&self, key: &[u8]) {
+        let state = self.shared.state.lock().unwrap();
+        if let Some(tx) = state.blocking_keys.get(key) {
+            let _ = tx.send(());
+        }
+    }
+
     /// Signals the purge background task to shut down. This is called by the
     /// `DbShutdown`s `Drop` implementation.
     fn shutdown_purge_task(&self) {
@@ -317,6 +516,11 @@ impl Db {
     }
 }
 
+/// Total number of subscribers of the broadcast channels.
+fn receiver_count<T>(pub_sub: &HashMap<Bytes, broadcast::Sender<T>>) -> usize {
+    pub_sub.values().map(|tx| tx.receiver_count()).sum()
+}
+
 impl Shared {
     /// Purge all expired keys and return the `Instant` at which the **next**
     /// key will expire. The background task will sleep until this instant.
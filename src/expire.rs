@@ -0,0 +1,266 @@
+use futures::FutureExt;
+use slog::{debug, error};
+use std::ops::Range;
+use tikv_client::{Key, KvPair};
+use tokio::time::{self, Duration, MissedTickBehavior};
+
+use crate::cluster::{key_hash_slot, Cluster};
+use crate::config::LOGGER;
+use crate::tikv::encoding::{DataType, KeyDecoder};
+use crate::tikv::errors::AsyncResult;
+use crate::tikv::hash::HashCommandCtx;
+use crate::tikv::list::ListCommandCtx;
+use crate::tikv::set::SetCommandCtx;
+use crate::tikv::string::StringCommandCtx;
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::tikv::{get_txn_client, KEY_ENCODER};
+use crate::utils::now_timestamp_in_millis;
+use crate::{
+    active_expire_enabled_or_default, active_expire_interval_or_default,
+    active_expire_keys_per_round_or_default,
+};
+
+/// Max number of slots holding entries in the ttl index which are scanned in
+/// one round, the following slots are scanned in the next rounds.
+const ACTIVE_EXPIRE_SLOTS_PER_ROUND: usize = 1024;
+
+/// Number of keys of the keyspace checked in one round of the ttl index
+/// backfill.
+const TTL_INDEX_BACKFILL_KEYS_PER_ROUND: u32 = 1024;
+
+/// Delete expired keys in background, keys written with a ttl and never
+/// accessed again would be kept forever by lazy expiration only.
+///
+/// Every ttl set to a key also writes an entry to the ttl index of the slot
+/// of the key, ordered by the expire timestamp. The expirer scans the expired
+/// entries of the slots owned by myself periodically and deletes the keys,
+/// so the nodes never race on the same entries. The slots without entries
+/// are skipped by seeking the next entry of the index.
+///
+/// The keys given a ttl before the ttl index existed are indexed by a one-off
+/// backfill, which checks a batch of the keyspace each round. Its progress is
+/// stored in `TiKV`, so it resumes after a restart and the nodes share it.
+#[derive(Debug, Clone)]
+pub struct ExpireMaster {
+    topo: Cluster,
+    next_slot: usize,
+    backfill_done: bool,
+}
+
+impl ExpireMaster {
+    pub fn new(topo: Cluster) -> Self {
+        ExpireMaster {
+            topo,
+            next_slot: 0,
+            backfill_done: false,
+        }
+    }
+
+    pub async fn run(&mut self) -> AsyncResult<()> {
+        let mut interval =
+            time::interval(Duration::from_millis(active_expire_interval_or_default()));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let txn_client = get_txn_client()?;
+        loop {
+            interval.tick().await;
+
+            if !active_expire_enabled_or_default() {
+                continue;
+            }
+
+            if !self.backfill_done {
+                match self.backfill_ttl_index().await {
+                    Ok(done) => self.backfill_done = done,
+                    Err(e) => error!(LOGGER, "[EXPIRE] backfill ttl index failed: {:?}", e),
+                }
+            }
+
+            // resume from the slot the last round stopped at
+            let (slot_start, slot_end) = self.topo.myself_owned_slots();
+            if self.next_slot < slot_start || self.next_slot > slot_end {
+                self.next_slot = slot_start;
+            }
+
+            let mut txn = txn_client.begin_with_latest();
+            let now = now_timestamp_in_millis();
+            let mut limit = active_expire_keys_per_round_or_default();
+            for _ in 0..ACTIVE_EXPIRE_SLOTS_PER_ROUND {
+                // seek the first entry of the following slots, the slots
+                // between are empty
+                let bound_range =
+                    KEY_ENCODER.encode_txnkv_expire_index_slots_range(self.next_slot, slot_end);
+                let first_key = match txn.scan_keys(bound_range, 1).await {
+                    Ok(mut iter) => iter.next(),
+                    Err(e) => {
+                        error!(LOGGER, "[EXPIRE] seek ttl index failed: {:?}", e);
+                        // retry next tick
+                        break;
+                    }
+                };
+                let (ttl, user_key) = match first_key {
+                    Some(key) => KeyDecoder::decode_key_expire_index(key),
+                    None => {
+                        // no entries left up to the last slot
+                        self.next_slot = slot_start;
+                        break;
+                    }
+                };
+                let slot = key_hash_slot(&user_key);
+
+                // the entries of a slot are ordered by the expire timestamp,
+                // there is nothing to expire in it if the first is still alive
+                if ttl < now {
+                    let bound_range = KEY_ENCODER.encode_txnkv_expire_index_key_range(slot, now);
+                    let index_keys: Vec<Key> = match txn.scan_keys(bound_range, limit).await {
+                        Ok(iter) => iter.collect(),
+                        Err(e) => {
+                            error!(LOGGER, "[EXPIRE] scan ttl index failed: {:?}", e);
+                            // retry next tick
+                            self.next_slot = slot;
+                            break;
+                        }
+                    };
+
+                    limit -= index_keys.len() as u32;
+                    for index_key in index_keys {
+                        let (ttl, user_key) =
+                            KeyDecoder::decode_key_expire_index(index_key.clone());
+                        if let Err(e) = self.expire_key(index_key, user_key, ttl).await {
+                            error!(LOGGER, "[EXPIRE] expire key failed: {:?}", e);
+                        }
+                    }
+
+                    // the slot may have more expired entries if the limit is reached
+                    if limit == 0 {
+                        self.next_slot = slot;
+                        break;
+                    }
+                }
+
+                if slot >= slot_end {
+                    self.next_slot = slot_start;
+                    break;
+                }
+                self.next_slot = slot + 1;
+            }
+        }
+    }
+
+    /// Write the missing entries of the ttl index for a batch of the
+    /// keyspace, returns true once the whole keyspace has been checked.
+    ///
+    /// The entries are put whether they exist or not, a stale entry is
+    /// harmless as the ttl of the key is checked before it is expired. The
+    /// batches of concurrent nodes conflict on the progress key, only one of
+    /// them commits and the others retry from the new progress next round.
+    async fn backfill_ttl_index(&self) -> AsyncResult<bool> {
+        let mut txn_client = get_txn_client()?;
+
+        txn_client
+            .exec_in_txn(None, |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    let backfill_key = KEY_ENCODER.encode_txnkv_expire_backfill_key();
+                    let start = match txn.get(backfill_key.clone()).await? {
+                        Some(v) if v.is_empty() => return Ok(true),
+                        Some(v) => Key::from(v),
+                        None => KEY_ENCODER.encode_txnkv_keyspace_start(),
+                    };
+
+                    // the iterator scans all keyspace include sub meta keys and data keys
+                    let range: Range<Key> = start..KEY_ENCODER.encode_txnkv_keyspace_end();
+                    let kvs: Vec<KvPair> = txn
+                        .scan(range, TTL_INDEX_BACKFILL_KEYS_PER_ROUND)
+                        .await?
+                        .collect();
+                    let done = kvs.len() < TTL_INDEX_BACKFILL_KEYS_PER_ROUND as usize;
+
+                    let mut next = vec![];
+                    for kv in kvs {
+                        let (user_key, is_meta_key) =
+                            KeyDecoder::decode_key_userkey_from_metakey(&kv.0);
+                        if is_meta_key {
+                            let ttl = KeyDecoder::decode_key_ttl(&kv.1);
+                            if ttl > 0 {
+                                let index_key =
+                                    KEY_ENCODER.encode_txnkv_expire_index_key(&user_key, ttl);
+                                txn.put(index_key, vec![]).await?;
+                            }
+                        }
+                        next = kv.0.into();
+                    }
+
+                    if done {
+                        debug!(LOGGER, "[EXPIRE] backfill ttl index done");
+                        txn.put(backfill_key, vec![]).await?;
+                    } else {
+                        // the smallest key after the last one scanned
+                        next.push(0);
+                        txn.put(backfill_key, next).await?;
+                    }
+                    Ok(done)
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Delete the user key if it still has the ttl of the index entry, the
+    /// entry is stale if the key has been deleted, persisted or given a new
+    /// ttl since, then only the entry itself is deleted.
+    async fn expire_key(&self, index_key: Key, user_key: Vec<u8>, ttl: u64) -> AsyncResult<()> {
+        let mut txn_client = get_txn_client()?;
+
+        txn_client
+            .exec_in_txn(None, |txn_rc| {
+                let index_key = index_key.clone();
+                let user_key = user_key.clone();
+                async move {
+                    let meta_key = KEY_ENCODER.encode_txnkv_string(&user_key);
+                    let meta_value = txn_rc.lock().await.get(meta_key).await?;
+                    if let Some(meta_value) = meta_value {
+                        if KeyDecoder::decode_key_ttl(&meta_value) == ttl {
+                            debug!(
+                                LOGGER,
+                                "[EXPIRE] delete expired key {}",
+                                String::from_utf8_lossy(&user_key)
+                            );
+                            let txn = Some(txn_rc.clone());
+                            match KeyDecoder::decode_key_type(&meta_value) {
+                                DataType::String => {
+                                    StringCommandCtx::new(txn)
+                                        .do_async_txnkv_string_expire_if_needed(&user_key)
+                                        .await?
+                                }
+                                DataType::Hash => {
+                                    HashCommandCtx::new(txn)
+                                        .do_async_txnkv_hash_expire_if_needed(&user_key)
+                                        .await?
+                                }
+                                DataType::List => {
+                                    ListCommandCtx::new(txn)
+                                        .do_async_txnkv_list_expire_if_needed(&user_key)
+                                        .await?
+                                }
+                                DataType::Set => {
+                                    SetCommandCtx::new(txn)
+                                        .do_async_txnkv_set_expire_if_needed(&user_key)
+                                        .await?
+                                }
+                                DataType::Zset => {
+                                    ZsetCommandCtx::new(txn)
+                                        .do_async_txnkv_zset_expire_if_needed(&user_key)
+                                        .await?
+                                }
+                                DataType::Null => 0,
+                            };
+                        }
+                    }
+                    txn_rc.lock().await.delete(index_key).await?;
+                    Ok(())
+                }
+                .boxed()
+            })
+            .await
+    }
+}
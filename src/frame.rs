@@ -9,7 +9,13 @@ use std::io::Cursor;
 use std::num::TryFromIntError;
 use std::string::FromUtf8Error;
 
+/// Max size of an inline request, the same limit redis uses.
+const INLINE_MAX_SIZE: usize = 64 * 1024;
+
 /// A frame in the Redis protocol.
+///
+/// The RESP3 only variants are downgraded to their RESP2 equivalent by
+/// `Connection` when the peer did not negotiate protocol 3 with `HELLO`.
 #[derive(Clone, Debug)]
 pub enum Frame {
     Simple(String),
@@ -18,7 +24,17 @@ pub enum Frame {
     Integer(i64),
     Bulk(Bytes),
     Null,
+    /// The null array, such as the reply of an aborted `EXEC`
+    NullArray,
     Array(Vec<Frame>),
+    Map(Vec<(Frame, Frame)>),
+    Set(Vec<Frame>),
+    Double(f64),
+    Boolean(bool),
+    BigNumber(String),
+    /// Verbatim string, the first field is the 3 bytes format such as `txt`
+    Verbatim(String, Bytes),
+    Push(Vec<Frame>),
 }
 
 #[derive(Debug)]
@@ -36,34 +52,122 @@ impl Frame {
         Frame::Array(vec![])
     }
 
-    /// Push a "bulk" frame into the array. `self` must be an Array frame.
+    /// Returns an empty push frame, used for out of band pub/sub messages
+    pub(crate) fn push() -> Frame {
+        Frame::Push(vec![])
+    }
+
+    /// Push a "bulk" frame into the array. `self` must be an Array or Push frame.
     ///
     /// # Panics
     ///
     /// panics if `self` is not an array
     pub(crate) fn push_bulk(&mut self, bytes: Bytes) {
         match self {
-            Frame::Array(vec) => {
+            Frame::Array(vec) | Frame::Push(vec) => {
                 vec.push(Frame::Bulk(bytes));
             }
             _ => panic!("not an array frame"),
         }
     }
 
-    /// Push an "integer" frame into the array. `self` must be an Array frame.
+    /// Push an "integer" frame into the array. `self` must be an Array or Push frame.
     ///
     /// # Panics
     ///
     /// panics if `self` is not an array
     pub(crate) fn push_int(&mut self, value: i64) {
         match self {
-            Frame::Array(vec) => {
+            Frame::Array(vec) | Frame::Push(vec) => {
                 vec.push(Frame::Integer(value));
             }
             _ => panic!("not an array frame"),
         }
     }
 
+    /// Build a map frame from a flat array of `[key, value, key, value ...]`
+    /// entries, as returned by HGETALL or CONFIG GET. Other frames are
+    /// returned untouched.
+    pub(crate) fn into_map(self) -> Frame {
+        match self {
+            Frame::Array(vec) => {
+                let mut pairs = Vec::with_capacity(vec.len() / 2);
+                let mut iter = vec.into_iter();
+                while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
+                    pairs.push((k, v));
+                }
+                Frame::Map(pairs)
+            }
+            frame => frame,
+        }
+    }
+
+    /// Format a double the way RESP3 and Redis replies expect it
+    pub(crate) fn format_double(value: f64) -> String {
+        if value.is_nan() {
+            "nan".to_owned()
+        } else if value.is_infinite() {
+            if value > 0f64 {
+                "inf".to_owned()
+            } else {
+                "-inf".to_owned()
+            }
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Returns true if `first` can't start a RESP frame, in which case the
+    /// request is sent with the inline command format, e.g. `PING\r\n`.
+    pub(crate) fn is_inline(first: u8) -> bool {
+        !matches!(
+            first,
+            b'+' | b'-'
+                | b':'
+                | b'$'
+                | b'*'
+                | b'~'
+                | b'>'
+                | b'%'
+                | b'|'
+                | b'_'
+                | b'#'
+                | b','
+                | b'('
+                | b'='
+                | b'!'
+        )
+    }
+
+    /// Parse an inline command from the start of `src` into an array of bulk
+    /// strings, the same frame a RESP client would send.
+    ///
+    /// Returns the parsed frame along with the number of consumed bytes, the
+    /// frame is `None` if the line holds no argument at all.
+    pub(crate) fn parse_inline(src: &[u8]) -> Result<(Option<Frame>, usize), Error> {
+        let end = match src.iter().position(|b| *b == b'\n') {
+            Some(end) => end,
+            None if src.len() > INLINE_MAX_SIZE => {
+                return Err("protocol error; too big inline request".into())
+            }
+            None => return Err(Error::Incomplete),
+        };
+
+        // both `\r\n` and `\n` are accepted as line terminator
+        let mut line = &src[..end];
+        if let Some(b'\r') = line.last() {
+            line = &line[..line.len() - 1];
+        }
+
+        let args = split_inline_args(line)?;
+        if args.is_empty() {
+            return Ok((None, end + 1));
+        }
+
+        let frame = Frame::Array(args.into_iter().map(Frame::Bulk).collect());
+        Ok((Some(frame), end + 1))
+    }
+
     /// Checks if an entire message can be decoded from `src`
     pub fn check(src: &mut Cursor<&[u8]>) -> Result<(), Error> {
         match get_u8(src)? {
@@ -91,7 +195,7 @@ impl Frame {
                     skip(src, len + 2)
                 }
             }
-            b'*' => {
+            b'*' | b'~' | b'>' => {
                 let len = get_decimal(src)?;
 
                 for _ in 0..len {
@@ -100,6 +204,34 @@ impl Frame {
 
                 Ok(())
             }
+            b'%' => {
+                let len = get_decimal(src)?;
+
+                for _ in 0..len * 2 {
+                    Frame::check(src)?;
+                }
+
+                Ok(())
+            }
+            b'|' => {
+                let len = get_decimal(src)?;
+
+                for _ in 0..len * 2 {
+                    Frame::check(src)?;
+                }
+
+                // attributes are always followed by the frame they describe
+                Frame::check(src)
+            }
+            b'_' | b'#' | b',' | b'(' => {
+                get_line(src)?;
+                Ok(())
+            }
+            b'=' | b'!' => {
+                // Read the blob, it has the same layout with bulk string
+                let len: usize = get_decimal(src)?.try_into()?;
+                skip(src, len + 2)
+            }
             actual => Err(format!("protocol error; invalid frame type byte `{}`", actual).into()),
         }
     }
@@ -156,20 +288,110 @@ impl Frame {
                 }
             }
             b'*' => {
-                let len = get_decimal(src)?.try_into()?;
-                let mut out = Vec::with_capacity(len);
-
-                for _ in 0..len {
-                    out.push(Frame::parse(src)?);
+                let len = get_decimal(src)?;
+                // `*-1\r\n` is the RESP2 null array
+                if len < 0 {
+                    return Ok(Frame::NullArray);
                 }
 
-                Ok(Frame::Array(out))
+                Ok(Frame::Array(parse_aggregate(src, len.try_into()?)?))
+            }
+            b'~' => {
+                let len = get_decimal(src)?.try_into()?;
+                Ok(Frame::Set(parse_aggregate(src, len)?))
+            }
+            b'>' => {
+                let len = get_decimal(src)?.try_into()?;
+                Ok(Frame::Push(parse_aggregate(src, len)?))
+            }
+            b'%' => {
+                let len = get_decimal(src)?.try_into()?;
+                Ok(Frame::Map(parse_pairs(src, len)?))
+            }
+            b'|' => {
+                // attributes are auxiliary data, skip them and return the
+                // frame they are attached to
+                let len = get_decimal(src)?.try_into()?;
+                parse_pairs(src, len)?;
+                Frame::parse(src)
+            }
+            b'_' => {
+                get_line(src)?;
+                Ok(Frame::Null)
+            }
+            b'#' => match get_line(src)? {
+                b"t" => Ok(Frame::Boolean(true)),
+                b"f" => Ok(Frame::Boolean(false)),
+                _ => Err("protocol error; invalid boolean".into()),
+            },
+            b',' => {
+                let line = get_line(src)?.to_vec();
+                let value = String::from_utf8(line)?
+                    .parse::<f64>()
+                    .map_err(|_| "protocol error; invalid double")?;
+                Ok(Frame::Double(value))
             }
-            _ => unimplemented!(),
+            b'(' => {
+                let line = get_line(src)?.to_vec();
+                Ok(Frame::BigNumber(String::from_utf8(line)?))
+            }
+            b'!' => {
+                let data = get_blob(src)?;
+                Ok(Frame::ErrorOwned(String::from_utf8(data.to_vec())?))
+            }
+            b'=' => {
+                let data = get_blob(src)?;
+                // verbatim strings are prefixed with a 3 bytes format and `:`
+                if data.len() < 4 || data[3] != b':' {
+                    return Err("protocol error; invalid verbatim string".into());
+                }
+                let format = String::from_utf8(data[..3].to_vec())?;
+                Ok(Frame::Verbatim(format, data.slice(4..)))
+            }
+            actual => Err(format!("protocol error; invalid frame type byte `{}`", actual).into()),
         }
     }
 }
 
+fn parse_aggregate(src: &mut Cursor<&[u8]>, len: usize) -> Result<Vec<Frame>, Error> {
+    let mut out = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        out.push(Frame::parse(src)?);
+    }
+
+    Ok(out)
+}
+
+fn parse_pairs(src: &mut Cursor<&[u8]>, len: usize) -> Result<Vec<(Frame, Frame)>, Error> {
+    let mut out = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        let key = Frame::parse(src)?;
+        let value = Frame::parse(src)?;
+        out.push((key, value));
+    }
+
+    Ok(out)
+}
+
+/// Read a length prefixed blob, the layout is the same as a bulk string
+fn get_blob(src: &mut Cursor<&[u8]>) -> Result<Bytes, Error> {
+    let len: usize = get_decimal(src)?.try_into()?;
+    let n = len + 2;
+
+    if src.remaining() < n {
+        return Err(Error::Incomplete);
+    }
+
+    let data = Bytes::copy_from_slice(&src.chunk()[..len]);
+
+    // skip that number of bytes + 2 (\r\n).
+    skip(src, n)?;
+
+    Ok(data)
+}
+
 impl PartialEq<&str> for Frame {
     fn eq(&self, other: &&str) -> bool {
         match self {
@@ -193,8 +415,8 @@ impl fmt::Display for Frame {
                 Ok(string) => string.fmt(fmt),
                 Err(_) => write!(fmt, "{:?}", msg),
             },
-            Frame::Null => "(nil)".fmt(fmt),
-            Frame::Array(parts) => {
+            Frame::Null | Frame::NullArray => "(nil)".fmt(fmt),
+            Frame::Array(parts) | Frame::Set(parts) | Frame::Push(parts) => {
                 for (i, part) in parts.iter().enumerate() {
                     if i > 0 {
                         write!(fmt, " ")?;
@@ -204,6 +426,23 @@ impl fmt::Display for Frame {
 
                 Ok(())
             }
+            Frame::Map(pairs) => {
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(fmt, " ")?;
+                    }
+                    write!(fmt, "{} {}", key, value)?;
+                }
+
+                Ok(())
+            }
+            Frame::Double(num) => Frame::format_double(*num).fmt(fmt),
+            Frame::Boolean(b) => b.fmt(fmt),
+            Frame::BigNumber(num) => num.fmt(fmt),
+            Frame::Verbatim(_, msg) => match str::from_utf8(msg) {
+                Ok(string) => string.fmt(fmt),
+                Err(_) => write!(fmt, "{:?}", msg),
+            },
         }
     }
 }
@@ -252,6 +491,89 @@ fn get_decimal(src: &mut Cursor<&[u8]>) -> Result<i64, Error> {
     atoi::<i64>(line).ok_or_else(|| "protocol error; invalid frame format".into())
 }
 
+/// Split an inline command line into arguments, quoted the same way as in redis-cli.
+fn split_inline_args(line: &[u8]) -> Result<Vec<Bytes>, Error> {
+    let is_space = |c: u8| matches!(c, b' ' | b'\n' | b'\r' | b'\t' | b'\0');
+    let hex_value = |c: u8| (c as char).to_digit(16).unwrap() as u8;
+
+    let mut args = vec![];
+    let mut i = 0;
+    loop {
+        while i < line.len() && is_space(line[i]) {
+            i += 1;
+        }
+        if i >= line.len() {
+            return Ok(args);
+        }
+
+        let mut current = vec![];
+        let mut in_dq = false;
+        let mut in_sq = false;
+        loop {
+            if in_dq {
+                if i >= line.len() {
+                    return Err("protocol error; unbalanced quotes in request".into());
+                }
+                if line[i] == b'\\'
+                    && i + 3 < line.len()
+                    && line[i + 1] == b'x'
+                    && line[i + 2].is_ascii_hexdigit()
+                    && line[i + 3].is_ascii_hexdigit()
+                {
+                    current.push(hex_value(line[i + 2]) * 16 + hex_value(line[i + 3]));
+                    i += 3;
+                } else if line[i] == b'\\' && i + 1 < line.len() {
+                    i += 1;
+                    current.push(match line[i] {
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        b'b' => 0x08,
+                        b'a' => 0x07,
+                        c => c,
+                    });
+                } else if line[i] == b'"' {
+                    // closing quote must be followed by a space or nothing at all
+                    if i + 1 < line.len() && !is_space(line[i + 1]) {
+                        return Err("protocol error; unbalanced quotes in request".into());
+                    }
+                    i += 1;
+                    break;
+                } else {
+                    current.push(line[i]);
+                }
+            } else if in_sq {
+                if i >= line.len() {
+                    return Err("protocol error; unbalanced quotes in request".into());
+                }
+                if line[i] == b'\\' && i + 1 < line.len() && line[i + 1] == b'\'' {
+                    i += 1;
+                    current.push(b'\'');
+                } else if line[i] == b'\'' {
+                    if i + 1 < line.len() && !is_space(line[i + 1]) {
+                        return Err("protocol error; unbalanced quotes in request".into());
+                    }
+                    i += 1;
+                    break;
+                } else {
+                    current.push(line[i]);
+                }
+            } else {
+                if i >= line.len() || is_space(line[i]) {
+                    break;
+                }
+                match line[i] {
+                    b'"' => in_dq = true,
+                    b'\'' => in_sq = true,
+                    c => current.push(c),
+                }
+            }
+            i += 1;
+        }
+        args.push(Bytes::from(current));
+    }
+}
+
 /// Find a line
 fn get_line<'a>(src: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], Error> {
     // Scan the bytes directly
@@ -123,30 +123,8 @@ impl GcMaster {
             for kv in iter {
                 let (user_key, version) = KeyDecoder::decode_key_gc_userkey_version(kv.0);
 
-                let (slot_range_left, slot_range_right) = self.topo.myself_owned_slots();
-                // crc16 to user key with hashtag `{}` support
-                // check if user key contains valid hashtag
-                let mut left_tag_idx = usize::MAX;
-                let mut right_tag_idx = usize::MAX;
-                for (idx, byte) in user_key.iter().enumerate() {
-                    if byte == &b'{' {
-                        left_tag_idx = idx;
-                    }
-                    if left_tag_idx != usize::MAX && byte == &b'}' {
-                        right_tag_idx = idx;
-                        break;
-                    }
-                }
-                let user_key_hash: usize =
-                    if right_tag_idx != usize::MAX && right_tag_idx - left_tag_idx > 1 {
-                        // we have a valid hashtag, do crc16 to string to the content in hashtag
-                        (CRC16.checksum(&user_key[left_tag_idx + 1..right_tag_idx]) & 0x3FFF).into()
-                    } else {
-                        (CRC16.checksum(&user_key) & 0x3FFF).into()
-                    };
-
                 // skip if user key is not owned by myself
-                if user_key_hash < slot_range_left || user_key_hash > slot_range_right {
+                if !self.topo.myself_owns_key(&user_key) {
                     continue;
                 }
                 let key_type = match kv.1[0] {
@@ -226,20 +204,34 @@ impl GcWorker {
                 let task = task.clone();
                 async move {
                     let mut txn = txn_rc.lock().await;
-                    let user_key = String::from_utf8_lossy(&task.user_key);
+                    let user_key = task.user_key.as_slice();
                     let version = task.version;
                     match task.key_type {
                         DataType::String => {
-                            panic!("string not support async deletion");
+                            debug!(
+                                LOGGER,
+                                "[GC] async delete string key {} with version {}",
+                                String::from_utf8_lossy(user_key),
+                                version
+                            );
+                            // delete all chunks of this key and version
+                            let bound_range =
+                                KEY_ENCODER.encode_txnkv_string_chunk_key_range(user_key, version);
+                            let iter = txn.scan_keys(bound_range, u32::MAX).await?;
+                            for k in iter {
+                                txn.delete(k).await?;
+                            }
                         }
                         DataType::Hash => {
                             debug!(
                                 LOGGER,
-                                "[GC] async delete hash key {} with version {}", user_key, version
+                                "[GC] async delete hash key {} with version {}",
+                                String::from_utf8_lossy(user_key),
+                                version
                             );
                             // delete all sub meta key of this key and version
                             let bound_range =
-                                KEY_ENCODER.encode_txnkv_sub_meta_key_range(&user_key, version);
+                                KEY_ENCODER.encode_txnkv_sub_meta_key_range(user_key, version);
                             let iter = txn.scan_keys(bound_range, u32::MAX).await?;
                             for k in iter {
                                 txn.delete(k).await?;
@@ -247,7 +239,7 @@ impl GcWorker {
 
                             // delete all data key of this key and version
                             let bound_range =
-                                KEY_ENCODER.encode_txnkv_hash_data_key_range(&user_key, version);
+                                KEY_ENCODER.encode_txnkv_hash_data_key_range(user_key, version);
                             let iter = txn.scan_keys(bound_range, u32::MAX).await?;
                             for k in iter {
                                 txn.delete(k).await?;
@@ -256,11 +248,21 @@ impl GcWorker {
                         DataType::List => {
                             debug!(
                                 LOGGER,
-                                "[GC] async delete list key {} with version {}", user_key, version
+                                "[GC] async delete list key {} with version {}",
+                                String::from_utf8_lossy(user_key),
+                                version
                             );
+                            // delete the positional index of a sparse list
+                            let bound_range =
+                                KEY_ENCODER.encode_txnkv_sub_meta_key_range(user_key, version);
+                            let iter = txn.scan_keys(bound_range, u32::MAX).await?;
+                            for k in iter {
+                                txn.delete(k).await?;
+                            }
+
                             // delete all data key of this key and version
                             let bound_range =
-                                KEY_ENCODER.encode_txnkv_list_data_key_range(&user_key, version);
+                                KEY_ENCODER.encode_txnkv_list_data_key_range(user_key, version);
                             let iter = txn.scan_keys(bound_range, u32::MAX).await?;
                             for k in iter {
                                 txn.delete(k).await?;
@@ -269,18 +271,20 @@ impl GcWorker {
                         DataType::Set => {
                             debug!(
                                 LOGGER,
-                                "[GC] async delete set key {} with version {}", user_key, version
+                                "[GC] async delete set key {} with version {}",
+                                String::from_utf8_lossy(user_key),
+                                version
                             );
                             // delete all sub meta key of this key and version
                             let bound_range =
-                                KEY_ENCODER.encode_txnkv_sub_meta_key_range(&user_key, version);
+                                KEY_ENCODER.encode_txnkv_sub_meta_key_range(user_key, version);
                             let iter = txn.scan_keys(bound_range, u32::MAX).await?;
                             for k in iter {
                                 txn.delete(k).await?;
                             }
                             // delete all data key of this key and version
                             let bound_range =
-                                KEY_ENCODER.encode_txnkv_set_data_key_range(&user_key, version);
+                                KEY_ENCODER.encode_txnkv_set_data_key_range(user_key, version);
                             let iter = txn.scan_keys(bound_range, u32::MAX).await?;
                             for k in iter {
                                 txn.delete(k).await?;
@@ -289,21 +293,21 @@ impl GcWorker {
                         DataType::Zset => {
                             debug!(
                                 LOGGER,
-                                "[GC] async delete zset key {} with version {}", user_key, version
+                                "[GC] async delete zset key {} with version {}",
+                                String::from_utf8_lossy(user_key),
+                                version
                             );
                             // delete all sub meta key of this key and version
                             let bound_range =
-                                KEY_ENCODER.encode_txnkv_sub_meta_key_range(&user_key, version);
+                                KEY_ENCODER.encode_txnkv_sub_meta_key_range(user_key, version);
                             let iter = txn.scan_keys(bound_range, u32::MAX).await?;
                             for k in iter {
                                 txn.delete(k).await?;
                             }
 
                             // delete all score key of this key and version
-                            let bound_range = KEY_ENCODER.encode_txnkv_zset_score_key_range(
-                                &String::from_utf8_lossy(&task.user_key),
-                                task.version,
-                            );
+                            let bound_range =
+                                KEY_ENCODER.encode_txnkv_zset_score_key_range(user_key, version);
                             let iter = txn.scan_keys(bound_range, u32::MAX).await?;
                             for k in iter {
                                 txn.delete(k).await?;
@@ -311,7 +315,7 @@ impl GcWorker {
 
                             // delete all data key of this key and version
                             let bound_range =
-                                KEY_ENCODER.encode_txnkv_zset_data_key_range(&user_key, version);
+                                KEY_ENCODER.encode_txnkv_zset_data_key_range(user_key, version);
                             let iter = txn.scan_keys(bound_range, u32::MAX).await?;
                             for k in iter {
                                 txn.delete(k).await?;
@@ -323,8 +327,7 @@ impl GcWorker {
                     }
 
                     // delete gc version key
-                    let gc_version_key =
-                        KEY_ENCODER.encode_txnkv_gc_version_key(&user_key, version);
+                    let gc_version_key = KEY_ENCODER.encode_txnkv_gc_version_key(user_key, version);
                     txn.delete(gc_version_key).await?;
 
                     Ok(())
@@ -339,9 +342,9 @@ impl GcWorker {
                 let task = task.clone();
                 async move {
                     let mut txn = txn_rc.lock().await;
-                    let user_key = String::from_utf8_lossy(&task.user_key);
+                    let user_key = task.user_key.as_slice();
                     // also delete gc key if version in gc key is same as task.version
-                    let gc_key = KEY_ENCODER.encode_txnkv_gc_key(&user_key);
+                    let gc_key = KEY_ENCODER.encode_txnkv_gc_key(user_key);
                     let version = task.version;
                     if let Some(v) = txn.get(gc_key.clone()).await? {
                         let ver = u16::from_be_bytes(v[..2].try_into().unwrap());
@@ -349,7 +352,7 @@ impl GcWorker {
                             debug!(
                                 LOGGER,
                                 "[GC] clean gc key for user key {} with version {}",
-                                user_key,
+                                String::from_utf8_lossy(user_key),
                                 version
                             );
                             txn.delete(gc_key).await?;
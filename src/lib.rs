@@ -41,19 +41,26 @@ pub use tikv::set_instance_id;
 
 pub mod cluster;
 
+mod notify;
+
 pub mod client;
 
 pub mod utils;
 
 pub mod config;
+pub use config::active_expire_enabled_or_default;
+pub use config::active_expire_interval_or_default;
+pub use config::active_expire_keys_per_round_or_default;
 pub use config::async_del_hash_threshold_or_default;
 pub use config::async_del_list_threshold_or_default;
 pub use config::async_del_set_threshold_or_default;
+pub use config::async_del_string_threshold_or_default;
 pub use config::async_del_zset_threshold_or_default;
 pub use config::async_deletion_enabled_or_default;
 pub use config::async_expire_hash_threshold_or_default;
 pub use config::async_expire_list_threshold_or_default;
 pub use config::async_expire_set_threshold_or_default;
+pub use config::async_expire_string_threshold_or_default;
 pub use config::async_expire_zset_threshold_or_default;
 pub use config::async_gc_interval_or_default;
 pub use config::async_gc_worker_number_or_default;
@@ -70,8 +77,7 @@ pub use config::backend_max_batch_wait_time_or_default;
 pub use config::backend_max_inflight_requests_or_default;
 pub use config::backend_overload_threshold_or_default;
 pub use config::backend_timeout_or_default;
-pub use config::cmd_linsert_length_limit_or_default;
-pub use config::cmd_lrem_length_limit_or_default;
+pub use config::blocking_poll_interval_or_default;
 pub use config::config_cluster_broadcast_addr_or_default;
 pub use config::config_cluster_topology_expire_or_default;
 pub use config::config_cluster_topology_interval_or_default;
@@ -107,6 +113,10 @@ pub use config::Config;
 
 pub mod gc;
 
+pub mod expire;
+
+pub mod peer;
+
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 
 /// Default port that a redis server listens on.
@@ -124,6 +124,25 @@ lazy_static! {
     )
     .unwrap();
 
+    // Pub/Sub
+    pub static ref PUBSUB_CHANNELS_GAUGE: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_redis_pubsub_channels",
+        "The number of channels with subscribers",
+        &["kind"]
+    )
+    .unwrap();
+    pub static ref PUBSUB_SUBSCRIPTIONS_GAUGE: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_redis_pubsub_subscriptions",
+        "The number of subscriptions of all clients",
+        &["kind"]
+    )
+    .unwrap();
+    pub static ref BLOCKED_CLIENTS_GAUGE: IntGauge = register_int_gauge!(
+        "tikv_redis_blocked_clients",
+        "The number of clients blocked by blocking commands"
+    )
+    .unwrap();
+
     // GC
     pub static ref GC_TASK_QUEUE_COUNTER: IntGaugeVec = register_int_gauge_vec!(
         "tikv_redis_gc_task_queue_count",
@@ -0,0 +1,237 @@
+//! Keyspace notifications, published to the `__keyspace@0__:<key>` and
+//! `__keyevent@0__:<event>` channels when keys are modified, as configured by
+//! `notify-keyspace-events`. They are forwarded to the other nodes of the
+//! cluster the same way as `PUBLISH` does. Also wakes up the clients of the
+//! local instance blocked on the modified keys.
+//!
+//! The events of the commands run by `EXEC` or a script are held back until
+//! their transaction is committed, and dropped if it is not.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::RwLock;
+
+use bytes::Bytes;
+use slog::warn;
+
+use crate::cluster::Cluster as Topo;
+use crate::config::{config_notify_keyspace_events_or_default, LOGGER};
+use crate::Db;
+
+pub const NOTIFY_KEYSPACE: u32 = 1 << 0; // K
+pub const NOTIFY_KEYEVENT: u32 = 1 << 1; // E
+pub const NOTIFY_GENERIC: u32 = 1 << 2; // g
+pub const NOTIFY_STRING: u32 = 1 << 3; // $
+pub const NOTIFY_LIST: u32 = 1 << 4; // l
+pub const NOTIFY_SET: u32 = 1 << 5; // s
+pub const NOTIFY_HASH: u32 = 1 << 6; // h
+pub const NOTIFY_ZSET: u32 = 1 << 7; // z
+pub const NOTIFY_EXPIRED: u32 = 1 << 8; // x
+pub const NOTIFY_EVICTED: u32 = 1 << 9; // e
+pub const NOTIFY_STREAM: u32 = 1 << 10; // t
+pub const NOTIFY_KEY_MISS: u32 = 1 << 11; // m
+pub const NOTIFY_MODULE: u32 = 1 << 12; // d
+pub const NOTIFY_NEW: u32 = 1 << 13; // n
+
+/// A, all the classes except key miss and new key events, as redis does.
+pub const NOTIFY_ALL: u32 = NOTIFY_GENERIC
+    | NOTIFY_STRING
+    | NOTIFY_LIST
+    | NOTIFY_SET
+    | NOTIFY_HASH
+    | NOTIFY_ZSET
+    | NOTIFY_EXPIRED
+    | NOTIFY_EVICTED
+    | NOTIFY_STREAM
+    | NOTIFY_MODULE;
+
+static NOTIFY_KEYSPACE_EVENTS: AtomicU32 = AtomicU32::new(0);
+
+lazy_static! {
+    static ref NOTIFY_DB: RwLock<Option<(Db, Topo)>> = RwLock::new(None);
+}
+
+tokio::task_local! {
+    /// The events of the transaction run by the current task, if any.
+    static PENDING_EVENTS: RefCell<Vec<PendingEvent>>;
+}
+
+/// An event held back until its transaction is committed.
+enum PendingEvent {
+    Keyspace(u32, String, Bytes),
+    KeyReady(Bytes),
+}
+
+/// The events of a transaction, published by `emit` once it is committed.
+#[derive(Default)]
+pub struct PendingEvents(Vec<PendingEvent>);
+
+impl PendingEvents {
+    pub fn emit(self) {
+        for event in self.0 {
+            match event {
+                PendingEvent::Keyspace(class, event, key) => {
+                    notify_keyspace_event(class, &event, &key)
+                }
+                PendingEvent::KeyReady(key) => signal_key_ready(&key),
+            }
+        }
+    }
+}
+
+/// Run `fut`, the commands of a transaction, holding back the events they
+/// notify. The events are returned for the caller to emit after the commit,
+/// none if an enclosing transaction already holds them back.
+pub async fn defer_events<F: Future>(fut: F) -> (F::Output, PendingEvents) {
+    if PENDING_EVENTS.try_with(|_| ()).is_ok() {
+        return (fut.await, PendingEvents::default());
+    }
+
+    PENDING_EVENTS
+        .scope(RefCell::new(vec![]), async move {
+            let output = fut.await;
+            let events = PENDING_EVENTS.with(|events| events.take());
+            (output, PendingEvents(events))
+        })
+        .await
+}
+
+/// Hold the event back if a transaction is running, returns true if so.
+fn defer_event(event: impl FnOnce() -> PendingEvent) -> bool {
+    PENDING_EVENTS
+        .try_with(|events| events.borrow_mut().push(event()))
+        .is_ok()
+}
+
+/// Start publishing keyspace notifications to the subscribers of `db` and the
+/// peers of `topo`, with the event classes of the config file.
+pub fn init_keyspace_notifications(db: Db, topo: Topo) {
+    NOTIFY_DB.write().unwrap().replace((db, topo));
+
+    let events = config_notify_keyspace_events_or_default();
+    let flags = keyspace_events_from_str(&events).unwrap_or_else(|| {
+        warn!(
+            LOGGER,
+            "invalid notify_keyspace_events {}, keyspace notifications disabled", events
+        );
+        0
+    });
+    set_notify_keyspace_events(flags);
+}
+
+pub fn notify_keyspace_events() -> u32 {
+    NOTIFY_KEYSPACE_EVENTS.load(Ordering::Relaxed)
+}
+
+pub fn set_notify_keyspace_events(flags: u32) {
+    NOTIFY_KEYSPACE_EVENTS.store(flags, Ordering::Relaxed)
+}
+
+/// Parse the event classes from the flags string, such as `KEA`, returns
+/// `None` if there is an unknown flag.
+pub fn keyspace_events_from_str(s: &str) -> Option<u32> {
+    let mut flags = 0;
+    for c in s.chars() {
+        flags |= match c {
+            'A' => NOTIFY_ALL,
+            'g' => NOTIFY_GENERIC,
+            '$' => NOTIFY_STRING,
+            'l' => NOTIFY_LIST,
+            's' => NOTIFY_SET,
+            'h' => NOTIFY_HASH,
+            'z' => NOTIFY_ZSET,
+            'x' => NOTIFY_EXPIRED,
+            'e' => NOTIFY_EVICTED,
+            'K' => NOTIFY_KEYSPACE,
+            'E' => NOTIFY_KEYEVENT,
+            't' => NOTIFY_STREAM,
+            'm' => NOTIFY_KEY_MISS,
+            'd' => NOTIFY_MODULE,
+            'n' => NOTIFY_NEW,
+            _ => return None,
+        };
+    }
+    Some(flags)
+}
+
+/// Format the event classes to the flags string, as `CONFIG GET` replies.
+pub fn keyspace_events_to_str(flags: u32) -> String {
+    let mut s = String::new();
+    if flags & NOTIFY_ALL == NOTIFY_ALL {
+        s.push('A');
+    } else {
+        for (flag, c) in [
+            (NOTIFY_GENERIC, 'g'),
+            (NOTIFY_STRING, '$'),
+            (NOTIFY_LIST, 'l'),
+            (NOTIFY_SET, 's'),
+            (NOTIFY_HASH, 'h'),
+            (NOTIFY_ZSET, 'z'),
+            (NOTIFY_EXPIRED, 'x'),
+            (NOTIFY_EVICTED, 'e'),
+            (NOTIFY_STREAM, 't'),
+            (NOTIFY_MODULE, 'd'),
+        ] {
+            if flags & flag != 0 {
+                s.push(c);
+            }
+        }
+    }
+    for (flag, c) in [
+        (NOTIFY_KEYSPACE, 'K'),
+        (NOTIFY_KEYEVENT, 'E'),
+        (NOTIFY_KEY_MISS, 'm'),
+        (NOTIFY_NEW, 'n'),
+    ] {
+        if flags & flag != 0 {
+            s.push(c);
+        }
+    }
+    s
+}
+
+/// Wake up the clients blocked on the key, after elements are added to it.
+pub fn signal_key_ready(key: &[u8]) {
+    if defer_event(|| PendingEvent::KeyReady(Bytes::copy_from_slice(key))) {
+        return;
+    }
+
+    if let Some((db, _)) = NOTIFY_DB.read().unwrap().as_ref() {
+        db.signal_key_ready(key);
+    }
+}
+
+/// Publish the event of the key if the class of the event is enabled.
+pub fn notify_keyspace_event(class: u32, event: &str, key: &[u8]) {
+    let flags = notify_keyspace_events();
+    if flags & class == 0 || flags & (NOTIFY_KEYSPACE | NOTIFY_KEYEVENT) == 0 {
+        return;
+    }
+
+    if defer_event(|| PendingEvent::Keyspace(class, event.to_owned(), Bytes::copy_from_slice(key)))
+    {
+        return;
+    }
+
+    let db_guard = NOTIFY_DB.read().unwrap();
+    let (db, topo) = match db_guard.as_ref() {
+        Some(notify_db) => notify_db,
+        None => return,
+    };
+
+    if flags & NOTIFY_KEYSPACE != 0 {
+        let channel = [&b"__keyspace@0__:"[..], key].concat();
+        publish(db, topo, channel.into(), Bytes::from(event.to_owned()));
+    }
+    if flags & NOTIFY_KEYEVENT != 0 {
+        let channel = format!("__keyevent@0__:{}", event);
+        publish(db, topo, channel.into(), Bytes::copy_from_slice(key));
+    }
+}
+
+/// Publish to the local subscribers, and forward to the peers in background.
+fn publish(db: &Db, topo: &Topo, channel: Bytes, message: Bytes) {
+    db.publish(&channel, message.clone());
+    topo.forward_to_peers(channel, message);
+}
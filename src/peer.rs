@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex as StdMutex, RwLock};
+
+use async_std::net::TcpStream;
+use async_tls::TlsConnector;
+use bytes::Bytes;
+use slog::{debug, warn};
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{self, Duration};
+
+use crate::config::{auth_password, LOGGER};
+use crate::{Connection, Frame};
+
+/// Max time to wait for the reply of a peer, the peer is skipped on timeout.
+const PEER_REQUEST_TIMEOUT_MS: u64 = 1000;
+
+/// Max number of messages waiting to be forwarded to a peer, the messages
+/// are dropped for the peer while its queue is full.
+const PEER_FORWARD_QUEUE_SIZE: usize = 1024;
+
+/// Connections to the other tidis instances of the cluster.
+///
+/// Messages published to an instance are forwarded to all of its peers with
+/// `PUBLISHLOCAL`, which only delivers them to the subscribers connected to
+/// the peer itself. The command carries the secret shared by the instances
+/// of the cluster through the backend store, and is rejected as unknown
+/// without it, so it is hidden from the clients.
+///
+/// A connection serves one request at a time, it is taken out of the idle
+/// ones during the request and only put back once the reply is read, so a
+/// connection whose request failed or timed out is dropped rather than
+/// reused with a pending reply. The peers are connected with TLS if the
+/// instances only serve TLS.
+///
+/// Messages forwarded in background are queued to a task per peer, which
+/// sends them one at a time, so a slow peer doesn't hold up the others.
+#[derive(Clone, Default)]
+pub struct Peers {
+    idle_conns: Arc<Mutex<HashMap<String, Vec<Connection>>>>,
+    forwarders: Arc<StdMutex<HashMap<String, mpsc::Sender<(Bytes, Bytes)>>>>,
+    tls_connector: Option<TlsConnector>,
+    secret: Arc<RwLock<Option<Bytes>>>,
+}
+
+impl fmt::Debug for Peers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Peers")
+            .field("tls", &self.tls_connector.is_some())
+            .finish()
+    }
+}
+
+impl Peers {
+    pub fn new(tls_connector: Option<TlsConnector>) -> Self {
+        Peers {
+            tls_connector,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the secret of the cluster, if it has been loaded yet.
+    pub fn secret(&self) -> Option<Bytes> {
+        self.secret.read().unwrap().clone()
+    }
+
+    pub fn set_secret(&self, secret: Bytes) {
+        *self.secret.write().unwrap() = Some(secret);
+    }
+
+    /// Returns true if `secret` is the secret of the cluster, which proves the
+    /// request comes from a peer.
+    pub fn is_secret(&self, secret: &[u8]) -> bool {
+        matches!(&*self.secret.read().unwrap(), Some(s) if s == secret)
+    }
+
+    /// Forward the message to the peers at `addrs` in background, through the
+    /// queue of each peer. The queues of the peers not in `addrs` are dropped.
+    pub fn forward(&self, addrs: Vec<String>, channel: Bytes, message: Bytes) {
+        let mut forwarders = self.forwarders.lock().unwrap();
+        // the task of a removed peer stops once its queue is dropped
+        forwarders.retain(|addr, _| addrs.contains(addr));
+        for addr in addrs {
+            let sender = forwarders
+                .entry(addr.clone())
+                .or_insert_with(|| self.spawn_forwarder(addr.clone()));
+            if let Err(e) = sender.try_send((channel.clone(), message.clone())) {
+                warn!(LOGGER, "[PEER] message to {} dropped: {}", addr, e);
+            }
+        }
+    }
+
+    /// Spawn the task sending the messages queued for the peer at `addr`.
+    fn spawn_forwarder(&self, addr: String) -> mpsc::Sender<(Bytes, Bytes)> {
+        let (tx, mut rx) = mpsc::channel::<(Bytes, Bytes)>(PEER_FORWARD_QUEUE_SIZE);
+        let peers = self.clone();
+        tokio::spawn(async move {
+            while let Some((channel, message)) = rx.recv().await {
+                if let Some(frame) = peers.publish_local_frame(&channel, &message) {
+                    peers.request_count(addr.clone(), frame).await;
+                }
+            }
+            peers.idle_conns.lock().await.remove(&addr);
+        });
+        tx
+    }
+
+    /// The `PUBLISHLOCAL` request of the message, nothing can be sent before
+    /// the secret is loaded by the topology manager.
+    fn publish_local_frame(&self, channel: &Bytes, message: &Bytes) -> Option<Frame> {
+        let secret = self.secret()?;
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from_static(b"publishlocal"));
+        frame.push_bulk(secret);
+        frame.push_bulk(channel.clone());
+        frame.push_bulk(message.clone());
+        Some(frame)
+    }
+
+    async fn request_count(&self, addr: String, frame: Frame) -> usize {
+        let timeout = Duration::from_millis(PEER_REQUEST_TIMEOUT_MS);
+        match time::timeout(timeout, self.request(&addr, &frame)).await {
+            Ok(Ok(Frame::Integer(n))) => n as usize,
+            res => {
+                warn!(LOGGER, "[PEER] request to {} failed: {:?}", addr, res);
+                0
+            }
+        }
+    }
+
+    /// Send the request on a connection owned by this call, the connection is
+    /// dropped along with the future on error or timeout.
+    async fn request(&self, addr: &str, frame: &Frame) -> crate::Result<Frame> {
+        let idle_conn = self
+            .idle_conns
+            .lock()
+            .await
+            .get_mut(addr)
+            .and_then(Vec::pop);
+        let mut conn = match idle_conn {
+            Some(conn) => conn,
+            None => self.connect(addr).await?,
+        };
+
+        conn.write_frame(frame).await?;
+        let response = match conn.read_frame().await? {
+            Some(frame) => frame,
+            None => return Err("connection closed by peer".into()),
+        };
+
+        self.idle_conns
+            .lock()
+            .await
+            .entry(addr.to_owned())
+            .or_default()
+            .push(conn);
+        Ok(response)
+    }
+
+    async fn connect(&self, addr: &str) -> crate::Result<Connection> {
+        debug!(LOGGER, "[PEER] connect to {}", addr);
+        let socket = TcpStream::connect(addr).await?;
+        let mut conn = match &self.tls_connector {
+            Some(connector) => {
+                let local_addr = socket.local_addr()?.to_string();
+                // the certificate of the peer is verified against its ip
+                let host = addr.rsplit_once(':').map_or(addr, |(host, _)| host);
+                let tls_stream = connector.connect(host, socket).await?;
+                Connection::new_tls(&local_addr, addr, tls_stream)
+            }
+            None => Connection::new(socket),
+        };
+
+        if let Some(password) = auth_password() {
+            let mut frame = Frame::array();
+            frame.push_bulk(Bytes::from_static(b"auth"));
+            frame.push_bulk(Bytes::from(password));
+            conn.write_frame(&frame).await?;
+            match conn.read_frame().await? {
+                Some(Frame::Simple(_)) => {}
+                res => return Err(format!("auth to peer failed: {:?}", res).into()),
+            }
+        }
+
+        Ok(conn)
+    }
+}
@@ -1,30 +1,39 @@
 use crate::cluster::Cluster;
+use crate::expire::ExpireMaster;
 use crate::gc::GcMaster;
 use crate::metrics::{
     CURRENT_CONNECTION_COUNTER, CURRENT_TLS_CONNECTION_COUNTER, REQUEST_CMD_COUNTER,
     REQUEST_CMD_ERROR_COUNTER, REQUEST_CMD_FINISH_COUNTER, REQUEST_CMD_HANDLE_TIME,
     REQUEST_COUNTER, TOTAL_CONNECTION_PROCESSED,
 };
+use crate::notify::init_keyspace_notifications;
+use crate::peer::Peers;
 use crate::tikv::encoding::KeyDecoder;
+use crate::tikv::string::StringCommandCtx;
 use crate::tikv::{get_txn_client, KEY_ENCODER};
-use crate::utils::{self, resp_err, resp_invalid_arguments, resp_ok, resp_queued, sleep};
+use crate::utils::{
+    self, resp_array, resp_bulk, resp_err, resp_int, resp_invalid_arguments, resp_ok, resp_queued,
+    sleep,
+};
 use crate::{
     async_gc_worker_number_or_default, config_cluster_broadcast_addr_or_default,
     config_cluster_topology_expire_or_default, config_cluster_topology_interval_or_default,
-    config_local_pool_number, is_auth_enabled, is_auth_matched, Command, Connection, Db,
-    DbDropGuard, Shutdown,
+    config_local_pool_number, is_auth_enabled, is_auth_matched, is_use_txn_api, Command,
+    Connection, Db, DbDropGuard, Frame, Shutdown,
 };
 use std::collections::HashMap;
 
 use async_std::net::{TcpListener, TcpStream};
+use bytes::Bytes;
 use futures::FutureExt;
+use hex::ToHex;
 use std::future::Future;
 use std::ops::Range;
 use std::sync::Arc;
-use tikv_client::{BoundRange, Key};
+use tikv_client::{BoundRange, Key, Timestamp};
 
 use async_std::prelude::StreamExt;
-use async_tls::TlsAcceptor;
+use async_tls::{TlsAcceptor, TlsConnector};
 use rand::Rng;
 use slog::{debug, error, info, warn};
 use tokio::sync::{broadcast, mpsc, Mutex};
@@ -39,7 +48,10 @@ use tokio_util::task::LocalPoolHandle;
 
 use crate::tikv::errors::{
     REDIS_AUTH_INVALID_PASSWORD_ERR, REDIS_AUTH_REQUIRED_ERR, REDIS_AUTH_WHEN_DISABLED_ERR,
-    REDIS_DISCARD_WITHOUT_MULTI_ERR, REDIS_EXEC_WITHOUT_MULTI_ERR, REDIS_MULTI_NESTED_ERR,
+    REDIS_DISCARD_WITHOUT_MULTI_ERR, REDIS_EXEC_ERR, REDIS_EXEC_WITHOUT_MULTI_ERR,
+    REDIS_HELLO_NOAUTH_ERR, REDIS_MULTI_NESTED_ERR, REDIS_NOPROTO_ERR,
+    REDIS_NOT_ALLOWED_IN_MULTI_ERR, REDIS_NOT_SUPPORTED_ERR, REDIS_WATCH_INSIDE_MULTI_ERR,
+    REDIS_WRONGPASS_ERR,
 };
 
 use crate::cmd::{script_clear_killed, script_interuptted};
@@ -149,6 +161,12 @@ struct Handler {
     /// The txn state of this connection.
     inner_txn: bool,
     queued_commands: Vec<Command>,
+    /// Set if a command was rejected while queued, `EXEC` then discards the
+    /// transaction.
+    queue_failed: bool,
+
+    /// Keys watched by `WATCH`, with the timestamp they are watched from.
+    watched_keys: Vec<(Bytes, Timestamp)>,
 
     /// Max connection semaphore.
     ///
@@ -202,10 +220,14 @@ struct Handler {
 ///
 /// `tokio::signal::ctrl_c()` can be used as the `shutdown` argument. This will
 /// listen for a SIGINT signal.
+///
+/// `peer_tls_connector` is used to connect the other instances of the cluster
+/// if they only serve TLS.
 pub async fn run(
     listener: Option<TcpListener>,
     tls_listener: Option<TcpListener>,
     tls_acceptor: Option<TlsAcceptor>,
+    peer_tls_connector: Option<TlsConnector>,
     shutdown: impl Future,
 ) {
     let tcp_enabled = listener.is_some();
@@ -213,7 +235,7 @@ pub async fn run(
 
     let topo_addr = config_cluster_broadcast_addr_or_default();
 
-    let topo_holder = Cluster::build_myself(&topo_addr);
+    let topo_holder = Cluster::build_myself(&topo_addr, Peers::new(peer_tls_connector));
 
     // When the provided `shutdown` future completes, we must send a shutdown
     // message to all active connections. We use a broadcast channel for this
@@ -221,6 +243,7 @@ pub async fn run(
     // a receiver is needed, the subscribe() method on the sender is used to create
     // one.
     let db_holder = DbDropGuard::new();
+    init_keyspace_notifications(db_holder.db(), topo_holder.clone());
 
     let topo_manager = TopologyManager {
         address: topo_addr,
@@ -232,6 +255,8 @@ pub async fn run(
     let mut gc_master = GcMaster::new(async_gc_worker_number_or_default(), topo_holder.clone());
     gc_master.start_workers().await;
 
+    let mut expire_master = ExpireMaster::new(topo_holder.clone());
+
     if tcp_enabled && !tls_enabled {
         let (notify_shutdown, _) = broadcast::channel(1);
         let (shutdown_complete_tx, shutdown_complete_rx) = mpsc::channel(1);
@@ -260,6 +285,9 @@ pub async fn run(
             _ = gc_master.run() => {
                 error!(LOGGER, "gc master exit");
             }
+            _ = expire_master.run() => {
+                error!(LOGGER, "expire master exit");
+            }
             _ = shutdown => {
                 // The shutdown signal has been received.
                 info!(LOGGER, "shutting down");
@@ -303,6 +331,9 @@ pub async fn run(
             _ = gc_master.run() => {
                 error!(LOGGER, "gc master exit");
             }
+            _ = expire_master.run() => {
+                error!(LOGGER, "expire master exit");
+            }
             _ = shutdown => {
                 // The shutdown signal has been received.
                 info!(LOGGER, "shutting down");
@@ -365,6 +396,9 @@ pub async fn run(
             _ = gc_master.run() => {
                 error!(LOGGER, "gc master exit");
             }
+            _ = expire_master.run() => {
+                error!(LOGGER, "expire master exit");
+            }
             _ = shutdown => {
                 // The shutdown signal has been received.
                 info!(LOGGER, "shutting down");
@@ -460,6 +494,8 @@ impl Listener {
 
                 inner_txn: false,
                 queued_commands: vec![],
+                queue_failed: false,
+                watched_keys: vec![],
 
                 // The connection state needs a handle to the max connections
                 // semaphore. When the handler is done processing the
@@ -576,6 +612,8 @@ impl TlsListener {
                 connection: Connection::new_tls(&local_addr, &peer_addr, tls_stream),
                 inner_txn: false,
                 queued_commands: vec![],
+                queue_failed: false,
+                watched_keys: vec![],
                 shutdown: Shutdown::new(self.tls_notify_shutdown.subscribe(), kill_rx),
                 authorized: !is_auth_enabled(),
                 lua: None,
@@ -656,14 +694,35 @@ impl TopologyManager {
                             topo_holder.update_topo(&remaining_node, &address);
                         }
 
-                        Ok(())
+                        // load the peer secret, the first instance generates it
+                        if topo_holder.peers().secret().is_some() {
+                            return Ok(None);
+                        }
+                        let secret_key = KEY_ENCODER.encode_txnkv_cluster_peer_secret();
+                        let secret = match txn.get(secret_key.clone()).await? {
+                            Some(secret) => secret,
+                            None => {
+                                let secret = rand::random::<[u8; 16]>()
+                                    .encode_hex::<String>()
+                                    .into_bytes();
+                                // fails if another instance just generated it
+                                txn.insert(secret_key, secret.clone()).await?;
+                                secret
+                            }
+                        };
+                        Ok(Some(secret))
                     }
                     .boxed()
                 })
                 .await;
 
             match resp {
-                Ok(_) => {}
+                Ok(secret) => {
+                    // set once committed
+                    if let Some(secret) = secret {
+                        topo_holder.peers().set_secret(secret.into());
+                    }
+                }
                 Err(err) => {
                     warn!(LOGGER, "topology update failed: {}", err);
                 }
@@ -676,15 +735,19 @@ impl TopologyManager {
     }
 }
 
+/// Max number of pipelined read commands served by one snapshot read.
+const PIPELINE_BATCH_MAX: usize = 256;
+
 impl Handler {
     /// Process a single connection.
     ///
     /// Request frames are read from the socket and processed. Responses are
     /// written back to the socket.
     ///
-    /// Currently, pipelining is not implemented. Pipelining is the ability to
-    /// process more than one request concurrently per connection without
-    /// interleaving frames. See for more details:
+    /// Requests pipelined by the client are served in the order received. A
+    /// run of consecutive `GET`/`MGET` commands already buffered is served by
+    /// one snapshot `batch_get`, and the replies are flushed with one write
+    /// once every buffered request has been served. See for more details:
     /// https://redis.io/topics/pipelining
     ///
     /// When the shutdown signal is received, the connection is processed until
@@ -715,175 +778,432 @@ impl Handler {
             // Convert the redis frame into a command struct. This returns an
             // error if the frame is not a valid redis command or it is an
             // unsupported command.
-            let cmd = Command::from_frame(frame)?;
-            let cmd_name = cmd.get_name().to_owned();
+            let cmd = match Command::from_frame(frame) {
+                Ok(cmd) => cmd,
+                Err(e) => {
+                    // send the replies of the requests pipelined before it
+                    self.connection.flush().await?;
+                    return Err(e);
+                }
+            };
+            if !self.batch_reads_allowed() || cmd.batch_read_keys().is_none() {
+                self.handle_command(cmd).await?;
+                continue;
+            }
+
+            // Look ahead at the requests already buffered, collecting the run
+            // of read commands which can share one snapshot read.
+            // An invalid request ends the run, it is reported once the reads
+            // received before it have been answered and the replies sent.
+            let mut reads = vec![cmd];
+            let mut next = None;
+            let mut error = None;
+            while reads.len() < PIPELINE_BATCH_MAX {
+                let frame = match self.connection.try_read_frame() {
+                    Ok(Some(frame)) => frame,
+                    Ok(None) => break,
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                };
+                match Command::from_frame(frame) {
+                    Ok(cmd) if cmd.batch_read_keys().is_some() => reads.push(cmd),
+                    Ok(cmd) => {
+                        next = Some(cmd);
+                        break;
+                    }
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                }
+            }
 
-            {
-                let mut w_client = self.cur_client.lock().await;
-                w_client.interact(&cmd_name);
+            if reads.len() == 1 {
+                self.handle_command(reads.pop().unwrap()).await?;
+            } else {
+                self.handle_batch_reads(reads).await?;
             }
+            if let Some(e) = error {
+                self.connection.flush().await?;
+                return Err(e);
+            }
+            if let Some(cmd) = next {
+                self.handle_command(cmd).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queue a command received after `MULTI` to be run by `EXEC`.
+    async fn queue_command(&mut self, cmd: Command) -> crate::Result<()> {
+        self.queued_commands.push(cmd);
+        self.connection.write_frame(&resp_queued()).await?;
+        Ok(())
+    }
+
+    /// Pipelined reads are only grouped if they would be executed right away,
+    /// rather than being rejected by authentication or queued by `MULTI`.
+    fn batch_reads_allowed(&self) -> bool {
+        is_use_txn_api() && self.authorized && !self.inner_txn
+    }
+
+    /// Update the client and request metrics before a command is executed.
+    async fn begin_command(&self, cmd: &Command) -> (String, Instant) {
+        let cmd_name = cmd.get_name().to_owned();
+
+        {
+            let mut w_client = self.cur_client.lock().await;
+            w_client.interact(&cmd_name);
+        }
+
+        let start_at = Instant::now();
+        REQUEST_COUNTER.inc();
+        REQUEST_CMD_COUNTER.with_label_values(&[&cmd_name]).inc();
+
+        debug!(
+            LOGGER,
+            "req {} -> {}, {:?}",
+            self.connection.peer_addr(),
+            self.connection.local_addr(),
+            cmd
+        );
+
+        (cmd_name, start_at)
+    }
+
+    /// Serve a run of pipelined read commands with one snapshot `batch_get`,
+    /// the replies are written in the order the commands were received.
+    async fn handle_batch_reads(&mut self, cmds: Vec<Command>) -> crate::Result<()> {
+        let keys: Vec<Bytes> = cmds
+            .iter()
+            .filter_map(Command::batch_read_keys)
+            .flatten()
+            .cloned()
+            .collect();
+
+        let mut values = match StringCommandCtx::new(None)
+            .do_async_txnkv_pipeline_get(&keys)
+            .await
+        {
+            Ok(values) => values.into_iter(),
+            Err(e) => {
+                warn!(
+                    LOGGER,
+                    "pipelined read of {} keys failed, execute one by one: {}",
+                    keys.len(),
+                    e
+                );
+                for cmd in cmds {
+                    self.handle_command(cmd).await?;
+                }
+                return Ok(());
+            }
+        };
 
-            let start_at = Instant::now();
-            REQUEST_COUNTER.inc();
-            REQUEST_CMD_COUNTER.with_label_values(&[&cmd_name]).inc();
+        for cmd in cmds {
+            let (cmd_name, start_at) = self.begin_command(&cmd).await;
 
+            let key_count = cmd.batch_read_keys().map_or(0, |keys| keys.len());
+            let response = cmd.batch_read_reply(values.by_ref().take(key_count).collect());
             debug!(
                 LOGGER,
-                "req {} -> {}, {:?}",
-                self.connection.peer_addr(),
+                "res, {} -> {}, {:?}",
                 self.connection.local_addr(),
-                cmd
+                self.connection.peer_addr(),
+                response
             );
+            self.connection.write_frame(&response).await?;
 
-            match cmd {
-                Command::Auth(c) => {
-                    if !c.valid() {
-                        self.connection
-                            .write_frame(&resp_invalid_arguments())
-                            .await?;
-                    } else if !is_auth_enabled() {
-                        // check password and update connection authorized flag
-                        self.connection
-                            .write_frame(&resp_err(REDIS_AUTH_WHEN_DISABLED_ERR))
-                            .await?;
-                    } else if is_auth_matched(c.passwd()) {
-                        self.connection.write_frame(&resp_ok()).await?;
-                        self.authorized = true;
-                    } else {
-                        self.connection
-                            .write_frame(&resp_err(REDIS_AUTH_INVALID_PASSWORD_ERR))
-                            .await?;
-                    }
+            finish_command(&cmd_name, start_at);
+        }
+
+        Ok(())
+    }
+
+    /// Execute a single command and write its replies to the connection.
+    async fn handle_command(&mut self, cmd: Command) -> crate::Result<()> {
+        let (cmd_name, start_at) = self.begin_command(&cmd).await;
+
+        if self.inner_txn {
+            if let Some(response) = queue_error(&cmd) {
+                self.queue_failed = true;
+                self.connection.write_frame(&response).await?;
+                finish_command(&cmd_name, start_at);
+                return Ok(());
+            }
+        }
+
+        match cmd {
+            Command::Auth(c) => {
+                if !c.valid() {
+                    self.connection
+                        .write_frame(&resp_invalid_arguments())
+                        .await?;
+                } else if !is_auth_enabled() {
+                    // check password and update connection authorized flag
+                    self.connection
+                        .write_frame(&resp_err(REDIS_AUTH_WHEN_DISABLED_ERR))
+                        .await?;
+                } else if is_auth_matched(c.passwd()) {
+                    self.connection.write_frame(&resp_ok()).await?;
+                    self.authorized = true;
+                } else {
+                    self.connection
+                        .write_frame(&resp_err(REDIS_AUTH_INVALID_PASSWORD_ERR))
+                        .await?;
                 }
-                _ => {
-                    if !self.authorized {
-                        self.connection
-                            .write_frame(&resp_err(REDIS_AUTH_REQUIRED_ERR))
-                            .await?;
-                    } else {
-                        match cmd {
-                            Command::Eval(_) | Command::Evalsha(_) => {
-                                if self.lua.is_none() {
-                                    // initialize the mlua once in same connection
-                                    let lua = Lua::new();
-                                    // set script interupt handler
-                                    lua.set_hook(HookTriggers::every_line(), |_lua, _debug| {
-                                        if script_interuptted() {
-                                            warn!(
-                                                LOGGER,
-                                                "Script kiiled by user with SCRIPT KILL..."
-                                            );
-
-                                            script_clear_killed();
-
-                                            Err(mlua::Error::RuntimeError(
-                                                "Script kiiled by user with SCRIPT KILL..."
-                                                    .to_string(),
-                                            ))
-                                        } else {
-                                            Ok(())
-                                        }
-                                    })
-                                    .unwrap();
+            }
+            Command::Hello(c) => {
+                if !c.valid() {
+                    self.connection
+                        .write_frame(&resp_invalid_arguments())
+                        .await?;
+                } else if matches!(c.protover(), Some(v) if v != 2 && v != 3) {
+                    self.connection
+                        .write_frame(&resp_err(REDIS_NOPROTO_ERR))
+                        .await?;
+                } else if matches!(c.auth(), Some((_, passwd))
+                    if is_auth_enabled() && !is_auth_matched(passwd))
+                {
+                    self.connection
+                        .write_frame(&resp_err(REDIS_WRONGPASS_ERR))
+                        .await?;
+                } else if !self.authorized && c.auth().is_none() {
+                    self.connection
+                        .write_frame(&resp_err(REDIS_HELLO_NOAUTH_ERR))
+                        .await?;
+                } else {
+                    self.authorized = true;
+                    let client_id = {
+                        let mut w_client = self.cur_client.lock().await;
+                        if let Some(name) = c.setname() {
+                            w_client.set_name(name);
+                        }
+                        w_client.id()
+                    };
+                    if let Some(protover) = c.protover() {
+                        self.connection.set_protocol(protover as u8);
+                    }
 
-                                    self.lua = Some(lua);
-                                }
+                    let frame = Frame::Map(vec![
+                        (resp_bulk(b"server".to_vec()), resp_bulk(b"tidis".to_vec())),
+                        (
+                            resp_bulk(b"version".to_vec()),
+                            resp_bulk(env!("CARGO_PKG_VERSION").as_bytes().to_vec()),
+                        ),
+                        (
+                            resp_bulk(b"proto".to_vec()),
+                            resp_int(self.connection.protocol() as i64),
+                        ),
+                        (resp_bulk(b"id".to_vec()), resp_int(client_id as i64)),
+                        (resp_bulk(b"mode".to_vec()), resp_bulk(b"cluster".to_vec())),
+                        (resp_bulk(b"role".to_vec()), resp_bulk(b"master".to_vec())),
+                        (resp_bulk(b"modules".to_vec()), resp_array(vec![])),
+                    ]);
+                    self.connection.write_frame(&frame).await?;
+                }
+            }
+            _ => {
+                if !self.authorized {
+                    self.connection
+                        .write_frame(&resp_err(REDIS_AUTH_REQUIRED_ERR))
+                        .await?;
+                } else {
+                    match cmd {
+                        Command::Eval(_) | Command::Evalsha(_) => {
+                            if self.lua.is_none() {
+                                // initialize the mlua once in same connection
+                                let lua = Lua::new();
+                                // set script interupt handler
+                                lua.set_hook(HookTriggers::every_line(), |_lua, _debug| {
+                                    if script_interuptted() {
+                                        warn!(LOGGER, "Script kiiled by user with SCRIPT KILL...");
+
+                                        script_clear_killed();
+
+                                        Err(mlua::Error::RuntimeError(
+                                            "Script kiiled by user with SCRIPT KILL...".to_string(),
+                                        ))
+                                    } else {
+                                        Ok(())
+                                    }
+                                })
+                                .unwrap();
+
+                                self.lua = Some(lua);
                             }
-                            Command::Multi(_) => {
-                                if self.inner_txn {
-                                    self.connection
-                                        .write_frame(&resp_err(REDIS_MULTI_NESTED_ERR))
-                                        .await?;
-                                } else {
-                                    self.inner_txn = true;
-                                    self.queued_commands.clear();
-                                    self.connection.write_frame(&resp_ok()).await?;
-                                }
+                            if self.inner_txn {
+                                return self.queue_command(cmd).await;
                             }
-                            Command::Exec(c) => {
-                                if !self.inner_txn {
-                                    self.connection
-                                        .write_frame(&resp_err(REDIS_EXEC_WITHOUT_MULTI_ERR))
-                                        .await?;
-                                } else {
-                                    self.inner_txn = false;
-                                    c.clone()
-                                        .exec(&mut self.connection, self.queued_commands.clone())
-                                        .await?;
-                                }
-
-                                let duration = Instant::now() - start_at;
-                                REQUEST_CMD_HANDLE_TIME
-                                    .with_label_values(&[&cmd_name])
-                                    .observe(duration_to_sec(duration));
-                                REQUEST_CMD_FINISH_COUNTER
-                                    .with_label_values(&[&cmd_name])
-                                    .inc();
-                                continue;
+                        }
+                        Command::Multi(_) => {
+                            if self.inner_txn {
+                                self.connection
+                                    .write_frame(&resp_err(REDIS_MULTI_NESTED_ERR))
+                                    .await?;
+                            } else {
+                                self.inner_txn = true;
+                                self.queue_failed = false;
+                                self.queued_commands.clear();
+                                self.connection.write_frame(&resp_ok()).await?;
                             }
-                            Command::Discard(_) => {
-                                if self.inner_txn {
-                                    self.inner_txn = false;
-                                    self.queued_commands.clear();
-                                    self.connection.write_frame(&resp_ok()).await?;
-                                } else {
-                                    self.connection
-                                        .write_frame(&resp_err(REDIS_DISCARD_WITHOUT_MULTI_ERR))
-                                        .await?;
-                                }
+                        }
+                        Command::Exec(c) => {
+                            if !self.inner_txn {
+                                self.connection
+                                    .write_frame(&resp_err(REDIS_EXEC_WITHOUT_MULTI_ERR))
+                                    .await?;
+                            } else if self.queue_failed {
+                                self.inner_txn = false;
+                                self.queued_commands.clear();
+                                self.watched_keys.clear();
+                                self.connection
+                                    .write_frame(&resp_err(REDIS_EXEC_ERR))
+                                    .await?;
+                            } else {
+                                self.inner_txn = false;
+                                let watched_keys = std::mem::take(&mut self.watched_keys);
+                                c.clone()
+                                    .exec(
+                                        &mut self.connection,
+                                        std::mem::take(&mut self.queued_commands),
+                                        watched_keys,
+                                        &self.db,
+                                        &self.topo,
+                                        self.cur_client.clone(),
+                                        self.clients.clone(),
+                                        &self.lua,
+                                    )
+                                    .await?;
                             }
-                            _ => {
-                                if self.inner_txn {
-                                    self.queued_commands.push(cmd);
-                                    self.connection.write_frame(&resp_queued()).await?;
-                                    continue;
+
+                            finish_command(&cmd_name, start_at);
+                            return Ok(());
+                        }
+                        Command::Discard(_) => {
+                            if self.inner_txn {
+                                self.inner_txn = false;
+                                self.queue_failed = false;
+                                self.queued_commands.clear();
+                                self.watched_keys.clear();
+                                self.connection.write_frame(&resp_ok()).await?;
+                            } else {
+                                self.connection
+                                    .write_frame(&resp_err(REDIS_DISCARD_WITHOUT_MULTI_ERR))
+                                    .await?;
+                            }
+                        }
+                        Command::Watch(ref c) => {
+                            if self.inner_txn {
+                                self.connection
+                                    .write_frame(&resp_err(REDIS_WATCH_INSIDE_MULTI_ERR))
+                                    .await?;
+                            } else if !c.valid() {
+                                self.connection
+                                    .write_frame(&resp_invalid_arguments())
+                                    .await?;
+                            } else if !is_use_txn_api() {
+                                self.connection
+                                    .write_frame(&resp_err(REDIS_NOT_SUPPORTED_ERR))
+                                    .await?;
+                            } else {
+                                match get_txn_client()?.current_timestamp().await {
+                                    Ok(ts) => {
+                                        for key in c.keys() {
+                                            self.watched_keys.push((key.clone(), ts.clone()));
+                                        }
+                                        self.connection.write_frame(&resp_ok()).await?;
+                                    }
+                                    Err(e) => {
+                                        self.connection.write_frame(&resp_err(e.into())).await?;
+                                    }
                                 }
                             }
                         }
-                        // Perform the work needed to apply the command. This may mutate the
-                        // database state as a result.
-                        //
-                        // The connection is passed into the apply function which allows the
-                        // command to write response frames directly to the connection. In
-                        // the case of pub/sub, multiple frames may be send back to the
-                        // peer.
-                        match cmd
-                            .apply(
-                                &self.db,
-                                &self.topo,
-                                &mut self.connection,
-                                self.cur_client.clone(),
-                                self.clients.clone(),
-                                &mut self.lua,
-                                &mut self.shutdown,
-                            )
-                            .await
-                        {
-                            Ok(_) => (),
-                            Err(e) => {
-                                REQUEST_CMD_ERROR_COUNTER
-                                    .with_label_values(&[&cmd_name])
-                                    .inc();
-                                return Err(e);
+                        Command::Unwatch(_) if !self.inner_txn => {
+                            self.watched_keys.clear();
+                            self.connection.write_frame(&resp_ok()).await?;
+                        }
+                        _ => {
+                            if self.inner_txn {
+                                return self.queue_command(cmd).await;
                             }
-                        };
+                        }
                     }
+                    // Perform the work needed to apply the command. This may mutate the
+                    // database state as a result.
+                    //
+                    // The connection is passed into the apply function which allows the
+                    // command to write response frames directly to the connection. In
+                    // the case of pub/sub, multiple frames may be send back to the
+                    // peer.
+                    match cmd
+                        .apply(
+                            &self.db,
+                            &self.topo,
+                            &mut self.connection,
+                            self.cur_client.clone(),
+                            self.clients.clone(),
+                            &mut self.lua,
+                            &mut self.shutdown,
+                        )
+                        .await
+                    {
+                        Ok(_) => (),
+                        Err(e) => {
+                            REQUEST_CMD_ERROR_COUNTER
+                                .with_label_values(&[&cmd_name])
+                                .inc();
+                            return Err(e);
+                        }
+                    };
                 }
             }
-
-            let duration = Instant::now() - start_at;
-            REQUEST_CMD_HANDLE_TIME
-                .with_label_values(&[&cmd_name])
-                .observe(duration_to_sec(duration));
-            REQUEST_CMD_FINISH_COUNTER
-                .with_label_values(&[&cmd_name])
-                .inc();
         }
 
+        finish_command(&cmd_name, start_at);
+
         Ok(())
     }
 }
 
+/// Returns the error replied to a command that can't be queued after `MULTI`,
+/// the transaction is discarded by `EXEC` then. Commands controlling the
+/// transaction itself are handled by the handler.
+fn queue_error(cmd: &Command) -> Option<Frame> {
+    match cmd {
+        Command::Multi(_)
+        | Command::Exec(_)
+        | Command::Discard(_)
+        | Command::Watch(_)
+        | Command::Unwatch(_) => None,
+        Command::Unknown(c) => Some(c.unknown()),
+        Command::Subscribe(_)
+        | Command::Unsubscribe(_)
+        | Command::Psubscribe(_)
+        | Command::Punsubscribe(_)
+        | Command::Ssubscribe(_)
+        | Command::Sunsubscribe(_)
+        | Command::Auth(_)
+        | Command::Hello(_) => Some(resp_err(REDIS_NOT_ALLOWED_IN_MULTI_ERR)),
+        _ if !cmd.is_valid() => Some(resp_invalid_arguments()),
+        _ => None,
+    }
+}
+
+fn finish_command(cmd_name: &str, start_at: Instant) {
+    let duration = Instant::now() - start_at;
+    REQUEST_CMD_HANDLE_TIME
+        .with_label_values(&[cmd_name])
+        .observe(duration_to_sec(duration));
+    REQUEST_CMD_FINISH_COUNTER
+        .with_label_values(&[cmd_name])
+        .inc();
+}
+
 #[inline]
 pub fn duration_to_sec(d: Duration) -> f64 {
     let nanos = f64::from(d.subsec_nanos());
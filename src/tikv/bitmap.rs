@@ -0,0 +1,603 @@
+use super::client::get_version_for_new_locked;
+use super::errors::*;
+use super::get_txn_client;
+use super::string::{
+    read_string_range, replace_string_chunks, string_len, write_string, write_string_range,
+    StringCommandCtx, STRING_CHUNK_SIZE,
+};
+use super::{
+    encoding::{DataType, KeyDecoder},
+    errors::AsyncResult,
+    KEY_ENCODER,
+};
+use crate::utils::{resp_array, resp_err, resp_int, resp_nil};
+use crate::Frame;
+use ::futures::future::FutureExt;
+use bytes::Bytes;
+use std::sync::Arc;
+use tikv_client::{Transaction, Value};
+use tokio::sync::Mutex;
+
+use crate::notify::{notify_keyspace_event, NOTIFY_GENERIC, NOTIFY_STRING};
+
+/// BITCOUNT and BITPOS read large strings this many bytes at a time.
+const BITMAP_SCAN_WINDOW: usize = 256 * STRING_CHUNK_SIZE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOperation {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitfieldOverflow {
+    Wrap,
+    Sat,
+    Fail,
+}
+
+/// An integer type of BITFIELD, like i8 or u16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitfieldType {
+    pub signed: bool,
+    pub bits: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitfieldOp {
+    Get(BitfieldType, u64),
+    Set(BitfieldType, u64, i64, BitfieldOverflow),
+    Incrby(BitfieldType, u64, i64, BitfieldOverflow),
+}
+
+#[derive(Clone)]
+pub struct BitmapCommandCtx {
+    txn: Option<Arc<Mutex<Transaction>>>,
+}
+
+impl BitmapCommandCtx {
+    pub fn new(txn: Option<Arc<Mutex<Transaction>>>) -> Self {
+        BitmapCommandCtx { txn }
+    }
+
+    pub async fn do_async_txnkv_setbit(
+        mut self,
+        key: &[u8],
+        offset: u64,
+        on: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ukey = key;
+        let key = key.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let old_value = StringCommandCtx::new(self.txn.clone())
+                        .txnkv_get_live_value(&txn_rc, &key)
+                        .await?;
+                    if let Some(val) = &old_value {
+                        if !matches!(KeyDecoder::decode_key_type(val), DataType::String) {
+                            return Err(REDIS_WRONG_TYPE_ERR);
+                        }
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let byte = (offset / 8) as usize;
+                    let mask = 0x80u8 >> (offset % 8);
+                    let len = old_value.as_deref().map_or(0, string_len);
+                    let old_byte = match &old_value {
+                        Some(val) if byte < len => {
+                            read_string_range(&mut txn, &key, val, byte, byte + 1).await?[0]
+                        }
+                        _ => 0,
+                    };
+                    let new_byte = if on {
+                        old_byte | mask
+                    } else {
+                        old_byte & !mask
+                    };
+                    // the string is still padded to the offset if the bit is unchanged
+                    if new_byte != old_byte || byte >= len {
+                        write_string_range(&mut txn, &key, old_value.as_deref(), byte, &[new_byte])
+                            .await?;
+                    }
+                    Ok(old_byte & mask != 0)
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(old_bit) => {
+                notify_keyspace_event(NOTIFY_STRING, "setbit", ukey);
+                Ok(resp_int(old_bit as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_getbit(mut self, key: &[u8], offset: u64) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let val = match self.txnkv_get_string(&txn_rc, &key).await? {
+                        Ok(Some(val)) => val,
+                        Ok(None) => return Ok(resp_int(0)),
+                        Err(e) => return Ok(resp_err(e)),
+                    };
+                    let byte = (offset / 8) as usize;
+                    if byte >= string_len(&val) {
+                        return Ok(resp_int(0));
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let data = read_string_range(&mut txn, &key, &val, byte, byte + 1).await?;
+                    Ok(resp_int(((data[0] >> (7 - offset % 8)) & 1) as i64))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// `range` is the inclusive start and end, in bits if the flag is set
+    /// or in bytes otherwise.
+    pub async fn do_async_txnkv_bitcount(
+        mut self,
+        key: &[u8],
+        range: Option<(i64, i64, bool)>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let val = match self.txnkv_get_string(&txn_rc, &key).await? {
+                        Ok(Some(val)) => val,
+                        Ok(None) => return Ok(resp_int(0)),
+                        Err(e) => return Ok(resp_err(e)),
+                    };
+                    let len = string_len(&val) as u64;
+                    let (start, end, is_bit) = range.unwrap_or((0, -1, false));
+                    let total = if is_bit { len * 8 } else { len };
+                    let (start_bit, end_bit) = match resolve_range(start, end, total as i64) {
+                        Some((s, e)) if is_bit => (s, e),
+                        Some((s, e)) => (s * 8, e * 8 + 7),
+                        None => return Ok(resp_int(0)),
+                    };
+
+                    let mut txn = txn_rc.lock().await;
+                    let mut count = 0;
+                    let last = (end_bit / 8) as usize;
+                    let mut pos = (start_bit / 8) as usize;
+                    while pos <= last {
+                        let to = (pos + BITMAP_SCAN_WINDOW).min(last + 1);
+                        let data = read_string_range(&mut txn, &key, &val, pos, to).await?;
+                        for (i, b) in data.iter().enumerate() {
+                            let mask = edge_mask((pos + i) as u64, start_bit, end_bit);
+                            count += (b & mask).count_ones() as i64;
+                        }
+                        pos = to;
+                    }
+                    Ok(resp_int(count))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    pub async fn do_async_txnkv_bitpos(
+        mut self,
+        key: &[u8],
+        bit: bool,
+        start: i64,
+        end: Option<i64>,
+        is_bit: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let val = match self.txnkv_get_string(&txn_rc, &key).await? {
+                        Ok(Some(val)) => val,
+                        // a missing key is an empty string of zero bits
+                        Ok(None) => return Ok(resp_int(if bit { -1 } else { 0 })),
+                        Err(e) => return Ok(resp_err(e)),
+                    };
+                    let len = string_len(&val) as u64;
+                    let total = if is_bit { len * 8 } else { len };
+                    let (start_bit, end_bit) =
+                        match resolve_range(start, end.unwrap_or(-1), total as i64) {
+                            Some((s, e)) if is_bit => (s, e),
+                            Some((s, e)) => (s * 8, e * 8 + 7),
+                            None => return Ok(resp_int(-1)),
+                        };
+
+                    let mut txn = txn_rc.lock().await;
+                    let last = (end_bit / 8) as usize;
+                    let mut pos = (start_bit / 8) as usize;
+                    while pos <= last {
+                        let to = (pos + BITMAP_SCAN_WINDOW).min(last + 1);
+                        let data = read_string_range(&mut txn, &key, &val, pos, to).await?;
+                        for (i, b) in data.iter().enumerate() {
+                            let byte = (pos + i) as u64;
+                            let b = if bit { *b } else { !*b };
+                            let matched = b & edge_mask(byte, start_bit, end_bit);
+                            if matched != 0 {
+                                return Ok(resp_int(
+                                    (byte * 8) as i64 + matched.leading_zeros() as i64,
+                                ));
+                            }
+                        }
+                        pos = to;
+                    }
+
+                    // the string is considered padded with zeros on the right
+                    // when looking for a clear bit without an explicit end
+                    if !bit && end.is_none() {
+                        return Ok(resp_int(end_bit as i64 + 1));
+                    }
+                    Ok(resp_int(-1))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    pub async fn do_async_txnkv_bitop(
+        mut self,
+        op: BitOperation,
+        dest: &[u8],
+        keys: &[Bytes],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let dest = dest.to_owned();
+        let udest = dest.clone();
+        let keys = keys.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let string_ctx = StringCommandCtx::new(self.txn.clone());
+
+                    // missing keys are treated as empty strings
+                    let mut metas = Vec::with_capacity(keys.len());
+                    for key in &keys {
+                        let meta = string_ctx.txnkv_get_live_value(&txn_rc, key).await?;
+                        if let Some(val) = &meta {
+                            if !matches!(KeyDecoder::decode_key_type(val), DataType::String) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+                        }
+                        metas.push(meta);
+                    }
+                    let len = metas
+                        .iter()
+                        .flatten()
+                        .map(|v| string_len(v))
+                        .max()
+                        .unwrap_or(0);
+
+                    // a destination which is also a source is overwritten in place,
+                    // each of its chunks is read before it is written
+                    let old_value = string_ctx.txnkv_get_live_value(&txn_rc, &dest).await?;
+                    let in_place = keys.iter().any(|key| key[..] == dest[..]);
+                    if let Some(val) = &old_value {
+                        if !in_place {
+                            string_ctx
+                                .txnkv_clear_for_overwrite(&txn_rc, &dest, val)
+                                .await?;
+                        }
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    if len <= STRING_CHUNK_SIZE {
+                        let result = bitop_range(&mut txn, op, &keys, &metas, 0, len).await?;
+                        if len > 0 {
+                            write_string(&mut txn, &dest, &result, 0).await?;
+                        } else if old_value.is_some() {
+                            txn.delete(KEY_ENCODER.encode_txnkv_string(&dest)).await?;
+                        }
+                        return Ok((len, old_value.is_some()));
+                    }
+
+                    // chunks overwritten in place keep their version
+                    let version = match &old_value {
+                        Some(val) if in_place && KeyDecoder::decode_key_string_is_chunked(val) => {
+                            KeyDecoder::decode_key_version(val)
+                        }
+                        _ => get_version_for_new_locked(&dest, &mut txn).await?,
+                    };
+
+                    // the sources are combined a window at a time
+                    for start in (0..len).step_by(BITMAP_SCAN_WINDOW) {
+                        let end = len.min(start + BITMAP_SCAN_WINDOW);
+                        let result = bitop_range(&mut txn, op, &keys, &metas, start, end).await?;
+                        replace_string_chunks(&mut txn, &dest, version, start, &result).await?;
+                    }
+                    let eval =
+                        KEY_ENCODER.encode_txnkv_string_chunked_meta_value(0, version, len as u64);
+                    txn.put(KEY_ENCODER.encode_txnkv_string(&dest), eval)
+                        .await?;
+                    Ok((len, old_value.is_some()))
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok((len, existed)) => {
+                if len > 0 {
+                    notify_keyspace_event(NOTIFY_STRING, "set", &udest);
+                } else if existed {
+                    notify_keyspace_event(NOTIFY_GENERIC, "del", &udest);
+                }
+                Ok(resp_int(len as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_bitfield(
+        mut self,
+        key: &[u8],
+        ops: &[BitfieldOp],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ukey = key;
+        let key = key.to_owned();
+        let ops = ops.to_owned();
+        let readonly = ops.iter().all(|op| matches!(op, BitfieldOp::Get(..)));
+
+        if readonly && self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut meta_value = StringCommandCtx::new(self.txn.clone())
+                        .txnkv_get_live_value(&txn_rc, &key)
+                        .await?;
+                    if let Some(val) = &meta_value {
+                        if !matches!(KeyDecoder::decode_key_type(val), DataType::String) {
+                            return Err(REDIS_WRONG_TYPE_ERR);
+                        }
+                    }
+
+                    let ekey = KEY_ENCODER.encode_txnkv_string(&key);
+                    let mut txn = txn_rc.lock().await;
+                    let mut replies = Vec::with_capacity(ops.len());
+                    let mut modified = false;
+                    for op in &ops {
+                        let (ty, offset) = match op {
+                            BitfieldOp::Get(ty, offset)
+                            | BitfieldOp::Set(ty, offset, ..)
+                            | BitfieldOp::Incrby(ty, offset, ..) => (*ty, *offset),
+                        };
+                        let first = (offset / 8) as usize;
+                        let last = ((offset + ty.bits as u64 - 1) / 8) as usize;
+                        let bit_offset = (offset % 8) as u32;
+
+                        let mut buf = vec![0; last - first + 1];
+                        if let Some(val) = &meta_value {
+                            let len = string_len(val);
+                            if first < len {
+                                let data = read_string_range(
+                                    &mut txn,
+                                    &key,
+                                    val,
+                                    first,
+                                    len.min(last + 1),
+                                )
+                                .await?;
+                                buf[..data.len()].copy_from_slice(&data);
+                            }
+                        }
+                        let old = get_bits(&buf, bit_offset, ty);
+
+                        let new = match op {
+                            BitfieldOp::Get(..) => {
+                                replies.push(resp_int(old));
+                                continue;
+                            }
+                            BitfieldOp::Set(_, _, value, overflow) => {
+                                bitfield_overflow(ty, *value as i128, *overflow)
+                                    .map(|new| (new, old))
+                            }
+                            BitfieldOp::Incrby(_, _, incr, overflow) => {
+                                bitfield_overflow(ty, old as i128 + *incr as i128, *overflow)
+                                    .map(|new| (new, new))
+                            }
+                        };
+                        // a FAIL overflow replies nil and leaves the value untouched
+                        let (new, reply) = match new {
+                            Some(new) => new,
+                            None => {
+                                replies.push(resp_nil());
+                                continue;
+                            }
+                        };
+                        set_bits(&mut buf, bit_offset, ty, new);
+                        write_string_range(&mut txn, &key, meta_value.as_deref(), first, &buf)
+                            .await?;
+                        meta_value = txn.get(ekey.clone()).await?;
+                        modified = true;
+                        replies.push(resp_int(reply));
+                    }
+                    Ok((replies, modified))
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok((replies, modified)) => {
+                if modified {
+                    notify_keyspace_event(NOTIFY_STRING, "setbit", ukey);
+                }
+                Ok(resp_array(replies))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Read the meta value of a string key, the inner result is a WRONGTYPE
+    /// error if the key holds another type.
+    async fn txnkv_get_string(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        key: &[u8],
+    ) -> AsyncResult<AsyncResult<Option<Value>>> {
+        let val = StringCommandCtx::new(Some(txn_rc.clone()))
+            .txnkv_get_live_value(txn_rc, key)
+            .await?;
+        match val {
+            Some(val) if !matches!(KeyDecoder::decode_key_type(&val), DataType::String) => {
+                Ok(Err(REDIS_WRONG_TYPE_ERR))
+            }
+            val => Ok(Ok(val)),
+        }
+    }
+}
+
+/// Resolve an inclusive range with negative indexes counting from the end,
+/// `None` if it selects nothing.
+fn resolve_range(start: i64, end: i64, total: i64) -> Option<(u64, u64)> {
+    if total == 0 || (start < 0 && end < 0 && start > end) {
+        return None;
+    }
+    let start = if start < 0 {
+        (total + start).max(0)
+    } else {
+        start
+    };
+    let end = if end < 0 {
+        (total + end).max(0)
+    } else {
+        end.min(total - 1)
+    };
+    if start > end {
+        return None;
+    }
+    Some((start as u64, end as u64))
+}
+
+/// The bits of byte `byte` which are inside the bit range `[start_bit, end_bit]`.
+fn edge_mask(byte: u64, start_bit: u64, end_bit: u64) -> u8 {
+    let mut mask = 0xff;
+    if byte == start_bit / 8 {
+        mask &= 0xff >> (start_bit % 8);
+    }
+    if byte == end_bit / 8 {
+        mask &= 0xff << (7 - end_bit % 8);
+    }
+    mask
+}
+
+fn get_bits(buf: &[u8], bit_offset: u32, ty: BitfieldType) -> i64 {
+    let mut value = 0u64;
+    for i in bit_offset..bit_offset + ty.bits {
+        let bit = (buf[(i / 8) as usize] >> (7 - i % 8)) & 1;
+        value = (value << 1) | bit as u64;
+    }
+    if ty.signed && ty.bits < 64 && value & (1 << (ty.bits - 1)) != 0 {
+        value |= u64::MAX << ty.bits;
+    }
+    value as i64
+}
+
+fn set_bits(buf: &mut [u8], bit_offset: u32, ty: BitfieldType, value: i64) {
+    for i in 0..ty.bits {
+        let pos = bit_offset + i;
+        let mask = 0x80 >> (pos % 8);
+        if (value as u64 >> (ty.bits - 1 - i)) & 1 == 1 {
+            buf[(pos / 8) as usize] |= mask;
+        } else {
+            buf[(pos / 8) as usize] &= !mask;
+        }
+    }
+}
+
+/// Fit `value` into the range of the bitfield type, `None` if it overflows
+/// with FAIL.
+fn bitfield_overflow(ty: BitfieldType, value: i128, overflow: BitfieldOverflow) -> Option<i64> {
+    let (min, max) = if ty.signed {
+        (-(1i128 << (ty.bits - 1)), (1i128 << (ty.bits - 1)) - 1)
+    } else {
+        (0, (1i128 << ty.bits) - 1)
+    };
+    if value >= min && value <= max {
+        return Some(value as i64);
+    }
+    match overflow {
+        BitfieldOverflow::Wrap => Some(((value - min).rem_euclid(1i128 << ty.bits) + min) as i64),
+        BitfieldOverflow::Sat => Some(if value < min { min as i64 } else { max as i64 }),
+        BitfieldOverflow::Fail => None,
+    }
+}
+
+/// Combine the bytes `[start, end)` of the sources, the sources shorter than
+/// `end` are padded with zeros.
+async fn bitop_range(
+    txn: &mut Transaction,
+    op: BitOperation,
+    keys: &[Bytes],
+    metas: &[Option<Value>],
+    start: usize,
+    end: usize,
+) -> AsyncResult<Vec<u8>> {
+    let mut result = vec![0; end - start];
+    for (i, (key, meta)) in keys.iter().zip(metas).enumerate() {
+        let data = match meta {
+            Some(meta) => {
+                let src_end = end.min(string_len(meta));
+                read_string_range(txn, key, meta, start.min(src_end), src_end).await?
+            }
+            None => vec![],
+        };
+        for (pos, r) in result.iter_mut().enumerate() {
+            let b = data.get(pos).copied().unwrap_or(0);
+            *r = match op {
+                BitOperation::Not => !b,
+                _ if i == 0 => b,
+                BitOperation::And => *r & b,
+                BitOperation::Or => *r | b,
+                BitOperation::Xor => *r ^ b,
+            };
+        }
+    }
+    Ok(result)
+}
@@ -1,4 +1,5 @@
 use std::convert::TryInto;
+use std::ops::Bound;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -16,7 +17,10 @@ use crate::{
     txn_region_backoff_delay_attemps, txn_region_backoff_delay_ms, txn_retry_count,
 };
 
+use super::encoding::{DataType, KeyDecoder};
 use super::errors::{AsyncResult, RTError, KEY_VERSION_EXHUSTED_ERR};
+use super::get_txn_client;
+use bytes::Bytes;
 
 use futures::future::BoxFuture;
 
@@ -29,9 +33,11 @@ use crate::metrics::{
 
 use super::{sleep, KEY_ENCODER};
 use crate::server::duration_to_sec;
+use crate::utils::key_is_expired;
 use tokio::time::Instant;
 
 const MAX_DELAY_MS: u64 = 500;
+const WATCH_SCAN_BATCH_SIZE: u32 = 256;
 
 pub struct TxnClientWrapper<'a> {
     client: &'a TransactionClient,
@@ -47,6 +53,11 @@ impl TxnClientWrapper<'static> {
     }
 
     pub fn begin_with_latest(&self) -> Transaction {
+        self.begin_read_only_at(Timestamp::from_version(u64::MAX))
+    }
+
+    /// Begin a read only transaction which reads the snapshot at `ts`
+    pub fn begin_read_only_at(&self, ts: Timestamp) -> Transaction {
         // add retry options
         let region_backoff = Backoff::no_jitter_backoff(
             txn_region_backoff_delay_ms(),
@@ -80,8 +91,11 @@ impl TxnClientWrapper<'static> {
             txn_options
         };
 
-        self.client
-            .new_transaction(Timestamp::from_version(u64::MAX), txn_options.read_only())
+        self.client.new_transaction(ts, txn_options.read_only())
+    }
+
+    pub async fn current_timestamp(&self) -> TiKVResult<Timestamp> {
+        self.client.current_timestamp().await
     }
 
     pub async fn begin(&self) -> TiKVResult<Transaction> {
@@ -275,14 +289,108 @@ impl TxnClientWrapper<'static> {
     }
 }
 
+/// Check if any of the watched keys has been modified since it was watched.
+///
+/// The meta key of each key read by `txn_rc` is compared with the snapshot at
+/// the timestamp of its `WATCH`, and so are the data keys of its current
+/// version, a batch at a time, so that the writes which leave the meta key
+/// untouched, such as overwriting an existing hash field, are detected too.
+/// The meta keys are locked in `txn_rc`, the commit fails with a write
+/// conflict if they are modified before it.
+pub async fn watched_keys_modified(
+    watched_keys: &[(Bytes, Timestamp)],
+    txn_rc: Arc<Mutex<Transaction>>,
+) -> AsyncResult<bool> {
+    let client = get_txn_client()?;
+    let mut txn = txn_rc.lock().await;
+    for (key, ts) in watched_keys {
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let mut snapshot = client.begin_read_only_at(ts.clone());
+        let watched = snapshot.get(meta_key.clone()).await?;
+        let current = txn.get(meta_key.clone()).await?;
+        if watched != current {
+            return Ok(true);
+        }
+
+        if let Some(meta_value) = current {
+            if let Some(range) = watched_data_key_range(key, &meta_value) {
+                let (start, end) = range.into_keys();
+                let mut from = Bound::Included(start);
+                let to = end.map_or(Bound::Unbounded, Bound::Excluded);
+                loop {
+                    let range = (from.clone(), to.clone());
+                    let watched: Vec<KvPair> = snapshot
+                        .scan(range.clone(), WATCH_SCAN_BATCH_SIZE)
+                        .await?
+                        .collect();
+                    let current: Vec<KvPair> =
+                        txn.scan(range, WATCH_SCAN_BATCH_SIZE).await?.collect();
+                    if watched != current {
+                        return Ok(true);
+                    }
+                    match current.last() {
+                        Some(last) if current.len() == WATCH_SCAN_BATCH_SIZE as usize => {
+                            from = Bound::Excluded(last.0.clone());
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+        txn.lock_keys(vec![meta_key]).await?;
+    }
+    Ok(false)
+}
+
+/// Check if any of the keys exists and is not expired, by reading their meta
+/// keys in one snapshot, which is cheaper than a transaction for polling.
+pub async fn any_key_exists(keys: &[Bytes]) -> AsyncResult<bool> {
+    let client = get_txn_client()?;
+    let mut snapshot = client.begin_with_latest();
+    let meta_keys: Vec<Key> = keys
+        .iter()
+        .map(|key| KEY_ENCODER.encode_txnkv_meta_key(key))
+        .collect();
+    Ok(snapshot
+        .batch_get(meta_keys)
+        .await?
+        .any(|kv| !key_is_expired(KeyDecoder::decode_key_ttl(&kv.1))))
+}
+
+/// The range of the data keys holding the value of `key`, none if the value
+/// is stored in the meta key. The sub meta keys and the index keys are left
+/// out, they only change along with the data keys.
+fn watched_data_key_range(key: &[u8], meta_value: &[u8]) -> Option<BoundRange> {
+    let version = KeyDecoder::decode_key_version(meta_value);
+    match KeyDecoder::decode_key_type(meta_value) {
+        DataType::String if KeyDecoder::decode_key_string_is_chunked(meta_value) => {
+            Some(KEY_ENCODER.encode_txnkv_string_chunk_key_range(key, version))
+        }
+        DataType::Hash => Some(KEY_ENCODER.encode_txnkv_hash_data_key_range(key, version)),
+        DataType::List => Some(KEY_ENCODER.encode_txnkv_list_data_key_range(key, version)),
+        DataType::Set => Some(KEY_ENCODER.encode_txnkv_set_data_key_range(key, version)),
+        DataType::Zset => Some(KEY_ENCODER.encode_txnkv_zset_data_key_range(key, version)),
+        DataType::String | DataType::Null => None,
+    }
+}
+
 // get_version_for_new must be called outside of a MutexGuard, otherwise it will deadlock.
-pub async fn get_version_for_new(key: &str, txn_rc: Arc<Mutex<Transaction>>) -> AsyncResult<u16> {
+pub async fn get_version_for_new(key: &[u8], txn_rc: Arc<Mutex<Transaction>>) -> AsyncResult<u16> {
     // check if async deletion is enabled, return ASAP if not
     if !async_deletion_enabled_or_default() {
         return Ok(0);
     }
 
     let mut txn = txn_rc.lock().await;
+    get_version_for_new_locked(key, &mut txn).await
+}
+
+// get_version_for_new_locked is get_version_for_new for the callers already holding the MutexGuard.
+pub async fn get_version_for_new_locked(key: &[u8], txn: &mut Transaction) -> AsyncResult<u16> {
+    if !async_deletion_enabled_or_default() {
+        return Ok(0);
+    }
+
     let gc_key = KEY_ENCODER.encode_txnkv_gc_key(key);
     let next_version = txn.get(gc_key).await?.map_or_else(
         || 0,
@@ -4,9 +4,16 @@ use bytes::Bytes;
 use tikv_client::Transaction;
 use tokio::sync::Mutex;
 
-use crate::{utils::resp_err, Frame};
+use crate::notify::{
+    keyspace_events_from_str, keyspace_events_to_str, notify_keyspace_events,
+    set_notify_keyspace_events,
+};
+use crate::{
+    utils::{resp_err, resp_ok},
+    Frame,
+};
 
-use super::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use super::errors::{AsyncResult, REDIS_INVALID_CONFIG_VALUE_ERR, REDIS_NOT_SUPPORTED_ERR};
 
 #[derive(Clone)]
 pub struct ConfigCommandCtx {
@@ -21,20 +28,36 @@ impl ConfigCommandCtx {
     pub async fn do_async_rawkv_get(&self, key: &str, field: &str) -> AsyncResult<Frame> {
         if let "get" = key.to_lowercase().as_str() {
             match field.to_lowercase().as_str() {
-                "save" => Ok(Frame::Array(vec![
+                "save" => Ok(Frame::Map(vec![(
                     Frame::Simple("save".into()),
                     Frame::Bulk(Bytes::from("3600 1 300 100 60 10000")),
-                ])),
-                "appendonly" => Ok(Frame::Array(vec![
+                )])),
+                "appendonly" => Ok(Frame::Map(vec![(
                     Frame::Simple("appendonly".into()),
                     Frame::Simple("no".into()),
-                ])),
+                )])),
+                "notify-keyspace-events" => Ok(Frame::Map(vec![(
+                    Frame::Simple("notify-keyspace-events".into()),
+                    Frame::Bulk(Bytes::from(
+                        keyspace_events_to_str(notify_keyspace_events()),
+                    )),
+                )])),
                 "*" => {
-                    let frame = Frame::Array(vec![
-                        Frame::Simple("save".into()),
-                        Frame::Bulk(Bytes::from("3600 1 300 100 60 10000")),
-                        Frame::Simple("appendonly".into()),
-                        Frame::Simple("no".into()),
+                    let frame = Frame::Map(vec![
+                        (
+                            Frame::Simple("save".into()),
+                            Frame::Bulk(Bytes::from("3600 1 300 100 60 10000")),
+                        ),
+                        (
+                            Frame::Simple("appendonly".into()),
+                            Frame::Simple("no".into()),
+                        ),
+                        (
+                            Frame::Simple("notify-keyspace-events".into()),
+                            Frame::Bulk(Bytes::from(keyspace_events_to_str(
+                                notify_keyspace_events(),
+                            ))),
+                        ),
                     ]);
 
                     Ok(frame)
@@ -45,4 +68,19 @@ impl ConfigCommandCtx {
             Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
         }
     }
+
+    /// Set the config at runtime, only affects this instance and is lost on
+    /// restart.
+    pub async fn do_async_rawkv_set(&self, field: &str, value: &str) -> AsyncResult<Frame> {
+        match field.to_lowercase().as_str() {
+            "notify-keyspace-events" => match keyspace_events_from_str(value) {
+                Some(flags) => {
+                    set_notify_keyspace_events(flags);
+                    Ok(resp_ok())
+                }
+                None => Ok(resp_err(REDIS_INVALID_CONFIG_VALUE_ERR)),
+            },
+            _ => Ok(resp_err(REDIS_NOT_SUPPORTED_ERR)),
+        }
+    }
 }
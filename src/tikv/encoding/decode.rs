@@ -2,7 +2,10 @@ use std::convert::TryInto;
 
 use crate::tikv::KEY_ENCODER;
 
-use super::{encode::DATA_TYPE_META, DataType, ENC_GROUP_SIZE, ENC_MARKER, SIGN_MASK};
+use super::{
+    encode::{DATA_TYPE_META, LIST_SPARSE_TYPE, STRING_CHUNKED_TYPE},
+    DataType, ENC_GROUP_SIZE, ENC_MARKER, SIGN_MASK,
+};
 use tikv_client::{Key, Value};
 
 pub struct KeyDecoder {}
@@ -40,9 +43,9 @@ impl KeyDecoder {
 
     pub fn decode_key_type(value: &[u8]) -> DataType {
         match value[0] {
-            0 => DataType::String,
+            0 | STRING_CHUNKED_TYPE => DataType::String,
             1 => DataType::Hash,
-            2 => DataType::List,
+            2 | LIST_SPARSE_TYPE => DataType::List,
             3 => DataType::Set,
             4 => DataType::Zset,
             _ => panic!("no support data type"),
@@ -69,6 +72,21 @@ impl KeyDecoder {
         &value[11..]
     }
 
+    pub fn decode_key_string_is_chunked(value: &[u8]) -> bool {
+        value[0] == STRING_CHUNKED_TYPE
+    }
+
+    pub fn decode_key_string_chunked_len(value: &[u8]) -> u64 {
+        u64::from_be_bytes(value[11..19].try_into().unwrap())
+    }
+
+    pub fn decode_key_string_chunk_idx_from_datakey(ukey: &[u8], key: Key) -> u32 {
+        let key: Vec<u8> = key.into();
+        let enc_ukey = KEY_ENCODER.encode_bytes(ukey);
+        let idx = 8 + enc_ukey.len();
+        u32::from_be_bytes(key[idx..idx + 4].try_into().unwrap())
+    }
+
     pub fn decode_key_version(value: &[u8]) -> u16 {
         u16::from_be_bytes(value[9..11].try_into().unwrap())
     }
@@ -85,33 +103,49 @@ impl KeyDecoder {
         )
     }
 
-    pub fn decode_key_hash_userkey_from_datakey(ukey: &str, key: Key) -> Vec<u8> {
+    pub fn decode_key_hash_userkey_from_datakey(ukey: &[u8], key: Key) -> Vec<u8> {
         let key: Vec<u8> = key.into();
-        let enc_ukey = KEY_ENCODER.encode_bytes(ukey.as_bytes());
+        let enc_ukey = KEY_ENCODER.encode_bytes(ukey);
         let idx = 8 + enc_ukey.len();
         key[idx..].to_vec()
     }
 
-    /// return (ttl, version, left, right)
-    pub fn decode_key_list_meta(value: &[u8]) -> (u64, u16, u64, u64) {
+    /// return (ttl, version, left, right, len), len is only set for a sparse list
+    pub fn decode_key_list_meta(value: &[u8]) -> (u64, u16, u64, u64, Option<u64>) {
+        let len = if value[0] == LIST_SPARSE_TYPE {
+            Some(u64::from_be_bytes(value[27..35].try_into().unwrap()))
+        } else {
+            None
+        };
         (
             u64::from_be_bytes(value[1..9].try_into().unwrap()),
             u16::from_be_bytes(value[9..11].try_into().unwrap()),
             u64::from_be_bytes(value[11..19].try_into().unwrap()),
-            u64::from_be_bytes(value[19..].try_into().unwrap()),
+            u64::from_be_bytes(value[19..27].try_into().unwrap()),
+            len,
         )
     }
 
-    pub fn decode_key_list_idx_from_datakey(ukey: &str, key: Key) -> u64 {
+    /// return the bucket or the group of the list index key
+    pub fn decode_key_list_index_bucket(key: Key) -> u64 {
         let key: Vec<u8> = key.into();
-        let enc_ukey = KEY_ENCODER.encode_bytes(ukey.as_bytes());
+        u64::from_be_bytes(key[key.len() - 8..].try_into().unwrap())
+    }
+
+    /// return (idx, fraction) of the list data key
+    pub fn decode_key_list_position_from_datakey(ukey: &[u8], key: Key) -> (u64, Vec<u8>) {
+        let key: Vec<u8> = key.into();
+        let enc_ukey = KEY_ENCODER.encode_bytes(ukey);
         let idx = 8 + enc_ukey.len();
-        u64::from_be_bytes(key[idx..].try_into().unwrap())
+        (
+            u64::from_be_bytes(key[idx..idx + 8].try_into().unwrap()),
+            key[idx + 8..].to_vec(),
+        )
     }
 
-    pub fn decode_key_set_member_from_datakey(ukey: &str, key: Key) -> Vec<u8> {
+    pub fn decode_key_set_member_from_datakey(ukey: &[u8], key: Key) -> Vec<u8> {
         let key: Vec<u8> = key.into();
-        let enc_ukey = KEY_ENCODER.encode_bytes(ukey.as_bytes());
+        let enc_ukey = KEY_ENCODER.encode_bytes(ukey);
         let idx = 8 + enc_ukey.len();
         key[idx..].to_vec()
     }
@@ -128,23 +162,23 @@ impl KeyDecoder {
         f64::from_bits(score)
     }
 
-    pub fn decode_key_zset_score_from_scorekey(ukey: &str, key: Key) -> f64 {
+    pub fn decode_key_zset_score_from_scorekey(ukey: &[u8], key: Key) -> f64 {
         let key: Vec<u8> = key.into();
-        let enc_ukey = KEY_ENCODER.encode_bytes(ukey.as_bytes());
+        let enc_ukey = KEY_ENCODER.encode_bytes(ukey);
         let idx = 8 + enc_ukey.len();
         Self::decode_cmp_uint64_to_f64(u64::from_be_bytes(key[idx..idx + 8].try_into().unwrap()))
     }
 
-    pub fn decode_key_zset_member_from_scorekey(ukey: &str, key: Key) -> Vec<u8> {
+    pub fn decode_key_zset_member_from_scorekey(ukey: &[u8], key: Key) -> Vec<u8> {
         let key: Vec<u8> = key.into();
-        let enc_ukey = KEY_ENCODER.encode_bytes(ukey.as_bytes());
+        let enc_ukey = KEY_ENCODER.encode_bytes(ukey);
         let idx = 17 + enc_ukey.len();
         key[idx..].to_vec()
     }
 
-    pub fn decode_key_zset_member_from_datakey(ukey: &str, key: Key) -> Vec<u8> {
+    pub fn decode_key_zset_member_from_datakey(ukey: &[u8], key: Key) -> Vec<u8> {
         let key: Vec<u8> = key.into();
-        let enc_ukey = KEY_ENCODER.encode_bytes(ukey.as_bytes());
+        let enc_ukey = KEY_ENCODER.encode_bytes(ukey);
         let idx = 8 + enc_ukey.len();
         key[idx..].to_vec()
     }
@@ -183,4 +217,12 @@ impl KeyDecoder {
         let ukey = Self::decode_bytes(&key[enc_key_start..]);
         (ukey, key[idx] == DATA_TYPE_META && idx + 1 == key.len())
     }
+
+    /// return (ttl, user key) of a ttl index key
+    pub fn decode_key_expire_index(key: Key) -> (u64, Vec<u8>) {
+        let key: Vec<u8> = key.into();
+        let ttl = u64::from_be_bytes(key[7..15].try_into().unwrap());
+        let ukey = Self::decode_bytes(&key[15..]);
+        (ttl, ukey)
+    }
 }
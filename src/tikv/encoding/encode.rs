@@ -3,11 +3,12 @@ use super::ENC_ASC_PADDING;
 use super::ENC_GROUP_SIZE;
 use super::ENC_MARKER;
 use super::SIGN_MASK;
+use crate::cluster::key_hash_slot;
 use crate::config_meta_key_number_or_default;
 use crate::tikv::get_instance_id;
+use bytes::Bytes;
 use std::convert::TryFrom;
 use std::ops::Range;
-use std::ops::RangeInclusive;
 use tikv_client::BoundRange;
 use tikv_client::Key;
 use tikv_client::Value;
@@ -26,6 +27,8 @@ pub const DATA_TYPE_USER_END: u8 = b'v';
 pub const DATA_TYPE_TOPO: u8 = b't';
 pub const DATA_TYPE_GC: u8 = b'g';
 pub const DATA_TYPE_GC_VERSION: u8 = b'v';
+pub const DATA_TYPE_EXPIRE: u8 = b'e';
+pub const DATA_TYPE_PEER_SECRET: u8 = b'p';
 
 pub const DATA_TYPE_META: u8 = b'm';
 pub const DATA_TYPE_SCORE: u8 = b'S';
@@ -33,9 +36,22 @@ pub const DATA_TYPE_HASH: u8 = b'h';
 pub const DATA_TYPE_LIST: u8 = b'l';
 pub const DATA_TYPE_SET: u8 = b's';
 pub const DATA_TYPE_ZSET: u8 = b'z';
+pub const DATA_TYPE_STRING_CHUNK: u8 = b'c';
+
+/// type byte in the meta value of a string stored in chunks, it is reported
+/// as a plain string to clients
+pub const STRING_CHUNKED_TYPE: u8 = 6;
+
+/// type byte in the meta value of a sparse list, whose length follows the
+/// indices, it is reported as a plain list to clients
+pub const LIST_SPARSE_TYPE: u8 = 7;
 
 pub const PLACE_HOLDER: u8 = b'`';
 
+/// first byte of the group keys of the index of a sparse list, the buckets
+/// of indices are below 2^54 so their first byte is always smaller
+const LIST_INDEX_GROUP_TAG: u8 = 0xff;
+
 impl KeyEncoder {
     pub fn new() -> Self {
         KeyEncoder {
@@ -106,17 +122,26 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_rawkv_string(&self, ukey: &str) -> Key {
+    /// The secret shared by the instances to authenticate the requests of peers
+    pub fn encode_txnkv_cluster_peer_secret(&self) -> Key {
+        let mut key = Vec::with_capacity(4);
+        key.push(TXN_KEY_PREFIX);
+        key.extend_from_slice(self.instance_id.as_slice());
+        key.push(DATA_TYPE_PEER_SECRET);
+        key.into()
+    }
+
+    pub fn encode_rawkv_string(&self, ukey: &[u8]) -> Key {
         let mut key = Vec::with_capacity(4 + ukey.len());
         key.push(RAW_KEY_PREFIX);
         key.extend_from_slice(self.instance_id.as_slice());
         key.push(DATA_TYPE_META);
-        key.extend_from_slice(ukey.as_bytes());
+        key.extend_from_slice(ukey);
         key.into()
     }
 
-    pub fn encode_txnkv_string(&self, ukey: &str) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_string(&self, ukey: &[u8]) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(5 + enc_ukey.len());
 
         key.push(TXN_KEY_PREFIX);
@@ -148,13 +173,62 @@ impl KeyEncoder {
         val
     }
 
-    pub fn encode_rawkv_strings(&self, keys: &[String]) -> Vec<Key> {
+    /// The meta value of a string whose bytes are stored in chunk data keys,
+    /// a missing chunk is all zeros and `len` is the length of the string.
+    pub fn encode_txnkv_string_chunked_meta_value(
+        &self,
+        ttl: u64,
+        version: u16,
+        len: u64,
+    ) -> Value {
+        let mut val = Vec::with_capacity(19);
+        val.push(STRING_CHUNKED_TYPE);
+        val.extend_from_slice(&ttl.to_be_bytes());
+        val.extend_from_slice(&version.to_be_bytes());
+        val.extend_from_slice(&len.to_be_bytes());
+        val
+    }
+
+    pub fn encode_txnkv_string_chunk_key(&self, ukey: &[u8], idx: u32, version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
+        let mut key = Vec::with_capacity(12 + enc_ukey.len());
+
+        self.encode_txnkv_type_data_key_prefix(
+            DATA_TYPE_STRING_CHUNK,
+            &enc_ukey,
+            &mut key,
+            version,
+        );
+        key.push(PLACE_HOLDER);
+        key.extend_from_slice(&idx.to_be_bytes());
+        key.into()
+    }
+
+    pub fn encode_txnkv_string_chunk_key_range(&self, ukey: &[u8], version: u16) -> BoundRange {
+        let enc_ukey = self.encode_bytes(ukey);
+        let mut start = Vec::with_capacity(8 + enc_ukey.len());
+
+        self.encode_txnkv_type_data_key_prefix(
+            DATA_TYPE_STRING_CHUNK,
+            &enc_ukey,
+            &mut start,
+            version,
+        );
+        let mut end = start.clone();
+        start.push(PLACE_HOLDER);
+        end.push(PLACE_HOLDER + 1);
+
+        let range: Range<Key> = start.into()..end.into();
+        range.into()
+    }
+
+    pub fn encode_rawkv_strings(&self, keys: &[Bytes]) -> Vec<Key> {
         keys.iter()
             .map(|ukey| self.encode_rawkv_string(ukey))
             .collect()
     }
 
-    pub fn encode_txnkv_strings(&self, keys: &[String]) -> Vec<Key> {
+    pub fn encode_txnkv_strings(&self, keys: &[Bytes]) -> Vec<Key> {
         keys.iter()
             .map(|ukey| self.encode_txnkv_string(ukey))
             .collect()
@@ -168,14 +242,22 @@ impl KeyEncoder {
         key.push(DATA_TYPE_META);
     }
 
-    pub fn encode_txnkv_meta_key(&self, ukey: &str) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_meta_key(&self, ukey: &[u8]) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(5 + enc_ukey.len());
 
         self.encode_txnkv_meta_common_prefix(&enc_ukey, &mut key);
         key.into()
     }
 
+    pub fn encode_txnkv_keyspace_start(&self) -> Key {
+        let mut key = Vec::with_capacity(4);
+        key.push(TXN_KEY_PREFIX);
+        key.extend_from_slice(self.instance_id.as_slice());
+        key.push(DATA_TYPE_USER);
+        key.into()
+    }
+
     pub fn encode_txnkv_keyspace_end(&self) -> Key {
         let mut key = Vec::with_capacity(4);
         key.push(TXN_KEY_PREFIX);
@@ -184,8 +266,8 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_sub_meta_key(&self, ukey: &str, version: u16, idx: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_sub_meta_key(&self, ukey: &[u8], version: u16, idx: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(10 + enc_ukey.len());
 
         self.encode_txnkv_meta_common_prefix(&enc_ukey, &mut key);
@@ -196,8 +278,60 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_sub_meta_key_start(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    /// The sub meta key counting the elements of a sparse list stored at the
+    /// indices of `bucket`, they locate a position without scanning all the
+    /// elements before it.
+    pub fn encode_txnkv_list_index_key(&self, ukey: &[u8], version: u16, bucket: u64) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
+        let mut key = Vec::with_capacity(16 + enc_ukey.len());
+
+        self.encode_txnkv_meta_common_prefix(&enc_ukey, &mut key);
+
+        key.extend_from_slice(&version.to_be_bytes());
+        key.push(PLACE_HOLDER);
+        key.extend_from_slice(&bucket.to_be_bytes());
+        key.into()
+    }
+
+    /// The sub meta key counting the elements of a sparse list stored at the
+    /// indices of the buckets of `group`, they locate the bucket of a
+    /// position without scanning all the buckets before it. They sort after
+    /// the keys of the buckets, the first byte of a bucket is never the tag.
+    pub fn encode_txnkv_list_index_group_key(&self, ukey: &[u8], version: u16, group: u64) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
+        let mut key = Vec::with_capacity(17 + enc_ukey.len());
+
+        self.encode_txnkv_meta_common_prefix(&enc_ukey, &mut key);
+
+        key.extend_from_slice(&version.to_be_bytes());
+        key.push(PLACE_HOLDER);
+        key.push(LIST_INDEX_GROUP_TAG);
+        key.extend_from_slice(&group.to_be_bytes());
+        key.into()
+    }
+
+    /// range of the group keys of the index of a sparse list
+    pub fn encode_txnkv_list_index_group_key_range(&self, ukey: &[u8], version: u16) -> BoundRange {
+        let range: Range<Key> = self.encode_txnkv_list_index_group_key(ukey, version, 0)
+            ..self.encode_txnkv_sub_meta_key_end(ukey, version);
+        range.into()
+    }
+
+    /// range of the bucket keys of the index of a sparse list in [start, end)
+    pub fn encode_txnkv_list_index_key_range(
+        &self,
+        ukey: &[u8],
+        version: u16,
+        start: u64,
+        end: u64,
+    ) -> BoundRange {
+        let range: Range<Key> = self.encode_txnkv_list_index_key(ukey, version, start)
+            ..self.encode_txnkv_list_index_key(ukey, version, end);
+        range.into()
+    }
+
+    pub fn encode_txnkv_sub_meta_key_start(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_meta_common_prefix(&enc_ukey, &mut key);
@@ -207,8 +341,8 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_sub_meta_key_end(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_sub_meta_key_end(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + ukey.len());
 
         self.encode_txnkv_meta_common_prefix(&enc_ukey, &mut key);
@@ -218,7 +352,7 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_sub_meta_key_range(&self, key: &str, version: u16) -> BoundRange {
+    pub fn encode_txnkv_sub_meta_key_range(&self, key: &[u8], version: u16) -> BoundRange {
         let sub_meta_key_start = self.encode_txnkv_sub_meta_key_start(key, version);
         let sub_meta_key_end = self.encode_txnkv_sub_meta_key_end(key, version);
         let range: Range<Key> = sub_meta_key_start..sub_meta_key_end;
@@ -240,18 +374,18 @@ impl KeyEncoder {
         key.extend_from_slice(&version.to_be_bytes());
     }
 
-    pub fn encode_txnkv_hash_data_key(&self, ukey: &str, field: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_hash_data_key(&self, ukey: &[u8], field: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len() + field.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_HASH, &enc_ukey, &mut key, version);
         key.push(PLACE_HOLDER);
-        key.extend_from_slice(field.as_bytes());
+        key.extend_from_slice(field);
         key.into()
     }
 
-    pub fn encode_txnkv_hash_data_key_start(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_hash_data_key_start(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_HASH, &enc_ukey, &mut key, version);
@@ -259,8 +393,8 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_hash_data_key_end(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_hash_data_key_end(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_HASH, &enc_ukey, &mut key, version);
@@ -268,7 +402,7 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_hash_data_key_range(&self, key: &str, version: u16) -> BoundRange {
+    pub fn encode_txnkv_hash_data_key_range(&self, key: &[u8], version: u16) -> BoundRange {
         let data_key_start = self.encode_txnkv_hash_data_key_start(key, version);
         let data_key_end = self.encode_txnkv_hash_data_key_end(key, version);
         let range: Range<Key> = data_key_start..data_key_end;
@@ -297,31 +431,32 @@ impl KeyEncoder {
     /// left initial value  1<<32, left is point to the left element
     /// right initial value 1<<32, right is point to the next right position of right element
     /// list is indicated as null if left index equal to right
-    pub fn encode_txnkv_list_data_key(&self, ukey: &str, idx: u64, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
-        let mut key = Vec::with_capacity(16 + enc_ukey.len());
+    pub fn encode_txnkv_list_data_key(&self, ukey: &[u8], idx: u64, version: u16) -> Key {
+        self.encode_txnkv_list_data_key_with_fraction(ukey, idx, &[], version)
+    }
+
+    /// The data key of an element inserted between neighbours of a sparse
+    /// list, the fraction orders it after `idx` and before `idx + 1`. It is
+    /// a base-256 fraction without trailing zeros, empty for plain indices.
+    pub fn encode_txnkv_list_data_key_with_fraction(
+        &self,
+        ukey: &[u8],
+        idx: u64,
+        fraction: &[u8],
+        version: u16,
+    ) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
+        let mut key = Vec::with_capacity(16 + enc_ukey.len() + fraction.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_LIST, &enc_ukey, &mut key, version);
         key.push(PLACE_HOLDER);
         key.extend_from_slice(&idx.to_be_bytes());
+        key.extend_from_slice(fraction);
         key.into()
     }
 
-    pub fn encode_txnkv_list_data_key_idx_range(
-        &self,
-        key: &str,
-        start: u64,
-        end: u64,
-        version: u16,
-    ) -> BoundRange {
-        let data_key_start = self.encode_txnkv_list_data_key(key, start, version);
-        let data_key_end = self.encode_txnkv_list_data_key(key, end, version);
-        let range: RangeInclusive<Key> = data_key_start..=data_key_end;
-        range.into()
-    }
-
-    fn encode_txnkv_list_data_key_start(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_list_data_key_start(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_LIST, &enc_ukey, &mut key, version);
@@ -329,8 +464,8 @@ impl KeyEncoder {
         key.into()
     }
 
-    fn encode_txnkv_list_data_key_end(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_list_data_key_end(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_LIST, &enc_ukey, &mut key, version);
@@ -338,28 +473,39 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_list_data_key_range(&self, key: &str, version: u16) -> BoundRange {
+    pub fn encode_txnkv_list_data_key_range(&self, key: &[u8], version: u16) -> BoundRange {
         let data_key_start = self.encode_txnkv_list_data_key_start(key, version);
         let data_key_end = self.encode_txnkv_list_data_key_end(key, version);
         let range: Range<Key> = data_key_start..data_key_end;
         range.into()
     }
 
+    /// `len` is only set for a sparse list, whose elements are not stored
+    /// at every index in [left, right), the length of a dense list is
+    /// `right - left`. The meta value of a dense list keeps the layout of the
+    /// versions without sparse lists.
     pub fn encode_txnkv_list_meta_value(
         &self,
         ttl: u64,
         version: u16,
         left: u64,
         right: u64,
+        len: Option<u64>,
     ) -> Value {
-        let dt = self.get_type_bytes(DataType::List);
-        let mut val = Vec::with_capacity(27);
+        let dt = match len {
+            Some(_) => LIST_SPARSE_TYPE,
+            None => self.get_type_bytes(DataType::List),
+        };
+        let mut val = Vec::with_capacity(35);
 
         val.push(dt);
         val.extend_from_slice(&ttl.to_be_bytes());
         val.extend_from_slice(&version.to_be_bytes());
         val.extend_from_slice(&left.to_be_bytes());
         val.extend_from_slice(&right.to_be_bytes());
+        if let Some(len) = len {
+            val.extend_from_slice(&len.to_be_bytes());
+        }
         val
     }
 
@@ -379,18 +525,18 @@ impl KeyEncoder {
         val
     }
 
-    pub fn encode_txnkv_set_data_key(&self, ukey: &str, member: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_set_data_key(&self, ukey: &[u8], member: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len() + member.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_SET, &enc_ukey, &mut key, version);
         key.push(PLACE_HOLDER);
-        key.extend_from_slice(member.as_bytes());
+        key.extend_from_slice(member);
         key.into()
     }
 
-    pub fn encode_txnkv_set_data_key_start(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_set_data_key_start(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_SET, &enc_ukey, &mut key, version);
@@ -398,8 +544,8 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_set_data_key_end(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_set_data_key_end(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_SET, &enc_ukey, &mut key, version);
@@ -407,7 +553,7 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_set_data_key_range(&self, key: &str, version: u16) -> BoundRange {
+    pub fn encode_txnkv_set_data_key_range(&self, key: &[u8], version: u16) -> BoundRange {
         let data_key_start = self.encode_txnkv_set_data_key_start(key, version);
         let data_key_end = self.encode_txnkv_set_data_key_end(key, version);
         let range: Range<Key> = data_key_start..data_key_end;
@@ -430,18 +576,18 @@ impl KeyEncoder {
         val
     }
 
-    pub fn encode_txnkv_zset_data_key(&self, ukey: &str, member: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_zset_data_key(&self, ukey: &[u8], member: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len() + member.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_ZSET, &enc_ukey, &mut key, version);
         key.push(PLACE_HOLDER);
-        key.extend_from_slice(member.as_bytes());
+        key.extend_from_slice(member);
         key.into()
     }
 
-    pub fn encode_txnkv_zset_data_key_start(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_zset_data_key_start(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_ZSET, &enc_ukey, &mut key, version);
@@ -449,8 +595,8 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_zset_data_key_end(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_zset_data_key_end(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_ZSET, &enc_ukey, &mut key, version);
@@ -458,7 +604,7 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_zset_data_key_range(&self, ukey: &str, version: u16) -> BoundRange {
+    pub fn encode_txnkv_zset_data_key_range(&self, ukey: &[u8], version: u16) -> BoundRange {
         let data_key_start = self.encode_txnkv_zset_data_key_start(ukey, version);
         let data_key_end = self.encode_txnkv_zset_data_key_end(ukey, version);
         let range: Range<Key> = data_key_start..data_key_end;
@@ -483,12 +629,12 @@ impl KeyEncoder {
     // encode the member to score key
     pub fn encode_txnkv_zset_score_key(
         &self,
-        ukey: &str,
+        ukey: &[u8],
         score: f64,
-        member: &str,
+        member: &[u8],
         version: u16,
     ) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(17 + enc_ukey.len() + member.len());
         let score = self.encode_f64_to_cmp_uint64(score);
 
@@ -496,12 +642,12 @@ impl KeyEncoder {
         key.push(PLACE_HOLDER);
         key.extend_from_slice(&score.to_be_bytes());
         key.push(PLACE_HOLDER);
-        key.extend_from_slice(member.as_bytes());
+        key.extend_from_slice(member);
         key.into()
     }
 
-    pub fn encode_txnkv_zset_score_key_start(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_zset_score_key_start(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_SCORE, &enc_ukey, &mut key, version);
@@ -509,8 +655,8 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_zset_score_key_end(&self, ukey: &str, version: u16) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_zset_score_key_end(&self, ukey: &[u8], version: u16) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
         self.encode_txnkv_type_data_key_prefix(DATA_TYPE_SCORE, &enc_ukey, &mut key, version);
@@ -518,7 +664,7 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_zset_score_key_range(&self, ukey: &str, version: u16) -> BoundRange {
+    pub fn encode_txnkv_zset_score_key_range(&self, ukey: &[u8], version: u16) -> BoundRange {
         let range_start = self.encode_txnkv_zset_score_key_start(ukey, version);
         let range_end = self.encode_txnkv_zset_score_key_end(ukey, version);
         let range: Range<Key> = range_start..range_end;
@@ -527,12 +673,12 @@ impl KeyEncoder {
 
     pub fn encode_txnkv_zset_score_key_score_start(
         &self,
-        ukey: &str,
+        ukey: &[u8],
         score: f64,
         with_frontier: bool,
         version: u16,
     ) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(17 + enc_ukey.len());
         let mut score = self.encode_f64_to_cmp_uint64(score);
         if !with_frontier {
@@ -549,12 +695,12 @@ impl KeyEncoder {
 
     pub fn encode_txnkv_zset_score_key_score_end(
         &self,
-        ukey: &str,
+        ukey: &[u8],
         score: f64,
         with_frontier: bool,
         version: u16,
     ) -> Key {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(17 + enc_ukey.len());
         let mut score = self.encode_f64_to_cmp_uint64(score);
         if !with_frontier {
@@ -568,8 +714,8 @@ impl KeyEncoder {
         key.into()
     }
 
-    pub fn encode_txnkv_gc_key_prefix(&self, ukey: &str, data_type: u8, extra: usize) -> Vec<u8> {
-        let enc_ukey = self.encode_bytes(ukey.as_bytes());
+    pub fn encode_txnkv_gc_key_prefix(&self, ukey: &[u8], data_type: u8, extra: usize) -> Vec<u8> {
+        let enc_ukey = self.encode_bytes(ukey);
         let mut key = Vec::with_capacity(extra + enc_ukey.len());
         key.push(TXN_KEY_PREFIX);
         key.extend_from_slice(self.instance_id.as_slice());
@@ -579,12 +725,12 @@ impl KeyEncoder {
         key
     }
 
-    pub fn encode_txnkv_gc_key(&self, ukey: &str) -> Key {
+    pub fn encode_txnkv_gc_key(&self, ukey: &[u8]) -> Key {
         self.encode_txnkv_gc_key_prefix(ukey, DATA_TYPE_GC, 5)
             .into()
     }
 
-    pub fn encode_txnkv_gc_version_key(&self, ukey: &str, version: u16) -> Key {
+    pub fn encode_txnkv_gc_version_key(&self, ukey: &[u8], version: u16) -> Key {
         let mut key = self.encode_txnkv_gc_key_prefix(ukey, DATA_TYPE_GC_VERSION, 7);
         key.extend_from_slice(&version.to_be_bytes());
         key.into()
@@ -609,4 +755,66 @@ impl KeyEncoder {
         let range: Range<Key> = range_start..range_end;
         range.into()
     }
+
+    /// encode key of the ttl index, ordered by the hash slot then the expire
+    /// timestamp of user keys, so each node scans the slots it owns
+    pub fn encode_txnkv_expire_index_key(&self, ukey: &[u8], ttl: u64) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
+        let slot = key_hash_slot(ukey) as u16;
+        let mut key = Vec::with_capacity(15 + enc_ukey.len());
+        key.push(TXN_KEY_PREFIX);
+        key.extend_from_slice(self.instance_id.as_slice());
+        key.push(DATA_TYPE_EXPIRE);
+        key.push(PLACE_HOLDER);
+        key.extend_from_slice(&slot.to_be_bytes());
+        key.extend_from_slice(&ttl.to_be_bytes());
+        key.extend_from_slice(&enc_ukey);
+        key.into()
+    }
+
+    fn encode_txnkv_expire_index_slot_prefix(&self, slot: usize) -> Vec<u8> {
+        let mut key = Vec::with_capacity(15);
+        key.push(TXN_KEY_PREFIX);
+        key.extend_from_slice(self.instance_id.as_slice());
+        key.push(DATA_TYPE_EXPIRE);
+        key.push(PLACE_HOLDER);
+        key.extend_from_slice(&(slot as u16).to_be_bytes());
+        key
+    }
+
+    /// range of the ttl index for keys in `slot` expired before `timestamp`
+    pub fn encode_txnkv_expire_index_key_range(&self, slot: usize, timestamp: u64) -> BoundRange {
+        let range_start = self.encode_txnkv_expire_index_slot_prefix(slot);
+        let mut range_end = range_start.clone();
+        range_end.extend_from_slice(&timestamp.to_be_bytes());
+
+        let range: Range<Key> = range_start.into()..range_end.into();
+        range.into()
+    }
+
+    /// range of the ttl index for keys in the slots from `slot_start` to
+    /// `slot_end` inclusive, whatever their expire timestamp
+    pub fn encode_txnkv_expire_index_slots_range(
+        &self,
+        slot_start: usize,
+        slot_end: usize,
+    ) -> BoundRange {
+        let range_start = self.encode_txnkv_expire_index_slot_prefix(slot_start);
+        let range_end = self.encode_txnkv_expire_index_slot_prefix(slot_end + 1);
+
+        let range: Range<Key> = range_start.into()..range_end.into();
+        range.into()
+    }
+
+    /// encode key of the progress of the ttl index backfill, it holds the
+    /// key the backfill resumes from, or nothing once it is done. It is out
+    /// of the ranges of the ttl index.
+    pub fn encode_txnkv_expire_backfill_key(&self) -> Key {
+        let mut key = Vec::with_capacity(5);
+        key.push(TXN_KEY_PREFIX);
+        key.extend_from_slice(self.instance_id.as_slice());
+        key.push(DATA_TYPE_EXPIRE);
+        key.push(PLACE_HOLDER + 1);
+        key.into()
+    }
 }
@@ -1,4 +1,5 @@
 use mlua::prelude::LuaError;
+use std::io;
 use std::num::{ParseFloatError, ParseIntError};
 use thiserror::Error;
 use tikv_client::Error as TiKVError;
@@ -38,6 +39,12 @@ impl From<LuaError> for RTError {
     }
 }
 
+impl From<io::Error> for RTError {
+    fn from(e: io::Error) -> Self {
+        RTError::Owned(e.to_string())
+    }
+}
+
 impl From<&'static str> for RTError {
     fn from(e: &'static str) -> Self {
         RTError::String(e)
@@ -66,6 +73,28 @@ pub const REDIS_VALUE_IS_NOT_INTEGER_ERR: RTError =
     RTError::String("ERR value is not an integer or out of range");
 pub const REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR: RTError =
     RTError::String("ERR value is not a valid float");
+pub const REDIS_INCR_NAN_OR_INFINITY_ERR: RTError =
+    RTError::String("ERR increment would produce NaN or Infinity");
+pub const REDIS_STRING_EXCEEDS_MAX_SIZE_ERR: RTError =
+    RTError::String("ERR string exceeds maximum allowed size (proto-max-bulk-len)");
+pub const REDIS_OFFSET_OUT_OF_RANGE_ERR: RTError = RTError::String("ERR offset is out of range");
+pub const REDIS_BIT_OFFSET_OUT_OF_RANGE_ERR: RTError =
+    RTError::String("ERR bit offset is not an integer or out of range");
+pub const REDIS_BIT_VALUE_OUT_OF_RANGE_ERR: RTError =
+    RTError::String("ERR bit is not an integer or out of range");
+pub const REDIS_BIT_ARGUMENT_ERR: RTError = RTError::String("ERR The bit argument must be 1 or 0.");
+pub const REDIS_BITOP_NOT_SINGLE_SOURCE_ERR: RTError =
+    RTError::String("ERR BITOP NOT must be called with a single source key.");
+pub const REDIS_INVALID_HLL_ERR: RTError =
+    RTError::String("WRONGTYPE Key is not a valid HyperLogLog string value.");
+pub const REDIS_CORRUPTED_HLL_ERR: RTError =
+    RTError::String("INVALIDOBJ Corrupted HLL object detected");
+pub const REDIS_INVALID_LEX_RANGE_ERR: RTError =
+    RTError::String("ERR min or max not valid string range item");
+pub const REDIS_GEO_MEMBER_NOT_FOUND_ERR: RTError =
+    RTError::String("ERR could not decode requested zset member");
+pub const REDIS_GEO_UNSUPPORTED_UNIT_ERR: RTError =
+    RTError::String("ERR unsupported unit provided. please use M, KM, FT, MI");
 pub const REDIS_BACKEND_NOT_CONNECTED_ERR: RTError = RTError::String("ERR backend not connected");
 pub const REDIS_COMPARE_AND_SWAP_EXHAUSTED_ERR: RTError =
     RTError::String("ERR compare-and-swap exhausted");
@@ -76,6 +105,11 @@ pub const REDIS_AUTH_WHEN_DISABLED_ERR: RTError =
     RTError::String("ERR Client sent AUTH, but no password is set");
 pub const REDIS_AUTH_INVALID_PASSWORD_ERR: RTError = RTError::String("ERR invalid password");
 pub const REDIS_AUTH_REQUIRED_ERR: RTError = RTError::String("NOAUTH Authentication required.");
+pub const REDIS_HELLO_NOAUTH_ERR: RTError =
+    RTError::String("NOAUTH HELLO must be called with the client already authenticated, otherwise the HELLO AUTH <user> <pass> option can be used to authenticate the client and select the RESP protocol version at the same time");
+pub const REDIS_WRONGPASS_ERR: RTError =
+    RTError::String("WRONGPASS invalid username-password pair or user is disabled.");
+pub const REDIS_NOPROTO_ERR: RTError = RTError::String("NOPROTO unsupported protocol version");
 pub const REDIS_NO_MATCHING_SCRIPT_ERR: RTError =
     RTError::String("NOSCRIPT No matching script. Please use EVAL.");
 pub const REDIS_LUA_CONTEXT_IS_NOT_INITIALIZED_ERR: RTError =
@@ -84,13 +118,22 @@ pub const REDIS_LUA_PANIC: RTError = RTError::String("ERR lua panic");
 pub const REDIS_UNKNOWN_SUBCOMMAND: RTError =
     RTError::String("Unknown subcommand or wrong number of arguments");
 pub const DECREMENT_OVERFLOW: RTError = RTError::String("Decrement would overflow");
-pub const REDIS_LIST_TOO_LARGE_ERR: RTError = RTError::String("ERR list is too large to execute");
 pub const KEY_VERSION_EXHUSTED_ERR: RTError = RTError::String("ERR key version exhausted");
 pub const REDIS_MULTI_NESTED_ERR: RTError = RTError::String("ERR MULTI calls can not be nested");
 pub const REDIS_DISCARD_WITHOUT_MULTI_ERR: RTError = RTError::String("ERR DISCARD without MULTI");
 pub const REDIS_EXEC_WITHOUT_MULTI_ERR: RTError = RTError::String("ERR EXEC without MULTI");
+pub const REDIS_WATCH_INSIDE_MULTI_ERR: RTError =
+    RTError::String("ERR WATCH inside MULTI is not allowed");
+pub const REDIS_NOT_ALLOWED_IN_MULTI_ERR: RTError =
+    RTError::String("ERR Command not allowed inside a transaction");
 pub const REDIS_EXEC_ERR: RTError =
     RTError::String("EXECABORT Transaction discarded because of previous errors.");
+pub const REDIS_INVALID_CONFIG_VALUE_ERR: RTError =
+    RTError::String("ERR Invalid argument for CONFIG SET");
+pub const REDIS_CROSSSLOT_ERR: RTError =
+    RTError::String("CROSSSLOT Keys in request don't hash to the same slot");
+pub const REDIS_SSUBSCRIBE_WRONG_ARGS_ERR: RTError =
+    RTError::String("ERR wrong number of arguments for 'ssubscribe' command");
 
 pub const REDIS_INVALID_CLIENT_ID_ERR: RTError = RTError::String("ERR Invalid client ID");
 pub const REDIS_NO_SUCH_CLIENT_ERR: RTError = RTError::String("ERR No such client");
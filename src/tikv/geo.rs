@@ -0,0 +1,685 @@
+use super::errors::*;
+use super::get_txn_client;
+use super::zset::ZsetCommandCtx;
+use super::{encoding::KeyDecoder, errors::AsyncResult, KEY_ENCODER};
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_int, resp_nil, resp_nil_array};
+use crate::Frame;
+use ::futures::future::FutureExt;
+use bytes::Bytes;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tikv_client::{BoundRange, Transaction};
+use tokio::sync::Mutex;
+
+use crate::notify::{notify_keyspace_event, NOTIFY_GENERIC, NOTIFY_ZSET};
+
+// The geohash encoding below follows geohash.c and geohash_helper.c of redis,
+// so the scores of the members are the same as the ones set by redis.
+const GEO_STEP_MAX: u32 = 26;
+pub const GEO_LAT_MIN: f64 = -85.05112878;
+pub const GEO_LAT_MAX: f64 = 85.05112878;
+pub const GEO_LONG_MIN: f64 = -180.0;
+pub const GEO_LONG_MAX: f64 = 180.0;
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+const MERCATOR_MAX: f64 = 20037726.37;
+const GEO_ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Where a GEOSEARCH starts from.
+#[derive(Debug, Clone)]
+pub enum GeoOrigin {
+    Member(Bytes),
+    Coord(f64, f64),
+}
+
+/// The area searched by GEOSEARCH, in meters.
+#[derive(Debug, Clone, Copy)]
+pub enum GeoShape {
+    Radius(f64),
+    Box(f64, f64),
+}
+
+#[derive(Debug, Clone)]
+pub struct GeoSearchQuery {
+    pub origin: GeoOrigin,
+    pub shape: GeoShape,
+    /// meters of the unit used by the replied distances
+    pub unit: f64,
+    /// sort by the distance, ascending if true
+    pub asc: Option<bool>,
+    pub count: Option<u64>,
+    pub any: bool,
+    pub with_coord: bool,
+    pub with_dist: bool,
+    pub with_hash: bool,
+}
+
+struct GeoPoint {
+    member: Vec<u8>,
+    score: f64,
+    coord: (f64, f64),
+    dist: f64,
+}
+
+#[derive(Clone)]
+pub struct GeoCommandCtx {
+    txn: Option<Arc<Mutex<Transaction>>>,
+}
+
+impl GeoCommandCtx {
+    pub fn new(txn: Option<Arc<Mutex<Transaction>>>) -> Self {
+        GeoCommandCtx { txn }
+    }
+
+    pub async fn do_async_txnkv_geopos(
+        mut self,
+        key: &[u8],
+        members: &[Bytes],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let members = members.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move { txnkv_geo_scores(&txn_rc, &key, &members).await }.boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(scores) => Ok(resp_array(
+                scores
+                    .into_iter()
+                    .map(|score| match score {
+                        Some(score) => coord_frame(geohash_decode_score(score)),
+                        None => resp_nil_array(),
+                    })
+                    .collect(),
+            )),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_geodist(
+        mut self,
+        key: &[u8],
+        member1: &[u8],
+        member2: &[u8],
+        unit: f64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let members = vec![
+            Bytes::from(member1.to_owned()),
+            Bytes::from(member2.to_owned()),
+        ];
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move { txnkv_geo_scores(&txn_rc, &key, &members).await }.boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(scores) => match (scores[0], scores[1]) {
+                (Some(score1), Some(score2)) => {
+                    let dist = geohash_distance(
+                        geohash_decode_score(score1),
+                        geohash_decode_score(score2),
+                    );
+                    Ok(dist_frame(dist / unit))
+                }
+                _ => Ok(resp_nil()),
+            },
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_geohash(
+        mut self,
+        key: &[u8],
+        members: &[Bytes],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let members = members.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move { txnkv_geo_scores(&txn_rc, &key, &members).await }.boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(scores) => Ok(resp_array(
+                scores
+                    .into_iter()
+                    .map(|score| match score {
+                        Some(score) => resp_bulk(geohash_string(score)),
+                        None => resp_nil(),
+                    })
+                    .collect(),
+            )),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_geosearch(
+        mut self,
+        key: &[u8],
+        query: &GeoSearchQuery,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let query = query.clone();
+        let search_query = query.clone();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move { txnkv_geo_search(&txn_rc, &key, &search_query).await }.boxed()
+            })
+            .await;
+
+        let points = match resp {
+            Ok(points) => points,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        let with_any = query.with_dist || query.with_hash || query.with_coord;
+        let frames = points
+            .into_iter()
+            .map(|point| {
+                if !with_any {
+                    return resp_bulk(point.member);
+                }
+                let mut item = vec![resp_bulk(point.member)];
+                if query.with_dist {
+                    item.push(dist_frame(point.dist / query.unit));
+                }
+                if query.with_hash {
+                    item.push(resp_int(point.score as i64));
+                }
+                if query.with_coord {
+                    item.push(coord_frame(point.coord));
+                }
+                resp_array(item)
+            })
+            .collect();
+        Ok(resp_array(frames))
+    }
+
+    /// Store the members found by the query in `dest`, scored by their
+    /// distances if `store_dist` is set or by their geohashes otherwise.
+    pub async fn do_async_txnkv_geosearchstore(
+        mut self,
+        dest: &[u8],
+        key: &[u8],
+        query: &GeoSearchQuery,
+        store_dist: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let dest = dest.to_owned();
+        let udest = dest.clone();
+        let key = key.to_owned();
+        let query = query.clone();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let points = txnkv_geo_search(&txn_rc, &key, &query).await?;
+                    let members: Vec<(Vec<u8>, f64)> = points
+                        .into_iter()
+                        .map(|point| {
+                            let score = if store_dist {
+                                point.dist / query.unit
+                            } else {
+                                point.score
+                            };
+                            (point.member, score)
+                        })
+                        .collect();
+                    let replaced = ZsetCommandCtx::new(self.txn.clone())
+                        .txnkv_zset_store(&txn_rc, &dest, &members)
+                        .await?;
+                    Ok((members.len(), replaced))
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok((len, replaced)) => {
+                if len > 0 {
+                    notify_keyspace_event(NOTIFY_ZSET, "geosearchstore", &udest);
+                } else if replaced {
+                    notify_keyspace_event(NOTIFY_GENERIC, "del", &udest);
+                }
+                Ok(resp_int(len as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+}
+
+/// Read the scores of the members of the sorted set stored at `key`.
+async fn txnkv_geo_scores(
+    txn_rc: &Arc<Mutex<Transaction>>,
+    key: &[u8],
+    members: &[Bytes],
+) -> AsyncResult<Vec<Option<f64>>> {
+    let version = match ZsetCommandCtx::new(Some(txn_rc.clone()))
+        .txnkv_zset_live_version(txn_rc, key)
+        .await?
+    {
+        Some(version) => version,
+        None => return Ok(vec![None; members.len()]),
+    };
+
+    let mut txn = txn_rc.lock().await;
+    let mut scores = Vec::with_capacity(members.len());
+    for member in members {
+        let data_key = KEY_ENCODER.encode_txnkv_zset_data_key(key, member, version);
+        let score = txn
+            .get(data_key)
+            .await?
+            .map(|v| KeyDecoder::decode_key_zset_data_value(&v));
+        scores.push(score);
+    }
+    Ok(scores)
+}
+
+/// Find the members inside the shape of the query, by scanning the score
+/// ranges of the geohash boxes covering it.
+async fn txnkv_geo_search(
+    txn_rc: &Arc<Mutex<Transaction>>,
+    key: &[u8],
+    query: &GeoSearchQuery,
+) -> AsyncResult<Vec<GeoPoint>> {
+    let version = match ZsetCommandCtx::new(Some(txn_rc.clone()))
+        .txnkv_zset_live_version(txn_rc, key)
+        .await?
+    {
+        Some(version) => version,
+        None => return Ok(vec![]),
+    };
+
+    let center = match &query.origin {
+        GeoOrigin::Coord(lon, lat) => (*lon, *lat),
+        GeoOrigin::Member(member) => {
+            let data_key = KEY_ENCODER.encode_txnkv_zset_data_key(key, member, version);
+            match txn_rc.lock().await.get(data_key).await? {
+                Some(v) => geohash_decode_score(KeyDecoder::decode_key_zset_data_value(&v)),
+                None => return Err(REDIS_GEO_MEMBER_NOT_FOUND_ERR),
+            }
+        }
+    };
+
+    // the ANY option stops the search as soon as enough members are found
+    let limit = match query.count {
+        Some(count) if query.any => count as usize,
+        _ => usize::MAX,
+    };
+    let mut points = vec![];
+    let mut txn = txn_rc.lock().await;
+    'boxes: for (min, max) in geohash_search_ranges(center, query.shape) {
+        let start_key =
+            KEY_ENCODER.encode_txnkv_zset_score_key_score_start(key, min as f64, true, version);
+        let end_key =
+            KEY_ENCODER.encode_txnkv_zset_score_key_score_end(key, max as f64, false, version);
+        let range: BoundRange = (start_key..end_key).into();
+        for kv in txn.scan(range, u32::MAX).await? {
+            let score = KeyDecoder::decode_key_zset_score_from_scorekey(key, kv.0);
+            let coord = geohash_decode_score(score);
+            if let Some(dist) = geohash_distance_in_shape(center, query.shape, coord) {
+                points.push(GeoPoint {
+                    member: kv.1,
+                    score,
+                    coord,
+                    dist,
+                });
+                if points.len() >= limit {
+                    break 'boxes;
+                }
+            }
+        }
+    }
+
+    // COUNT without ANY returns the nearest members
+    let asc = match query.asc {
+        None if query.count.is_some() && !query.any => Some(true),
+        asc => asc,
+    };
+    match asc {
+        Some(true) => points.sort_by(|a, b| a.dist.total_cmp(&b.dist)),
+        Some(false) => points.sort_by(|a, b| b.dist.total_cmp(&a.dist)),
+        None => {}
+    }
+    if let Some(count) = query.count {
+        points.truncate(count as usize);
+    }
+    Ok(points)
+}
+
+fn dist_frame(dist: f64) -> Frame {
+    resp_bulk(format!("{:.4}", dist).into_bytes())
+}
+
+fn coord_frame((lon, lat): (f64, f64)) -> Frame {
+    resp_array(vec![
+        resp_bulk(human_double(lon).into_bytes()),
+        resp_bulk(human_double(lat).into_bytes()),
+    ])
+}
+
+fn human_double(value: f64) -> String {
+    let s = format!("{:.17}", value);
+    s.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+/// Check if the coordinates are inside the range of the geohash encoding.
+pub fn geo_coord_is_valid(lon: f64, lat: f64) -> bool {
+    (GEO_LONG_MIN..=GEO_LONG_MAX).contains(&lon) && (GEO_LAT_MIN..=GEO_LAT_MAX).contains(&lat)
+}
+
+/// Encode the coordinates to the 52-bit geohash used as the score.
+pub fn geohash_encode_score(lon: f64, lat: f64) -> f64 {
+    geohash_encode(
+        (GEO_LONG_MIN, GEO_LONG_MAX),
+        (GEO_LAT_MIN, GEO_LAT_MAX),
+        lon,
+        lat,
+        GEO_STEP_MAX,
+    ) as f64
+}
+
+fn geohash_encode(
+    long_range: (f64, f64),
+    lat_range: (f64, f64),
+    lon: f64,
+    lat: f64,
+    step: u32,
+) -> u64 {
+    let lat_offset = (lat - lat_range.0) / (lat_range.1 - lat_range.0);
+    let long_offset = (lon - long_range.0) / (long_range.1 - long_range.0);
+    let lat_offset = (lat_offset * (1u64 << step) as f64) as u32;
+    let long_offset = (long_offset * (1u64 << step) as f64) as u32;
+    interleave64(lat_offset, long_offset)
+}
+
+/// The area of a geohash as ((long_min, long_max), (lat_min, lat_max)).
+fn geohash_decode(bits: u64, step: u32) -> ((f64, f64), (f64, f64)) {
+    let (ilato, ilono) = deinterleave64(bits);
+    let cells = (1u64 << step) as f64;
+    let lat_scale = GEO_LAT_MAX - GEO_LAT_MIN;
+    let long_scale = GEO_LONG_MAX - GEO_LONG_MIN;
+    (
+        (
+            GEO_LONG_MIN + (ilono as f64 / cells) * long_scale,
+            GEO_LONG_MIN + ((ilono as f64 + 1.0) / cells) * long_scale,
+        ),
+        (
+            GEO_LAT_MIN + (ilato as f64 / cells) * lat_scale,
+            GEO_LAT_MIN + ((ilato as f64 + 1.0) / cells) * lat_scale,
+        ),
+    )
+}
+
+/// Decode a score to the coordinates at the center of its geohash area.
+fn geohash_decode_score(score: f64) -> (f64, f64) {
+    let (long_range, lat_range) = geohash_decode(score as u64, GEO_STEP_MAX);
+    let lon = ((long_range.0 + long_range.1) / 2.0).clamp(GEO_LONG_MIN, GEO_LONG_MAX);
+    let lat = ((lat_range.0 + lat_range.1) / 2.0).clamp(GEO_LAT_MIN, GEO_LAT_MAX);
+    (lon, lat)
+}
+
+/// The standard 11 characters geohash string of a score, which uses the
+/// latitude range of [-90, 90] instead of the one of the score.
+fn geohash_string(score: f64) -> Vec<u8> {
+    let (lon, lat) = geohash_decode_score(score);
+    let bits = geohash_encode((-180.0, 180.0), (-90.0, 90.0), lon, lat, GEO_STEP_MAX);
+    (0..11)
+        .map(|i| {
+            // 52 bits only fill 10 characters and 2 bits of the last one
+            let idx = if i == 10 {
+                0
+            } else {
+                (bits >> (52 - (i + 1) * 5)) & 0x1f
+            };
+            GEO_ALPHABET[idx as usize]
+        })
+        .collect()
+}
+
+fn interleave64(xlo: u32, ylo: u32) -> u64 {
+    const B: [u64; 5] = [
+        0x5555555555555555,
+        0x3333333333333333,
+        0x0F0F0F0F0F0F0F0F,
+        0x00FF00FF00FF00FF,
+        0x0000FFFF0000FFFF,
+    ];
+    const S: [u32; 5] = [1, 2, 4, 8, 16];
+    let mut x = xlo as u64;
+    let mut y = ylo as u64;
+    for i in (0..5).rev() {
+        x = (x | (x << S[i])) & B[i];
+        y = (y | (y << S[i])) & B[i];
+    }
+    x | (y << 1)
+}
+
+fn deinterleave64(interleaved: u64) -> (u32, u32) {
+    const B: [u64; 6] = [
+        0x5555555555555555,
+        0x3333333333333333,
+        0x0F0F0F0F0F0F0F0F,
+        0x00FF00FF00FF00FF,
+        0x0000FFFF0000FFFF,
+        0x00000000FFFFFFFF,
+    ];
+    const S: [u32; 6] = [0, 1, 2, 4, 8, 16];
+    let mut x = interleaved;
+    let mut y = interleaved >> 1;
+    for i in 0..6 {
+        x = (x | (x >> S[i])) & B[i];
+        y = (y | (y >> S[i])) & B[i];
+    }
+    (x as u32, y as u32)
+}
+
+/// Move a geohash by one box to the east (d > 0) or west (d < 0).
+fn geohash_move_x(bits: u64, step: u32, d: i8) -> u64 {
+    let mut x = bits & 0xaaaaaaaaaaaaaaaa;
+    let y = bits & 0x5555555555555555;
+    let zz = 0x5555555555555555u64 >> (64 - step * 2);
+    if d > 0 {
+        x = x.wrapping_add(zz + 1);
+    } else {
+        x = (x | zz).wrapping_sub(zz + 1);
+    }
+    x &= 0xaaaaaaaaaaaaaaaau64 >> (64 - step * 2);
+    x | y
+}
+
+/// Move a geohash by one box to the north (d > 0) or south (d < 0).
+fn geohash_move_y(bits: u64, step: u32, d: i8) -> u64 {
+    let x = bits & 0xaaaaaaaaaaaaaaaa;
+    let mut y = bits & 0x5555555555555555;
+    let zz = 0xaaaaaaaaaaaaaaaau64 >> (64 - step * 2);
+    if d > 0 {
+        y = y.wrapping_add(zz + 1);
+    } else {
+        y = (y | zz).wrapping_sub(zz + 1);
+    }
+    y &= 0x5555555555555555u64 >> (64 - step * 2);
+    x | y
+}
+
+fn geohash_estimate_steps_by_radius(mut range_meters: f64, lat: f64) -> u32 {
+    if range_meters == 0.0 {
+        return GEO_STEP_MAX;
+    }
+    let mut step: i32 = 1;
+    while range_meters < MERCATOR_MAX {
+        range_meters *= 2.0;
+        step += 1;
+    }
+    // make sure the range is included in most of the base cases
+    step -= 2;
+    // the boxes are narrower towards the poles
+    if !(-66.0..=66.0).contains(&lat) {
+        step -= 1;
+        if !(-80.0..=80.0).contains(&lat) {
+            step -= 1;
+        }
+    }
+    step.clamp(1, GEO_STEP_MAX as i32) as u32
+}
+
+/// The bounding box of the shape as (long_min, lat_min, long_max, lat_max).
+fn geohash_bounding_box((lon, lat): (f64, f64), shape: GeoShape) -> (f64, f64, f64, f64) {
+    let (width, height) = match shape {
+        GeoShape::Radius(radius) => (radius, radius),
+        GeoShape::Box(width, height) => (width / 2.0, height / 2.0),
+    };
+    let lat_delta = (height / EARTH_RADIUS_IN_METERS).to_degrees();
+    let long_delta_top =
+        (width / EARTH_RADIUS_IN_METERS / (lat + lat_delta).to_radians().cos()).to_degrees();
+    let long_delta_bottom =
+        (width / EARTH_RADIUS_IN_METERS / (lat - lat_delta).to_radians().cos()).to_degrees();
+    let long_delta = if lat < 0.0 {
+        long_delta_bottom
+    } else {
+        long_delta_top
+    };
+    (
+        lon - long_delta,
+        lat - lat_delta,
+        lon + long_delta,
+        lat + lat_delta,
+    )
+}
+
+/// The score ranges `[min, max)` of the geohash box containing the center
+/// and its neighbors, which together cover the whole shape.
+fn geohash_search_ranges(center: (f64, f64), shape: GeoShape) -> Vec<(u64, u64)> {
+    let (min_lon, min_lat, max_lon, max_lat) = geohash_bounding_box(center, shape);
+    let radius = match shape {
+        GeoShape::Radius(radius) => radius,
+        GeoShape::Box(width, height) => ((width / 2.0).powi(2) + (height / 2.0).powi(2)).sqrt(),
+    };
+    let encode = |step| {
+        geohash_encode(
+            (GEO_LONG_MIN, GEO_LONG_MAX),
+            (GEO_LAT_MIN, GEO_LAT_MAX),
+            center.0,
+            center.1,
+            step,
+        )
+    };
+
+    let mut step = geohash_estimate_steps_by_radius(radius, center.1);
+    let mut bits = encode(step);
+    // the estimated step may be too large if the center is near an edge
+    // of its box, so that the neighbors don't cover the whole shape
+    if step > 1 {
+        let (_, north) = geohash_decode(geohash_move_y(bits, step, 1), step);
+        let (_, south) = geohash_decode(geohash_move_y(bits, step, -1), step);
+        let (east, _) = geohash_decode(geohash_move_x(bits, step, 1), step);
+        let (west, _) = geohash_decode(geohash_move_x(bits, step, -1), step);
+        if north.1 < max_lat || south.0 > min_lat || east.1 < max_lon || west.0 > min_lon {
+            step -= 1;
+            bits = encode(step);
+        }
+    }
+
+    let (long_range, lat_range) = geohash_decode(bits, step);
+    let mut boxes = vec![];
+    for dy in [-1i8, 0, 1] {
+        for dx in [-1i8, 0, 1] {
+            // skip the neighbors which are useless for the shape
+            if step >= 2
+                && ((dy < 0 && lat_range.0 < min_lat)
+                    || (dy > 0 && lat_range.1 > max_lat)
+                    || (dx < 0 && long_range.0 < min_lon)
+                    || (dx > 0 && long_range.1 > max_lon))
+            {
+                continue;
+            }
+            let mut neighbor = bits;
+            if dx != 0 {
+                neighbor = geohash_move_x(neighbor, step, dx);
+            }
+            if dy != 0 {
+                neighbor = geohash_move_y(neighbor, step, dy);
+            }
+            boxes.push(neighbor);
+        }
+    }
+
+    // neighbors may be the same box for a huge radius
+    let shift = (GEO_STEP_MAX - step) * 2;
+    let mut seen = HashSet::new();
+    boxes
+        .into_iter()
+        .filter(|bits| seen.insert(*bits))
+        .map(|bits| (bits << shift, (bits + 1) << shift))
+        .collect()
+}
+
+/// The haversine distance in meters between two coordinates.
+fn geohash_distance((lon1, lat1): (f64, f64), (lon2, lat2): (f64, f64)) -> f64 {
+    let (lon1r, lat1r) = (lon1.to_radians(), lat1.to_radians());
+    let (lon2r, lat2r) = (lon2.to_radians(), lat2.to_radians());
+    let v = ((lon2r - lon1r) / 2.0).sin();
+    let u = ((lat2r - lat1r) / 2.0).sin();
+    let a = u * u + lat1r.cos() * lat2r.cos() * v * v;
+    2.0 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+}
+
+/// The distance from the center to the point, if the point is inside the shape.
+fn geohash_distance_in_shape(
+    center: (f64, f64),
+    shape: GeoShape,
+    point: (f64, f64),
+) -> Option<f64> {
+    match shape {
+        GeoShape::Radius(radius) => {
+            let dist = geohash_distance(center, point);
+            (dist <= radius).then_some(dist)
+        }
+        GeoShape::Box(width, height) => {
+            let lat_dist =
+                EARTH_RADIUS_IN_METERS * (point.1.to_radians() - center.1.to_radians()).abs();
+            if lat_dist > height / 2.0 {
+                return None;
+            }
+            let lon_dist = geohash_distance((point.0, point.1), (center.0, point.1));
+            if lon_dist > width / 2.0 {
+                return None;
+            }
+            Some(geohash_distance(center, point))
+        }
+    }
+}
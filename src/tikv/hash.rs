@@ -13,6 +13,7 @@ use crate::{
     Frame,
 };
 
+use bytes::Bytes;
 use futures::future::FutureExt;
 use slog::debug;
 use std::{collections::HashMap, convert::TryInto, ops::Range, sync::Arc};
@@ -23,6 +24,7 @@ use super::errors::*;
 use crate::utils::{resp_array, resp_bulk, resp_err, resp_int, resp_nil};
 
 use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::notify::{notify_keyspace_event, NOTIFY_EXPIRED, NOTIFY_HASH};
 
 #[derive(Clone)]
 pub struct HashCommandCtx {
@@ -34,7 +36,7 @@ impl<'a> HashCommandCtx {
         HashCommandCtx { txn }
     }
 
-    async fn txnkv_sum_key_size(mut self, key: &str, version: u16) -> AsyncResult<i64> {
+    async fn txnkv_sum_key_size(mut self, key: &[u8], version: u16) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
 
@@ -76,12 +78,13 @@ impl<'a> HashCommandCtx {
 
     pub async fn do_async_txnkv_hset(
         mut self,
-        key: &str,
+        key: &[u8],
         fvs: &[KvPair],
         is_hmset: bool,
         is_nx: bool,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
         let fvs_copy = fvs.to_vec();
@@ -120,11 +123,8 @@ impl<'a> HashCommandCtx {
                                 // when is_nx == true, fvs_len must be 1
                                 let kv = fvs_copy.get(0).unwrap();
                                 let field: Vec<u8> = kv.clone().0.into();
-                                let datakey = KEY_ENCODER.encode_txnkv_hash_data_key(
-                                    &key,
-                                    &String::from_utf8_lossy(&field),
-                                    version,
-                                );
+                                let datakey =
+                                    KEY_ENCODER.encode_txnkv_hash_data_key(&key, &field, version);
                                 if txn.key_exists(datakey.clone()).await? {
                                     return Ok(0);
                                 }
@@ -135,11 +135,8 @@ impl<'a> HashCommandCtx {
                                 let mut fields_data_key = Vec::with_capacity(fvs_len);
                                 for kv in fvs_copy.clone() {
                                     let field: Vec<u8> = kv.0.into();
-                                    let datakey = KEY_ENCODER.encode_txnkv_hash_data_key(
-                                        &key,
-                                        &String::from_utf8_lossy(&field),
-                                        version,
-                                    );
+                                    let datakey = KEY_ENCODER
+                                        .encode_txnkv_hash_data_key(&key, &field, version);
                                     fields_data_key.push(datakey);
                                 }
                                 // batch get
@@ -150,11 +147,8 @@ impl<'a> HashCommandCtx {
 
                             for kv in fvs_copy {
                                 let field: Vec<u8> = kv.0.into();
-                                let datakey = KEY_ENCODER.encode_txnkv_hash_data_key(
-                                    &key,
-                                    &String::from_utf8_lossy(&field),
-                                    version,
-                                );
+                                let datakey =
+                                    KEY_ENCODER.encode_txnkv_hash_data_key(&key, &field, version);
                                 txn.put(datakey, kv.1).await?;
                             }
 
@@ -185,7 +179,12 @@ impl<'a> HashCommandCtx {
                             drop(txn);
                             let version = get_version_for_new(&key, txn_rc.clone()).await?;
 
-                            debug!(LOGGER, "hset new key {} with version: {}", key, version);
+                            debug!(
+                                LOGGER,
+                                "hset new key {} with version: {}",
+                                String::from_utf8_lossy(&key),
+                                version
+                            );
 
                             txn = txn_rc.lock().await;
 
@@ -194,22 +193,16 @@ impl<'a> HashCommandCtx {
                             let mut fields_data_key = vec![];
                             for kv in fvs_copy.clone() {
                                 let field: Vec<u8> = kv.0.into();
-                                let datakey = KEY_ENCODER.encode_txnkv_hash_data_key(
-                                    &key,
-                                    &String::from_utf8_lossy(&field),
-                                    version,
-                                );
+                                let datakey =
+                                    KEY_ENCODER.encode_txnkv_hash_data_key(&key, &field, version);
                                 fields_data_key.push(datakey);
                             }
                             let real_fields_count = count_unique_keys(&fields_data_key);
 
                             for kv in fvs_copy {
                                 let field: Vec<u8> = kv.0.into();
-                                let datakey = KEY_ENCODER.encode_txnkv_hash_data_key(
-                                    &key,
-                                    &String::from_utf8_lossy(&field),
-                                    version,
-                                );
+                                let datakey =
+                                    KEY_ENCODER.encode_txnkv_hash_data_key(&key, &field, version);
                                 txn.put(datakey, kv.1).await?;
                             }
 
@@ -233,6 +226,9 @@ impl<'a> HashCommandCtx {
             .await;
         match resp {
             Ok(num) => {
+                if !is_nx || num > 0 {
+                    notify_keyspace_event(NOTIFY_HASH, "hset", ukey);
+                }
                 if is_hmset {
                     Ok(resp_ok())
                 } else {
@@ -243,7 +239,7 @@ impl<'a> HashCommandCtx {
         }
     }
 
-    pub async fn do_async_txnkv_hget(mut self, key: &str, field: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_hget(mut self, key: &[u8], field: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
         let field = field.to_owned();
@@ -267,7 +263,12 @@ impl<'a> HashCommandCtx {
                             let (ttl, version, _meta_size) =
                                 KeyDecoder::decode_key_meta(&meta_value);
 
-                            debug!(LOGGER, "hget key {} with version: {}", key, version);
+                            debug!(
+                                LOGGER,
+                                "hget key {} with version: {}",
+                                String::from_utf8_lossy(&key),
+                                version
+                            );
 
                             if key_is_expired(ttl) {
                                 drop(txn);
@@ -292,7 +293,7 @@ impl<'a> HashCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_hstrlen(mut self, key: &str, field: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_hstrlen(mut self, key: &[u8], field: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
         let field = field.to_owned();
@@ -340,7 +341,7 @@ impl<'a> HashCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_hexists(mut self, key: &str, field: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_hexists(mut self, key: &[u8], field: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
         let field = field.to_owned();
@@ -391,8 +392,8 @@ impl<'a> HashCommandCtx {
 
     pub async fn do_async_txnkv_hmget(
         mut self,
-        key: &str,
-        fields: &[String],
+        key: &[u8],
+        fields: &[Bytes],
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
@@ -462,7 +463,7 @@ impl<'a> HashCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_hlen(mut self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_hlen(mut self, key: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
         let key = key.to_owned();
@@ -503,7 +504,7 @@ impl<'a> HashCommandCtx {
 
     pub async fn do_async_txnkv_hgetall(
         mut self,
-        key: &str,
+        key: &[u8],
         with_field: bool,
         with_value: bool,
     ) -> AsyncResult<Frame> {
@@ -577,8 +578,9 @@ impl<'a> HashCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_hdel(mut self, key: &str, fields: &[String]) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_hdel(mut self, key: &[u8], fields: &[Bytes]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let fields = fields.to_vec();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -662,6 +664,11 @@ impl<'a> HashCommandCtx {
             })
             .await;
 
+        if let Ok(n) = resp {
+            if n > 0 {
+                notify_keyspace_event(NOTIFY_HASH, "hdel", ukey);
+            }
+        }
         match resp {
             Ok(n) => Ok(resp_int(n)),
             Err(e) => Ok(resp_err(e)),
@@ -670,11 +677,12 @@ impl<'a> HashCommandCtx {
 
     pub async fn do_async_txnkv_hincrby(
         self,
-        key: &str,
-        field: &str,
+        key: &[u8],
+        field: &[u8],
         step: i64,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let field = field.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -781,12 +789,15 @@ impl<'a> HashCommandCtx {
             .await;
 
         match resp {
-            Ok(n) => Ok(resp_int(n)),
+            Ok(n) => {
+                notify_keyspace_event(NOTIFY_HASH, "hincrby", ukey);
+                Ok(resp_int(n))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    pub async fn do_async_txnkv_hash_del(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnkv_hash_del(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -850,12 +861,13 @@ impl<'a> HashCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_hash_expire_if_needed(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnkv_hash_expire_if_needed(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
 
-        client
+        let resp = client
             .exec_in_txn(self.txn.clone(), |txn_arc| {
                 async move {
                     if self.txn.is_none() {
@@ -917,6 +929,10 @@ impl<'a> HashCommandCtx {
                 }
                 .boxed()
             })
-            .await
+            .await;
+        if let Ok(1) = resp {
+            notify_keyspace_event(NOTIFY_EXPIRED, "expired", ukey);
+        }
+        resp
     }
 }
@@ -0,0 +1,489 @@
+use super::errors::*;
+use super::get_txn_client;
+use super::string::{delete_string_chunks, read_string, write_string, StringCommandCtx};
+use super::{
+    encoding::{DataType, KeyDecoder},
+    errors::AsyncResult,
+};
+use crate::async_del_string_threshold_or_default;
+use crate::utils::{resp_err, resp_int, resp_ok};
+use crate::Frame;
+use ::futures::future::FutureExt;
+use bytes::Bytes;
+use std::convert::TryInto;
+use std::sync::Arc;
+use tikv_client::{Transaction, Value};
+use tokio::sync::Mutex;
+
+use crate::notify::{notify_keyspace_event, NOTIFY_STRING};
+
+// The layout below is the same as hyperloglog.c of redis, so the string
+// values can be moved between tidis and redis as they are.
+const HLL_P: u32 = 14;
+const HLL_Q: u32 = 64 - HLL_P;
+const HLL_REGISTERS: usize = 1 << HLL_P;
+const HLL_BITS: usize = 6;
+const HLL_REGISTER_MAX: u8 = (1 << HLL_BITS) - 1;
+const HLL_HDR_SIZE: usize = 16;
+const HLL_DENSE_SIZE: usize = HLL_HDR_SIZE + HLL_REGISTERS * HLL_BITS / 8;
+const HLL_DENSE: u8 = 0;
+const HLL_SPARSE: u8 = 1;
+const HLL_MAGIC: &[u8] = b"HYLL";
+const HLL_ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+const HLL_HASH_SEED: u64 = 0xadc8_3b19;
+
+const HLL_SPARSE_XZERO_BIT: u8 = 0x40;
+const HLL_SPARSE_VAL_BIT: u8 = 0x80;
+const HLL_SPARSE_VAL_MAX_VALUE: u8 = 32;
+const HLL_SPARSE_VAL_MAX_LEN: usize = 4;
+const HLL_SPARSE_ZERO_MAX_LEN: usize = 64;
+const HLL_SPARSE_XZERO_MAX_LEN: usize = 16384;
+/// Sparse HLLs growing beyond this size are promoted to dense, same as the
+/// default hll-sparse-max-bytes of redis.
+const HLL_SPARSE_MAX_BYTES: usize = 3000;
+
+#[derive(Clone)]
+pub struct HyperLogLogCommandCtx {
+    txn: Option<Arc<Mutex<Transaction>>>,
+}
+
+impl HyperLogLogCommandCtx {
+    pub fn new(txn: Option<Arc<Mutex<Transaction>>>) -> Self {
+        HyperLogLogCommandCtx { txn }
+    }
+
+    pub async fn do_async_txnkv_pfadd(
+        mut self,
+        key: &[u8],
+        elements: &[Bytes],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ukey = key;
+        let key = key.to_owned();
+        let elements = elements.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let (meta_value, mut hll) = match self.txnkv_get_hll(&txn_rc, &key).await? {
+                        Some((meta_value, hll)) => (Some(meta_value), hll),
+                        None => (None, Hll::new()),
+                    };
+
+                    let mut updated = meta_value.is_none();
+                    for element in &elements {
+                        updated |= hll.add(element);
+                    }
+                    if updated {
+                        put_hll(&mut *txn_rc.lock().await, &key, meta_value, &hll).await?;
+                    }
+                    Ok(updated)
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(updated) => {
+                if updated {
+                    notify_keyspace_event(NOTIFY_STRING, "pfadd", ukey);
+                }
+                Ok(resp_int(updated as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_pfcount(mut self, keys: &[Bytes]) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+
+        // all the keys are read from one snapshot
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if keys.len() == 1 {
+                        return match self.txnkv_get_hll(&txn_rc, &keys[0]).await? {
+                            Some((_, hll)) => Ok(hll.count()),
+                            None => Ok(0),
+                        };
+                    }
+
+                    let mut merged = Hll::new();
+                    for key in &keys {
+                        if let Some((_, hll)) = self.txnkv_get_hll(&txn_rc, key).await? {
+                            merged.merge(&hll);
+                        }
+                    }
+                    Ok(merged.count())
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(count) => Ok(resp_int(count as i64)),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_pfmerge(
+        mut self,
+        dest: &[u8],
+        keys: &[Bytes],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let dest = dest.to_owned();
+        let udest = dest.clone();
+        let keys = keys.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    // the destination is one of the sources if it exists
+                    let (meta_value, mut merged) = match self.txnkv_get_hll(&txn_rc, &dest).await? {
+                        Some((meta_value, hll)) => (Some(meta_value), hll),
+                        None => (None, Hll::new()),
+                    };
+                    for key in &keys {
+                        if let Some((_, hll)) = self.txnkv_get_hll(&txn_rc, key).await? {
+                            merged.merge(&hll);
+                        }
+                    }
+                    put_hll(&mut *txn_rc.lock().await, &dest, meta_value, &merged).await
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(_) => {
+                notify_keyspace_event(NOTIFY_STRING, "pfadd", &udest);
+                Ok(resp_ok())
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Read the HLL stored at `key` with its meta value, an error is returned
+    /// if the key holds something else.
+    async fn txnkv_get_hll(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        key: &[u8],
+    ) -> AsyncResult<Option<(Value, Hll)>> {
+        let meta_value = StringCommandCtx::new(Some(txn_rc.clone()))
+            .txnkv_get_live_value(txn_rc, key)
+            .await?;
+        let meta_value = match meta_value {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::String) {
+            return Err(REDIS_WRONG_TYPE_ERR);
+        }
+        let data = read_string(&mut *txn_rc.lock().await, key, &meta_value).await?;
+        let hll = Hll::decode(&data)?;
+        Ok(Some((meta_value, hll)))
+    }
+}
+
+/// Store the HLL as the string value of `key`, keeping the ttl of the old value.
+async fn put_hll(
+    txn: &mut Transaction,
+    key: &[u8],
+    meta_value: Option<Value>,
+    hll: &Hll,
+) -> AsyncResult<()> {
+    let mut ttl = 0;
+    if let Some(meta_value) = meta_value {
+        ttl = KeyDecoder::decode_key_ttl(&meta_value);
+        delete_string_chunks(
+            txn,
+            key,
+            &meta_value,
+            async_del_string_threshold_or_default(),
+        )
+        .await?;
+    }
+    write_string(txn, key, &hll.encode(), ttl).await
+}
+
+/// A HyperLogLog with its registers unpacked, one byte per register.
+struct Hll {
+    registers: Vec<u8>,
+    dense: bool,
+    /// the cardinality cached in the header, if still valid
+    cached: Option<u64>,
+}
+
+impl Hll {
+    fn new() -> Hll {
+        Hll {
+            registers: vec![0; HLL_REGISTERS],
+            dense: false,
+            cached: None,
+        }
+    }
+
+    fn decode(data: &[u8]) -> AsyncResult<Hll> {
+        if data.len() < HLL_HDR_SIZE || &data[..4] != HLL_MAGIC {
+            return Err(REDIS_INVALID_HLL_ERR);
+        }
+        let cached = match data[15] & 0x80 {
+            0 => Some(u64::from_le_bytes(data[8..16].try_into().unwrap())),
+            _ => None,
+        };
+        let payload = &data[HLL_HDR_SIZE..];
+        let mut registers = vec![0; HLL_REGISTERS];
+
+        match data[4] {
+            HLL_DENSE => {
+                if data.len() != HLL_DENSE_SIZE {
+                    return Err(REDIS_INVALID_HLL_ERR);
+                }
+                for (idx, register) in registers.iter_mut().enumerate() {
+                    *register = dense_get_register(payload, idx);
+                }
+            }
+            HLL_SPARSE => {
+                let mut idx = 0;
+                let mut pos = 0;
+                while pos < payload.len() {
+                    let opcode = payload[pos];
+                    if opcode & HLL_SPARSE_VAL_BIT != 0 {
+                        let value = ((opcode >> 2) & 0x1f) + 1;
+                        let len = (opcode & 0x3) as usize + 1;
+                        if idx + len > HLL_REGISTERS {
+                            return Err(REDIS_CORRUPTED_HLL_ERR);
+                        }
+                        registers[idx..idx + len].fill(value);
+                        idx += len;
+                        pos += 1;
+                    } else if opcode & HLL_SPARSE_XZERO_BIT != 0 {
+                        if pos + 1 >= payload.len() {
+                            return Err(REDIS_CORRUPTED_HLL_ERR);
+                        }
+                        idx += ((((opcode & 0x3f) as usize) << 8) | payload[pos + 1] as usize) + 1;
+                        pos += 2;
+                    } else {
+                        idx += (opcode & 0x3f) as usize + 1;
+                        pos += 1;
+                    }
+                }
+                if idx != HLL_REGISTERS {
+                    return Err(REDIS_CORRUPTED_HLL_ERR);
+                }
+            }
+            _ => return Err(REDIS_INVALID_HLL_ERR),
+        }
+
+        Ok(Hll {
+            registers,
+            dense: data[4] == HLL_DENSE,
+            cached,
+        })
+    }
+
+    /// Encode the HLL with an invalidated cached cardinality, a sparse HLL
+    /// is promoted to dense once it no longer fits the sparse encoding.
+    fn encode(&self) -> Vec<u8> {
+        if !self.dense {
+            if let Some(data) = self.encode_sparse() {
+                return data;
+            }
+        }
+        let mut data = hll_header(HLL_DENSE);
+        data.resize(HLL_DENSE_SIZE, 0);
+        for (idx, register) in self.registers.iter().enumerate() {
+            dense_set_register(&mut data[HLL_HDR_SIZE..], idx, *register);
+        }
+        data
+    }
+
+    fn encode_sparse(&self) -> Option<Vec<u8>> {
+        let mut data = hll_header(HLL_SPARSE);
+        let mut idx = 0;
+        while idx < HLL_REGISTERS {
+            let value = self.registers[idx];
+            let run = self.registers[idx..]
+                .iter()
+                .take_while(|r| **r == value)
+                .count();
+            let mut left = run;
+            if value == 0 {
+                while left > HLL_SPARSE_ZERO_MAX_LEN {
+                    let len = left.min(HLL_SPARSE_XZERO_MAX_LEN);
+                    data.push(HLL_SPARSE_XZERO_BIT | ((len - 1) >> 8) as u8);
+                    data.push(((len - 1) & 0xff) as u8);
+                    left -= len;
+                }
+                if left > 0 {
+                    data.push((left - 1) as u8);
+                }
+            } else {
+                if value > HLL_SPARSE_VAL_MAX_VALUE {
+                    return None;
+                }
+                while left > 0 {
+                    let len = left.min(HLL_SPARSE_VAL_MAX_LEN);
+                    data.push(HLL_SPARSE_VAL_BIT | ((value - 1) << 2) | (len - 1) as u8);
+                    left -= len;
+                }
+            }
+            if data.len() > HLL_SPARSE_MAX_BYTES {
+                return None;
+            }
+            idx += run;
+        }
+        Some(data)
+    }
+
+    /// Add an element, returns true if any register is updated.
+    fn add(&mut self, element: &[u8]) -> bool {
+        let hash = murmur_hash64a(element, HLL_HASH_SEED);
+        let idx = (hash & (HLL_REGISTERS as u64 - 1)) as usize;
+        // the bit at HLL_Q ends the count of zeros
+        let count = ((hash >> HLL_P) | (1 << HLL_Q)).trailing_zeros() as u8 + 1;
+        if count > self.registers[idx] {
+            self.registers[idx] = count;
+            self.cached = None;
+            return true;
+        }
+        false
+    }
+
+    /// Merge the registers of `other`, the result is dense if any of them is.
+    fn merge(&mut self, other: &Hll) {
+        for (register, other) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(*other);
+        }
+        self.dense |= other.dense;
+        self.cached = None;
+    }
+
+    /// Estimate the cardinality with the improved estimator of Otmar Ertl,
+    /// same as redis.
+    fn count(&self) -> u64 {
+        if let Some(cached) = self.cached {
+            return cached;
+        }
+        let mut histogram = [0u32; 64];
+        for register in &self.registers {
+            histogram[(*register).min(HLL_REGISTER_MAX) as usize] += 1;
+        }
+
+        let m = HLL_REGISTERS as f64;
+        let mut z = m * hll_tau((m - histogram[HLL_Q as usize + 1] as f64) / m);
+        for j in (1..=HLL_Q as usize).rev() {
+            z += histogram[j] as f64;
+            z *= 0.5;
+        }
+        z += m * hll_sigma(histogram[0] as f64 / m);
+        (HLL_ALPHA_INF * m * m / z).round() as u64
+    }
+}
+
+fn hll_header(encoding: u8) -> Vec<u8> {
+    let mut header = Vec::with_capacity(HLL_DENSE_SIZE);
+    header.extend_from_slice(HLL_MAGIC);
+    header.push(encoding);
+    header.extend_from_slice(&[0; 10]);
+    // the most significant bit of the cardinality marks it as invalid
+    header.push(0x80);
+    header
+}
+
+fn dense_get_register(registers: &[u8], idx: usize) -> u8 {
+    let byte = idx * HLL_BITS / 8;
+    let fb = idx * HLL_BITS % 8;
+    let b0 = registers[byte] as u16;
+    let b1 = registers.get(byte + 1).copied().unwrap_or(0) as u16;
+    (((b0 >> fb) | (b1 << (8 - fb))) & HLL_REGISTER_MAX as u16) as u8
+}
+
+fn dense_set_register(registers: &mut [u8], idx: usize, value: u8) {
+    let byte = idx * HLL_BITS / 8;
+    let fb = idx * HLL_BITS % 8;
+    let value = value as u16;
+    let mask = HLL_REGISTER_MAX as u16;
+    registers[byte] &= !((mask << fb) as u8);
+    registers[byte] |= (value << fb) as u8;
+    if let Some(next) = registers.get_mut(byte + 1) {
+        *next &= !((mask >> (8 - fb)) as u8);
+        *next |= (value >> (8 - fb)) as u8;
+    }
+}
+
+fn hll_sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let z_prime = z;
+        z += x * y;
+        y += y;
+        if z_prime == z {
+            return z;
+        }
+    }
+}
+
+fn hll_tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let z_prime = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if z_prime == z {
+            return z / 3.0;
+        }
+    }
+}
+
+/// MurmurHash2 with 64-bit output, the hash function of redis HLLs.
+fn murmur_hash64a(data: &[u8], seed: u64) -> u64 {
+    const M: u64 = 0xc6a4_a793_5bd1_e995;
+    const R: u32 = 47;
+
+    let mut h = seed ^ (data.len() as u64).wrapping_mul(M);
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, b) in tail.iter().enumerate() {
+            h ^= (*b as u64) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^= h >> R;
+    h
+}
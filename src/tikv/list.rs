@@ -7,21 +7,67 @@ use super::{
     errors::AsyncResult,
 };
 use crate::async_del_list_threshold_or_default;
-use crate::cmd_linsert_length_limit_or_default;
-use crate::cmd_lrem_length_limit_or_default;
 use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
-use crate::utils::{resp_array, resp_bulk, resp_err, resp_int, resp_nil, resp_ok};
+use crate::notify::{notify_keyspace_event, signal_key_ready, NOTIFY_EXPIRED, NOTIFY_LIST};
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_int, resp_nil, resp_nil_array, resp_ok};
 use crate::{utils::key_is_expired, Frame};
 use bytes::Bytes;
-use core::ops::RangeFrom;
+use core::ops::{Bound, Range, RangeFrom};
 use futures::future::FutureExt;
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::sync::Arc;
-use tikv_client::{BoundRange, Key, Transaction};
+use tikv_client::{BoundRange, Key, KvPair, Transaction};
 use tokio::sync::Mutex;
 
 const INIT_INDEX: u64 = 1 << 32;
 
+/// Max number of elements scanned in one batch when searching the list.
+const LIST_SCAN_BATCH_SIZE: u64 = 256;
+
+/// Lists up to this length are renumbered by LINSERT and LREM to keep them
+/// dense, longer lists become sparse instead of moving all the elements.
+const LIST_REWRITE_MAX_LEN: u64 = 256;
+
+/// The elements of a sparse list are counted by buckets of this many
+/// indices, to locate the position of an element.
+const LIST_INDEX_BUCKET_BITS: u32 = 10;
+
+/// The elements of a sparse list are also counted by groups of this many
+/// buckets, so only the buckets of one group are scanned to locate a position.
+const LIST_INDEX_GROUP_BITS: u32 = 10;
+
+/// Max length of the fraction of an inserted element, the elements sharing
+/// the index are spread out again when it is exceeded.
+const LIST_MAX_FRACTION_LEN: usize = 16;
+
+fn push_event(op_left: bool) -> &'static str {
+    if op_left {
+        "lpush"
+    } else {
+        "rpush"
+    }
+}
+
+fn pop_event(op_left: bool) -> &'static str {
+    if op_left {
+        "lpop"
+    } else {
+        "rpop"
+    }
+}
+
+/// Notify the events of the lists modified by a committed txn, the clients
+/// blocked on the lists pushed to are woken up.
+fn notify_list_events(events: &[(&str, &[u8])]) {
+    for (event, key) in events {
+        notify_keyspace_event(NOTIFY_LIST, event, key);
+        if event.ends_with("push") {
+            signal_key_ready(key);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ListCommandCtx {
     txn: Option<Arc<Mutex<Transaction>>>,
@@ -33,239 +79,450 @@ impl<'a> ListCommandCtx {
     }
 
     pub async fn do_async_txnkv_push(
-        mut self,
-        key: &str,
+        self,
+        key: &[u8],
         values: &Vec<Bytes>,
         op_left: bool,
+        exists: bool,
     ) -> AsyncResult<Frame> {
+        match self
+            .do_async_txnkv_push_values(key, values, op_left, exists)
+            .await
+        {
+            Ok(n) => Ok(resp_int(n as i64)),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Push the values to the list, returns the length of the list after the
+    /// push. Nothing is pushed if `exists` is set and the list does not exist.
+    pub async fn do_async_txnkv_push_values(
+        mut self,
+        key: &[u8],
+        values: &Vec<Bytes>,
+        op_left: bool,
+        exists: bool,
+    ) -> AsyncResult<u64> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let values = values.to_owned();
 
-        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
-
         let resp = client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
                         self.txn = Some(txn_rc.clone());
                     }
-                    let mut txn = txn_rc.lock().await;
-                    match txn.get(meta_key.clone()).await? {
-                        Some(meta_value) => {
-                            // check key type and ttl
-                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
-                                return Err(REDIS_WRONG_TYPE_ERR);
-                            }
-
-                            let (ttl, mut version, mut left, mut right) =
-                                KeyDecoder::decode_key_list_meta(&meta_value);
-                            if key_is_expired(ttl) {
-                                drop(txn);
-                                self.clone()
-                                    .do_async_txnkv_list_expire_if_needed(&key)
-                                    .await?;
-                                left = INIT_INDEX;
-                                right = INIT_INDEX;
-                                version = get_version_for_new(&key, txn_rc.clone()).await?;
-                                txn = txn_rc.lock().await;
-                            }
-
-                            let mut idx: u64;
-                            for value in values {
-                                if op_left {
-                                    left -= 1;
-                                    idx = left;
-                                } else {
-                                    idx = right;
-                                    right += 1;
-                                }
+                    self.txnkv_push_values(txn_rc, &key, values, op_left, exists)
+                        .await
+                }
+                .boxed()
+            })
+            .await;
 
-                                let data_key =
-                                    KEY_ENCODER.encode_txnkv_list_data_key(&key, idx, version);
-                                txn.put(data_key, value.to_vec()).await?;
-                            }
+        if matches!(resp, Ok(n) if n > 0) {
+            notify_list_events(&[(push_event(op_left), ukey)]);
+        }
+        resp
+    }
 
-                            // update meta key
-                            let new_meta_value =
-                                KEY_ENCODER.encode_txnkv_list_meta_value(ttl, version, left, right);
-                            txn.put(meta_key, new_meta_value).await?;
+    /// The body of `do_async_txnkv_push_values` in `txn_rc`, the events are
+    /// left to the caller, to be notified once the txn is committed.
+    async fn txnkv_push_values(
+        self,
+        txn_rc: Arc<Mutex<Transaction>>,
+        key: &[u8],
+        values: Vec<Bytes>,
+        op_left: bool,
+        exists: bool,
+    ) -> AsyncResult<u64> {
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
 
-                            Ok(right - left)
-                        }
-                        None => {
-                            // get next version available for new key
-                            drop(txn);
-                            let version = get_version_for_new(&key, txn_rc.clone()).await?;
-                            txn = txn_rc.lock().await;
+        let mut txn = txn_rc.lock().await;
+        match txn.get(meta_key.clone()).await? {
+            Some(meta_value) => {
+                // check key type and ttl
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
 
-                            let mut left = INIT_INDEX;
-                            let mut right = INIT_INDEX;
-                            let mut idx: u64;
+                let (ttl, mut version, mut left, mut right, mut sparse_len) =
+                    KeyDecoder::decode_key_list_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    drop(txn);
+                    self.clone()
+                        .do_async_txnkv_list_expire_if_needed(key)
+                        .await?;
+                    if exists {
+                        return Ok(0);
+                    }
+                    left = INIT_INDEX;
+                    right = INIT_INDEX;
+                    sparse_len = None;
+                    version = get_version_for_new(key, txn_rc.clone()).await?;
+                    txn = txn_rc.lock().await;
+                }
 
-                            for value in values {
-                                if op_left {
-                                    left -= 1;
-                                    idx = left
-                                } else {
-                                    idx = right;
-                                    right += 1;
-                                }
+                let mut idx: u64;
+                let len = sparse_len.unwrap_or(right - left) + values.len() as u64;
+                let mut indices = Vec::with_capacity(values.len());
+                for value in values {
+                    if op_left {
+                        left -= 1;
+                        idx = left;
+                    } else {
+                        idx = right;
+                        right += 1;
+                    }
 
-                                // add data key
-                                let data_key =
-                                    KEY_ENCODER.encode_txnkv_list_data_key(&key, idx, version);
-                                txn.put(data_key, value.to_vec()).await?;
-                            }
+                    let data_key = KEY_ENCODER.encode_txnkv_list_data_key(key, idx, version);
+                    txn.put(data_key, value.to_vec()).await?;
+                    indices.push(idx);
+                }
+                if sparse_len.is_some() {
+                    update_list_index(&mut txn, key, version, indices, true).await?;
+                }
 
-                            // add meta key
-                            let meta_value =
-                                KEY_ENCODER.encode_txnkv_list_meta_value(0, version, left, right);
-                            txn.put(meta_key, meta_value).await?;
+                // update meta key
+                let new_meta_value = KEY_ENCODER.encode_txnkv_list_meta_value(
+                    ttl,
+                    version,
+                    left,
+                    right,
+                    sparse_len.map(|_| len),
+                );
+                txn.put(meta_key, new_meta_value).await?;
+
+                Ok(len)
+            }
+            None => {
+                if exists {
+                    return Ok(0);
+                }
 
-                            Ok(right - left)
-                        }
+                // get next version available for new key
+                drop(txn);
+                let version = get_version_for_new(key, txn_rc.clone()).await?;
+                txn = txn_rc.lock().await;
+
+                let mut left = INIT_INDEX;
+                let mut right = INIT_INDEX;
+                let mut idx: u64;
+
+                for value in values {
+                    if op_left {
+                        left -= 1;
+                        idx = left
+                    } else {
+                        idx = right;
+                        right += 1;
                     }
+
+                    // add data key
+                    let data_key = KEY_ENCODER.encode_txnkv_list_data_key(key, idx, version);
+                    txn.put(data_key, value.to_vec()).await?;
                 }
-                .boxed()
-            })
-            .await;
 
-        match resp {
-            Ok(n) => Ok(resp_int(n as i64)),
-            Err(e) => Ok(resp_err(e)),
+                // add meta key
+                let meta_value =
+                    KEY_ENCODER.encode_txnkv_list_meta_value(0, version, left, right, None);
+                txn.put(meta_key, meta_value).await?;
+
+                Ok(right - left)
+            }
         }
     }
 
+    /// Pop a value, or an array of at most `count` values if `count` is set.
     pub async fn do_async_txnkv_pop(
+        self,
+        key: &[u8],
+        op_left: bool,
+        count: Option<i64>,
+    ) -> AsyncResult<Frame> {
+        if count == Some(0) {
+            return Ok(resp_array(vec![]));
+        }
+
+        match self
+            .do_async_txnkv_pop_values(key, op_left, count.unwrap_or(1))
+            .await
+        {
+            Ok(mut values) => match count {
+                None => Ok(values.pop().unwrap_or_else(resp_nil)),
+                Some(_) if values.is_empty() => Ok(resp_nil_array()),
+                Some(_) => Ok(resp_array(values)),
+            },
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Pop at most `count` values from the list, returns no values if the
+    /// list does not exist.
+    pub async fn do_async_txnkv_pop_values(
         mut self,
-        key: &str,
+        key: &[u8],
         op_left: bool,
         count: i64,
-    ) -> AsyncResult<Frame> {
+    ) -> AsyncResult<Vec<Frame>> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
 
-        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
-
         let resp = client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
                         self.txn = Some(txn_rc.clone());
                     }
-                    let mut values = Vec::new();
-                    let mut txn = txn_rc.lock().await;
-                    match txn.get(meta_key.clone()).await? {
-                        Some(meta_value) => {
-                            // check key type and ttl
-                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
-                                return Err(REDIS_WRONG_TYPE_ERR);
-                            }
+                    self.txnkv_pop_values(txn_rc, &key, op_left, count).await
+                }
+                .boxed()
+            })
+            .await;
 
-                            let (ttl, version, mut left, mut right) =
-                                KeyDecoder::decode_key_list_meta(&meta_value);
-                            if key_is_expired(ttl) {
-                                drop(txn);
-                                self.clone()
-                                    .do_async_txnkv_list_expire_if_needed(&key)
-                                    .await?;
-                                return Ok(values);
-                            }
+        if matches!(&resp, Ok(values) if !values.is_empty()) {
+            notify_list_events(&[(pop_event(op_left), ukey)]);
+        }
+        resp
+    }
 
-                            let mut idx: u64;
+    /// The body of `do_async_txnkv_pop_values` in `txn_rc`, the events are
+    /// left to the caller, to be notified once the txn is committed.
+    async fn txnkv_pop_values(
+        self,
+        txn_rc: Arc<Mutex<Transaction>>,
+        key: &[u8],
+        op_left: bool,
+        count: i64,
+    ) -> AsyncResult<Vec<Frame>> {
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
 
-                            if count == 1 {
-                                if op_left {
-                                    idx = left;
-                                    left += 1;
-                                } else {
-                                    right -= 1;
-                                    idx = right;
-                                }
-                                let data_key =
-                                    KEY_ENCODER.encode_txnkv_list_data_key(&key, idx, version);
-                                // get data and delete
-                                let value = txn.get(data_key.clone()).await.unwrap().unwrap();
-                                values.push(resp_bulk(value));
+        let mut values = Vec::new();
+        let mut txn = txn_rc.lock().await;
+        match txn.get(meta_key.clone()).await? {
+            Some(meta_value) => {
+                // check key type and ttl
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
 
-                                txn.delete(data_key).await?;
+                let (ttl, version, mut left, mut right, sparse_len) =
+                    KeyDecoder::decode_key_list_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    drop(txn);
+                    self.clone()
+                        .do_async_txnkv_list_expire_if_needed(key)
+                        .await?;
+                    return Ok(values);
+                }
 
-                                if left == right {
-                                    // delete meta key
-                                    txn.delete(meta_key).await?;
-                                } else {
-                                    // update meta key
-                                    let new_meta_value = KEY_ENCODER
-                                        .encode_txnkv_list_meta_value(ttl, version, left, right);
-                                    txn.put(meta_key, new_meta_value).await?;
-                                }
-                                Ok(values)
-                            } else {
-                                let mut real_count = count as u64;
-                                if real_count > right - left {
-                                    real_count = right - left;
-                                }
+                // the elements at the ends of a sparse list are found by scan
+                if let Some(len) = sparse_len {
+                    let bound_range = KEY_ENCODER.encode_txnkv_list_data_key_range(key, version);
+                    let limit = scan_limit((count as u64).min(len));
+                    let kvs: Vec<KvPair> = if op_left {
+                        txn.scan(bound_range, limit).await?.collect()
+                    } else {
+                        txn.scan_reverse(bound_range, limit).await?.collect()
+                    };
+                    let len = len - kvs.len() as u64;
+                    let mut indices = Vec::with_capacity(kvs.len());
+                    for kv in kvs {
+                        values.push(resp_bulk(kv.1));
+                        indices.push(
+                            KeyDecoder::decode_key_list_position_from_datakey(key, kv.0.clone()).0,
+                        );
+                        txn.delete(kv.0).await?;
+                    }
+                    update_list_index(&mut txn, key, version, indices, false).await?;
 
-                                let mut data_keys = Vec::with_capacity(real_count as usize);
-                                for _ in 0..real_count {
-                                    if op_left {
-                                        idx = left;
-                                        left += 1;
-                                    } else {
-                                        idx = right - 1;
-                                        right -= 1;
-                                    }
-                                    data_keys.push(
-                                        KEY_ENCODER.encode_txnkv_list_data_key(&key, idx, version),
-                                    );
-                                }
-                                for pair in txn.batch_get(data_keys).await? {
-                                    values.push(resp_bulk(pair.1));
-                                    txn.delete(pair.0).await?;
-                                }
+                    if len == 0 {
+                        txn.delete(meta_key).await?;
+                    } else {
+                        put_sparse_list_meta(&mut txn, key, ttl, version, left, right, len).await?;
+                    }
+                    return Ok(values);
+                }
 
-                                if left == right {
-                                    // all elements popped, just delete meta key
-                                    txn.delete(meta_key.clone()).await?;
-                                } else {
-                                    // update meta key
-                                    let new_meta_value = KEY_ENCODER
-                                        .encode_txnkv_list_meta_value(ttl, version, left, right);
-                                    txn.put(meta_key.clone(), new_meta_value).await?;
-                                }
+                let mut idx: u64;
 
-                                Ok(values)
-                            }
+                if count == 1 {
+                    if op_left {
+                        idx = left;
+                        left += 1;
+                    } else {
+                        right -= 1;
+                        idx = right;
+                    }
+                    let data_key = KEY_ENCODER.encode_txnkv_list_data_key(key, idx, version);
+                    // get data and delete
+                    let value = txn.get(data_key.clone()).await.unwrap().unwrap();
+                    values.push(resp_bulk(value));
+
+                    txn.delete(data_key).await?;
+
+                    if left == right {
+                        // delete meta key
+                        txn.delete(meta_key).await?;
+                    } else {
+                        // update meta key
+                        let new_meta_value = KEY_ENCODER
+                            .encode_txnkv_list_meta_value(ttl, version, left, right, None);
+                        txn.put(meta_key, new_meta_value).await?;
+                    }
+                    Ok(values)
+                } else {
+                    let mut real_count = count as u64;
+                    if real_count > right - left {
+                        real_count = right - left;
+                    }
+
+                    let mut data_keys = Vec::with_capacity(real_count as usize);
+                    for _ in 0..real_count {
+                        if op_left {
+                            idx = left;
+                            left += 1;
+                        } else {
+                            idx = right - 1;
+                            right -= 1;
                         }
-                        None => Ok(values),
+                        data_keys.push(KEY_ENCODER.encode_txnkv_list_data_key(key, idx, version));
                     }
+                    for pair in txn.batch_get(data_keys).await? {
+                        values.push(resp_bulk(pair.1));
+                        txn.delete(pair.0).await?;
+                    }
+
+                    if left == right {
+                        // all elements popped, just delete meta key
+                        txn.delete(meta_key.clone()).await?;
+                    } else {
+                        // update meta key
+                        let new_meta_value = KEY_ENCODER
+                            .encode_txnkv_list_meta_value(ttl, version, left, right, None);
+                        txn.put(meta_key.clone(), new_meta_value).await?;
+                    }
+
+                    Ok(values)
+                }
+            }
+            None => Ok(values),
+        }
+    }
+
+    /// Pop at most `count` values from the first non-empty list of the keys,
+    /// returns `None` if all the lists are empty.
+    pub async fn do_async_txnkv_mpop(
+        mut self,
+        keys: &[Bytes],
+        op_left: bool,
+        count: i64,
+    ) -> AsyncResult<Option<(Bytes, Vec<Frame>)>> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    for key in keys {
+                        let values = self
+                            .clone()
+                            .txnkv_pop_values(txn_rc.clone(), &key, op_left, count)
+                            .await?;
+                        if !values.is_empty() {
+                            return Ok(Some((key, values)));
+                        }
+                    }
+                    Ok(None)
                 }
                 .boxed()
             })
             .await;
 
-        match resp {
-            Ok(values) => {
-                if values.is_empty() {
-                    Ok(resp_nil())
-                } else if values.len() == 1 {
-                    Ok(values[0].clone())
-                } else {
-                    Ok(resp_array(values))
+        if let Ok(Some((key, _))) = &resp {
+            notify_list_events(&[(pop_event(op_left), key)]);
+        }
+        resp
+    }
+
+    /// Pop a value from the `src` list and push it to the `dst` list in one
+    /// transaction, returns `None` if the `src` list is empty.
+    pub async fn do_async_txnkv_lmove(
+        mut self,
+        src: &[u8],
+        dst: &[u8],
+        from_left: bool,
+        to_left: bool,
+    ) -> AsyncResult<Option<Frame>> {
+        let mut client = get_txn_client()?;
+        let (usrc, udst) = (src, dst);
+        let src = src.to_owned();
+        let dst = dst.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    // check the types before popping, the txn of MULTI or a
+                    // script is committed after an error, which would lose
+                    // the popped value if pushing it to `dst` failed
+                    let mut txn = txn_rc.lock().await;
+                    let src_meta_key = KEY_ENCODER.encode_txnkv_meta_key(&src);
+                    match txn.get(src_meta_key).await? {
+                        Some(meta_value) => {
+                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+                        }
+                        None => return Ok(None),
+                    }
+                    let dst_meta_key = KEY_ENCODER.encode_txnkv_meta_key(&dst);
+                    if let Some(meta_value) = txn.get(dst_meta_key).await? {
+                        if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                            return Err(REDIS_WRONG_TYPE_ERR);
+                        }
+                    }
+                    drop(txn);
+
+                    let mut values = self
+                        .clone()
+                        .txnkv_pop_values(txn_rc.clone(), &src, from_left, 1)
+                        .await?;
+                    let value = match values.pop() {
+                        Some(Frame::Bulk(value)) => value,
+                        _ => return Ok(None),
+                    };
+                    self.clone()
+                        .txnkv_push_values(txn_rc, &dst, vec![value.clone()], to_left, false)
+                        .await?;
+                    Ok(Some(resp_bulk(value.to_vec())))
                 }
-            }
-            Err(e) => Ok(resp_err(e)),
+                .boxed()
+            })
+            .await;
+
+        if matches!(resp, Ok(Some(_))) {
+            notify_list_events(&[(pop_event(from_left), usrc), (push_event(to_left), udst)]);
         }
+        resp
     }
 
     pub async fn do_async_txnkv_ltrim(
         mut self,
-        key: &str,
+        key: &[u8],
         mut start: i64,
         mut end: i64,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
 
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -284,18 +541,18 @@ impl<'a> ListCommandCtx {
                                 return Err(REDIS_WRONG_TYPE_ERR);
                             }
 
-                            let (ttl, version, mut left, mut right) =
+                            let (ttl, version, mut left, mut right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             if key_is_expired(ttl) {
                                 drop(txn);
                                 self.clone()
                                     .do_async_txnkv_list_expire_if_needed(&key)
                                     .await?;
-                                return Ok(());
+                                return Ok(false);
                             }
 
                             // convert start and end to positive
-                            let len = (right - left) as i64;
+                            let len = sparse_len.unwrap_or(right - left) as i64;
                             if start < 0 {
                                 start += len;
                             }
@@ -318,6 +575,57 @@ impl<'a> ListCommandCtx {
                                 end = len - 1;
                             }
 
+                            if sparse_len.is_some() {
+                                // drop the elements out of [start, end] from both ends
+                                let bound_range =
+                                    KEY_ENCODER.encode_txnkv_list_data_key_range(&key, version);
+                                let head = start as u64;
+                                let tail = (len - 1 - end) as u64;
+                                let mut data_keys: Vec<Key> = vec![];
+                                if head > 0 {
+                                    data_keys.extend(
+                                        txn.scan_keys(bound_range.clone(), scan_limit(head))
+                                            .await?,
+                                    );
+                                }
+                                if tail > 0 {
+                                    data_keys.extend(
+                                        txn.scan_keys_reverse(bound_range, scan_limit(tail))
+                                            .await?,
+                                    );
+                                }
+                                let mut indices = Vec::with_capacity(data_keys.len());
+                                for data_key in data_keys {
+                                    indices.push(
+                                        KeyDecoder::decode_key_list_position_from_datakey(
+                                            &key,
+                                            data_key.clone(),
+                                        )
+                                        .0,
+                                    );
+                                    txn.delete(data_key).await?;
+                                }
+
+                                if start > end {
+                                    delete_list_index(&mut txn, &key, version).await?;
+                                    txn.delete(meta_key).await?;
+                                } else {
+                                    update_list_index(&mut txn, &key, version, indices, false)
+                                        .await?;
+                                    put_sparse_list_meta(
+                                        &mut txn,
+                                        &key,
+                                        ttl,
+                                        version,
+                                        left,
+                                        right,
+                                        (end - start + 1) as u64,
+                                    )
+                                    .await?;
+                                }
+                                return Ok(true);
+                            }
+
                             // convert to relative position
                             start += left as i64;
                             end += left as i64;
@@ -351,12 +659,12 @@ impl<'a> ListCommandCtx {
                             } else {
                                 // update meta key
                                 let new_meta_value = KEY_ENCODER
-                                    .encode_txnkv_list_meta_value(ttl, version, left, right);
+                                    .encode_txnkv_list_meta_value(ttl, version, left, right, None);
                                 txn.put(meta_key, new_meta_value).await?;
                             }
-                            Ok(())
+                            Ok(true)
                         }
-                        None => Ok(()),
+                        None => Ok(false),
                     }
                 }
                 .boxed()
@@ -364,14 +672,19 @@ impl<'a> ListCommandCtx {
             .await;
 
         match resp {
-            Ok(_) => Ok(resp_ok()),
+            Ok(trimmed) => {
+                if trimmed {
+                    notify_keyspace_event(NOTIFY_LIST, "ltrim", ukey);
+                }
+                Ok(resp_ok())
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
     pub async fn do_async_txnkv_lrange(
         mut self,
-        key: &str,
+        key: &[u8],
         mut r_left: i64,
         mut r_right: i64,
     ) -> AsyncResult<Frame> {
@@ -393,7 +706,7 @@ impl<'a> ListCommandCtx {
                             if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
                                 return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
                             }
-                            let (ttl, version, left, right) =
+                            let (ttl, version, left, right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             if key_is_expired(ttl) {
                                 drop(txn);
@@ -403,7 +716,7 @@ impl<'a> ListCommandCtx {
                                 return Ok(resp_array(vec![]));
                             }
 
-                            let llen: i64 = (right - left) as i64;
+                            let llen: i64 = sparse_len.unwrap_or(right - left) as i64;
 
                             // convert negative index to positive index
                             if r_left < 0 {
@@ -412,28 +725,24 @@ impl<'a> ListCommandCtx {
                             if r_right < 0 {
                                 r_right += llen;
                             }
-                            if r_left > r_right || r_left > llen {
+                            let r_left = r_left.max(0);
+                            let r_right = r_right.min(llen - 1);
+                            if r_left > r_right {
                                 return Ok(resp_array(vec![]));
                             }
 
-                            let real_left = r_left + left as i64;
-                            let mut real_length = r_right - r_left + 1;
-                            if real_length > llen {
-                                real_length = llen;
-                            }
-
-                            let data_key_start = KEY_ENCODER.encode_txnkv_list_data_key(
+                            let kvs = scan_list_range(
+                                &mut txn,
                                 &key,
-                                real_left as u64,
                                 version,
-                            );
-                            let range: RangeFrom<Key> = data_key_start..;
-                            let from_range: BoundRange = range.into();
-                            let iter = txn
-                                .scan(from_range, real_length.try_into().unwrap())
-                                .await?;
-
-                            let resp = iter.map(|kv| resp_bulk(kv.1)).collect();
+                                left,
+                                sparse_len,
+                                r_left as u64,
+                                (r_right - r_left + 1) as u64,
+                            )
+                            .await?;
+
+                            let resp = kvs.into_iter().map(|kv| resp_bulk(kv.1)).collect();
                             Ok(resp_array(resp))
                         }
                         None => Ok(resp_array(vec![])),
@@ -444,7 +753,7 @@ impl<'a> ListCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_llen(mut self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_llen(mut self, key: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
 
@@ -464,7 +773,7 @@ impl<'a> ListCommandCtx {
                             if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
                                 return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
                             }
-                            let (ttl, _, left, right) =
+                            let (ttl, _, left, right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             if key_is_expired(ttl) {
                                 drop(txn);
@@ -474,7 +783,7 @@ impl<'a> ListCommandCtx {
                                 return Ok(resp_int(0));
                             }
 
-                            let llen: i64 = (right - left) as i64;
+                            let llen: i64 = sparse_len.unwrap_or(right - left) as i64;
                             Ok(resp_int(llen))
                         }
                         None => Ok(resp_int(0)),
@@ -485,7 +794,7 @@ impl<'a> ListCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_lindex(mut self, key: &str, mut idx: i64) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_lindex(mut self, key: &[u8], mut idx: i64) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
         let key = key.to_owned();
@@ -504,7 +813,7 @@ impl<'a> ListCommandCtx {
                             if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
                                 return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
                             }
-                            let (ttl, version, left, right) =
+                            let (ttl, version, left, right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             if key_is_expired(ttl) {
                                 drop(txn);
@@ -514,24 +823,22 @@ impl<'a> ListCommandCtx {
                                 return Ok(resp_nil());
                             }
 
-                            let len = right - left;
+                            let len = sparse_len.unwrap_or(right - left);
                             // try convert idx to positive if needed
                             if idx < 0 {
                                 idx += len as i64;
                             }
+                            if idx < 0 || idx >= len as i64 {
+                                return Ok(resp_nil());
+                            }
 
-                            let real_idx = left as i64 + idx;
-
-                            // get value from data key
-                            let data_key = KEY_ENCODER.encode_txnkv_list_data_key(
-                                &key,
-                                real_idx as u64,
-                                version,
-                            );
-                            if let Some(value) = txn.get(data_key).await? {
-                                Ok(resp_bulk(value))
-                            } else {
-                                Ok(resp_nil())
+                            let mut kvs = scan_list_range(
+                                &mut txn, &key, version, left, sparse_len, idx as u64, 1,
+                            )
+                            .await?;
+                            match kvs.pop() {
+                                Some(kv) => Ok(resp_bulk(kv.1)),
+                                None => Ok(resp_nil()),
                             }
                         }
                         None => Ok(resp_nil()),
@@ -542,13 +849,119 @@ impl<'a> ListCommandCtx {
             .await
     }
 
+    /// Find the positions of the element in the list, the list is scanned in
+    /// batches from the head, or from the tail if `rank` is negative. Skips
+    /// the first `|rank| - 1` matches and compares at most `maxlen` elements,
+    /// zero means the whole list.
+    pub async fn do_async_txnkv_lpos(
+        mut self,
+        key: &[u8],
+        element: &[u8],
+        rank: i64,
+        count: Option<u64>,
+        maxlen: u64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let element = element.to_owned();
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut positions = vec![];
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(meta_key).await? {
+                        Some(meta_value) => {
+                            // check key type and ttl
+                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+                            let (ttl, version, left, right, sparse_len) =
+                                KeyDecoder::decode_key_list_meta(&meta_value);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_list_expire_if_needed(&key)
+                                    .await?;
+                                return Ok(positions);
+                            }
+
+                            let llen = sparse_len.unwrap_or(right - left);
+                            let scan_len = if maxlen == 0 { llen } else { maxlen.min(llen) };
+                            // zero count means all the matches
+                            let limit = match count {
+                                None => 1,
+                                Some(0) => u64::MAX,
+                                Some(n) => n,
+                            };
+                            let mut skip = rank.unsigned_abs() - 1;
+                            let mut scanned = 0;
+                            let mut last = None;
+
+                            while scanned < scan_len && (positions.len() as u64) < limit {
+                                let batch = LIST_SCAN_BATCH_SIZE.min(scan_len - scanned);
+                                let kvs =
+                                    scan_list_batch(&mut txn, &key, version, last, rank < 0, batch)
+                                        .await?;
+                                if kvs.is_empty() {
+                                    break;
+                                }
+                                last = kvs.last().map(|kv| kv.0.clone());
+
+                                for kv in kvs {
+                                    let pos = if rank > 0 {
+                                        scanned
+                                    } else {
+                                        llen - 1 - scanned
+                                    };
+                                    scanned += 1;
+                                    if kv.1 != element {
+                                        continue;
+                                    }
+                                    if skip > 0 {
+                                        skip -= 1;
+                                        continue;
+                                    }
+                                    positions.push(pos as i64);
+                                    if positions.len() as u64 == limit {
+                                        break;
+                                    }
+                                }
+                            }
+
+                            Ok(positions)
+                        }
+                        None => Ok(positions),
+                    }
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(positions) => match count {
+                None => Ok(positions
+                    .first()
+                    .map_or_else(resp_nil, |pos| resp_int(*pos))),
+                Some(_) => Ok(resp_array(positions.into_iter().map(resp_int).collect())),
+            },
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
     pub async fn do_async_txnkv_lset(
         mut self,
-        key: &str,
+        key: &[u8],
         mut idx: i64,
         ele: &Bytes,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let ele = ele.to_owned();
 
@@ -566,7 +979,7 @@ impl<'a> ListCommandCtx {
                             if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
                                 return Err(REDIS_WRONG_TYPE_ERR);
                             }
-                            let (ttl, version, left, right) =
+                            let (ttl, version, left, right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             if key_is_expired(ttl) {
                                 drop(txn);
@@ -577,17 +990,31 @@ impl<'a> ListCommandCtx {
                             }
 
                             // convert idx to positive is needed
+                            let len = sparse_len.unwrap_or(right - left) as i64;
                             if idx < 0 {
-                                idx += (right - left) as i64;
+                                idx += len;
                             }
-
-                            let uidx = idx + left as i64;
-                            if idx < 0 || uidx < left as i64 || uidx > (right - 1) as i64 {
+                            if idx < 0 || idx >= len {
                                 return Err(REDIS_INDEX_OUT_OF_RANGE_ERR);
                             }
 
-                            let data_key =
-                                KEY_ENCODER.encode_txnkv_list_data_key(&key, uidx as u64, version);
+                            let data_key = match sparse_len {
+                                Some(_) => {
+                                    let mut kvs = scan_list_range(
+                                        &mut txn, &key, version, left, sparse_len, idx as u64, 1,
+                                    )
+                                    .await?;
+                                    match kvs.pop() {
+                                        Some(kv) => kv.0,
+                                        None => return Err(REDIS_INDEX_OUT_OF_RANGE_ERR),
+                                    }
+                                }
+                                None => KEY_ENCODER.encode_txnkv_list_data_key(
+                                    &key,
+                                    left + idx as u64,
+                                    version,
+                                ),
+                            };
                             // data keys exists, update it to new value
                             txn.put(data_key, ele.to_vec()).await?;
                             Ok(())
@@ -603,19 +1030,23 @@ impl<'a> ListCommandCtx {
             .await;
 
         match resp {
-            Ok(_) => Ok(resp_ok()),
+            Ok(_) => {
+                notify_keyspace_event(NOTIFY_LIST, "lset", ukey);
+                Ok(resp_ok())
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
     pub async fn do_async_txnkv_linsert(
         mut self,
-        key: &str,
+        key: &[u8],
         before_pivot: bool,
         pivot: &Bytes,
         element: &Bytes,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let pivot = pivot.to_owned();
         let element = element.to_owned();
@@ -634,7 +1065,7 @@ impl<'a> ListCommandCtx {
                             if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
                                 return Err(REDIS_WRONG_TYPE_ERR);
                             }
-                            let (ttl, version, mut left, mut right) =
+                            let (ttl, version, mut left, mut right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             if key_is_expired(ttl) {
                                 drop(txn);
@@ -644,109 +1075,160 @@ impl<'a> ListCommandCtx {
                                 return Ok(0);
                             }
 
-                            // check list length is not too long
-                            let limit_len = cmd_linsert_length_limit_or_default();
-                            if limit_len > 0 && right - left > limit_len as u64 {
-                                return Err(REDIS_LIST_TOO_LARGE_ERR);
-                            }
+                            let len = sparse_len.unwrap_or(right - left);
+
+                            // small lists are simply renumbered, which keeps them dense
+                            if len < LIST_REWRITE_MAX_LEN {
+                                let kvs = scan_list_range(
+                                    &mut txn, &key, version, left, sparse_len, 0, len,
+                                )
+                                .await?;
+                                let pos = match kvs.iter().position(|kv| kv.1 == pivot) {
+                                    Some(pos) if before_pivot => pos,
+                                    Some(pos) => pos + 1,
+                                    // no matched pivot, ignore
+                                    None => return Ok(-1),
+                                };
+
+                                let mut values: Vec<Vec<u8>> = vec![];
+                                for kv in kvs {
+                                    txn.delete(kv.0).await?;
+                                    values.push(kv.1);
+                                }
+                                values.insert(pos, element.to_vec());
+                                if sparse_len.is_some() {
+                                    delete_list_index(&mut txn, &key, version).await?;
+                                }
+
+                                let right = left + values.len() as u64;
+                                for (idx, value) in (left..right).zip(values) {
+                                    let data_key =
+                                        KEY_ENCODER.encode_txnkv_list_data_key(&key, idx, version);
+                                    txn.put(data_key, value).await?;
+                                }
+                                let new_meta_value = KEY_ENCODER
+                                    .encode_txnkv_list_meta_value(ttl, version, left, right, None);
+                                txn.put(meta_key, new_meta_value).await?;
 
-                            // get list items bound range
-                            let bound_range =
-                                KEY_ENCODER.encode_txnkv_list_data_key_range(&key, version);
+                                return Ok((len + 1) as i64);
+                            }
 
-                            // iter will only return the matched kvpair
-                            let mut iter = txn.scan(bound_range, u32::MAX).await?.filter(|kv| {
-                                if kv.1 == pivot.to_vec() {
-                                    return true;
+                            // find the pivot and the element before it
+                            let mut prev: Option<Key> = None;
+                            let mut found = None;
+                            while found.is_none() {
+                                let kvs = scan_list_batch(
+                                    &mut txn,
+                                    &key,
+                                    version,
+                                    prev.clone(),
+                                    false,
+                                    LIST_SCAN_BATCH_SIZE,
+                                )
+                                .await?;
+                                if kvs.is_empty() {
+                                    // no matched pivot, ignore
+                                    return Ok(-1);
                                 }
-                                false
-                            });
-
-                            // yeild the first matched kvpair
-                            if let Some(kv) = iter.next() {
-                                // decode the idx from data key
-                                let idx = KeyDecoder::decode_key_list_idx_from_datakey(&key, kv.0);
-
-                                // compare the pivot distance to left and right, choose the shorter one
-                                let from_left = idx - left < right - idx;
-
-                                let idx_op;
-                                if from_left {
-                                    idx_op = if before_pivot { idx - 1 } else { idx };
-                                    // move data key from left to left-1
-                                    // move backwards for elements in idx [left, idx_op], add the new element to idx_op
-                                    if idx_op >= left {
-                                        let left_range = KEY_ENCODER
-                                            .encode_txnkv_list_data_key_idx_range(
-                                                &key, left, idx_op, version,
-                                            );
-                                        let iter = txn.scan(left_range, u32::MAX).await?;
-
-                                        for kv in iter {
-                                            let key_idx =
-                                                KeyDecoder::decode_key_list_idx_from_datakey(
-                                                    &key, kv.0,
-                                                );
-                                            let new_data_key = KEY_ENCODER
-                                                .encode_txnkv_list_data_key(
-                                                    &key,
-                                                    key_idx - 1,
-                                                    version,
-                                                );
-                                            txn.put(new_data_key, kv.1).await?;
-                                        }
+                                for kv in kvs {
+                                    if kv.1 == pivot {
+                                        found = Some(kv.0);
+                                        break;
                                     }
+                                    prev = Some(kv.0);
+                                }
+                            }
+                            let pivot_key = found.unwrap();
+
+                            // the neighbours to insert the element between
+                            let (lower, upper) = if before_pivot {
+                                (prev, Some(pivot_key))
+                            } else {
+                                let mut next = scan_list_batch(
+                                    &mut txn,
+                                    &key,
+                                    version,
+                                    Some(pivot_key.clone()),
+                                    false,
+                                    1,
+                                )
+                                .await?;
+                                (Some(pivot_key), next.pop().map(|kv| kv.0))
+                            };
 
+                            let (old_left, old_right) = (left, right);
+                            let (idx, data_key) = match (lower, upper) {
+                                (None, _) => {
                                     left -= 1;
-                                } else {
-                                    idx_op = if before_pivot { idx } else { idx + 1 };
-                                    // move data key from right to right+1
-                                    // move forwards for elements in idx [idx_op, right-1], add the new element to idx_op
-                                    // if idx_op == right, no need to move data key
-                                    if idx_op < right {
-                                        let right_range = KEY_ENCODER
-                                            .encode_txnkv_list_data_key_idx_range(
-                                                &key,
-                                                idx_op,
-                                                right - 1,
-                                                version,
-                                            );
-                                        let iter = txn.scan(right_range, u32::MAX).await?;
-
-                                        for kv in iter {
-                                            let key_idx =
-                                                KeyDecoder::decode_key_list_idx_from_datakey(
-                                                    &key, kv.0,
-                                                );
-                                            let new_data_key = KEY_ENCODER
-                                                .encode_txnkv_list_data_key(
-                                                    &key,
-                                                    key_idx + 1,
-                                                    version,
-                                                );
-                                            txn.put(new_data_key, kv.1).await?;
+                                    (
+                                        left,
+                                        KEY_ENCODER.encode_txnkv_list_data_key(&key, left, version),
+                                    )
+                                }
+                                (_, None) => {
+                                    right += 1;
+                                    (
+                                        right - 1,
+                                        KEY_ENCODER.encode_txnkv_list_data_key(
+                                            &key,
+                                            right - 1,
+                                            version,
+                                        ),
+                                    )
+                                }
+                                (Some(lower), Some(upper)) => {
+                                    let mut lower =
+                                        KeyDecoder::decode_key_list_position_from_datakey(
+                                            &key, lower,
+                                        );
+                                    let mut upper =
+                                        KeyDecoder::decode_key_list_position_from_datakey(
+                                            &key, upper,
+                                        );
+                                    let (mut idx, mut fraction) =
+                                        list_position_between(&lower, &upper);
+                                    if fraction.len() > LIST_MAX_FRACTION_LEN {
+                                        let moved =
+                                            rebalance_list_fractions(&mut txn, &key, version, idx)
+                                                .await?;
+                                        for (old, new) in moved {
+                                            if old == lower {
+                                                lower = new;
+                                            } else if old == upper {
+                                                upper = new;
+                                            }
                                         }
+                                        (idx, fraction) = list_position_between(&lower, &upper);
                                     }
-
-                                    right += 1;
+                                    (
+                                        idx,
+                                        KEY_ENCODER.encode_txnkv_list_data_key_with_fraction(
+                                            &key, idx, &fraction, version,
+                                        ),
+                                    )
                                 }
-
-                                // fill the pivot
-                                let pivot_data_key =
-                                    KEY_ENCODER.encode_txnkv_list_data_key(&key, idx_op, version);
-                                txn.put(pivot_data_key, element.to_vec()).await?;
-
-                                // update meta key
-                                let new_meta_value = KEY_ENCODER
-                                    .encode_txnkv_list_meta_value(ttl, version, left, right);
-                                txn.put(meta_key, new_meta_value).await?;
-
-                                let len = (right - left) as i64;
-                                Ok(len)
-                            } else {
-                                // no matched pivot, ignore
-                                Ok(-1)
+                            };
+                            txn.put(data_key, element.to_vec()).await?;
+
+                            // the list stays dense if the element is pushed to either end
+                            let dense = sparse_len.is_none() && right - left == len + 1;
+                            if !dense {
+                                if sparse_len.is_none() {
+                                    build_list_index(&mut txn, &key, version, old_left, old_right)
+                                        .await?;
+                                }
+                                update_list_index(&mut txn, &key, version, [idx], true).await?;
                             }
+                            let new_meta_value = KEY_ENCODER.encode_txnkv_list_meta_value(
+                                ttl,
+                                version,
+                                left,
+                                right,
+                                if dense { None } else { Some(len + 1) },
+                            );
+                            txn.put(meta_key, new_meta_value).await?;
+
+                            Ok((len + 1) as i64)
                         }
                         None => {
                             // when key does not exist, it is considered an empty list and no operation is performed
@@ -759,20 +1241,28 @@ impl<'a> ListCommandCtx {
             .await;
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_LIST, "linsert", ukey);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    /// LREM just support remove element from head to tail for now
+    /// Remove the first `count` elements equal to `ele` from the head or the
+    /// tail, zero count removes all of them. The removed elements just leave
+    /// holes in a long list, which makes it sparse.
     pub async fn do_async_txnkv_lrem(
         mut self,
-        key: &str,
+        key: &[u8],
         count: usize,
         from_head: bool,
         ele: &Bytes,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let ele = ele.to_owned();
 
@@ -790,7 +1280,7 @@ impl<'a> ListCommandCtx {
                             if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
                                 return Err(REDIS_WRONG_TYPE_ERR);
                             }
-                            let (ttl, version, left, right) =
+                            let (ttl, version, left, right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             if key_is_expired(ttl) {
                                 drop(txn);
@@ -800,133 +1290,117 @@ impl<'a> ListCommandCtx {
                                 return Ok(0);
                             }
 
-                            let len = right - left;
-
-                            // check list length is not too long
-                            let limit_len = cmd_lrem_length_limit_or_default();
-                            if limit_len > 0 && len > limit_len as u64 {
-                                return Err(REDIS_LIST_TOO_LARGE_ERR);
-                            }
-
-                            // get list items bound range
-                            let bound_range =
-                                KEY_ENCODER.encode_txnkv_list_data_key_range(&key, version);
-
-                            // iter will only return the matched kvpair
-                            let iter =
-                                txn.scan(bound_range.clone(), u32::MAX).await?.filter(|kv| {
-                                    if kv.1 == ele.to_vec() {
-                                        return true;
-                                    }
-                                    false
-                                });
-
-                            // hole saves the elements to be removed in order
-                            let mut hole: Vec<u64> = iter
-                                .map(|kv| KeyDecoder::decode_key_list_idx_from_datakey(&key, kv.0))
-                                .collect();
-
-                            // no matched element, return 0
-                            if hole.is_empty() {
-                                return Ok(0);
-                            }
-
-                            if !from_head {
-                                hole.reverse();
-                            }
-
+                            let len = sparse_len.unwrap_or(right - left);
+                            let limit = if count == 0 { u64::MAX } else { count as u64 };
                             let mut removed_count = 0;
 
-                            if from_head {
-                                let iter = txn.scan(bound_range, u32::MAX).await?;
-
-                                for kv in iter {
-                                    let key_idx = KeyDecoder::decode_key_list_idx_from_datakey(
-                                        &key,
-                                        kv.0.clone(),
-                                    );
-                                    if hole.is_empty() {
-                                        break;
-                                    }
+                            // small lists are simply renumbered, which keeps them dense
+                            if len <= LIST_REWRITE_MAX_LEN {
+                                let mut kvs = scan_list_range(
+                                    &mut txn, &key, version, left, sparse_len, 0, len,
+                                )
+                                .await?;
+                                if !from_head {
+                                    kvs.reverse();
+                                }
 
-                                    if !((count > 0 && removed_count == count)
-                                        || removed_count == hole.len())
-                                        && hole[removed_count] == key_idx
-                                    {
-                                        txn.delete(kv.0).await?;
+                                let mut values: Vec<Vec<u8>> = vec![];
+                                for kv in kvs {
+                                    if kv.1 == ele && removed_count < limit {
                                         removed_count += 1;
-                                        continue;
-                                    }
-
-                                    // check if key idx need to be backward move
-                                    if removed_count > 0 {
-                                        let new_data_key = KEY_ENCODER.encode_txnkv_list_data_key(
-                                            &key,
-                                            key_idx - removed_count as u64,
-                                            version,
-                                        );
-                                        txn.put(new_data_key, kv.1).await?;
-                                        txn.delete(kv.0).await?;
+                                    } else {
+                                        values.push(kv.1);
                                     }
+                                    txn.delete(kv.0).await?;
+                                }
+                                // no matched element, return 0
+                                if removed_count == 0 {
+                                    return Ok(0);
+                                }
+                                if !from_head {
+                                    values.reverse();
+                                }
+                                if sparse_len.is_some() {
+                                    delete_list_index(&mut txn, &key, version).await?;
                                 }
 
-                                // update meta key or delete it if no element left
-                                if len == removed_count as u64 {
+                                if values.is_empty() {
                                     txn.delete(meta_key).await?;
                                 } else {
+                                    let right = left + values.len() as u64;
+                                    for (idx, value) in (left..right).zip(values) {
+                                        let data_key = KEY_ENCODER
+                                            .encode_txnkv_list_data_key(&key, idx, version);
+                                        txn.put(data_key, value).await?;
+                                    }
                                     let new_meta_value = KEY_ENCODER.encode_txnkv_list_meta_value(
-                                        ttl,
-                                        version,
-                                        left,
-                                        right - removed_count as u64,
+                                        ttl, version, left, right, None,
                                     );
                                     txn.put(meta_key, new_meta_value).await?;
                                 }
-                            } else {
-                                let iter = txn.scan_reverse(bound_range, u32::MAX).await?;
-
-                                for kv in iter {
-                                    let key_idx = KeyDecoder::decode_key_list_idx_from_datakey(
-                                        &key,
-                                        kv.0.clone(),
-                                    );
-                                    if hole.is_empty() {
-                                        break;
-                                    }
+                                return Ok(removed_count as i64);
+                            }
 
-                                    if !((count > 0 && removed_count == count)
-                                        || removed_count == hole.len())
-                                        && hole[removed_count] == key_idx
-                                    {
-                                        txn.delete(kv.0).await?;
-                                        removed_count += 1;
-                                        continue;
-                                    }
+                            let mut indices = vec![];
+                            let mut last = None;
+                            while removed_count < limit {
+                                let kvs = scan_list_batch(
+                                    &mut txn,
+                                    &key,
+                                    version,
+                                    last,
+                                    !from_head,
+                                    LIST_SCAN_BATCH_SIZE,
+                                )
+                                .await?;
+                                if kvs.is_empty() {
+                                    break;
+                                }
+                                last = kvs.last().map(|kv| kv.0.clone());
 
-                                    // check if key idx need to be forward move
-                                    if removed_count > 0 {
-                                        let new_data_key = KEY_ENCODER.encode_txnkv_list_data_key(
-                                            &key,
-                                            key_idx + removed_count as u64,
-                                            version,
+                                for kv in kvs {
+                                    if kv.1 == ele {
+                                        indices.push(
+                                            KeyDecoder::decode_key_list_position_from_datakey(
+                                                &key,
+                                                kv.0.clone(),
+                                            )
+                                            .0,
                                         );
-                                        txn.put(new_data_key, kv.1).await?;
                                         txn.delete(kv.0).await?;
+                                        removed_count += 1;
+                                        if removed_count == limit {
+                                            break;
+                                        }
                                     }
                                 }
+                            }
+                            // no matched element, return 0
+                            if removed_count == 0 {
+                                return Ok(0);
+                            }
 
-                                // update meta key or delete it if no element left
-                                if len == removed_count as u64 {
-                                    txn.delete(meta_key).await?;
-                                } else {
-                                    let new_meta_value = KEY_ENCODER.encode_txnkv_list_meta_value(
-                                        ttl,
-                                        version,
-                                        left + removed_count as u64,
-                                        right,
-                                    );
-                                    txn.put(meta_key, new_meta_value).await?;
-                                }
+                            // the removed elements leave holes, which makes the list sparse
+                            if sparse_len.is_none() {
+                                build_list_index(&mut txn, &key, version, left, right).await?;
+                            }
+
+                            // update meta key or delete it if no element left
+                            if len == removed_count {
+                                delete_list_index(&mut txn, &key, version).await?;
+                                txn.delete(meta_key).await?;
+                            } else {
+                                update_list_index(&mut txn, &key, version, indices, false).await?;
+                                put_sparse_list_meta(
+                                    &mut txn,
+                                    &key,
+                                    ttl,
+                                    version,
+                                    left,
+                                    right,
+                                    len - removed_count,
+                                )
+                                .await?;
                             }
                             Ok(removed_count as i64)
                         }
@@ -941,12 +1415,17 @@ impl<'a> ListCommandCtx {
             .await;
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_LIST, "lrem", ukey);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    pub async fn do_async_txnkv_list_del(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnkv_list_del(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -961,9 +1440,9 @@ impl<'a> ListCommandCtx {
                     let mut txn = txn_rc.lock().await;
                     match txn.get(meta_key.clone()).await? {
                         Some(meta_value) => {
-                            let (_, version, left, right) =
+                            let (_, version, left, right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
-                            let len = right - left;
+                            let len = sparse_len.unwrap_or(right - left);
                             if len >= async_del_list_threshold_or_default() as u64 {
                                 // async delete
                                 // delete meta key and create gc key and gc version key with the version
@@ -987,6 +1466,9 @@ impl<'a> ListCommandCtx {
                                 for k in iter {
                                     txn.delete(k).await?;
                                 }
+                                if sparse_len.is_some() {
+                                    delete_list_index(&mut txn, &key, version).await?;
+                                }
                                 txn.delete(meta_key).await?;
                             }
                             Ok(1)
@@ -999,12 +1481,13 @@ impl<'a> ListCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_list_expire_if_needed(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnkv_list_expire_if_needed(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
 
-        client
+        let resp = client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
@@ -1014,12 +1497,12 @@ impl<'a> ListCommandCtx {
                     let mut txn = txn_rc.lock().await;
                     match txn.get(meta_key.clone()).await? {
                         Some(meta_value) => {
-                            let (ttl, version, left, right) =
+                            let (ttl, version, left, right, sparse_len) =
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             if !key_is_expired(ttl) {
                                 return Ok(0);
                             }
-                            let len = right - left;
+                            let len = sparse_len.unwrap_or(right - left);
                             if len >= async_del_list_threshold_or_default() as u64 {
                                 // async delete
                                 // delete meta key and create gc key and gc version key with the version
@@ -1043,6 +1526,9 @@ impl<'a> ListCommandCtx {
                                 for k in iter {
                                     txn.delete(k).await?;
                                 }
+                                if sparse_len.is_some() {
+                                    delete_list_index(&mut txn, &key, version).await?;
+                                }
                                 txn.delete(meta_key).await?;
                             }
 
@@ -1056,6 +1542,308 @@ impl<'a> ListCommandCtx {
                 }
                 .boxed()
             })
-            .await
+            .await;
+        if let Ok(1) = resp {
+            notify_keyspace_event(NOTIFY_EXPIRED, "expired", ukey);
+        }
+        resp
+    }
+}
+
+/// Read `count` elements from position `start` of the list, which must be
+/// in range. The positions of a dense list map to the indices directly, while
+/// the bucket holding the position of a sparse list is located by the counts
+/// of the groups of its index then by those of the buckets in the group, the
+/// elements before the position in the bucket are skipped.
+async fn scan_list_range(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    left: u64,
+    sparse_len: Option<u64>,
+    start: u64,
+    count: u64,
+) -> AsyncResult<Vec<KvPair>> {
+    if sparse_len.is_none() {
+        let data_key_start = KEY_ENCODER.encode_txnkv_list_data_key(key, left + start, version);
+        let range: RangeFrom<Key> = data_key_start..;
+        let from_range: BoundRange = range.into();
+        return Ok(txn.scan(from_range, scan_limit(count)).await?.collect());
+    }
+
+    let mut from = KEY_ENCODER.encode_txnkv_list_data_key_start(key, version);
+    let mut skipped = 0;
+    let mut first_bucket = 0;
+    let group_range = KEY_ENCODER.encode_txnkv_list_index_group_key_range(key, version);
+    for kv in txn.scan(group_range, u32::MAX).await? {
+        let group_len = u64::from_be_bytes(kv.1.try_into().unwrap());
+        if skipped + group_len > start {
+            first_bucket = KeyDecoder::decode_key_list_index_bucket(kv.0) << LIST_INDEX_GROUP_BITS;
+            break;
+        }
+        skipped += group_len;
+    }
+
+    let index_range = KEY_ENCODER.encode_txnkv_list_index_key_range(
+        key,
+        version,
+        first_bucket,
+        first_bucket + (1 << LIST_INDEX_GROUP_BITS),
+    );
+    for kv in txn.scan(index_range, 1 << LIST_INDEX_GROUP_BITS).await? {
+        let bucket_len = u64::from_be_bytes(kv.1.try_into().unwrap());
+        if skipped + bucket_len > start {
+            let bucket = KeyDecoder::decode_key_list_index_bucket(kv.0);
+            from = KEY_ENCODER.encode_txnkv_list_data_key(
+                key,
+                bucket << LIST_INDEX_BUCKET_BITS,
+                version,
+            );
+            break;
+        }
+        skipped += bucket_len;
+    }
+
+    let offset = start - skipped;
+    let data_key_end = KEY_ENCODER.encode_txnkv_list_data_key_end(key, version);
+    let range: Range<Key> = from..data_key_end;
+    Ok(txn
+        .scan(range, scan_limit(offset + count))
+        .await?
+        .skip(offset as usize)
+        .collect())
+}
+
+/// Scan the next batch of the list elements after the `last` data key, or
+/// before it if `reverse` is set, from the end if `last` is not set.
+async fn scan_list_batch(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    last: Option<Key>,
+    reverse: bool,
+    limit: u64,
+) -> AsyncResult<Vec<KvPair>> {
+    let data_key_start =
+        Bound::Included(KEY_ENCODER.encode_txnkv_list_data_key_start(key, version));
+    let data_key_end = Bound::Excluded(KEY_ENCODER.encode_txnkv_list_data_key_end(key, version));
+    let limit = scan_limit(limit);
+    if reverse {
+        let to = last.map_or(data_key_end, Bound::Excluded);
+        Ok(txn
+            .scan_reverse((data_key_start, to), limit)
+            .await?
+            .collect())
+    } else {
+        let from = last.map_or(data_key_start, Bound::Excluded);
+        Ok(txn.scan((from, data_key_end), limit).await?.collect())
+    }
+}
+
+/// The limit of a scan for `count` elements, a longer list is read in parts.
+fn scan_limit(count: u64) -> u32 {
+    count.min(u32::MAX as u64) as u32
+}
+
+/// Count the elements of a dense list stored in [left, right) by buckets
+/// and groups of indices, for the list becoming sparse.
+async fn build_list_index(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    left: u64,
+    right: u64,
+) -> AsyncResult<()> {
+    let mut groups: BTreeMap<u64, u64> = BTreeMap::new();
+    for bucket in left >> LIST_INDEX_BUCKET_BITS..=(right - 1) >> LIST_INDEX_BUCKET_BITS {
+        let bucket_start = left.max(bucket << LIST_INDEX_BUCKET_BITS);
+        let bucket_end = right.min((bucket + 1) << LIST_INDEX_BUCKET_BITS);
+        let index_key = KEY_ENCODER.encode_txnkv_list_index_key(key, version, bucket);
+        txn.put(
+            index_key,
+            (bucket_end - bucket_start).to_be_bytes().to_vec(),
+        )
+        .await?;
+        *groups.entry(bucket >> LIST_INDEX_GROUP_BITS).or_default() += bucket_end - bucket_start;
+    }
+    for (group, group_len) in groups {
+        let group_key = KEY_ENCODER.encode_txnkv_list_index_group_key(key, version, group);
+        txn.put(group_key, group_len.to_be_bytes().to_vec()).await?;
+    }
+    Ok(())
+}
+
+/// Count the elements added to, or removed from a sparse list at `indices`
+/// in the buckets and the groups of its index, empty ones are removed.
+async fn update_list_index(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    indices: impl IntoIterator<Item = u64>,
+    added: bool,
+) -> AsyncResult<()> {
+    let mut deltas: BTreeMap<u64, u64> = BTreeMap::new();
+    let mut group_deltas: BTreeMap<u64, u64> = BTreeMap::new();
+    for idx in indices {
+        let bucket = idx >> LIST_INDEX_BUCKET_BITS;
+        *deltas.entry(bucket).or_default() += 1;
+        *group_deltas
+            .entry(bucket >> LIST_INDEX_GROUP_BITS)
+            .or_default() += 1;
+    }
+
+    for (bucket, delta) in deltas {
+        let index_key = KEY_ENCODER.encode_txnkv_list_index_key(key, version, bucket);
+        update_list_index_count(txn, index_key, delta, added).await?;
+    }
+    for (group, delta) in group_deltas {
+        let group_key = KEY_ENCODER.encode_txnkv_list_index_group_key(key, version, group);
+        update_list_index_count(txn, group_key, delta, added).await?;
+    }
+    Ok(())
+}
+
+async fn update_list_index_count(
+    txn: &mut Transaction,
+    index_key: Key,
+    delta: u64,
+    added: bool,
+) -> AsyncResult<()> {
+    let count = txn
+        .get(index_key.clone())
+        .await?
+        .map_or(0, |v| u64::from_be_bytes(v.try_into().unwrap()));
+    let count = if added {
+        count + delta
+    } else {
+        count.saturating_sub(delta)
+    };
+    if count == 0 {
+        txn.delete(index_key).await?;
+    } else {
+        txn.put(index_key, count.to_be_bytes().to_vec()).await?;
+    }
+    Ok(())
+}
+
+async fn delete_list_index(txn: &mut Transaction, key: &[u8], version: u16) -> AsyncResult<()> {
+    let index_range = KEY_ENCODER.encode_txnkv_sub_meta_key_range(key, version);
+    for index_key in txn.scan_keys(index_range, u32::MAX).await? {
+        txn.delete(index_key).await?;
+    }
+    Ok(())
+}
+
+/// Update the meta of a sparse list whose elements were removed, a list
+/// left with few elements is renumbered from `left` to be dense again.
+async fn put_sparse_list_meta(
+    txn: &mut Transaction,
+    key: &[u8],
+    ttl: u64,
+    version: u16,
+    left: u64,
+    right: u64,
+    len: u64,
+) -> AsyncResult<()> {
+    let meta_value = if len <= LIST_REWRITE_MAX_LEN {
+        let bound_range = KEY_ENCODER.encode_txnkv_list_data_key_range(key, version);
+        let kvs: Vec<KvPair> = txn.scan(bound_range, scan_limit(len)).await?.collect();
+        for kv in &kvs {
+            txn.delete(kv.0.clone()).await?;
+        }
+        let right = left + kvs.len() as u64;
+        for (idx, kv) in (left..right).zip(kvs) {
+            let data_key = KEY_ENCODER.encode_txnkv_list_data_key(key, idx, version);
+            txn.put(data_key, kv.1).await?;
+        }
+        delete_list_index(txn, key, version).await?;
+        KEY_ENCODER.encode_txnkv_list_meta_value(ttl, version, left, right, None)
+    } else {
+        KEY_ENCODER.encode_txnkv_list_meta_value(ttl, version, left, right, Some(len))
+    };
+    let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+    txn.put(meta_key, meta_value).await?;
+    Ok(())
+}
+
+/// Returns a position between the `(idx, fraction)` positions of two
+/// neighbours. The next index is used if it is free, otherwise the element
+/// shares the index of `lower` with a fraction after it.
+fn list_position_between(lower: &(u64, Vec<u8>), upper: &(u64, Vec<u8>)) -> (u64, Vec<u8>) {
+    // the neighbours are adjacent, so the index right after lower is free
+    // unless upper is stored at it
+    if lower.0 + 1 < upper.0 || (lower.0 + 1 == upper.0 && !upper.1.is_empty()) {
+        return (lower.0 + 1, vec![]);
+    }
+    if lower.0 == upper.0 {
+        (lower.0, fraction_between(&lower.1, Some(&upper.1)))
+    } else {
+        (lower.0, fraction_between(&lower.1, None))
+    }
+}
+
+/// Returns the shortest base-256 fraction between `lower` and `upper`,
+/// without upper bound if it is not set. The result never ends with zero,
+/// so there is always room for another fraction before it.
+fn fraction_between(lower: &[u8], mut upper: Option<&[u8]>) -> Vec<u8> {
+    let mut fraction = vec![];
+    for i in 0.. {
+        let lo = lower.get(i).copied().unwrap_or(0) as u16;
+        let hi = match upper {
+            Some(upper) => upper.get(i).copied().unwrap_or(0) as u16,
+            None => 256,
+        };
+        if hi > lo + 1 {
+            fraction.push(((lo + hi) / 2) as u8);
+            break;
+        }
+        fraction.push(lo as u8);
+        if hi == lo + 1 {
+            // any fraction after the rest of lower fits in
+            upper = None;
+        }
+    }
+    fraction
+}
+
+/// Spread out the fractions of the elements sharing the index evenly, once
+/// the fractions grow too long. Returns the positions of the moved elements
+/// before and after.
+async fn rebalance_list_fractions(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    idx: u64,
+) -> AsyncResult<Vec<((u64, Vec<u8>), (u64, Vec<u8>))>> {
+    let range: Range<Key> = KEY_ENCODER.encode_txnkv_list_data_key(key, idx, version)
+        ..KEY_ENCODER.encode_txnkv_list_data_key(key, idx + 1, version);
+    let kvs: Vec<KvPair> = txn.scan(range, u32::MAX).await?.collect();
+
+    // leave a gap of at least one byte between the fractions
+    let count = kvs.len() as u64;
+    let mut width = 1;
+    while width < 7 && 1 << (8 * width) < count << 8 {
+        width += 1;
+    }
+    let step = (1 << (8 * width)) / count;
+
+    for kv in &kvs {
+        txn.delete(kv.0.clone()).await?;
+    }
+    let mut moved = Vec::with_capacity(kvs.len());
+    for (i, kv) in kvs.into_iter().enumerate() {
+        let bytes = (i as u64 * step).to_be_bytes();
+        let mut fraction = bytes[8 - width..].to_vec();
+        while fraction.last() == Some(&0) {
+            fraction.pop();
+        }
+        let data_key =
+            KEY_ENCODER.encode_txnkv_list_data_key_with_fraction(key, idx, &fraction, version);
+        txn.put(data_key, kv.1).await?;
+        moved.push((
+            KeyDecoder::decode_key_list_position_from_datakey(key, kv.0),
+            (idx, fraction),
+        ));
     }
+    Ok(moved)
 }
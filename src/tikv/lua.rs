@@ -28,7 +28,7 @@ impl<'a> LuaCommandCtx<'a> {
     pub async fn do_async_eval_inner(
         self,
         script: &str,
-        keys: &[String],
+        keys: &[Bytes],
         args: &[Bytes],
     ) -> LuaResult<Frame> {
         let lua = match self.lua {
@@ -42,7 +42,8 @@ impl<'a> LuaCommandCtx<'a> {
         // Add KEYS and ARGV to lua state
         let keys_table = lua.create_table()?;
         for (idx, key) in keys.iter().enumerate() {
-            keys_table.set(idx + 1, key.clone())?;
+            let str = lua.create_string(key)?;
+            keys_table.set(idx + 1, str)?;
         }
         let args_table = lua.create_table()?;
         for (idx, arg) in args.iter().enumerate() {
@@ -96,14 +97,40 @@ impl<'a> LuaCommandCtx<'a> {
                     Command::Decr(mut cmd) => cmd.incr_by(txn_rc.clone(), false).await,
                     Command::DecrBy(mut cmd) => cmd.incr_by(txn_rc.clone(), false).await,
                     Command::Strlen(cmd) => cmd.strlen(txn_rc.clone()).await,
+                    Command::IncrByFloat(cmd) => cmd.incr_by_float(txn_rc.clone()).await,
+                    Command::Append(cmd) => cmd.append(txn_rc.clone()).await,
+                    Command::Getrange(cmd) => cmd.getrange(txn_rc.clone()).await,
+                    Command::Substr(cmd) => cmd.getrange(txn_rc.clone()).await,
+                    Command::Setrange(cmd) => cmd.setrange(txn_rc.clone()).await,
+                    Command::Getdel(cmd) => cmd.getdel(txn_rc.clone()).await,
+                    Command::Getex(cmd) => cmd.getex(txn_rc.clone()).await,
+                    Command::Setbit(cmd) => cmd.setbit(txn_rc.clone()).await,
+                    Command::Getbit(cmd) => cmd.getbit(txn_rc.clone()).await,
+                    Command::Bitcount(cmd) => cmd.bitcount(txn_rc.clone()).await,
+                    Command::Bitpos(cmd) => cmd.bitpos(txn_rc.clone()).await,
+                    Command::Bitop(cmd) => cmd.bitop(txn_rc.clone()).await,
+                    Command::Bitfield(cmd) => cmd.bitfield(txn_rc.clone()).await,
+                    Command::BitfieldRo(cmd) => cmd.bitfield(txn_rc.clone()).await,
+                    Command::Pfadd(cmd) => cmd.pfadd(txn_rc.clone()).await,
+                    Command::Pfcount(cmd) => cmd.pfcount(txn_rc.clone()).await,
+                    Command::Pfmerge(cmd) => cmd.pfmerge(txn_rc.clone()).await,
+                    Command::Geoadd(cmd) => cmd.geoadd(txn_rc.clone()).await,
+                    Command::Geopos(cmd) => cmd.geopos(txn_rc.clone()).await,
+                    Command::Geodist(cmd) => cmd.geodist(txn_rc.clone()).await,
+                    Command::Geohash(cmd) => cmd.geohash(txn_rc.clone()).await,
+                    Command::Geosearch(cmd) => cmd.geosearch(txn_rc.clone()).await,
+                    Command::Geosearchstore(cmd) => cmd.geosearchstore(txn_rc.clone()).await,
                     Command::Del(cmd) => cmd.del(txn_rc.clone()).await,
                     Command::Exists(cmd) => cmd.exists(txn_rc.clone()).await,
                     Command::Get(cmd) => cmd.get(txn_rc.clone()).await,
                     Command::Set(cmd) => cmd.set(txn_rc.clone()).await,
                     Command::SetNX(cmd) => cmd.put_not_exists(txn_rc.clone()).await,
                     Command::SetEX(cmd) => cmd.setex(txn_rc.clone()).await,
+                    Command::Psetex(cmd) => cmd.setex(txn_rc.clone()).await,
+                    Command::Getset(cmd) => cmd.set(txn_rc.clone()).await,
                     Command::Mget(cmd) => cmd.batch_get(txn_rc.clone()).await,
                     Command::Mset(cmd) => cmd.batch_put(txn_rc.clone()).await,
+                    Command::Msetnx(cmd) => cmd.batch_put_not_exists(txn_rc.clone()).await,
                     Command::Type(cmd) => cmd.cmd_type(txn_rc.clone()).await,
                     Command::TTL(cmd) => cmd.ttl(false, txn_rc.clone()).await,
                     Command::PTTL(cmd) => cmd.ttl(true, txn_rc.clone()).await,
@@ -125,17 +152,23 @@ impl<'a> LuaCommandCtx<'a> {
                     Command::Hincrby(cmd) => cmd.hincrby(txn_rc.clone()).await,
                     Command::Hexists(cmd) => cmd.hexists(txn_rc.clone()).await,
                     Command::Hstrlen(cmd) => cmd.hstrlen(txn_rc.clone()).await,
-                    Command::Lpush(cmd) => cmd.push(txn_rc.clone(), true).await,
-                    Command::Rpush(cmd) => cmd.push(txn_rc.clone(), false).await,
+                    Command::Lpush(cmd) => cmd.push(txn_rc.clone(), true, false).await,
+                    Command::Rpush(cmd) => cmd.push(txn_rc.clone(), false, false).await,
+                    Command::Lpushx(cmd) => cmd.push(txn_rc.clone(), true, true).await,
+                    Command::Rpushx(cmd) => cmd.push(txn_rc.clone(), false, true).await,
                     Command::Lpop(cmd) => cmd.pop(txn_rc.clone(), true).await,
                     Command::Rpop(cmd) => cmd.pop(txn_rc.clone(), false).await,
                     Command::Lrange(cmd) => cmd.lrange(txn_rc.clone()).await,
                     Command::Llen(cmd) => cmd.llen(txn_rc.clone()).await,
                     Command::Lindex(cmd) => cmd.lindex(txn_rc.clone()).await,
+                    Command::Lpos(cmd) => cmd.lpos(txn_rc.clone()).await,
                     Command::Lset(cmd) => cmd.lset(txn_rc.clone()).await,
                     Command::Ltrim(cmd) => cmd.ltrim(txn_rc.clone()).await,
                     Command::Lrem(cmd) => cmd.lrem(txn_rc.clone()).await,
                     Command::Linsert(cmd) => cmd.linsert(txn_rc.clone()).await,
+                    Command::Lmpop(cmd) => cmd.lmpop(txn_rc.clone()).await,
+                    Command::Lmove(cmd) => cmd.lmove(txn_rc.clone()).await,
+                    Command::Rpoplpush(cmd) => cmd.lmove(txn_rc.clone()).await,
                     Command::Sadd(cmd) => cmd.sadd(txn_rc.clone()).await,
                     Command::Scard(cmd) => cmd.scard(txn_rc.clone()).await,
                     Command::Sismember(cmd) => cmd.sismember(txn_rc.clone()).await,
@@ -143,6 +176,14 @@ impl<'a> LuaCommandCtx<'a> {
                     Command::Smembers(cmd) => cmd.smembers(txn_rc.clone()).await,
                     Command::Srandmember(cmd) => cmd.srandmember(txn_rc.clone()).await,
                     Command::Spop(cmd) => cmd.spop(txn_rc.clone()).await,
+                    Command::Sinter(cmd) => cmd.sinter(txn_rc.clone()).await,
+                    Command::Sunion(cmd) => cmd.sinter(txn_rc.clone()).await,
+                    Command::Sdiff(cmd) => cmd.sinter(txn_rc.clone()).await,
+                    Command::Sinterstore(cmd) => cmd.sinterstore(txn_rc.clone()).await,
+                    Command::Sunionstore(cmd) => cmd.sinterstore(txn_rc.clone()).await,
+                    Command::Sdiffstore(cmd) => cmd.sinterstore(txn_rc.clone()).await,
+                    Command::Sintercard(cmd) => cmd.sintercard(txn_rc.clone()).await,
+                    Command::Smove(cmd) => cmd.smove(txn_rc.clone()).await,
                     Command::Srem(cmd) => cmd.srem(txn_rc.clone()).await,
                     Command::Zadd(cmd) => cmd.zadd(txn_rc.clone()).await,
                     Command::Zcard(cmd) => cmd.zcard(txn_rc.clone()).await,
@@ -159,6 +200,17 @@ impl<'a> LuaCommandCtx<'a> {
                     Command::Zpopmax(cmd) => cmd.zpop(txn_rc.clone(), false).await,
                     Command::Zrank(cmd) => cmd.zrank(txn_rc.clone()).await,
                     Command::Zincryby(cmd) => cmd.zincrby(txn_rc.clone()).await,
+                    Command::Zunion(cmd) => cmd.zunion(txn_rc.clone()).await,
+                    Command::Zinter(cmd) => cmd.zunion(txn_rc.clone()).await,
+                    Command::Zdiff(cmd) => cmd.zunion(txn_rc.clone()).await,
+                    Command::Zunionstore(cmd) => cmd.zunionstore(txn_rc.clone()).await,
+                    Command::Zinterstore(cmd) => cmd.zunionstore(txn_rc.clone()).await,
+                    Command::Zdiffstore(cmd) => cmd.zunionstore(txn_rc.clone()).await,
+                    Command::Zintercard(cmd) => cmd.zintercard(txn_rc.clone()).await,
+                    Command::Zrangebylex(cmd) => cmd.zrangebylex(txn_rc.clone(), false).await,
+                    Command::Zrevrangebylex(cmd) => cmd.zrangebylex(txn_rc.clone(), true).await,
+                    Command::Zlexcount(cmd) => cmd.zlexcount(txn_rc.clone()).await,
+                    Command::Zremrangebylex(cmd) => cmd.zremrangebylex(txn_rc.clone()).await,
                     Command::Scan(cmd) => cmd.scan(txn_rc.clone()).await,
                     Command::Xscan(cmd) => cmd.scan(txn_rc.clone()).await,
                     _ => Ok(resp_invalid_arguments()),
@@ -225,7 +277,7 @@ impl<'a> LuaCommandCtx<'a> {
         self,
         script: &str,
         _: &Db,
-        keys: &[String],
+        keys: &[Bytes],
         args: &[Bytes],
     ) -> AsyncResult<Frame> {
         Ok(self.clone().do_async_eval_inner(script, keys, args).await?)
@@ -235,7 +287,7 @@ impl<'a> LuaCommandCtx<'a> {
         self,
         sha1: &str,
         db: &Db,
-        keys: &[String],
+        keys: &[Bytes],
         args: &[Bytes],
     ) -> AsyncResult<Frame> {
         // get script from cache with sha1 key
@@ -28,11 +28,14 @@ use self::client::TxnClientWrapper;
 
 use self::errors::{AsyncResult, RTError};
 
+pub mod bitmap;
 pub mod client;
 pub mod config;
 pub mod encoding;
 pub mod errors;
+pub mod geo;
 pub mod hash;
+pub mod hyperloglog;
 pub mod list;
 pub mod lua;
 pub mod set;
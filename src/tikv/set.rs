@@ -2,6 +2,8 @@ use super::client::get_version_for_new;
 use super::errors::*;
 use super::gen_next_meta_index;
 use super::get_txn_client;
+use super::string::StringCommandCtx;
+use super::zset::txnkv_zset_put_member;
 use super::KEY_ENCODER;
 use super::{
     encoding::{DataType, KeyDecoder},
@@ -13,9 +15,11 @@ use crate::utils::count_unique_keys;
 use crate::utils::{key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil};
 use crate::Frame;
 use ::futures::future::FutureExt;
+use bytes::Bytes;
 use rand::prelude::SliceRandom;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryInto;
+use std::ops::Bound;
 use std::sync::Arc;
 use tikv_client::Key;
 use tikv_client::Transaction;
@@ -25,8 +29,27 @@ use tokio::sync::Mutex;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 
 use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::notify::{notify_keyspace_event, NOTIFY_EXPIRED, NOTIFY_GENERIC, NOTIFY_SET};
 
 const RANDOM_BASE: i64 = 100;
+const SET_SCAN_BATCH_SIZE: u32 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOperation {
+    Inter,
+    Union,
+    Diff,
+}
+
+impl SetOperation {
+    fn store_event(&self) -> &'static str {
+        match self {
+            SetOperation::Inter => "sinterstore",
+            SetOperation::Union => "sunionstore",
+            SetOperation::Diff => "sdiffstore",
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct SetCommandCtx {
@@ -38,7 +61,7 @@ impl SetCommandCtx {
         SetCommandCtx { txn }
     }
 
-    async fn txnkv_sum_key_size(mut self, key: &str, version: u16) -> AsyncResult<i64> {
+    async fn txnkv_sum_key_size(mut self, key: &[u8], version: u16) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
 
@@ -80,11 +103,12 @@ impl SetCommandCtx {
 
     pub async fn do_async_txnkv_sadd(
         mut self,
-        key: &str,
-        members: &Vec<String>,
+        key: &[u8],
+        members: &Vec<Bytes>,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
 
+        let ukey = key;
         let key = key.to_owned();
         let members = members.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -190,12 +214,17 @@ impl SetCommandCtx {
             .await;
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_SET, "sadd", ukey);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    pub async fn do_async_txnkv_scard(mut self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_scard(mut self, key: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
         let key = key.to_owned();
@@ -240,8 +269,8 @@ impl SetCommandCtx {
     // called by SISMEMBER and SMISMEMBER
     pub async fn do_async_txnkv_sismember(
         mut self,
-        key: &str,
-        members: &Vec<String>,
+        key: &[u8],
+        members: &Vec<Bytes>,
         resp_in_arr: bool,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
@@ -338,7 +367,7 @@ impl SetCommandCtx {
     // returned, so client should not be strongly rely on the random behavior
     pub async fn do_async_txnkv_srandmemeber(
         mut self,
-        key: &str,
+        key: &[u8],
         count: i64,
         repeatable: bool,
         array_resp: bool,
@@ -437,7 +466,7 @@ impl SetCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_smembers(mut self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_smembers(mut self, key: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
         let key = key.to_owned();
@@ -492,11 +521,12 @@ impl SetCommandCtx {
 
     pub async fn do_async_txnkv_srem(
         mut self,
-        key: &str,
-        members: &Vec<String>,
+        key: &[u8],
+        members: &Vec<Bytes>,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
 
+        let ukey = key;
         let key = key.to_owned();
         let members = members.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -578,14 +608,20 @@ impl SetCommandCtx {
             .await;
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_SET, "srem", ukey);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
     /// spop will pop members by alphabetical order
-    pub async fn do_async_txnkv_spop(mut self, key: &str, count: u64) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_spop(mut self, key: &[u8], count: u64) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
         let rand_idx = gen_next_meta_index();
@@ -678,6 +714,9 @@ impl SetCommandCtx {
 
         match resp {
             Ok(mut v) => {
+                if !v.is_empty() {
+                    notify_keyspace_event(NOTIFY_SET, "spop", ukey);
+                }
                 if count == 1 {
                     if v.is_empty() {
                         Ok(resp_nil())
@@ -692,7 +731,7 @@ impl SetCommandCtx {
         }
     }
 
-    pub async fn do_async_txnkv_set_del(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnkv_set_del(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -756,12 +795,13 @@ impl SetCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_set_expire_if_needed(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnkv_set_expire_if_needed(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
 
-        client
+        let resp = client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
@@ -821,6 +861,619 @@ impl SetCommandCtx {
                 }
                 .boxed()
             })
-            .await
+            .await;
+        if let Ok(1) = resp {
+            notify_keyspace_event(NOTIFY_EXPIRED, "expired", ukey);
+        }
+        resp
+    }
+
+    /// Return the version of the set stored at `key`, or None if the key
+    /// does not exist or is expired.
+    pub(crate) async fn txnkv_set_live_version(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        key: &[u8],
+    ) -> AsyncResult<Option<u16>> {
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let meta_value = txn_rc.lock().await.get(meta_key).await?;
+        match meta_value {
+            Some(meta_value) => {
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Set) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
+                let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    self.clone()
+                        .do_async_txnkv_set_expire_if_needed(key)
+                        .await?;
+                    return Ok(None);
+                }
+                Ok(Some(version))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn txnkv_set_cursors(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        keys: &[Bytes],
+    ) -> AsyncResult<Vec<MemberCursor>> {
+        let mut cursors = Vec::with_capacity(keys.len());
+        for key in keys {
+            let cursor = match self.txnkv_set_live_version(txn_rc, key).await? {
+                Some(version) => MemberCursor::new(key, version, DataType::Set, set_member_score),
+                None => MemberCursor::empty(),
+            };
+            cursors.push(cursor);
+        }
+        Ok(cursors)
+    }
+
+    /// Return the members of the intersection, union or difference of the sets.
+    pub async fn do_async_txnkv_set_algebra(
+        mut self,
+        op: SetOperation,
+        keys: &[Bytes],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut cursors = self.txnkv_set_cursors(&txn_rc, &keys).await?;
+                    let mut sink = MemberSink::Collect(vec![]);
+                    let mut txn = txn_rc.lock().await;
+                    merge_members(
+                        &mut txn,
+                        op,
+                        &mut cursors,
+                        keep_score,
+                        usize::MAX,
+                        &mut sink,
+                    )
+                    .await?;
+                    match sink {
+                        MemberSink::Collect(members) => Ok(members),
+                        _ => unreachable!(),
+                    }
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(members) => Ok(resp_array(
+                members.into_iter().map(|(m, _)| resp_bulk(m)).collect(),
+            )),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Return the cardinality of the intersection of the sets, counting up to
+    /// `limit` members if it is not 0.
+    pub async fn do_async_txnkv_sintercard(
+        mut self,
+        keys: &[Bytes],
+        limit: usize,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+        let limit = if limit == 0 { usize::MAX } else { limit };
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut cursors = self.txnkv_set_cursors(&txn_rc, &keys).await?;
+                    let mut txn = txn_rc.lock().await;
+                    merge_members(
+                        &mut txn,
+                        SetOperation::Inter,
+                        &mut cursors,
+                        keep_score,
+                        limit,
+                        &mut MemberSink::Count,
+                    )
+                    .await
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(count) => Ok(resp_int(count as i64)),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Store the result of the set operation in `dest`, replacing its old
+    /// value of any type.
+    pub async fn do_async_txnkv_set_algebra_store(
+        mut self,
+        op: SetOperation,
+        dest: &[u8],
+        keys: &[Bytes],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let udest = dest.to_owned();
+        let dest = dest.to_owned();
+        let keys = keys.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut cursors = self.txnkv_set_cursors(&txn_rc, &keys).await?;
+                    let string_ctx = StringCommandCtx::new(Some(txn_rc.clone()));
+                    let old_value = string_ctx.txnkv_get_live_value(&txn_rc, &dest).await?;
+
+                    // the result is written as it is merged, unless the
+                    // destination is also a source which can only be
+                    // replaced after all its members are read
+                    let mut sink = MemberSink::Collect(vec![]);
+                    let dest_is_source = keys.iter().any(|key| key[..] == dest[..]);
+                    if !dest_is_source || old_value.is_none() {
+                        if let Some(v) = &old_value {
+                            string_ctx
+                                .txnkv_del_with_type(&dest, KeyDecoder::decode_key_type(v))
+                                .await?;
+                        }
+                        let version = get_version_for_new(&dest, txn_rc.clone()).await?;
+                        sink = MemberSink::Write {
+                            key: &dest,
+                            version,
+                            data_type: DataType::Set,
+                        };
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let stored = merge_members(
+                        &mut txn,
+                        op,
+                        &mut cursors,
+                        keep_score,
+                        usize::MAX,
+                        &mut sink,
+                    )
+                    .await?;
+                    let version = match sink {
+                        MemberSink::Write { version, .. } => version,
+                        MemberSink::Collect(members) => {
+                            drop(txn);
+                            if let Some(v) = &old_value {
+                                string_ctx
+                                    .txnkv_del_with_type(&dest, KeyDecoder::decode_key_type(v))
+                                    .await?;
+                            }
+                            let version = get_version_for_new(&dest, txn_rc.clone()).await?;
+                            txn = txn_rc.lock().await;
+                            for (member, _) in members {
+                                let data_key =
+                                    KEY_ENCODER.encode_txnkv_set_data_key(&dest, &member, version);
+                                txn.put(data_key, vec![0]).await?;
+                            }
+                            version
+                        }
+                        MemberSink::Count => unreachable!(),
+                    };
+
+                    if stored > 0 {
+                        let sub_meta_key = KEY_ENCODER.encode_txnkv_sub_meta_key(
+                            &dest,
+                            version,
+                            gen_next_meta_index(),
+                        );
+                        txn.put(sub_meta_key, (stored as i64).to_be_bytes().to_vec())
+                            .await?;
+                        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&dest);
+                        let meta_value = KEY_ENCODER.encode_txnkv_set_meta_value(0, version, 0);
+                        txn.put(meta_key, meta_value).await?;
+                    }
+                    Ok((stored, old_value.is_some()))
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok((stored, replaced)) => {
+                if stored > 0 {
+                    notify_keyspace_event(NOTIFY_SET, op.store_event(), &udest);
+                } else if replaced {
+                    notify_keyspace_event(NOTIFY_GENERIC, "del", &udest);
+                }
+                Ok(resp_int(stored as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Move `member` from the set stored at `src` to the one at `dest`.
+    pub async fn do_async_txnkv_smove(
+        mut self,
+        src: &[u8],
+        dest: &[u8],
+        member: &[u8],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let usrc = src.to_owned();
+        let udest = dest.to_owned();
+        let src = src.to_owned();
+        let dest = dest.to_owned();
+        let member = member.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let src_version = self.txnkv_set_live_version(&txn_rc, &src).await?;
+                    let dest_version = self.txnkv_set_live_version(&txn_rc, &dest).await?;
+                    let src_version = match src_version {
+                        Some(version) => version,
+                        None => return Ok(0),
+                    };
+
+                    let mut txn = txn_rc.lock().await;
+                    let src_data_key =
+                        KEY_ENCODER.encode_txnkv_set_data_key(&src, &member, src_version);
+                    if txn.get(src_data_key.clone()).await?.is_none() {
+                        return Ok(0);
+                    }
+                    if src == dest {
+                        return Ok(1);
+                    }
+
+                    // remove the member from the source, and the source itself
+                    // if it was the last member
+                    drop(txn);
+                    let size = self.txnkv_sum_key_size(&src, src_version).await?;
+                    txn = txn_rc.lock().await;
+                    txn.delete(src_data_key).await?;
+                    if size <= 1 {
+                        txn.delete(KEY_ENCODER.encode_txnkv_meta_key(&src)).await?;
+                        let sub_meta_range =
+                            KEY_ENCODER.encode_txnkv_sub_meta_key_range(&src, src_version);
+                        let iter = txn.scan_keys(sub_meta_range, u32::MAX).await?;
+                        for k in iter {
+                            txn.delete(k).await?;
+                        }
+                    } else {
+                        txnkv_incr_set_sub_meta(&mut txn, &src, src_version, -1).await?;
+                    }
+
+                    match dest_version {
+                        Some(version) => {
+                            let data_key =
+                                KEY_ENCODER.encode_txnkv_set_data_key(&dest, &member, version);
+                            if txn.get(data_key.clone()).await?.is_none() {
+                                txn.put(data_key, vec![0]).await?;
+                                txnkv_incr_set_sub_meta(&mut txn, &dest, version, 1).await?;
+                            }
+                        }
+                        None => {
+                            drop(txn);
+                            let version = get_version_for_new(&dest, txn_rc.clone()).await?;
+                            txn = txn_rc.lock().await;
+                            let data_key =
+                                KEY_ENCODER.encode_txnkv_set_data_key(&dest, &member, version);
+                            txn.put(data_key, vec![0]).await?;
+                            txnkv_incr_set_sub_meta(&mut txn, &dest, version, 1).await?;
+                            let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&dest);
+                            let meta_value = KEY_ENCODER.encode_txnkv_set_meta_value(0, version, 0);
+                            txn.put(meta_key, meta_value).await?;
+                        }
+                    }
+                    Ok(1)
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(v) => {
+                if v > 0 && usrc != udest {
+                    notify_keyspace_event(NOTIFY_SET, "srem", &usrc);
+                    notify_keyspace_event(NOTIFY_SET, "sadd", &udest);
+                }
+                Ok(resp_int(v))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+}
+
+/// Add `delta` to a random sub meta key of the set, creating it if needed.
+async fn txnkv_incr_set_sub_meta(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    delta: i64,
+) -> AsyncResult<()> {
+    let sub_meta_key = KEY_ENCODER.encode_txnkv_sub_meta_key(key, version, gen_next_meta_index());
+    let value = txn.get(sub_meta_key.clone()).await?.map_or_else(
+        || delta,
+        |v| i64::from_be_bytes(v.try_into().unwrap()) + delta,
+    );
+    txn.put(sub_meta_key, value.to_be_bytes().to_vec()).await?;
+    Ok(())
+}
+
+/// The score of every member of a plain set, which is used as a sorted set.
+pub(crate) fn set_member_score(_value: &[u8]) -> f64 {
+    1.0
+}
+
+/// Iterates the members of a set or a sorted set in the order of their data
+/// keys, with the scores decoded from the data values by `decode_score` and
+/// multiplied by the weight. The data keys are scanned in batches so that
+/// large sets are never loaded at once.
+pub(crate) struct MemberCursor {
+    key: Vec<u8>,
+    version: u16,
+    data_type: DataType,
+    decode_score: fn(&[u8]) -> f64,
+    weight: f64,
+    from: Bound<Key>,
+    members: VecDeque<(Vec<u8>, f64)>,
+    exhausted: bool,
+}
+
+impl MemberCursor {
+    pub(crate) fn new(
+        key: &[u8],
+        version: u16,
+        data_type: DataType,
+        decode_score: fn(&[u8]) -> f64,
+    ) -> Self {
+        let mut cursor = MemberCursor {
+            key: key.to_owned(),
+            version,
+            data_type,
+            decode_score,
+            weight: 1.0,
+            from: Bound::Unbounded,
+            members: VecDeque::new(),
+            exhausted: false,
+        };
+        cursor.from = Bound::Included(cursor.data_key_bound(true));
+        cursor
+    }
+
+    /// A cursor over a missing key.
+    pub(crate) fn empty() -> Self {
+        MemberCursor {
+            key: vec![],
+            version: 0,
+            data_type: DataType::Null,
+            decode_score: set_member_score,
+            weight: 1.0,
+            from: Bound::Unbounded,
+            members: VecDeque::new(),
+            exhausted: true,
+        }
+    }
+
+    pub(crate) fn set_weight(&mut self, weight: f64) {
+        self.weight = weight;
+    }
+
+    fn data_key_bound(&self, start: bool) -> Key {
+        match (&self.data_type, start) {
+            (DataType::Zset, true) => {
+                KEY_ENCODER.encode_txnkv_zset_data_key_start(&self.key, self.version)
+            }
+            (DataType::Zset, false) => {
+                KEY_ENCODER.encode_txnkv_zset_data_key_end(&self.key, self.version)
+            }
+            (_, true) => KEY_ENCODER.encode_txnkv_set_data_key_start(&self.key, self.version),
+            (_, false) => KEY_ENCODER.encode_txnkv_set_data_key_end(&self.key, self.version),
+        }
+    }
+
+    async fn peek(&mut self, txn: &mut Transaction) -> AsyncResult<Option<&(Vec<u8>, f64)>> {
+        if self.members.is_empty() && !self.exhausted {
+            let end = self.data_key_bound(false);
+            let kvs: Vec<_> = txn
+                .scan(
+                    (self.from.clone(), Bound::Excluded(end)),
+                    SET_SCAN_BATCH_SIZE,
+                )
+                .await?
+                .collect();
+            self.exhausted = kvs.len() < SET_SCAN_BATCH_SIZE as usize;
+            if let Some(last) = kvs.last() {
+                self.from = Bound::Excluded(last.0.clone());
+            }
+            for kv in kvs {
+                // the weighted score is NaN for 0 * inf, which is used as 0
+                let score = (self.decode_score)(&kv.1) * self.weight;
+                let score = if score.is_nan() { 0.0 } else { score };
+                // sets and sorted sets share the layout of the data keys
+                let member = KeyDecoder::decode_key_set_member_from_datakey(&self.key, kv.0);
+                self.members.push_back((member, score));
+            }
+        }
+        Ok(self.members.front())
+    }
+
+    fn advance(&mut self) {
+        self.members.pop_front();
+    }
+
+    /// Skip the members before `target`, the ones not fetched yet are skipped
+    /// without being scanned.
+    fn seek(&mut self, target: &[u8]) {
+        while let Some((member, _)) = self.members.front() {
+            if &member[..] >= target {
+                return;
+            }
+            self.members.pop_front();
+        }
+        if !self.exhausted {
+            let data_key = match self.data_type {
+                DataType::Zset => {
+                    KEY_ENCODER.encode_txnkv_zset_data_key(&self.key, target, self.version)
+                }
+                _ => KEY_ENCODER.encode_txnkv_set_data_key(&self.key, target, self.version),
+            };
+            self.from = Bound::Included(data_key);
+        }
+    }
+}
+
+/// Where the merged members go.
+pub(crate) enum MemberSink<'a> {
+    Collect(Vec<(Vec<u8>, f64)>),
+    Count,
+    /// Write the members to a new set, or sorted set with their scores.
+    Write {
+        key: &'a [u8],
+        version: u16,
+        data_type: DataType,
+    },
+}
+
+impl MemberSink<'_> {
+    async fn push(
+        &mut self,
+        txn: &mut Transaction,
+        member: Vec<u8>,
+        score: f64,
+    ) -> AsyncResult<()> {
+        match self {
+            MemberSink::Collect(members) => members.push((member, score)),
+            MemberSink::Count => {}
+            MemberSink::Write {
+                key,
+                version,
+                data_type: DataType::Zset,
+            } => {
+                txnkv_zset_put_member(txn, key, *version, &member, score).await?;
+            }
+            MemberSink::Write { key, version, .. } => {
+                let data_key = KEY_ENCODER.encode_txnkv_set_data_key(key, &member, *version);
+                txn.put(data_key, vec![0]).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The scores of set members are all 1, the first one is kept.
+fn keep_score(score: f64, _: f64) -> f64 {
+    score
+}
+
+/// Merge the ordered members of the sets or sorted sets, pushing the ones in
+/// the result of the operation with their scores combined by `aggregate` to
+/// the sink until `limit` of them are found. Returns the number of the pushed
+/// members.
+pub(crate) async fn merge_members(
+    txn: &mut Transaction,
+    op: SetOperation,
+    cursors: &mut [MemberCursor],
+    aggregate: impl Fn(f64, f64) -> f64,
+    limit: usize,
+    sink: &mut MemberSink<'_>,
+) -> AsyncResult<usize> {
+    let mut count = 0;
+    while count < limit {
+        let (member, score) = match op {
+            SetOperation::Union => {
+                let mut min: Option<Vec<u8>> = None;
+                for cursor in cursors.iter_mut() {
+                    match (cursor.peek(txn).await?, &min) {
+                        (Some((m, _)), Some(min)) if m >= min => {}
+                        (Some((m, _)), _) => min = Some(m.clone()),
+                        (None, _) => {}
+                    }
+                }
+                let min = match min {
+                    Some(min) => min,
+                    None => break,
+                };
+                let mut score: Option<f64> = None;
+                for cursor in cursors.iter_mut() {
+                    if let Some((m, s)) = cursor.peek(txn).await? {
+                        if m == &min {
+                            score = Some(score.map_or(*s, |acc| aggregate(acc, *s)));
+                            cursor.advance();
+                        }
+                    }
+                }
+                (min, score.unwrap())
+            }
+            SetOperation::Inter => {
+                let (first, rest) = cursors.split_first_mut().unwrap();
+                let (target, mut score) = match first.peek(txn).await? {
+                    Some((m, s)) => (m.clone(), *s),
+                    None => break,
+                };
+                // leapfrog to the largest head until all the heads are equal
+                let mut matched = true;
+                for cursor in rest.iter_mut() {
+                    cursor.seek(&target);
+                    match cursor.peek(txn).await? {
+                        Some((m, s)) if m == &target => score = aggregate(score, *s),
+                        Some((m, _)) => {
+                            let m = m.clone();
+                            first.seek(&m);
+                            matched = false;
+                            break;
+                        }
+                        None => return Ok(count),
+                    }
+                }
+                if !matched {
+                    continue;
+                }
+                first.advance();
+                for cursor in rest.iter_mut() {
+                    cursor.advance();
+                }
+                (target, score)
+            }
+            SetOperation::Diff => {
+                let (first, rest) = cursors.split_first_mut().unwrap();
+                let (target, score) = match first.peek(txn).await? {
+                    Some((m, s)) => (m.clone(), *s),
+                    None => break,
+                };
+                first.advance();
+                let mut found = false;
+                for cursor in rest.iter_mut() {
+                    cursor.seek(&target);
+                    if matches!(cursor.peek(txn).await?, Some((m, _)) if m == &target) {
+                        found = true;
+                        break;
+                    }
+                }
+                if found {
+                    continue;
+                }
+                (target, score)
+            }
+        };
+        sink.push(txn, member, score).await?;
+        count += 1;
     }
+    Ok(count)
 }
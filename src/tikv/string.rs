@@ -11,20 +11,36 @@ use crate::{
 use ::futures::future::FutureExt;
 use regex::bytes::Regex;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::str;
 use std::sync::Arc;
 use tikv_client::{BoundRange, Key, KvPair, Transaction, Value};
 use tokio::sync::Mutex;
 
+use super::client::get_version_for_new_locked;
 use super::errors::*;
 use super::{get_client, get_txn_client};
 use super::{hash::HashCommandCtx, list::ListCommandCtx, set::SetCommandCtx, zset::ZsetCommandCtx};
 use crate::utils::{
-    key_is_expired, resp_err, resp_int, resp_ok_ignore, resp_str, sleep, ttl_from_timestamp,
+    float_to_string, key_is_expired, resp_err, resp_int, resp_ok_ignore, resp_str, sleep,
+    ttl_from_timestamp,
 };
 use bytes::Bytes;
 
+use crate::config::{
+    async_del_string_threshold_or_default, async_deletion_enabled_or_default,
+    async_expire_string_threshold_or_default,
+};
 use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::notify::{notify_keyspace_event, NOTIFY_EXPIRED, NOTIFY_GENERIC, NOTIFY_STRING};
+
+/// Strings can not grow beyond 512MB, same as the proto-max-bulk-len of redis.
+pub(crate) const STRING_MAX_SIZE: usize = 512 * 1024 * 1024;
+
+/// Strings growing beyond one chunk by bit or range writes are stored in
+/// chunks of this size under separate data keys, so updating a few bytes
+/// doesn't rewrite the whole value. A missing chunk reads as zeros.
+pub(crate) const STRING_CHUNK_SIZE: usize = 4096;
 
 #[derive(Clone)]
 pub struct StringCommandCtx {
@@ -36,7 +52,7 @@ impl StringCommandCtx {
         StringCommandCtx { txn }
     }
 
-    pub async fn do_async_rawkv_get(&self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_rawkv_get(&self, key: &[u8]) -> AsyncResult<Frame> {
         let client = get_client()?;
         let ekey = KEY_ENCODER.encode_rawkv_string(key);
         match client.get(ekey).await? {
@@ -45,7 +61,7 @@ impl StringCommandCtx {
         }
     }
 
-    pub async fn do_async_txnkv_get(mut self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_get(mut self, key: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekey = KEY_ENCODER.encode_txnkv_string(key);
         let key = key.to_owned();
@@ -81,7 +97,7 @@ impl StringCommandCtx {
                                 return Ok(resp_nil());
                             }
 
-                            let data = KeyDecoder::decode_key_string_value(&val);
+                            let data = read_string(&mut txn, &key, &val).await?;
                             Ok(resp_bulk(data))
                         }
                         None => Ok(resp_nil()),
@@ -92,7 +108,7 @@ impl StringCommandCtx {
             .await
     }
 
-    pub async fn do_async_rawkv_type(&self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_rawkv_type(&self, key: &[u8]) -> AsyncResult<Frame> {
         let client = get_client()?;
         let ekey = KEY_ENCODER.encode_rawkv_string(key);
 
@@ -102,7 +118,7 @@ impl StringCommandCtx {
         }
     }
 
-    pub async fn do_async_txnkv_type(mut self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_type(mut self, key: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekey = KEY_ENCODER.encode_txnkv_string(key);
         let key = key.to_owned();
@@ -142,7 +158,7 @@ impl StringCommandCtx {
             .await
     }
 
-    pub async fn do_async_rawkv_strlen(&self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_rawkv_strlen(&self, key: &[u8]) -> AsyncResult<Frame> {
         let client = get_client()?;
         let ekey = KEY_ENCODER.encode_rawkv_string(key);
         match client.get(ekey).await? {
@@ -151,7 +167,7 @@ impl StringCommandCtx {
         }
     }
 
-    pub async fn do_async_txnkv_strlen(mut self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_strlen(mut self, key: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekey = KEY_ENCODER.encode_txnkv_string(key);
         let key = key.to_owned();
@@ -187,8 +203,7 @@ impl StringCommandCtx {
                                 return Ok(resp_int(0));
                             }
 
-                            let data = KeyDecoder::decode_key_string_value(&val);
-                            Ok(resp_int(data.len() as i64))
+                            Ok(resp_int(string_len(&val) as i64))
                         }
                         None => Ok(resp_int(0)),
                     }
@@ -198,7 +213,7 @@ impl StringCommandCtx {
             .await
     }
 
-    pub async fn do_async_rawkv_put(self, key: &str, val: &Bytes) -> AsyncResult<Frame> {
+    pub async fn do_async_rawkv_put(self, key: &[u8], val: &Bytes) -> AsyncResult<Frame> {
         let client = get_client()?;
         let ekey = KEY_ENCODER.encode_rawkv_string(key);
         client.put(ekey, val.to_vec()).await?;
@@ -207,30 +222,47 @@ impl StringCommandCtx {
 
     pub async fn do_async_txnkv_put(
         mut self,
-        key: &str,
+        key: &[u8],
         val: &Bytes,
         timestamp: u64,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekey = KEY_ENCODER.encode_txnkv_string(key);
         let eval = KEY_ENCODER.encode_txnkv_string_value(&mut val.to_vec(), timestamp);
+        let ukey = key;
+        let key = key.to_owned();
         let resp = client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
                         self.txn = Some(txn_rc.clone());
                     }
+                    let old_value = txn_rc.lock().await.get(ekey.clone()).await?;
+                    if let Some(old_value) = old_value {
+                        self.txnkv_clear_for_overwrite(&txn_rc, &key, &old_value)
+                            .await?;
+                    }
                     let mut txn = txn_rc.lock().await;
                     txn.put(ekey, eval).await?;
+                    if timestamp > 0 {
+                        let index_key = KEY_ENCODER.encode_txnkv_expire_index_key(&key, timestamp);
+                        txn.put(index_key, vec![]).await?;
+                    }
                     Ok(())
                 }
                 .boxed()
             })
             .await;
+        if resp.is_ok() {
+            notify_keyspace_event(NOTIFY_STRING, "set", ukey);
+            if timestamp > 0 {
+                notify_keyspace_event(NOTIFY_GENERIC, "expire", ukey);
+            }
+        }
         resp.map(resp_ok_ignore)
     }
 
-    pub async fn do_async_rawkv_batch_get(self, keys: &[String]) -> AsyncResult<Frame> {
+    pub async fn do_async_rawkv_batch_get(self, keys: &[Bytes]) -> AsyncResult<Frame> {
         let client = get_client()?;
         let ekeys = KEY_ENCODER.encode_rawkv_strings(keys);
         let result = client.batch_get(ekeys.clone()).await?;
@@ -249,9 +281,10 @@ impl StringCommandCtx {
         Ok(Frame::Array(values))
     }
 
-    pub async fn do_async_txnkv_batch_get(mut self, keys: &[String]) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_batch_get(mut self, keys: &[Bytes]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekeys = KEY_ENCODER.encode_txnkv_strings(keys);
+        let keys = keys.to_owned();
 
         // if get is executed from a new transaction, we can do get with latest commit
         if self.txn.is_none() {
@@ -271,23 +304,23 @@ impl StringCommandCtx {
                     let ret: HashMap<Key, Value> =
                         result.into_iter().map(|pair| (pair.0, pair.1)).collect();
 
-                    let values: Vec<Frame> = ekeys
-                        .into_iter()
-                        .map(|k| {
-                            let data = ret.get(k.as_ref());
-                            match data {
-                                Some(val) => {
-                                    let ttl = KeyDecoder::decode_key_ttl(val);
-                                    if key_is_expired(ttl) {
-                                        return Frame::Null;
-                                    }
-                                    let data = KeyDecoder::decode_key_string_value(val);
-                                    Frame::Bulk(data.into())
+                    let mut values = Vec::with_capacity(keys.len());
+                    for (key, ekey) in keys.iter().zip(ekeys) {
+                        let frame = match ret.get(ekey.as_ref()) {
+                            Some(val) => {
+                                let ttl = KeyDecoder::decode_key_ttl(val);
+                                if key_is_expired(ttl)
+                                    || !matches!(KeyDecoder::decode_key_type(val), DataType::String)
+                                {
+                                    Frame::Null
+                                } else {
+                                    Frame::Bulk(read_string(&mut txn, key, val).await?.into())
                                 }
-                                None => Frame::Null,
                             }
-                        })
-                        .collect();
+                            None => Frame::Null,
+                        };
+                        values.push(frame);
+                    }
                     Ok(Frame::Array(values))
                 }
                 .boxed()
@@ -295,6 +328,77 @@ impl StringCommandCtx {
             .await
     }
 
+    /// Read `keys` with `GET` semantics from one snapshot, this serves a run
+    /// of pipelined read commands with a single `batch_get`.
+    ///
+    /// The reply of each key is returned in order, a key holding a non-string
+    /// value gets a wrong type error.
+    pub async fn do_async_txnkv_pipeline_get(mut self, keys: &[Bytes]) -> AsyncResult<Vec<Frame>> {
+        let mut client = get_txn_client()?;
+        let ekeys = KEY_ENCODER.encode_txnkv_strings(keys);
+        let keys = keys.to_owned();
+
+        // the read only snapshot below is never committed, the expired keys
+        // are deleted afterwards in the transaction of the caller or a new one
+        let outer_txn = self.txn.clone();
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let (values, expired_keys) = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    let result = txn.batch_get(ekeys.clone()).await?;
+                    let ret: HashMap<Key, Value> =
+                        result.into_iter().map(|pair| (pair.0, pair.1)).collect();
+
+                    let mut values = Vec::with_capacity(keys.len());
+                    let mut expired_keys = vec![];
+                    for (key, ekey) in keys.iter().zip(ekeys) {
+                        let frame = match ret.get(ekey.as_ref()) {
+                            Some(val) => {
+                                let dt = KeyDecoder::decode_key_type(val);
+                                let ttl = KeyDecoder::decode_key_ttl(val);
+                                if !matches!(dt, DataType::String) {
+                                    resp_err(REDIS_WRONG_TYPE_ERR)
+                                } else if key_is_expired(ttl) {
+                                    expired_keys.push(key.clone());
+                                    resp_nil()
+                                } else {
+                                    resp_bulk(read_string(&mut txn, key, val).await?)
+                                }
+                            }
+                            None => resp_nil(),
+                        };
+                        values.push(frame);
+                    }
+
+                    Ok((values, expired_keys))
+                }
+                .boxed()
+            })
+            .await?;
+
+        if !expired_keys.is_empty() {
+            client
+                .exec_in_txn(outer_txn, |txn_rc| {
+                    async move {
+                        for key in expired_keys {
+                            StringCommandCtx::new(Some(txn_rc.clone()))
+                                .do_async_txnkv_string_expire_if_needed(&key)
+                                .await?;
+                        }
+                        Ok(())
+                    }
+                    .boxed()
+                })
+                .await?;
+        }
+        Ok(values)
+    }
+
     pub async fn do_async_rawkv_batch_put(self, kvs: Vec<KvPair>) -> AsyncResult<Frame> {
         let client = get_client()?;
         client.batch_put(kvs).await?;
@@ -309,24 +413,40 @@ impl StringCommandCtx {
                     if self.txn.is_none() {
                         self.txn = Some(txn_rc.clone());
                     }
+                    let ekeys: Vec<Key> = kvs.iter().map(|kv| kv.0.clone()).collect();
+                    let old_values: Vec<KvPair> =
+                        txn_rc.lock().await.batch_get(ekeys).await?.collect();
+                    for old_value in old_values {
+                        let key = KeyDecoder::decode_key_userkey_from_metakey(&old_value.0).0;
+                        self.txnkv_clear_for_overwrite(&txn_rc, &key, &old_value.1)
+                            .await?;
+                    }
+
                     let mut txn = txn_rc.lock().await;
+                    let mut keys = Vec::with_capacity(kvs.len());
                     for kv in kvs {
+                        keys.push(KeyDecoder::decode_key_userkey_from_metakey(&kv.0).0);
                         txn.put(kv.0, kv.1).await?;
                     }
-                    Ok(())
+                    Ok(keys)
                 }
                 .boxed()
             })
             .await;
         match resp {
-            Ok(_) => Ok(resp_ok()),
+            Ok(keys) => {
+                for key in keys {
+                    notify_keyspace_event(NOTIFY_STRING, "set", &key);
+                }
+                Ok(resp_ok())
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
     pub async fn do_async_rawkv_put_not_exists(
         self,
-        key: &str,
+        key: &[u8],
         value: &Bytes,
     ) -> AsyncResult<Frame> {
         let client = get_client()?;
@@ -341,11 +461,12 @@ impl StringCommandCtx {
 
     pub async fn do_async_txnkv_put_not_exists(
         mut self,
-        key: &str,
+        key: &[u8],
         value: &Bytes,
         return_number: bool,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let ekey = KEY_ENCODER.encode_txnkv_string(&key);
         let eval = KEY_ENCODER.encode_txnkv_string_value(&mut value.to_vec(), 0);
@@ -361,8 +482,9 @@ impl StringCommandCtx {
                     if let Some(ref v) = old_value {
                         let ttl = KeyDecoder::decode_key_ttl(v);
                         if key_is_expired(ttl) {
-                            // no need to delete, just overwrite
-                            txn.put(ekey, eval).await?;
+                            drop(txn);
+                            self.txnkv_clear_for_overwrite(&txn_rc, &key, v).await?;
+                            txn_rc.lock().await.put(ekey, eval).await?;
                             return Ok(1);
                         }
                         Ok(0)
@@ -375,6 +497,9 @@ impl StringCommandCtx {
             })
             .await;
 
+        if let Ok(1) = resp {
+            notify_keyspace_event(NOTIFY_STRING, "set", ukey);
+        }
         match resp {
             Ok(n) => {
                 if return_number {
@@ -390,7 +515,7 @@ impl StringCommandCtx {
         }
     }
 
-    pub async fn do_async_rawkv_exists(self, keys: &[String]) -> AsyncResult<Frame> {
+    pub async fn do_async_rawkv_exists(self, keys: &[Bytes]) -> AsyncResult<Frame> {
         let client = get_client()?;
         let ekeys = KEY_ENCODER.encode_rawkv_strings(keys);
         let result = client.batch_get(ekeys).await?;
@@ -398,7 +523,7 @@ impl StringCommandCtx {
         Ok(resp_int(num_items as i64))
     }
 
-    pub async fn do_async_txnkv_exists(mut self, keys: &Vec<String>) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_exists(mut self, keys: &Vec<Bytes>) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let keys = keys.to_owned();
 
@@ -446,7 +571,7 @@ impl StringCommandCtx {
             .await
     }
 
-    pub async fn do_async_rawkv_incr(self, key: &str, step: i64) -> AsyncResult<Frame> {
+    pub async fn do_async_rawkv_incr(self, key: &[u8], step: i64) -> AsyncResult<Frame> {
         let client = get_client()?;
         let ekey = KEY_ENCODER.encode_rawkv_string(key);
         let mut new_int: i64 = 0;
@@ -483,9 +608,10 @@ impl StringCommandCtx {
         }
     }
 
-    pub async fn do_async_txnkv_incr(mut self, key: &str, step: i64) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_incr(mut self, key: &[u8], step: i64) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let ukey = key;
         let key = key.to_owned();
 
         let resp = client
@@ -495,11 +621,13 @@ impl StringCommandCtx {
                         self.txn = Some(txn_rc.clone())
                     }
                     let prev_int;
+                    let mut ttl = 0;
                     let mut txn = txn_rc.lock().await;
                     match txn.get(ekey.clone()).await? {
                         Some(val) => {
-                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            ttl = KeyDecoder::decode_key_ttl(&val);
                             if key_is_expired(ttl) {
+                                ttl = 0;
                                 drop(txn);
                                 self.clone()
                                     .do_async_txnkv_string_expire_if_needed(&key)
@@ -507,6 +635,13 @@ impl StringCommandCtx {
                                 txn = txn_rc.lock().await;
                                 prev_int = 0;
                             } else {
+                                if !matches!(KeyDecoder::decode_key_type(&val), DataType::String) {
+                                    return Err(REDIS_WRONG_TYPE_ERR);
+                                }
+                                // a chunked string is too long to be an integer
+                                if KeyDecoder::decode_key_string_is_chunked(&val) {
+                                    return Err(REDIS_VALUE_IS_NOT_INTEGER_ERR);
+                                }
                                 let real_value = KeyDecoder::decode_key_string_slice(&val);
                                 prev_int = str::from_utf8(real_value)
                                     .map_err(RTError::to_is_not_integer_error)?
@@ -520,8 +655,8 @@ impl StringCommandCtx {
 
                     let new_int = prev_int + step;
                     let new_val = new_int.to_string();
-                    let eval =
-                        KEY_ENCODER.encode_txnkv_string_value(&mut new_val.as_bytes().to_vec(), 0);
+                    let eval = KEY_ENCODER
+                        .encode_txnkv_string_value(&mut new_val.as_bytes().to_vec(), ttl);
                     txn.put(ekey, eval).await?;
                     Ok(new_int)
                 }
@@ -530,12 +665,601 @@ impl StringCommandCtx {
             .await;
 
         match resp {
-            Ok(n) => Ok(resp_int(n)),
+            Ok(n) => {
+                notify_keyspace_event(NOTIFY_STRING, "incrby", ukey);
+                Ok(resp_int(n))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_incr_float(mut self, key: &[u8], step: f64) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let ukey = key;
+        let key = key.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone())
+                    }
+                    let (prev_float, ttl) = match self.txnkv_get_live_value(&txn_rc, &key).await? {
+                        Some(val) => {
+                            if !matches!(KeyDecoder::decode_key_type(&val), DataType::String) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+                            if KeyDecoder::decode_key_string_is_chunked(&val) {
+                                return Err(REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR);
+                            }
+                            let real_value = KeyDecoder::decode_key_string_slice(&val);
+                            let prev_float = str::from_utf8(real_value)
+                                .map_err(|_| REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR)?
+                                .parse::<f64>()?;
+                            if !prev_float.is_finite() {
+                                return Err(REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR);
+                            }
+                            (prev_float, KeyDecoder::decode_key_ttl(&val))
+                        }
+                        None => (0f64, 0),
+                    };
+
+                    let new_float = prev_float + step;
+                    if !new_float.is_finite() {
+                        return Err(REDIS_INCR_NAN_OR_INFINITY_ERR);
+                    }
+                    let new_val = float_to_string(new_float);
+                    let eval = KEY_ENCODER.encode_txnkv_string_slice(new_val.as_bytes(), ttl);
+                    txn_rc.lock().await.put(ekey, eval).await?;
+                    Ok(new_val)
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(v) => {
+                notify_keyspace_event(NOTIFY_STRING, "incrbyfloat", ukey);
+                Ok(resp_bulk(v.into_bytes()))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    pub async fn do_async_txnkv_string_del(mut self, key: &str) -> AsyncResult<i64> {
+    /// Set `key` to `value` honoring the options of the SET command.
+    ///
+    /// `timestamp` is the absolute expire time in milliseconds, `Some(0)` drops
+    /// the old ttl and `None` retains it (KEEPTTL).
+    pub async fn do_async_txnkv_set(
+        mut self,
+        key: &[u8],
+        value: &Bytes,
+        timestamp: Option<u64>,
+        nx: bool,
+        xx: bool,
+        get: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let ukey = key;
+        let key = key.to_owned();
+        let value = value.to_vec();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let old_value = self.txnkv_get_live_value(&txn_rc, &key).await?;
+                    let old_string = match &old_value {
+                        Some(v) if get => {
+                            if !matches!(KeyDecoder::decode_key_type(v), DataType::String) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+                            Some(read_string(&mut *txn_rc.lock().await, &key, v).await?)
+                        }
+                        _ => None,
+                    };
+
+                    let exists = old_value.is_some();
+                    if (nx && exists) || (xx && !exists) {
+                        return Ok((old_string, None));
+                    }
+
+                    let ts = match timestamp {
+                        Some(ts) => ts,
+                        None => old_value
+                            .as_ref()
+                            .map_or(0, |v| KeyDecoder::decode_key_ttl(v)),
+                    };
+                    if let Some(old_value) = &old_value {
+                        self.txnkv_clear_for_overwrite(&txn_rc, &key, old_value)
+                            .await?;
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    txn.put(ekey, KEY_ENCODER.encode_txnkv_string_slice(&value, ts))
+                        .await?;
+                    if ts > 0 {
+                        let index_key = KEY_ENCODER.encode_txnkv_expire_index_key(&key, ts);
+                        txn.put(index_key, vec![]).await?;
+                    }
+                    Ok((old_string, Some(ts)))
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok((old_string, set_ts)) => {
+                if let Some(ts) = set_ts {
+                    notify_keyspace_event(NOTIFY_STRING, "set", ukey);
+                    if ts > 0 {
+                        notify_keyspace_event(NOTIFY_GENERIC, "expire", ukey);
+                    }
+                }
+                if get {
+                    Ok(old_string.map_or_else(resp_nil, resp_bulk))
+                } else if set_ts.is_some() {
+                    Ok(resp_ok())
+                } else {
+                    Ok(resp_nil())
+                }
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_getdel(mut self, key: &[u8]) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let ukey = key;
+        let key = key.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    match self.txnkv_get_live_value(&txn_rc, &key).await? {
+                        Some(val) => {
+                            if !matches!(KeyDecoder::decode_key_type(&val), DataType::String) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+                            let mut txn = txn_rc.lock().await;
+                            let data = read_string(&mut txn, &key, &val).await?;
+                            delete_string_chunks(
+                                &mut txn,
+                                &key,
+                                &val,
+                                async_del_string_threshold_or_default(),
+                            )
+                            .await?;
+                            txn.delete(ekey).await?;
+                            Ok(Some(data))
+                        }
+                        None => Ok(None),
+                    }
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(Some(v)) => {
+                notify_keyspace_event(NOTIFY_GENERIC, "del", ukey);
+                Ok(resp_bulk(v))
+            }
+            Ok(None) => Ok(resp_nil()),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Get the value of `key` and optionally change its ttl.
+    ///
+    /// `timestamp` is the new absolute expire time in milliseconds, `Some(0)`
+    /// persists the key and `None` leaves the ttl untouched.
+    pub async fn do_async_txnkv_getex(
+        mut self,
+        key: &[u8],
+        timestamp: Option<u64>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let ukey = key;
+        let key = key.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let val = match self.txnkv_get_live_value(&txn_rc, &key).await? {
+                        Some(val) => val,
+                        None => return Ok((None, None)),
+                    };
+                    if !matches!(KeyDecoder::decode_key_type(&val), DataType::String) {
+                        return Err(REDIS_WRONG_TYPE_ERR);
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let data = read_string(&mut txn, &key, &val).await?;
+                    let ttl = KeyDecoder::decode_key_ttl(&val);
+
+                    let event = match timestamp {
+                        Some(0) if ttl == 0 => None,
+                        Some(0) => {
+                            txn.put(ekey, string_meta_with_ttl(&val, 0)).await?;
+                            Some("persist")
+                        }
+                        // an expire time in the past deletes the key
+                        Some(ts) if key_is_expired(ts) => {
+                            delete_string_chunks(
+                                &mut txn,
+                                &key,
+                                &val,
+                                async_del_string_threshold_or_default(),
+                            )
+                            .await?;
+                            txn.delete(ekey).await?;
+                            Some("del")
+                        }
+                        Some(ts) => {
+                            txn.put(ekey, string_meta_with_ttl(&val, ts)).await?;
+                            let index_key = KEY_ENCODER.encode_txnkv_expire_index_key(&key, ts);
+                            txn.put(index_key, vec![]).await?;
+                            Some("expire")
+                        }
+                        None => None,
+                    };
+                    Ok((Some(data), event))
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok((data, event)) => {
+                if let Some(event) = event {
+                    notify_keyspace_event(NOTIFY_GENERIC, event, ukey);
+                }
+                Ok(data.map_or_else(resp_nil, resp_bulk))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_append(mut self, key: &[u8], value: &Bytes) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ukey = key;
+        let key = key.to_owned();
+        let value = value.to_vec();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let old_value = self.txnkv_get_live_value(&txn_rc, &key).await?;
+                    if let Some(val) = &old_value {
+                        if !matches!(KeyDecoder::decode_key_type(val), DataType::String) {
+                            return Err(REDIS_WRONG_TYPE_ERR);
+                        }
+                    }
+                    let len = old_value.as_deref().map_or(0, string_len);
+                    if len + value.len() > STRING_MAX_SIZE {
+                        return Err(REDIS_STRING_EXCEEDS_MAX_SIZE_ERR);
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    write_string_range(&mut txn, &key, old_value.as_deref(), len, &value).await?;
+                    Ok(len + value.len())
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(len) => {
+                notify_keyspace_event(NOTIFY_STRING, "append", ukey);
+                Ok(resp_int(len as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_getrange(
+        mut self,
+        key: &[u8],
+        start: i64,
+        end: i64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+
+        // if get is executed from a new transaction, we can do get with latest commit
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let val = match self.txnkv_get_live_value(&txn_rc, &key).await? {
+                        Some(val) => val,
+                        None => return Ok(resp_bulk(vec![])),
+                    };
+                    if !matches!(KeyDecoder::decode_key_type(&val), DataType::String) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
+
+                    let len = string_len(&val) as i64;
+                    if len == 0 || (start < 0 && end < 0 && start > end) {
+                        return Ok(resp_bulk(vec![]));
+                    }
+                    let start = if start < 0 {
+                        (len + start).max(0)
+                    } else {
+                        start
+                    };
+                    let end = if end < 0 {
+                        (len + end).max(0)
+                    } else {
+                        end.min(len - 1)
+                    };
+                    if start > end {
+                        return Ok(resp_bulk(vec![]));
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let data =
+                        read_string_range(&mut txn, &key, &val, start as usize, end as usize + 1)
+                            .await?;
+                    Ok(resp_bulk(data))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    pub async fn do_async_txnkv_setrange(
+        mut self,
+        key: &[u8],
+        offset: usize,
+        value: &Bytes,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ukey = key;
+        let key = key.to_owned();
+        let value = value.to_vec();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let old_value = self.txnkv_get_live_value(&txn_rc, &key).await?;
+                    if let Some(val) = &old_value {
+                        if !matches!(KeyDecoder::decode_key_type(val), DataType::String) {
+                            return Err(REDIS_WRONG_TYPE_ERR);
+                        }
+                    }
+                    let len = old_value.as_deref().map_or(0, string_len);
+                    // an empty value never creates or extends the string
+                    if value.is_empty() {
+                        return Ok((len, false));
+                    }
+                    if offset + value.len() > STRING_MAX_SIZE {
+                        return Err(REDIS_STRING_EXCEEDS_MAX_SIZE_ERR);
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    write_string_range(&mut txn, &key, old_value.as_deref(), offset, &value)
+                        .await?;
+                    Ok((len.max(offset + value.len()), true))
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok((len, modified)) => {
+                if modified {
+                    notify_keyspace_event(NOTIFY_STRING, "setrange", ukey);
+                }
+                Ok(resp_int(len as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Set all the keys only if none of them exists, reply 1 if they are set.
+    pub async fn do_async_txnkv_batch_put_not_exists(
+        mut self,
+        keys: &[Bytes],
+        vals: &[Bytes],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ukeys = keys;
+        let keys = keys.to_owned();
+        let vals = vals.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let ekeys = KEY_ENCODER.encode_txnkv_strings(&keys);
+                    let kv_map: HashMap<Key, Value> = txn_rc
+                        .lock()
+                        .await
+                        .batch_get(ekeys.clone())
+                        .await?
+                        .map(|pair| (pair.0, pair.1))
+                        .collect();
+
+                    let mut expired = vec![];
+                    for (idx, ekey) in ekeys.iter().enumerate() {
+                        if let Some(v) = kv_map.get(ekey) {
+                            if !key_is_expired(KeyDecoder::decode_key_ttl(v)) {
+                                return Ok(0);
+                            }
+                            expired.push((idx, KeyDecoder::decode_key_type(v)));
+                        }
+                    }
+                    for (idx, dt) in expired {
+                        self.txnkv_expire_if_needed_with_type(&keys[idx], dt)
+                            .await?;
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    for (ekey, val) in ekeys.into_iter().zip(vals.iter()) {
+                        let eval = KEY_ENCODER.encode_txnkv_string_slice(val, 0);
+                        txn.put(ekey, eval).await?;
+                    }
+                    Ok(1)
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(n) => {
+                if n == 1 {
+                    for key in ukeys {
+                        notify_keyspace_event(NOTIFY_STRING, "set", key);
+                    }
+                }
+                Ok(resp_int(n))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Clear the data of the old value of `key` which is about to be
+    /// overwritten by a plain string. Nothing is left to clear for a plain
+    /// string, the chunks of a string are deleted like those of a deleted
+    /// string, while the data of another type is handed to the async deletion
+    /// like a deleted key, so the new value does not wait for it and the type
+    /// gets a new version next time.
+    pub(crate) async fn txnkv_clear_for_overwrite(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        key: &[u8],
+        old_value: &[u8],
+    ) -> AsyncResult<()> {
+        match KeyDecoder::decode_key_type(old_value) {
+            DataType::String => {
+                let threshold = async_del_string_threshold_or_default();
+                delete_string_chunks(&mut *txn_rc.lock().await, key, old_value, threshold).await
+            }
+            DataType::Null => Ok(()),
+            dt if async_deletion_enabled_or_default() => {
+                let version = KeyDecoder::decode_key_version(old_value);
+                let mut txn = txn_rc.lock().await;
+                let gc_key = KEY_ENCODER.encode_txnkv_gc_key(key);
+                txn.put(gc_key, version.to_be_bytes()).await?;
+
+                let gc_version_key = KEY_ENCODER.encode_txnkv_gc_version_key(key, version);
+                txn.put(gc_version_key, vec![KEY_ENCODER.get_type_bytes(dt)])
+                    .await?;
+                Ok(())
+            }
+            dt => self.txnkv_del_with_type(key, dt).await,
+        }
+    }
+
+    /// Read the meta value of `key` inside the transaction, removing it
+    /// first if it has expired.
+    pub(crate) async fn txnkv_get_live_value(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        key: &[u8],
+    ) -> AsyncResult<Option<Value>> {
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let value = txn_rc.lock().await.get(ekey).await?;
+        match value {
+            Some(v) if key_is_expired(KeyDecoder::decode_key_ttl(&v)) => {
+                self.txnkv_expire_if_needed_with_type(key, KeyDecoder::decode_key_type(&v))
+                    .await?;
+                Ok(None)
+            }
+            v => Ok(v),
+        }
+    }
+
+    async fn txnkv_expire_if_needed_with_type(&self, key: &[u8], dt: DataType) -> AsyncResult<()> {
+        match dt {
+            DataType::String => {
+                self.clone()
+                    .do_async_txnkv_string_expire_if_needed(key)
+                    .await?;
+            }
+            DataType::Hash => {
+                HashCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_hash_expire_if_needed(key)
+                    .await?;
+            }
+            DataType::Set => {
+                SetCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_set_expire_if_needed(key)
+                    .await?;
+            }
+            DataType::List => {
+                ListCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_list_expire_if_needed(key)
+                    .await?;
+            }
+            DataType::Zset => {
+                ZsetCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_zset_expire_if_needed(key)
+                    .await?;
+            }
+            DataType::Null => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn txnkv_del_with_type(&self, key: &[u8], dt: DataType) -> AsyncResult<()> {
+        match dt {
+            DataType::String => {
+                self.clone().do_async_txnkv_string_del(key).await?;
+            }
+            DataType::Hash => {
+                HashCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_hash_del(key)
+                    .await?;
+            }
+            DataType::List => {
+                ListCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_list_del(key)
+                    .await?;
+            }
+            DataType::Set => {
+                SetCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_set_del(key)
+                    .await?;
+            }
+            DataType::Zset => {
+                ZsetCommandCtx::new(self.txn.clone())
+                    .do_async_txnk_zset_del(key)
+                    .await?;
+            }
+            DataType::Null => {}
+        }
+        Ok(())
+    }
+
+    pub async fn do_async_txnkv_string_del(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
 
@@ -547,7 +1271,14 @@ impl StringCommandCtx {
                     }
                     let mut txn = txn_rc.lock().await;
                     let ekey = KEY_ENCODER.encode_txnkv_string(&key);
-                    if (txn.get(ekey.to_owned()).await?).is_some() {
+                    if let Some(v) = txn.get(ekey.to_owned()).await? {
+                        delete_string_chunks(
+                            &mut txn,
+                            &key,
+                            &v,
+                            async_del_string_threshold_or_default(),
+                        )
+                        .await?;
                         txn.delete(ekey).await?;
                         return Ok(1);
                     }
@@ -558,11 +1289,12 @@ impl StringCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_string_expire_if_needed(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnkv_string_expire_if_needed(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
 
-        client
+        let resp = client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
@@ -573,6 +1305,13 @@ impl StringCommandCtx {
                     if let Some(v) = txn.get(ekey.to_owned()).await? {
                         let ttl = KeyDecoder::decode_key_ttl(&v);
                         if key_is_expired(ttl) {
+                            delete_string_chunks(
+                                &mut txn,
+                                &key,
+                                &v,
+                                async_expire_string_threshold_or_default(),
+                            )
+                            .await?;
                             txn.delete(ekey).await?;
                             REMOVED_EXPIRED_KEY_COUNTER
                                 .with_label_values(&["string"])
@@ -584,11 +1323,16 @@ impl StringCommandCtx {
                 }
                 .boxed()
             })
-            .await
+            .await;
+        if let Ok(1) = resp {
+            notify_keyspace_event(NOTIFY_EXPIRED, "expired", ukey);
+        }
+        resp
     }
 
-    pub async fn do_async_txnkv_expire(mut self, key: &str, timestamp: u64) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_expire(mut self, key: &[u8], timestamp: u64) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let ekey = KEY_ENCODER.encode_txnkv_string(&key);
 
@@ -610,7 +1354,7 @@ impl StringCommandCtx {
                             let dt = KeyDecoder::decode_key_type(&meta_value);
                             let version = KeyDecoder::decode_key_version(&meta_value);
 
-                            match dt {
+                            let new_meta_value = match dt {
                                 DataType::String => {
                                     // check key expired
                                     if key_is_expired(ttl) {
@@ -618,11 +1362,7 @@ impl StringCommandCtx {
                                         self.do_async_txnkv_string_expire_if_needed(&key).await?;
                                         return Ok(0);
                                     }
-                                    let value = KeyDecoder::decode_key_string_slice(&meta_value);
-                                    let new_meta_value =
-                                        KEY_ENCODER.encode_txnkv_string_slice(value, timestamp);
-                                    txn.put(ekey, new_meta_value).await?;
-                                    Ok(1)
+                                    string_meta_with_ttl(&meta_value, timestamp)
                                 }
                                 DataType::Hash => {
                                     if key_is_expired(ttl) {
@@ -632,10 +1372,7 @@ impl StringCommandCtx {
                                             .await?;
                                         return Ok(0);
                                     }
-                                    let new_meta_value = KEY_ENCODER
-                                        .encode_txnkv_hash_meta_value(timestamp, version, 0);
-                                    txn.put(ekey, new_meta_value).await?;
-                                    Ok(1)
+                                    KEY_ENCODER.encode_txnkv_hash_meta_value(timestamp, version, 0)
                                 }
                                 DataType::List => {
                                     if key_is_expired(ttl) {
@@ -645,13 +1382,11 @@ impl StringCommandCtx {
                                             .await?;
                                         return Ok(0);
                                     }
-                                    let (_, version, left, right) =
+                                    let (_, version, left, right, len) =
                                         KeyDecoder::decode_key_list_meta(&meta_value);
-                                    let new_meta_value = KEY_ENCODER.encode_txnkv_list_meta_value(
-                                        timestamp, version, left, right,
-                                    );
-                                    txn.put(ekey, new_meta_value).await?;
-                                    Ok(1)
+                                    KEY_ENCODER.encode_txnkv_list_meta_value(
+                                        timestamp, version, left, right, len,
+                                    )
                                 }
                                 DataType::Set => {
                                     if key_is_expired(ttl) {
@@ -661,10 +1396,7 @@ impl StringCommandCtx {
                                             .await?;
                                         return Ok(0);
                                     }
-                                    let new_meta_value = KEY_ENCODER
-                                        .encode_txnkv_set_meta_value(timestamp, version, 0);
-                                    txn.put(ekey, new_meta_value).await?;
-                                    Ok(1)
+                                    KEY_ENCODER.encode_txnkv_set_meta_value(timestamp, version, 0)
                                 }
                                 DataType::Zset => {
                                     if key_is_expired(ttl) {
@@ -674,13 +1406,18 @@ impl StringCommandCtx {
                                             .await?;
                                         return Ok(0);
                                     }
-                                    let new_meta_value = KEY_ENCODER
-                                        .encode_txnkv_zset_meta_value(timestamp, version, 0);
-                                    txn.put(ekey, new_meta_value).await?;
-                                    Ok(1)
+                                    KEY_ENCODER.encode_txnkv_zset_meta_value(timestamp, version, 0)
                                 }
-                                _ => Ok(0),
+                                _ => return Ok(0),
+                            };
+                            txn.put(ekey, new_meta_value).await?;
+                            // stale index entries of an old ttl are skipped by the expirer
+                            if timestamp > 0 {
+                                let index_key =
+                                    KEY_ENCODER.encode_txnkv_expire_index_key(&key, timestamp);
+                                txn.put(index_key, vec![]).await?;
                             }
+                            Ok(1)
                         }
                         None => Ok(0),
                     }
@@ -688,13 +1425,17 @@ impl StringCommandCtx {
                 .boxed()
             })
             .await;
+        if let Ok(1) = resp {
+            let event = if timestamp > 0 { "expire" } else { "persist" };
+            notify_keyspace_event(NOTIFY_GENERIC, event, ukey);
+        }
         match resp {
             Ok(v) => Ok(resp_int(v)),
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    pub async fn do_async_txnkv_ttl(mut self, key: &str, is_millis: bool) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_ttl(mut self, key: &[u8], is_millis: bool) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekey = KEY_ENCODER.encode_txnkv_string(key);
         let key = key.to_owned();
@@ -714,32 +1455,7 @@ impl StringCommandCtx {
                             let ttl = KeyDecoder::decode_key_ttl(&meta_value);
                             if key_is_expired(ttl) {
                                 drop(txn);
-                                match dt {
-                                    DataType::String => {
-                                        self.do_async_txnkv_string_expire_if_needed(&key).await?;
-                                    }
-                                    DataType::Hash => {
-                                        HashCommandCtx::new(self.txn.clone())
-                                            .do_async_txnkv_hash_expire_if_needed(&key)
-                                            .await?;
-                                    }
-                                    DataType::Set => {
-                                        SetCommandCtx::new(self.txn.clone())
-                                            .do_async_txnkv_set_expire_if_needed(&key)
-                                            .await?;
-                                    }
-                                    DataType::List => {
-                                        ListCommandCtx::new(self.txn.clone())
-                                            .do_async_txnkv_list_expire_if_needed(&key)
-                                            .await?;
-                                    }
-                                    DataType::Zset => {
-                                        ZsetCommandCtx::new(self.txn.clone())
-                                            .do_async_txnkv_zset_expire_if_needed(&key)
-                                            .await?;
-                                    }
-                                    _ => {}
-                                }
+                                self.txnkv_expire_if_needed_with_type(&key, dt).await?;
                                 return Ok(resp_int(-2));
                             }
 
@@ -761,7 +1477,7 @@ impl StringCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_del(mut self, keys: &Vec<String>) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_del(mut self, keys: &Vec<Bytes>) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let keys = keys.to_owned();
         let keys_len = keys.len();
@@ -792,54 +1508,34 @@ impl StringCommandCtx {
                         }
                     }
 
-                    let mut resp = 0;
+                    let mut deleted = Vec::with_capacity(keys_len);
                     for idx in 0..keys_len {
-                        match dts[idx] {
-                            DataType::String => {
-                                self.clone().do_async_txnkv_string_del(&keys[idx]).await?;
-                                resp += 1;
-                            }
-                            DataType::Hash => {
-                                HashCommandCtx::new(self.txn.clone())
-                                    .do_async_txnkv_hash_del(&keys[idx])
-                                    .await?;
-                                resp += 1;
-                            }
-                            DataType::List => {
-                                ListCommandCtx::new(self.txn.clone())
-                                    .do_async_txnkv_list_del(&keys[idx])
-                                    .await?;
-                                resp += 1;
-                            }
-                            DataType::Set => {
-                                SetCommandCtx::new(self.txn.clone())
-                                    .do_async_txnkv_set_del(&keys[idx])
-                                    .await?;
-                                resp += 1;
-                            }
-                            DataType::Zset => {
-                                ZsetCommandCtx::new(self.txn.clone())
-                                    .do_async_txnk_zset_del(&keys[idx])
-                                    .await?;
-                                resp += 1;
-                            }
-                            DataType::Null => {}
+                        if matches!(dts[idx], DataType::Null) {
+                            continue;
                         }
+                        self.txnkv_del_with_type(&keys[idx], dts[idx].clone())
+                            .await?;
+                        deleted.push(keys[idx].clone());
                     }
-                    Ok(resp)
+                    Ok(deleted)
                 }
                 .boxed()
             })
             .await;
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(deleted) => {
+                for key in &deleted {
+                    notify_keyspace_event(NOTIFY_GENERIC, "del", key);
+                }
+                Ok(resp_int(deleted.len() as i64))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
     pub async fn do_async_txnkv_scan(
         mut self,
-        start: &str,
+        start: &[u8],
         count: u32,
         regex: &str,
     ) -> AsyncResult<Frame> {
@@ -899,9 +1595,7 @@ impl StringCommandCtx {
                             if key_is_expired(ttl) {
                                 drop(txn);
                                 self.clone()
-                                    .do_async_txnkv_del(&vec![
-                                        String::from_utf8_lossy(&userkey).to_string()
-                                    ])
+                                    .do_async_txnkv_del(&vec![Bytes::from(userkey.clone())])
                                     .await?;
                                 txn = txn_rc.lock().await;
                             }
@@ -929,3 +1623,257 @@ impl StringCommandCtx {
             .await
     }
 }
+
+/// The length of the string stored in `meta_value`.
+pub(crate) fn string_len(meta_value: &[u8]) -> usize {
+    if KeyDecoder::decode_key_string_is_chunked(meta_value) {
+        KeyDecoder::decode_key_string_chunked_len(meta_value) as usize
+    } else {
+        KeyDecoder::decode_key_string_slice(meta_value).len()
+    }
+}
+
+/// Encode the meta value of a string again with a new ttl.
+pub(crate) fn string_meta_with_ttl(meta_value: &[u8], ttl: u64) -> Value {
+    if KeyDecoder::decode_key_string_is_chunked(meta_value) {
+        KEY_ENCODER.encode_txnkv_string_chunked_meta_value(
+            ttl,
+            KeyDecoder::decode_key_version(meta_value),
+            KeyDecoder::decode_key_string_chunked_len(meta_value),
+        )
+    } else {
+        KEY_ENCODER.encode_txnkv_string_slice(KeyDecoder::decode_key_string_slice(meta_value), ttl)
+    }
+}
+
+/// Read bytes `[start, end)` of the string stored in `meta_value`, `end`
+/// must not be beyond the length of the string.
+pub(crate) async fn read_string_range(
+    txn: &mut Transaction,
+    key: &[u8],
+    meta_value: &[u8],
+    start: usize,
+    end: usize,
+) -> AsyncResult<Vec<u8>> {
+    if start >= end {
+        return Ok(vec![]);
+    }
+    if !KeyDecoder::decode_key_string_is_chunked(meta_value) {
+        return Ok(KeyDecoder::decode_key_string_slice(meta_value)[start..end].to_vec());
+    }
+
+    let version = KeyDecoder::decode_key_version(meta_value);
+    let first = (start / STRING_CHUNK_SIZE) as u32;
+    let last = ((end - 1) / STRING_CHUNK_SIZE) as u32;
+    let range: Range<Key> = KEY_ENCODER.encode_txnkv_string_chunk_key(key, first, version)
+        ..KEY_ENCODER.encode_txnkv_string_chunk_key(key, last + 1, version);
+
+    // missing chunks and the trimmed tail of chunks are zeros
+    let mut data = vec![0; end - start];
+    for kv in txn.scan(range, u32::MAX).await? {
+        let idx = KeyDecoder::decode_key_string_chunk_idx_from_datakey(key, kv.0);
+        let chunk_start = idx as usize * STRING_CHUNK_SIZE;
+        let from = chunk_start.max(start);
+        let to = (chunk_start + kv.1.len()).min(end);
+        if from < to {
+            data[from - start..to - start]
+                .copy_from_slice(&kv.1[from - chunk_start..to - chunk_start]);
+        }
+    }
+    Ok(data)
+}
+
+pub(crate) async fn read_string(
+    txn: &mut Transaction,
+    key: &[u8],
+    meta_value: &[u8],
+) -> AsyncResult<Vec<u8>> {
+    read_string_range(txn, key, meta_value, 0, string_len(meta_value)).await
+}
+
+/// Delete the chunks of a chunked string, the meta key is left to the caller.
+/// The chunks of a string longer than `threshold` chunks are handed to the
+/// async deletion.
+pub(crate) async fn delete_string_chunks(
+    txn: &mut Transaction,
+    key: &[u8],
+    meta_value: &[u8],
+    threshold: u32,
+) -> AsyncResult<()> {
+    if !KeyDecoder::decode_key_string_is_chunked(meta_value) {
+        return Ok(());
+    }
+    let version = KeyDecoder::decode_key_version(meta_value);
+    let len = KeyDecoder::decode_key_string_chunked_len(meta_value) as usize;
+    let chunks = ((len + STRING_CHUNK_SIZE - 1) / STRING_CHUNK_SIZE) as u32;
+
+    if chunks > threshold {
+        let gc_key = KEY_ENCODER.encode_txnkv_gc_key(key);
+        txn.put(gc_key, version.to_be_bytes()).await?;
+
+        let gc_version_key = KEY_ENCODER.encode_txnkv_gc_version_key(key, version);
+        txn.put(
+            gc_version_key,
+            vec![KEY_ENCODER.get_type_bytes(DataType::String)],
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // there is at most one key for each chunk
+    let bound_range = KEY_ENCODER.encode_txnkv_string_chunk_key_range(key, version);
+    for k in txn.scan_keys(bound_range, chunks).await? {
+        txn.delete(k).await?;
+    }
+    Ok(())
+}
+
+/// Store `data` as the value of a new string, chunks of an old value must
+/// have been deleted or handed to the async deletion before.
+pub(crate) async fn write_string(
+    txn: &mut Transaction,
+    key: &[u8],
+    data: &[u8],
+    ttl: u64,
+) -> AsyncResult<()> {
+    let ekey = KEY_ENCODER.encode_txnkv_string(key);
+    if data.len() <= STRING_CHUNK_SIZE {
+        txn.put(ekey, KEY_ENCODER.encode_txnkv_string_slice(data, ttl))
+            .await?;
+        return Ok(());
+    }
+    let version = get_version_for_new_locked(key, txn).await?;
+    put_string_chunks(txn, key, version, data).await?;
+    let eval = KEY_ENCODER.encode_txnkv_string_chunked_meta_value(ttl, version, data.len() as u64);
+    txn.put(ekey, eval).await?;
+    Ok(())
+}
+
+/// Overwrite the bytes at `offset` of the string with `data`, the string is
+/// padded with zeros if it is shorter. The ttl is kept, and a string growing
+/// beyond one chunk is converted to be stored in chunks.
+pub(crate) async fn write_string_range(
+    txn: &mut Transaction,
+    key: &[u8],
+    meta_value: Option<&[u8]>,
+    offset: usize,
+    data: &[u8],
+) -> AsyncResult<()> {
+    let ekey = KEY_ENCODER.encode_txnkv_string(key);
+    let ttl = meta_value.map_or(0, KeyDecoder::decode_key_ttl);
+    let len = meta_value.map_or(0, string_len);
+    let new_len = len.max(offset + data.len());
+
+    match meta_value {
+        Some(meta_value) if KeyDecoder::decode_key_string_is_chunked(meta_value) => {
+            let version = KeyDecoder::decode_key_version(meta_value);
+            update_string_chunks(txn, key, version, offset, data).await?;
+            if new_len > len {
+                let eval = KEY_ENCODER.encode_txnkv_string_chunked_meta_value(
+                    ttl,
+                    version,
+                    new_len as u64,
+                );
+                txn.put(ekey, eval).await?;
+            }
+        }
+        _ => {
+            let mut value = meta_value.map_or_else(Vec::new, KeyDecoder::decode_key_string_value);
+            if new_len <= STRING_CHUNK_SIZE {
+                value.resize(new_len, 0);
+                value[offset..offset + data.len()].copy_from_slice(data);
+                let eval = KEY_ENCODER.encode_txnkv_string_value(&mut value, ttl);
+                txn.put(ekey, eval).await?;
+            } else {
+                // the chunks of an older string of this key may still wait
+                // for the async deletion, so a new version is allocated
+                let version = get_version_for_new_locked(key, txn).await?;
+                put_string_chunks(txn, key, version, &value).await?;
+                update_string_chunks(txn, key, version, offset, data).await?;
+                let eval = KEY_ENCODER.encode_txnkv_string_chunked_meta_value(
+                    ttl,
+                    version,
+                    new_len as u64,
+                );
+                txn.put(ekey, eval).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn put_string_chunks(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    data: &[u8],
+) -> AsyncResult<()> {
+    for (idx, chunk) in data.chunks(STRING_CHUNK_SIZE).enumerate() {
+        if chunk.iter().any(|b| *b != 0) {
+            let chunk_key = KEY_ENCODER.encode_txnkv_string_chunk_key(key, idx as u32, version);
+            put_string_chunk(txn, chunk_key, chunk.to_vec()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Overwrite the chunks of a chunked string stored at `version` with `data`
+/// from `offset`, which is at the start of a chunk. All zero chunks are
+/// deleted.
+pub(crate) async fn replace_string_chunks(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    offset: usize,
+    data: &[u8],
+) -> AsyncResult<()> {
+    let first = offset / STRING_CHUNK_SIZE;
+    for (idx, chunk) in (first..).zip(data.chunks(STRING_CHUNK_SIZE)) {
+        let chunk_key = KEY_ENCODER.encode_txnkv_string_chunk_key(key, idx as u32, version);
+        put_string_chunk(txn, chunk_key, chunk.to_vec()).await?;
+    }
+    Ok(())
+}
+
+async fn update_string_chunks(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    offset: usize,
+    data: &[u8],
+) -> AsyncResult<()> {
+    let end = offset + data.len();
+    let mut pos = offset;
+    while pos < end {
+        let idx = pos / STRING_CHUNK_SIZE;
+        let chunk_start = idx * STRING_CHUNK_SIZE;
+        let to = end.min(chunk_start + STRING_CHUNK_SIZE);
+
+        let chunk_key = KEY_ENCODER.encode_txnkv_string_chunk_key(key, idx as u32, version);
+        let mut chunk = txn.get(chunk_key.clone()).await?.unwrap_or_default();
+        if chunk.len() < to - chunk_start {
+            chunk.resize(to - chunk_start, 0);
+        }
+        chunk[pos - chunk_start..to - chunk_start]
+            .copy_from_slice(&data[pos - offset..to - offset]);
+        put_string_chunk(txn, chunk_key, chunk).await?;
+        pos = to;
+    }
+    Ok(())
+}
+
+/// Store a chunk without its trailing zeros, an all zero chunk is deleted.
+async fn put_string_chunk(
+    txn: &mut Transaction,
+    chunk_key: Key,
+    mut chunk: Vec<u8>,
+) -> AsyncResult<()> {
+    let len = chunk.iter().rposition(|b| *b != 0).map_or(0, |pos| pos + 1);
+    if len == 0 {
+        txn.delete(chunk_key).await?;
+    } else {
+        chunk.truncate(len);
+        txn.put(chunk_key, chunk).await?;
+    }
+    Ok(())
+}
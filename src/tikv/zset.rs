@@ -2,6 +2,10 @@ use super::client::get_version_for_new;
 use super::errors::*;
 use super::gen_next_meta_index;
 use super::get_txn_client;
+use super::set::{
+    merge_members, set_member_score, MemberCursor, MemberSink, SetCommandCtx, SetOperation,
+};
+use super::string::StringCommandCtx;
 use super::KEY_ENCODER;
 use super::{
     encoding::{DataType, KeyDecoder},
@@ -12,13 +16,79 @@ use crate::async_expire_zset_threshold_or_default;
 use crate::utils::{key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil};
 use crate::Frame;
 use ::futures::future::FutureExt;
+use bytes::Bytes;
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::ops::Bound;
 use std::sync::Arc;
 use tikv_client::{BoundRange, Key, Transaction, Value};
 use tokio::sync::Mutex;
 
 use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::notify::{
+    notify_keyspace_event, signal_key_ready, NOTIFY_EXPIRED, NOTIFY_GENERIC, NOTIFY_ZSET,
+};
+
+const ZSET_SCAN_BATCH_SIZE: u32 = 256;
+
+/// How the scores of a member in several sorted sets are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZsetAggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+impl ZsetAggregate {
+    fn apply(&self, acc: f64, score: f64) -> f64 {
+        match self {
+            // inf + -inf is NaN, which is stored as 0 like redis does
+            ZsetAggregate::Sum => {
+                let sum = acc + score;
+                if sum.is_nan() {
+                    0.0
+                } else {
+                    sum
+                }
+            }
+            ZsetAggregate::Min => acc.min(score),
+            ZsetAggregate::Max => acc.max(score),
+        }
+    }
+}
+
+/// One end of a lexicographic range of sorted set members.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LexBound {
+    /// `-`, lower than any member
+    Min,
+    /// `+`, greater than any member
+    Max,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    /// Whether the member is in range when this is the lower end.
+    fn lower_contains(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => true,
+            LexBound::Max => false,
+            LexBound::Inclusive(bound) => &bound[..] <= member,
+            LexBound::Exclusive(bound) => &bound[..] < member,
+        }
+    }
+
+    /// Whether the member is in range when this is the upper end.
+    fn upper_contains(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(bound) => &bound[..] >= member,
+            LexBound::Exclusive(bound) => &bound[..] > member,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct ZsetCommandCtx {
@@ -30,7 +100,7 @@ impl ZsetCommandCtx {
         ZsetCommandCtx { txn }
     }
 
-    async fn txnkv_sum_key_size(mut self, key: &str, version: u16) -> AsyncResult<i64> {
+    async fn txnkv_sum_key_size(mut self, key: &[u8], version: u16) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
 
@@ -72,8 +142,8 @@ impl ZsetCommandCtx {
 
     pub async fn do_async_txnkv_zadd(
         mut self,
-        key: &str,
-        members: &Vec<String>,
+        key: &[u8],
+        members: &Vec<Bytes>,
         scores: &Vec<f64>,
         exists: Option<bool>,
         changed_only: bool,
@@ -81,6 +151,7 @@ impl ZsetCommandCtx {
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
 
+        let ukey = key;
         let key = key.to_owned();
         let members = members.to_owned();
         let scores = scores.to_owned();
@@ -316,12 +387,18 @@ impl ZsetCommandCtx {
             .await;
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_ZSET, "zadd", ukey);
+                    signal_key_ready(ukey);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    pub async fn do_async_txnkv_zcard(mut self, key: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_zcard(mut self, key: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
         let key = key.to_owned();
@@ -362,7 +439,7 @@ impl ZsetCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_zcore(mut self, key: &str, member: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_zcore(mut self, key: &[u8], member: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
         let key = key.to_owned();
@@ -413,7 +490,7 @@ impl ZsetCommandCtx {
 
     pub async fn do_async_txnkv_zcount(
         mut self,
-        key: &str,
+        key: &[u8],
         min: f64,
         min_inclusive: bool,
         max: f64,
@@ -480,7 +557,7 @@ impl ZsetCommandCtx {
 
     pub async fn do_async_txnkv_zrange(
         mut self,
-        key: &str,
+        key: &[u8],
         mut min: i64,
         mut max: i64,
         with_scores: bool,
@@ -581,7 +658,7 @@ impl ZsetCommandCtx {
     #[allow(clippy::too_many_arguments)]
     pub async fn do_async_txnkv_zrange_by_score(
         mut self,
-        key: &str,
+        key: &[u8],
         mut min: f64,
         mut min_inclusive: bool,
         mut max: f64,
@@ -678,17 +755,159 @@ impl ZsetCommandCtx {
             .await
     }
 
-    // pub async fn do_async_txnkv_zrange_by_lex(self, key: &str, min: &str, with_min: bool, max: &str, with_max: bool, with_scores: bool, reverse: bool) -> AsyncResult<Frame> {
-    //     Ok(resp_nil())
-    // }
+    pub async fn do_async_txnkv_zrange_by_lex(
+        mut self,
+        key: &[u8],
+        mut min: LexBound,
+        mut max: LexBound,
+        offset: i64,
+        count: i64,
+        reverse: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
 
-    pub async fn do_async_txnkv_zpop(
+                    let mut txn = txn_rc.lock().await;
+
+                    match txn.get(meta_key.to_owned()).await? {
+                        Some(meta_value) => {
+                            // check key type and ttl
+                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_zset_expire_if_needed(&key)
+                                    .await?;
+                                return Ok(resp_array(vec![]));
+                            }
+
+                            // a negative offset returns nothing, a negative count returns all
+                            if offset < 0 {
+                                return Ok(resp_array(vec![]));
+                            }
+                            let count = if count < 0 {
+                                None
+                            } else {
+                                Some(count as usize)
+                            };
+
+                            // if reverse is set, min and max means opposite, exchange them
+                            if reverse {
+                                (min, max) = (max, min);
+                            }
+
+                            let keys = txnkv_zset_lex_range(
+                                &mut txn,
+                                &key,
+                                version,
+                                &min,
+                                &max,
+                                reverse,
+                                offset as usize,
+                                count,
+                            )
+                            .await?;
+                            let resp = keys
+                                .into_iter()
+                                .map(|k| {
+                                    resp_bulk(KeyDecoder::decode_key_zset_member_from_scorekey(
+                                        &key, k,
+                                    ))
+                                })
+                                .collect();
+                            Ok(resp_array(resp))
+                        }
+                        None => Ok(resp_array(vec![])),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    pub async fn do_async_txnkv_zlexcount(
         mut self,
-        key: &str,
+        key: &[u8],
+        min: LexBound,
+        max: LexBound,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+
+                    match txn.get(meta_key.to_owned()).await? {
+                        Some(meta_value) => {
+                            // check key type and ttl
+                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_zset_expire_if_needed(&key)
+                                    .await?;
+                                return Ok(resp_int(0));
+                            }
+
+                            let keys = txnkv_zset_lex_range(
+                                &mut txn, &key, version, &min, &max, false, 0, None,
+                            )
+                            .await?;
+                            Ok(resp_int(keys.len() as i64))
+                        }
+                        None => Ok(resp_int(0)),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    pub async fn do_async_txnkv_zpop(
+        self,
+        key: &[u8],
         from_min: bool,
         count: u64,
     ) -> AsyncResult<Frame> {
+        match self.do_async_txnkv_zpop_values(key, from_min, count).await {
+            Ok(v) => Ok(resp_array(v)),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Pop at most `count` members from the sorted set, returns the members
+    /// followed by their scores.
+    pub async fn do_async_txnkv_zpop_values(
+        mut self,
+        key: &[u8],
+        from_min: bool,
+        count: u64,
+    ) -> AsyncResult<Vec<Frame>> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
         let rand_idx = gen_next_meta_index();
@@ -728,11 +947,8 @@ impl ZsetCommandCtx {
                                         &key,
                                         k.clone(),
                                     );
-                                    let data_key = KEY_ENCODER.encode_txnkv_zset_data_key(
-                                        &key,
-                                        &String::from_utf8_lossy(&member),
-                                        version,
-                                    );
+                                    let data_key = KEY_ENCODER
+                                        .encode_txnkv_zset_data_key(&key, &member, version);
 
                                     // push member to resp
                                     resp.push(resp_bulk(member));
@@ -756,11 +972,8 @@ impl ZsetCommandCtx {
                                         &key,
                                         k.clone(),
                                     );
-                                    let data_key = KEY_ENCODER.encode_txnkv_zset_data_key(
-                                        &key,
-                                        &String::from_utf8_lossy(&member),
-                                        version,
-                                    );
+                                    let data_key = KEY_ENCODER
+                                        .encode_txnkv_zset_data_key(&key, &member, version);
 
                                     // push member to resp
                                     resp.push(resp_bulk(member));
@@ -817,13 +1030,47 @@ impl ZsetCommandCtx {
             })
             .await;
 
-        match resp {
-            Ok(v) => Ok(resp_array(v)),
-            Err(e) => Ok(resp_err(e)),
+        if matches!(&resp, Ok(v) if !v.is_empty()) {
+            let event = if from_min { "zpopmin" } else { "zpopmax" };
+            notify_keyspace_event(NOTIFY_ZSET, event, ukey);
         }
+        resp
+    }
+
+    /// Pop at most `count` members from the first non-empty sorted set of the
+    /// keys, returns `None` if all the sorted sets are empty.
+    pub async fn do_async_txnkv_mzpop(
+        mut self,
+        keys: &[Bytes],
+        from_min: bool,
+        count: u64,
+    ) -> AsyncResult<Option<(Bytes, Vec<Frame>)>> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    for key in keys {
+                        let values = self
+                            .clone()
+                            .do_async_txnkv_zpop_values(&key, from_min, count)
+                            .await?;
+                        if !values.is_empty() {
+                            return Ok(Some((key, values)));
+                        }
+                    }
+                    Ok(None)
+                }
+                .boxed()
+            })
+            .await
     }
 
-    pub async fn do_async_txnkv_zrank(mut self, key: &str, member: &str) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_zrank(mut self, key: &[u8], member: &[u8]) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
         let key = key.to_owned();
@@ -889,14 +1136,15 @@ impl ZsetCommandCtx {
 
     pub async fn do_async_txnkv_zincrby(
         mut self,
-        key: &str,
+        key: &[u8],
         step: f64,
-        member: &str,
+        member: &[u8],
     ) -> AsyncResult<Frame> {
         if step.is_nan() {
             return Ok(resp_err(REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR));
         }
 
+        let ukey = key;
         let key = key.to_owned();
         let member = member.to_owned();
         let mut client = get_txn_client()?;
@@ -1011,17 +1259,22 @@ impl ZsetCommandCtx {
             .await;
 
         match resp {
-            Ok(new_score) => Ok(resp_bulk(new_score.to_string().as_bytes().to_vec())),
+            Ok(new_score) => {
+                notify_keyspace_event(NOTIFY_ZSET, "zincr", ukey);
+                signal_key_ready(ukey);
+                Ok(resp_bulk(new_score.to_string().as_bytes().to_vec()))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
     pub async fn do_async_txnkv_zrem(
         mut self,
-        key: &str,
-        members: &Vec<String>,
+        key: &[u8],
+        members: &Vec<Bytes>,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let members = members.to_owned();
 
@@ -1117,23 +1370,130 @@ impl ZsetCommandCtx {
             .await;
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_ZSET, "zrem", ukey);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    // pub async fn do_async_txnkv_zremrange_by_lex(self, key: &str, min: &str, with_min: bool, max: &str, with_max: bool) -> AsyncResult<Frame> {
-    //     Ok(resp_nil())
-    // }
+    pub async fn do_async_txnkv_zremrange_by_lex(
+        mut self,
+        key: &[u8],
+        min: LexBound,
+        max: LexBound,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ukey = key;
+        let key = key.to_owned();
+
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
+        let rand_idx = gen_next_meta_index();
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(meta_key.clone()).await? {
+                        Some(meta_value) => {
+                            // check key type and ttl
+                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+
+                            let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_zset_expire_if_needed(&key)
+                                    .await?;
+                                return Ok(0);
+                            }
+
+                            let keys = txnkv_zset_lex_range(
+                                &mut txn, &key, version, &min, &max, false, 0, None,
+                            )
+                            .await?;
+                            let removed_count = keys.len() as i64;
+                            if removed_count == 0 {
+                                return Ok(0);
+                            }
+
+                            // remove score key and data key
+                            for k in keys {
+                                let member = KeyDecoder::decode_key_zset_member_from_scorekey(
+                                    &key,
+                                    k.clone(),
+                                );
+                                let data_key =
+                                    KEY_ENCODER.encode_txnkv_zset_data_key(&key, &member, version);
+                                txn.delete(data_key).await?;
+                                txn.delete(k).await?;
+                            }
+
+                            drop(txn);
+                            let size = self.txnkv_sum_key_size(&key, version).await?;
+                            txn = txn_rc.lock().await;
+                            // delete all sub meta keys and meta key if all members removed
+                            if removed_count >= size {
+                                let bound_range =
+                                    KEY_ENCODER.encode_txnkv_sub_meta_key_range(&key, version);
+                                let iter = txn.scan_keys(bound_range, u32::MAX).await?;
+                                for k in iter {
+                                    txn.delete(k).await?;
+                                }
+                                txn.delete(meta_key).await?;
+                            } else {
+                                // update a random sub meta key
+                                let sub_meta_key =
+                                    KEY_ENCODER.encode_txnkv_sub_meta_key(&key, version, rand_idx);
+                                let new_sub_meta_value =
+                                    txn.get(sub_meta_key.clone()).await?.map_or_else(
+                                        || -removed_count,
+                                        |v| {
+                                            let old_sub_meta_value =
+                                                i64::from_be_bytes(v.try_into().unwrap());
+                                            old_sub_meta_value - removed_count
+                                        },
+                                    );
+                                txn.put(sub_meta_key, new_sub_meta_value.to_be_bytes().to_vec())
+                                    .await?;
+                            }
+
+                            Ok(removed_count)
+                        }
+                        None => Ok(0),
+                    }
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_ZSET, "zremrangebylex", ukey);
+                }
+                Ok(resp_int(v))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
 
     pub async fn do_async_txnkv_zremrange_by_rank(
         mut self,
-        key: &str,
+        key: &[u8],
         mut min: i64,
         mut max: i64,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let ukey = key;
         let key = key.to_owned();
         let rand_idx = gen_next_meta_index();
 
@@ -1187,7 +1547,7 @@ impl ZsetCommandCtx {
                                 }
                                 idx += 1;
 
-                                let member = String::from_utf8_lossy(&kv.1);
+                                let member = kv.1;
                                 // encode member key
                                 let member_key =
                                     KEY_ENCODER.encode_txnkv_zset_data_key(&key, &member, version);
@@ -1235,18 +1595,24 @@ impl ZsetCommandCtx {
             })
             .await;
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_ZSET, "zremrangebyrank", ukey);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
     pub async fn do_async_txnkv_zremrange_by_score(
         mut self,
-        key: &str,
+        key: &[u8],
         min: f64,
         max: f64,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
 
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -1298,11 +1664,8 @@ impl ZsetCommandCtx {
                                     k.clone(),
                                 );
                                 // fetch this score key member
-                                let data_key = KEY_ENCODER.encode_txnkv_zset_data_key(
-                                    &key,
-                                    &String::from_utf8_lossy(&member),
-                                    version,
-                                );
+                                let data_key =
+                                    KEY_ENCODER.encode_txnkv_zset_data_key(&key, &member, version);
                                 txn.delete(data_key).await?;
                                 txn.delete(k).await?;
                                 removed_count += 1;
@@ -1347,12 +1710,17 @@ impl ZsetCommandCtx {
             .await;
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_keyspace_event(NOTIFY_ZSET, "zremrangebyscore", ukey);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    pub async fn do_async_txnk_zset_del(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnk_zset_del(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
@@ -1398,12 +1766,10 @@ impl ZsetCommandCtx {
                                     let score = KeyDecoder::decode_key_zset_data_value(&kv.1);
 
                                     // decode member from data key
-                                    let member_vec =
-                                        KeyDecoder::decode_key_zset_member_from_datakey(
-                                            &key,
-                                            kv.0.clone(),
-                                        );
-                                    let member = String::from_utf8_lossy(&member_vec);
+                                    let member = KeyDecoder::decode_key_zset_member_from_datakey(
+                                        &key,
+                                        kv.0.clone(),
+                                    );
 
                                     // remove member and score key
                                     let score_key = KEY_ENCODER
@@ -1431,12 +1797,13 @@ impl ZsetCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_zset_expire_if_needed(mut self, key: &str) -> AsyncResult<i64> {
+    pub async fn do_async_txnkv_zset_expire_if_needed(mut self, key: &[u8]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
+        let ukey = key;
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
 
-        client
+        let resp = client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
@@ -1482,12 +1849,10 @@ impl ZsetCommandCtx {
                                     let score = KeyDecoder::decode_key_zset_data_value(&kv.1);
 
                                     // decode member from data key
-                                    let member_vec =
-                                        KeyDecoder::decode_key_zset_member_from_datakey(
-                                            &key,
-                                            kv.0.clone(),
-                                        );
-                                    let member = String::from_utf8_lossy(&member_vec);
+                                    let member = KeyDecoder::decode_key_zset_member_from_datakey(
+                                        &key,
+                                        kv.0.clone(),
+                                    );
 
                                     // remove member and score key
                                     let score_key = KEY_ENCODER
@@ -1516,6 +1881,449 @@ impl ZsetCommandCtx {
                 }
                 .boxed()
             })
-            .await
+            .await;
+        if let Ok(1) = resp {
+            notify_keyspace_event(NOTIFY_EXPIRED, "expired", ukey);
+        }
+        resp
+    }
+
+    /// Read the version of the live sorted set stored at `key`, removing it
+    /// first if it has expired.
+    pub(crate) async fn txnkv_zset_live_version(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        key: &[u8],
+    ) -> AsyncResult<Option<u16>> {
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let meta_value = txn_rc.lock().await.get(meta_key).await?;
+        match meta_value {
+            Some(meta_value) => {
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
+                let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    self.clone()
+                        .do_async_txnkv_zset_expire_if_needed(key)
+                        .await?;
+                    return Ok(None);
+                }
+                Ok(Some(version))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Replace the value of `key`, whatever its type, by a sorted set of the
+    /// unique `members`, the key is deleted if there are none. Returns true
+    /// if an old value is replaced.
+    pub(crate) async fn txnkv_zset_store(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        key: &[u8],
+        members: &[(Vec<u8>, f64)],
+    ) -> AsyncResult<bool> {
+        let string_ctx = StringCommandCtx::new(Some(txn_rc.clone()));
+        let old_value = string_ctx.txnkv_get_live_value(txn_rc, key).await?;
+        if let Some(v) = &old_value {
+            string_ctx
+                .txnkv_del_with_type(key, KeyDecoder::decode_key_type(v))
+                .await?;
+        }
+        if members.is_empty() {
+            return Ok(old_value.is_some());
+        }
+
+        let version = get_version_for_new(key, txn_rc.clone()).await?;
+        let mut txn = txn_rc.lock().await;
+        for (member, score) in members {
+            txnkv_zset_put_member(&mut txn, key, version, member, *score).await?;
+        }
+        txnkv_zset_put_meta(&mut txn, key, version, members.len()).await?;
+        Ok(old_value.is_some())
+    }
+
+    /// Cursors over the members of the sorted sets with their scores
+    /// multiplied by `weights`, a missing weight is 1.
+    async fn txnkv_zset_algebra_cursors(
+        &self,
+        txn_rc: &Arc<Mutex<Transaction>>,
+        keys: &[Bytes],
+        weights: &[f64],
+    ) -> AsyncResult<Vec<MemberCursor>> {
+        let mut cursors = Vec::with_capacity(keys.len());
+        for (i, key) in keys.iter().enumerate() {
+            let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+            let meta_value = txn_rc.lock().await.get(meta_key).await?;
+            // plain sets are used as sorted sets with all scores 1
+            let cursor = match meta_value.map(|v| KeyDecoder::decode_key_type(&v)) {
+                Some(DataType::Zset) => {
+                    self.txnkv_zset_live_version(txn_rc, key)
+                        .await?
+                        .map(|version| {
+                            MemberCursor::new(
+                                key,
+                                version,
+                                DataType::Zset,
+                                KeyDecoder::decode_key_zset_data_value,
+                            )
+                        })
+                }
+                Some(DataType::Set) => SetCommandCtx::new(Some(txn_rc.clone()))
+                    .txnkv_set_live_version(txn_rc, key)
+                    .await?
+                    .map(|version| {
+                        MemberCursor::new(key, version, DataType::Set, set_member_score)
+                    }),
+                Some(_) => return Err(REDIS_WRONG_TYPE_ERR),
+                None => None,
+            };
+            let mut cursor = cursor.unwrap_or_else(MemberCursor::empty);
+            cursor.set_weight(weights.get(i).copied().unwrap_or(1.0));
+            cursors.push(cursor);
+        }
+        Ok(cursors)
+    }
+
+    /// Return the union, intersection or difference of the sorted sets,
+    /// ordered by score.
+    pub async fn do_async_txnkv_zset_algebra(
+        mut self,
+        op: SetOperation,
+        keys: &[Bytes],
+        weights: &[f64],
+        aggregate: ZsetAggregate,
+        with_scores: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+        let weights = weights.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut cursors = self
+                        .txnkv_zset_algebra_cursors(&txn_rc, &keys, &weights)
+                        .await?;
+                    let mut sink = MemberSink::Collect(vec![]);
+                    let mut txn = txn_rc.lock().await;
+                    merge_members(
+                        &mut txn,
+                        op,
+                        &mut cursors,
+                        |acc, score| aggregate.apply(acc, score),
+                        usize::MAX,
+                        &mut sink,
+                    )
+                    .await?;
+                    match sink {
+                        MemberSink::Collect(members) => Ok(members),
+                        _ => unreachable!(),
+                    }
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(mut members) => {
+                members.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+                let mut resp = vec![];
+                for (member, score) in members {
+                    resp.push(resp_bulk(member));
+                    if with_scores {
+                        resp.push(resp_bulk(score.to_string().as_bytes().to_vec()));
+                    }
+                }
+                Ok(resp_array(resp))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Return the cardinality of the intersection of the sorted sets,
+    /// counting up to `limit` members if it is not 0.
+    pub async fn do_async_txnkv_zintercard(
+        mut self,
+        keys: &[Bytes],
+        limit: usize,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+        let limit = if limit == 0 { usize::MAX } else { limit };
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut cursors = self.txnkv_zset_algebra_cursors(&txn_rc, &keys, &[]).await?;
+                    let mut txn = txn_rc.lock().await;
+                    merge_members(
+                        &mut txn,
+                        SetOperation::Inter,
+                        &mut cursors,
+                        |acc, score| ZsetAggregate::Sum.apply(acc, score),
+                        limit,
+                        &mut MemberSink::Count,
+                    )
+                    .await
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(count) => Ok(resp_int(count as i64)),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Store the union, intersection or difference of the sorted sets in
+    /// `dest`, replacing its old value of any type.
+    pub async fn do_async_txnkv_zset_algebra_store(
+        mut self,
+        op: SetOperation,
+        dest: &[u8],
+        keys: &[Bytes],
+        weights: &[f64],
+        aggregate: ZsetAggregate,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let udest = dest.to_owned();
+        let dest = dest.to_owned();
+        let keys = keys.to_owned();
+        let weights = weights.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut cursors = self
+                        .txnkv_zset_algebra_cursors(&txn_rc, &keys, &weights)
+                        .await?;
+
+                    // a destination which is also a source can only be
+                    // replaced after all its members are read
+                    if keys.iter().any(|key| key[..] == dest[..]) {
+                        let mut sink = MemberSink::Collect(vec![]);
+                        let mut txn = txn_rc.lock().await;
+                        merge_members(
+                            &mut txn,
+                            op,
+                            &mut cursors,
+                            |acc, score| aggregate.apply(acc, score),
+                            usize::MAX,
+                            &mut sink,
+                        )
+                        .await?;
+                        drop(txn);
+                        let members = match sink {
+                            MemberSink::Collect(members) => members,
+                            _ => unreachable!(),
+                        };
+                        let replaced = self.txnkv_zset_store(&txn_rc, &dest, &members).await?;
+                        return Ok((members.len(), replaced));
+                    }
+
+                    // otherwise the result is written as it is merged
+                    let string_ctx = StringCommandCtx::new(Some(txn_rc.clone()));
+                    let old_value = string_ctx.txnkv_get_live_value(&txn_rc, &dest).await?;
+                    if let Some(v) = &old_value {
+                        string_ctx
+                            .txnkv_del_with_type(&dest, KeyDecoder::decode_key_type(v))
+                            .await?;
+                    }
+                    let version = get_version_for_new(&dest, txn_rc.clone()).await?;
+                    let mut txn = txn_rc.lock().await;
+                    let stored = merge_members(
+                        &mut txn,
+                        op,
+                        &mut cursors,
+                        |acc, score| aggregate.apply(acc, score),
+                        usize::MAX,
+                        &mut MemberSink::Write {
+                            key: &dest,
+                            version,
+                            data_type: DataType::Zset,
+                        },
+                    )
+                    .await?;
+                    if stored > 0 {
+                        txnkv_zset_put_meta(&mut txn, &dest, version, stored).await?;
+                    }
+                    Ok((stored, old_value.is_some()))
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok((stored, replaced)) => {
+                if stored > 0 {
+                    let event = match op {
+                        SetOperation::Inter => "zinterstore",
+                        SetOperation::Union => "zunionstore",
+                        SetOperation::Diff => "zdiffstore",
+                    };
+                    notify_keyspace_event(NOTIFY_ZSET, event, &udest);
+                } else if replaced {
+                    notify_keyspace_event(NOTIFY_GENERIC, "del", &udest);
+                }
+                Ok(resp_int(stored as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+}
+
+pub(crate) async fn txnkv_zset_put_member(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    member: &[u8],
+    score: f64,
+) -> AsyncResult<()> {
+    let data_key = KEY_ENCODER.encode_txnkv_zset_data_key(key, member, version);
+    let score_key = KEY_ENCODER.encode_txnkv_zset_score_key(key, score, member, version);
+    txn.put(data_key, KEY_ENCODER.encode_txnkv_zset_data_value(score))
+        .await?;
+    txn.put(score_key, member.to_vec()).await?;
+    Ok(())
+}
+
+/// Write the meta key and the size of a new sorted set.
+async fn txnkv_zset_put_meta(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    size: usize,
+) -> AsyncResult<()> {
+    let sub_meta_key = KEY_ENCODER.encode_txnkv_sub_meta_key(key, version, gen_next_meta_index());
+    txn.put(sub_meta_key, (size as i64).to_be_bytes().to_vec())
+        .await?;
+    let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+    txn.put(
+        meta_key,
+        KEY_ENCODER.encode_txnkv_zset_meta_value(0, version, 0),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Return the score keys of the members in the lex range, in order or
+/// reversed, skipping the first `offset` of them and keeping at most `count`
+/// if it is given.
+///
+/// Lex ranges expect all the members to have the same score, so the scan
+/// seeks to the bound under the score of the first member, the last one when
+/// reversed, and stops at the first member out of the range like redis does.
+#[allow(clippy::too_many_arguments)]
+async fn txnkv_zset_lex_range(
+    txn: &mut Transaction,
+    key: &[u8],
+    version: u16,
+    min: &LexBound,
+    max: &LexBound,
+    reverse: bool,
+    mut offset: usize,
+    count: Option<usize>,
+) -> AsyncResult<Vec<Key>> {
+    let mut keys = vec![];
+    if count == Some(0) {
+        return Ok(keys);
+    }
+
+    let range = KEY_ENCODER.encode_txnkv_zset_score_key_range(key, version);
+    let edge = if reverse {
+        txn.scan_keys_reverse(range, 1).await?.next()
+    } else {
+        txn.scan_keys(range, 1).await?.next()
+    };
+    let score = match edge {
+        Some(k) => KeyDecoder::decode_key_zset_score_from_scorekey(key, k),
+        None => return Ok(keys),
+    };
+
+    let seek = |bound: &LexBound| match bound {
+        LexBound::Min => {
+            Bound::Included(KEY_ENCODER.encode_txnkv_zset_score_key(key, score, &[], version))
+        }
+        LexBound::Max => Bound::Excluded(
+            KEY_ENCODER.encode_txnkv_zset_score_key_score_end(key, score, true, version),
+        ),
+        LexBound::Inclusive(member) => {
+            Bound::Included(KEY_ENCODER.encode_txnkv_zset_score_key(key, score, member, version))
+        }
+        LexBound::Exclusive(member) => {
+            Bound::Excluded(KEY_ENCODER.encode_txnkv_zset_score_key(key, score, member, version))
+        }
+    };
+    let (mut from, mut to) = if reverse {
+        if *max == LexBound::Min {
+            return Ok(keys);
+        }
+        let start = KEY_ENCODER.encode_txnkv_zset_score_key_start(key, version);
+        (Bound::Included(start), seek(max))
+    } else {
+        if *min == LexBound::Max {
+            return Ok(keys);
+        }
+        let end = KEY_ENCODER.encode_txnkv_zset_score_key_end(key, version);
+        (seek(min), Bound::Excluded(end))
+    };
+
+    loop {
+        let range = (from.clone(), to.clone());
+        let batch: Vec<Key> = if reverse {
+            txn.scan_keys_reverse(range, ZSET_SCAN_BATCH_SIZE)
+                .await?
+                .collect()
+        } else {
+            txn.scan_keys(range, ZSET_SCAN_BATCH_SIZE).await?.collect()
+        };
+        let exhausted = batch.len() < ZSET_SCAN_BATCH_SIZE as usize;
+        if let Some(last) = batch.last() {
+            if reverse {
+                to = Bound::Excluded(last.clone());
+            } else {
+                from = Bound::Excluded(last.clone());
+            }
+        }
+
+        for k in batch {
+            let member = KeyDecoder::decode_key_zset_member_from_scorekey(key, k.clone());
+            let in_range = if reverse {
+                min.lower_contains(&member)
+            } else {
+                max.upper_contains(&member)
+            };
+            if !in_range {
+                return Ok(keys);
+            }
+            if offset > 0 {
+                offset -= 1;
+                continue;
+            }
+            keys.push(k);
+            if count == Some(keys.len()) {
+                return Ok(keys);
+            }
+        }
+        if exhausted {
+            return Ok(keys);
+        }
     }
 }
@@ -3,6 +3,7 @@ use hex::ToHex;
 use mlua::{Lua, Value as LuaValue};
 use sha1::{Digest, Sha1};
 use std::io;
+use std::str;
 use std::{
     collections::HashSet,
     convert::TryInto,
@@ -12,7 +13,7 @@ use tokio::time::Duration;
 
 use crate::tikv::errors::{RTError, REDIS_LUA_PANIC};
 use rustls::server::{AllowAnyAuthenticatedClient, NoClientAuth};
-use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig};
 use rustls_pemfile::rsa_private_keys;
 use std::fs::File;
 use std::io::BufReader;
@@ -60,6 +61,10 @@ pub fn resp_nil() -> Frame {
     Frame::Null
 }
 
+pub fn resp_nil_array() -> Frame {
+    Frame::NullArray
+}
+
 pub fn resp_array(val: Vec<Frame>) -> Frame {
     Frame::Array(val)
 }
@@ -132,8 +137,8 @@ pub fn redis_resp_to_lua_resp(resp: Frame, lua: &Lua) -> LuaValue {
             LuaValue::Table(table)
         }
         Frame::Integer(i) => LuaValue::Integer(i),
-        Frame::Null => LuaValue::Boolean(false),
-        Frame::Array(arr) => {
+        Frame::Null | Frame::NullArray => LuaValue::Boolean(false),
+        Frame::Array(arr) | Frame::Set(arr) | Frame::Push(arr) => {
             let table = lua.create_table().unwrap();
             for (idx, value) in arr.iter().enumerate() {
                 let v = redis_resp_to_lua_resp(value.clone(), lua);
@@ -142,6 +147,52 @@ pub fn redis_resp_to_lua_resp(resp: Frame, lua: &Lua) -> LuaValue {
             }
             LuaValue::Table(table)
         }
+        // scripts always see RESP2 replies, so maps are flatten to arrays
+        Frame::Map(pairs) => {
+            let table = lua.create_table().unwrap();
+            for (idx, (key, value)) in pairs.into_iter().enumerate() {
+                table
+                    .raw_set(idx * 2 + 1, redis_resp_to_lua_resp(key, lua))
+                    .unwrap();
+                table
+                    .raw_set(idx * 2 + 2, redis_resp_to_lua_resp(value, lua))
+                    .unwrap();
+            }
+            LuaValue::Table(table)
+        }
+        Frame::Double(d) => LuaValue::String(lua.create_string(&Frame::format_double(d)).unwrap()),
+        Frame::Boolean(b) => LuaValue::Integer(b as i64),
+        Frame::BigNumber(n) => LuaValue::String(lua.create_string(&n).unwrap()),
+        Frame::Verbatim(_, v) => {
+            let str = String::from_utf8_lossy(&v).to_string();
+            LuaValue::String(lua.create_string(&str).unwrap())
+        }
+    }
+}
+
+/// Convert a flat `[member, score, member, score ...]` reply to the RESP3
+/// form, which is an array of `[member, score]` pairs with double scores.
+pub fn resp3_score_pairs(resp: Frame) -> Frame {
+    match resp {
+        Frame::Array(arr) => {
+            let mut pairs = Vec::with_capacity(arr.len() / 2);
+            let mut iter = arr.into_iter();
+            while let (Some(member), Some(score)) = (iter.next(), iter.next()) {
+                let score = match score {
+                    Frame::Bulk(s) => {
+                        let parsed = str::from_utf8(&s).map(|v| v.parse::<f64>());
+                        match parsed {
+                            Ok(Ok(v)) => Frame::Double(v),
+                            _ => Frame::Bulk(s),
+                        }
+                    }
+                    frame => frame,
+                };
+                pairs.push(Frame::Array(vec![member, score]));
+            }
+            Frame::Array(pairs)
+        }
+        frame => frame,
     }
 }
 
@@ -235,6 +286,35 @@ pub fn load_config(
     Ok(config)
 }
 
+/// Configure the TLS client connecting to the other instances of the cluster.
+///
+/// The instance presents its own certificate to the peers, which is required
+/// if they authenticate clients. The peers are verified with the CA
+/// certificate, or with the certificate of the instance itself if no CA is
+/// configured, since all the instances usually share the same one.
+pub fn load_client_config(cert: &str, key: &str, ca_cert: &str) -> io::Result<ClientConfig> {
+    let certs = load_certs(Path::new(cert))?;
+    let mut keys = load_keys(Path::new(key))?;
+
+    let root_certs = if ca_cert.is_empty() {
+        certs.clone()
+    } else {
+        load_certs(Path::new(ca_cert))?
+    };
+    let mut roots = RootCertStore::empty();
+    for root in root_certs {
+        roots
+            .add(&root)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    }
+
+    ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_single_cert(certs, keys.remove(0))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
 pub fn sha1hex(s: &str) -> String {
     let mut hasher = Sha1::new();
     hasher.update(s);
@@ -242,6 +322,96 @@ pub fn sha1hex(s: &str) -> String {
     sha1.encode_hex::<String>()
 }
 
+/// Glob-style pattern matching as redis, supports `*`, `?`, `[...]` and `\\`
+/// escaping.
+pub fn glob_match(pattern: &[u8], string: &[u8]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    while p < pattern.len() && s < string.len() {
+        match pattern[p] {
+            b'*' => {
+                while p + 1 < pattern.len() && pattern[p + 1] == b'*' {
+                    p += 1;
+                }
+                if p + 1 == pattern.len() {
+                    return true;
+                }
+                return (s..string.len()).any(|i| glob_match(&pattern[p + 1..], &string[i..]));
+            }
+            b'?' => s += 1,
+            b'[' => {
+                p += 1;
+                let not = p < pattern.len() && pattern[p] == b'^';
+                if not {
+                    p += 1;
+                }
+                let mut matched = false;
+                loop {
+                    if p >= pattern.len() {
+                        // unterminated class, matches to the end of pattern
+                        p -= 1;
+                        break;
+                    }
+                    if pattern[p] == b'\\' && pattern.len() - p >= 2 {
+                        p += 1;
+                        matched |= pattern[p] == string[s];
+                    } else if pattern[p] == b']' {
+                        break;
+                    } else if pattern.len() - p >= 3 && pattern[p + 1] == b'-' {
+                        let (start, end) = if pattern[p] <= pattern[p + 2] {
+                            (pattern[p], pattern[p + 2])
+                        } else {
+                            (pattern[p + 2], pattern[p])
+                        };
+                        matched |= string[s] >= start && string[s] <= end;
+                        p += 2;
+                    } else {
+                        matched |= pattern[p] == string[s];
+                    }
+                    p += 1;
+                }
+                if matched == not {
+                    return false;
+                }
+                s += 1;
+            }
+            c => {
+                let c = if c == b'\\' && pattern.len() - p >= 2 {
+                    p += 1;
+                    pattern[p]
+                } else {
+                    c
+                };
+                if c != string[s] {
+                    return false;
+                }
+                s += 1;
+            }
+        }
+        p += 1;
+    }
+    if s == string.len() {
+        while p < pattern.len() && pattern[p] == b'*' {
+            p += 1;
+        }
+    }
+    p == pattern.len() && s == string.len()
+}
+
+/// Format a finite float like redis `%.17g` with the trailing zeros trimmed,
+/// using the shortest digits that read back to the same value.
+pub fn float_to_string(value: f64) -> String {
+    // `{:e}` gives the shortest digits, e.g. `1.5e-7`
+    let sci = format!("{:e}", value);
+    let (mantissa, exp) = sci.split_once('e').unwrap();
+    let exp: i32 = exp.parse().unwrap();
+    if (-4..17).contains(&exp) {
+        value.to_string()
+    } else {
+        let sign = if exp < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, sign, exp.abs())
+    }
+}
+
 pub fn count_unique_keys<T: std::hash::Hash + std::cmp::Eq>(keys: &[T]) -> usize {
     keys.iter().collect::<HashSet<&T>>().len()
 }
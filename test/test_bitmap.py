@@ -0,0 +1,163 @@
+import unittest
+
+from rediswrap import RedisWrapper
+from test_util import trigger_async_del_size
+
+
+class BitmapTest(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        cls.r = RedisWrapper.get_instance()
+
+        cls.k1 = '__bitmap1__'
+        cls.k2 = '__bitmap2__'
+        cls.k3 = '__bitmap3__'
+
+    def setUp(self):
+        self.r.delete(self.k1)
+        self.r.delete(self.k2)
+        self.r.delete(self.k3)
+
+    def test_setbit_getbit(self):
+        self.assertEqual(self.r.setbit(self.k1, 7, 1), 0)
+        self.assertEqual(self.r.setbit(self.k1, 7, 1), 1)
+        self.assertEqual(self.r.getbit(self.k1, 7), 1)
+        self.assertEqual(self.r.getbit(self.k1, 6), 0)
+        self.assertEqual(self.r.getbit(self.k1, 100), 0)
+        self.assertEqual(self.r.get(self.k1), '\x01')
+        self.assertEqual(self.r.setbit(self.k1, 15, 0), 0)
+        self.assertEqual(self.r.strlen(self.k1), 2)
+        self.assertEqual(self.r.getbit(self.k2, 0), 0)
+        self.assertRaises(Exception, self.r.setbit, self.k1, 2 ** 32, 1)
+        self.assertRaises(Exception, self.r.execute_command, 'SETBIT', self.k1, 0, 2)
+        self.r.lpush(self.k2, 'a')
+        self.assertRaises(Exception, self.r.setbit, self.k2, 0, 1)
+
+    def test_bitcount(self):
+        self.r.set(self.k1, 'foobar')
+        self.assertEqual(self.r.bitcount(self.k1), 26)
+        self.assertEqual(self.r.bitcount(self.k1, 0, 0), 4)
+        self.assertEqual(self.r.bitcount(self.k1, 1, 1), 6)
+        self.assertEqual(self.r.bitcount(self.k1, -2, -1), 7)
+        self.assertEqual(self.r.execute_command('BITCOUNT', self.k1, 1, 1, 'BYTE'), 6)
+        self.assertEqual(self.r.execute_command('BITCOUNT', self.k1, 5, 30, 'BIT'), 17)
+        self.assertEqual(self.r.bitcount(self.k1, 3, 1), 0)
+        self.assertEqual(self.r.bitcount(self.k2), 0)
+
+    def test_bitpos(self):
+        self.r.set(self.k1, b'\xff\xf0\x00')
+        self.assertEqual(self.r.bitpos(self.k1, 0), 12)
+        self.r.set(self.k1, '\x00\x0f\x00')
+        self.assertEqual(self.r.bitpos(self.k1, 1), 12)
+        self.assertEqual(self.r.bitpos(self.k1, 1, 2), -1)
+        self.assertEqual(self.r.bitpos(self.k1, 1, 2, -1), -1)
+        self.assertEqual(self.r.execute_command('BITPOS', self.k1, 1, 7, 15, 'BIT'), 12)
+        self.assertEqual(self.r.execute_command('BITPOS', self.k1, 0, 12, 15, 'BIT'), -1)
+        self.r.set(self.k1, '\x7f')
+        self.assertEqual(self.r.bitpos(self.k1, 0), 0)
+        self.r.set(self.k1, '\x7f\x7f')
+        self.assertEqual(self.r.bitpos(self.k1, 0, 1), 8)
+        self.assertEqual(self.r.bitpos(self.k3, 1), -1)
+        self.assertEqual(self.r.bitpos(self.k3, 0), 0)
+
+    def test_bitop(self):
+        self.r.set(self.k1, 'foobar')
+        self.r.set(self.k2, 'abcdef')
+        self.assertEqual(self.r.bitop('AND', self.k3, self.k1, self.k2), 6)
+        self.assertEqual(self.r.get(self.k3), '`bc`ab')
+        self.assertEqual(self.r.bitop('OR', self.k3, self.k1, self.k2), 6)
+        self.assertEqual(self.r.get(self.k3), 'goofev')
+        self.assertEqual(self.r.bitop('XOR', self.k3, self.k1, self.k3), 6)
+        self.assertEqual(self.r.get(self.k3), '\x01\x00\x00\x04\x04\x04')
+        self.r.set(self.k1, '\x7f')
+        self.assertEqual(self.r.bitop('NOT', self.k3, self.k1), 1)
+        self.assertEqual(self.r.bitcount(self.k3), 1)
+        self.assertRaises(Exception, self.r.bitop, 'NOT', self.k3, self.k1, self.k2)
+        self.r.delete(self.k1, self.k2)
+        self.assertEqual(self.r.bitop('OR', self.k3, self.k1, self.k2), 0)
+        self.assertEqual(self.r.exists(self.k3), 0)
+
+    def test_bitop_chunked(self):
+        # the sources span several windows of chunks and differ in length
+        self.assertEqual(self.r.setrange(self.k1, 2 ** 21, 'a'), 2 ** 21 + 1)
+        self.assertEqual(self.r.setrange(self.k1, 10, 'b'), 2 ** 21 + 1)
+        self.assertEqual(self.r.setrange(self.k2, 5000, 'c'), 5001)
+        self.assertEqual(self.r.bitop('OR', self.k3, self.k1, self.k2), 2 ** 21 + 1)
+        self.assertEqual(self.r.getrange(self.k3, 10, 10), 'b')
+        self.assertEqual(self.r.getrange(self.k3, 5000, 5000), 'c')
+        self.assertEqual(self.r.getrange(self.k3, -1, -1), 'a')
+        self.assertEqual(self.r.bitcount(self.k3), 10)
+        # the destination is also a source
+        self.assertEqual(self.r.bitop('AND', self.k1, self.k1, self.k2), 2 ** 21 + 1)
+        self.assertEqual(self.r.bitcount(self.k1), 0)
+        self.assertEqual(self.r.bitop('XOR', self.k3, self.k3, self.k2), 2 ** 21 + 1)
+        self.assertEqual(self.r.getrange(self.k3, 5000, 5000), '\x00')
+        self.assertEqual(self.r.bitcount(self.k3), 6)
+
+    def test_bitfield(self):
+        bf = self.r.bitfield(self.k1)
+        bf.incrby('i5', 100, 1).get('u4', 0)
+        self.assertListEqual(bf.execute(), [1, 0])
+        bf = self.r.bitfield(self.k1)
+        bf.set('u8', '#1', 255).get('u8', 8).get('i8', 8)
+        self.assertListEqual(bf.execute(), [0, 255, -1])
+        bf = self.r.bitfield(self.k2, default_overflow='SAT')
+        bf.incrby('u2', 0, 5).incrby('i8', 8, -200)
+        self.assertListEqual(bf.execute(), [3, -128])
+        bf = self.r.bitfield(self.k2, default_overflow='FAIL')
+        bf.incrby('u2', 0, 1).incrby('u2', 0, -1)
+        self.assertListEqual(bf.execute(), [None, 2])
+        bf = self.r.bitfield(self.k3)
+        bf.set('i8', 0, 128).incrby('u8', 8, 257)
+        self.assertListEqual(bf.execute(), [0, 1])
+        self.assertEqual(self.r.getbit(self.k3, 0), 1)
+        self.assertListEqual(self.r.execute_command('BITFIELD_RO', self.k3, 'GET', 'i8', 0), [-128])
+        self.assertRaises(Exception, self.r.execute_command, 'BITFIELD_RO', self.k3, 'SET', 'i8', 0, 1)
+        self.assertRaises(Exception, self.r.execute_command, 'BITFIELD', self.k3, 'GET', 'u64', 0)
+
+    def test_large_offset(self):
+        self.assertEqual(self.r.setbit(self.k1, 2 ** 32 - 1, 1), 0)
+        self.assertEqual(self.r.strlen(self.k1), 2 ** 29)
+        self.assertEqual(self.r.getbit(self.k1, 2 ** 32 - 1), 1)
+        self.assertEqual(self.r.bitcount(self.k1), 1)
+        self.assertEqual(self.r.bitpos(self.k1, 1), 2 ** 32 - 1)
+        self.assertEqual(self.r.getrange(self.k1, -1, -1), '\x01')
+        self.assertEqual(self.r.setbit(self.k1, 8, 1), 0)
+        self.assertEqual(self.r.bitcount(self.k1, 0, 10), 1)
+        self.assertTrue(self.r.expire(self.k1, 100))
+        self.assertEqual(self.r.setbit(self.k1, 2 ** 32 - 1, 0), 1)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.assertEqual(self.r.bitcount(self.k1), 1)
+        self.assertEqual(self.r.delete(self.k1), 1)
+        self.assertEqual(self.r.getbit(self.k1, 8), 0)
+
+    def test_chunked_string(self):
+        self.assertEqual(self.r.setrange(self.k1, 10000, 'abc'), 10003)
+        self.assertEqual(self.r.getrange(self.k1, 9999, -1), '\x00abc')
+        self.assertEqual(self.r.append(self.k1, 'def'), 10006)
+        self.assertEqual(self.r.get(self.k1), '\x00' * 10000 + 'abcdef')
+        self.assertEqual(self.r.setrange(self.k1, 4095, 'xy'), 10006)
+        self.assertEqual(self.r.getrange(self.k1, 4094, 4097), '\x00xy\x00')
+        self.assertEqual(self.r.bitcount(self.k1), self.r.bitcount(self.k1, 4095, -1))
+        self.assertTrue(self.r.set(self.k1, 'short'))
+        self.assertEqual(self.r.get(self.k1), 'short')
+        self.assertEqual(self.r.getrange(self.k1, 0, -1), 'short')
+        self.assertEqual(self.r.setrange(self.k2, 5000, 'a'), 5001)
+        self.assertEqual(self.r.getdel(self.k2), '\x00' * 5000 + 'a')
+        self.assertEqual(self.r.setrange(self.k2, 10, 'b'), 11)
+        self.assertEqual(self.r.get(self.k2), '\x00' * 10 + 'b')
+
+    def test_chunked_string_async_del(self):
+        # the chunks of a long string may be deleted after a new string of
+        # the key is written, which must not lose any of its chunks
+        size = trigger_async_del_size() * 4096
+        for i in range(3):
+            self.assertEqual(self.r.setrange(self.k1, 0, 'x'), 1)
+            self.assertEqual(self.r.setrange(self.k1, size, 'y'), size + 1)
+            self.assertEqual(self.r.delete(self.k1), 1)
+            self.assertEqual(self.r.setrange(self.k1, 5000, 'z'), 5001)
+            self.assertEqual(self.r.getrange(self.k1, 0, 0), '\x00')
+            self.assertEqual(self.r.getrange(self.k1, 5000, -1), 'z')
+            self.assertEqual(self.r.bitop('or', self.k2, self.k1), 5001)
+            self.assertEqual(self.r.bitcount(self.k2), self.r.bitcount(self.k1))
+            self.assertEqual(self.r.delete(self.k1, self.k2), 2)
@@ -1,3 +1,4 @@
+import socket
 import unittest
 
 from redis import exceptions
@@ -27,6 +28,24 @@ class GenericTest(unittest.TestCase):
     def test_ping(self):
         self.assertTrue(self.r.ping())
 
+    @unittest.skipIf(RedisWrapper.requirepass, "skip inline when requirepass is true")
+    def test_inline_command(self):
+        conn = self.r.connection_pool.get_connection("")
+        with socket.create_connection((conn.host, conn.port)) as s:
+            s.sendall(b'PING\r\n\r\nset ' + self.k1.encode() + b' "a b\\x41"\r\nget ' + self.k1.encode() + b'\n')
+            buf = b''
+            while buf.count(b'\r\n') < 4:
+                buf += s.recv(1024)
+        self.r.connection_pool.release(conn)
+        self.assertEqual(buf, b'+PONG\r\n+OK\r\n$4\r\na bA\r\n')
+
+    def test_hello(self):
+        res = self.r.execute_command('hello', 2)
+        info = dict(zip(res[::2], res[1::2]))
+        self.assertEqual(info['server'], 'tidis')
+        self.assertEqual(info['proto'], 2)
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'hello', 4)
+
     def test_multi_exec(self):
         self.assertTrue(self.r.execute_command('multi'))
         self.r.execute_command('set', self.k1, 'value1')
@@ -58,6 +77,91 @@ class GenericTest(unittest.TestCase):
         err = cm.exception
         self.assertEqual(str(err), 'DISCARD without MULTI')
 
+    def test_multi_queue_error(self):
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('set', self.k1, 'value1')
+        with self.assertRaises(exceptions.ResponseError):
+            self.r.execute_command('get', self.k1, self.k2)
+        with self.assertRaises(exceptions.ResponseError):
+            self.r.execute_command('notacommand')
+        with self.assertRaises(exceptions.ResponseError) as cm:
+            self.r.execute_command('exec')
+        self.assertTrue(str(cm.exception).startswith('Transaction discarded'))
+        self.assertIsNone(self.r.get(self.k1))
+
+    def test_multi_runtime_error(self):
+        self.assertTrue(self.r.set(self.k2, 'value2'))
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('set', self.k1, 'value1')
+        self.r.execute_command('lpush', self.k2, 'a')
+        self.r.execute_command('eval', "return redis.call('get', KEYS[1])", 1, self.k1)
+        self.r.execute_command('ping')
+        res = self.r.execute_command('exec')
+        self.assertEqual(res[0], 'OK')
+        self.assertIsInstance(res[1], exceptions.ResponseError)
+        self.assertListEqual(res[2:], ['value1', 'PONG'])
+        self.assertEqual(self.r.get(self.k1), 'value1')
+
+    def test_watch(self):
+        client2 = RedisWrapper.clone()
+        self.assertTrue(self.r.execute_command('watch', self.k1))
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('set', self.k1, 'value1')
+        self.assertListEqual(self.r.execute_command('exec'), ['OK'])
+
+        self.assertTrue(self.r.execute_command('watch', self.k1))
+        self.assertTrue(client2.set(self.k1, 'value2'))
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('set', self.k1, 'value1')
+        self.assertIsNone(self.r.execute_command('exec'))
+        self.assertEqual(self.r.get(self.k1), 'value2')
+
+        # adding a field to a watched hash aborts the transaction too
+        self.assertEqual(client2.hset(self.k2, 'f1', 'v1'), 1)
+        self.assertTrue(self.r.execute_command('watch', self.k2))
+        self.assertEqual(client2.hset(self.k2, 'f2', 'v2'), 1)
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('hget', self.k2, 'f1')
+        self.assertIsNone(self.r.execute_command('exec'))
+
+        # so does overwriting an existing field
+        self.assertTrue(self.r.execute_command('watch', self.k2))
+        self.assertEqual(client2.hset(self.k2, 'f1', 'v3'), 0)
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('hget', self.k2, 'f1')
+        self.assertIsNone(self.r.execute_command('exec'))
+
+        # changing the score of an existing member
+        self.r.execute_command('del', self.k2)
+        self.assertEqual(client2.zadd(self.k2, {'m1': 1}), 1)
+        self.assertTrue(self.r.execute_command('watch', self.k2))
+        self.assertEqual(client2.zincrby(self.k2, 2, 'm1'), 3)
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('zscore', self.k2, 'm1')
+        self.assertIsNone(self.r.execute_command('exec'))
+
+        # replacing an element of a list
+        self.r.execute_command('del', self.k2)
+        self.assertEqual(client2.rpush(self.k2, 'a', 'b'), 2)
+        self.assertTrue(self.r.execute_command('watch', self.k2))
+        self.assertTrue(client2.lset(self.k2, 0, 'c'))
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('lindex', self.k2, 0)
+        self.assertIsNone(self.r.execute_command('exec'))
+
+        self.assertTrue(self.r.execute_command('watch', self.k1))
+        self.assertTrue(client2.set(self.k1, 'value3'))
+        self.assertTrue(self.r.execute_command('unwatch'))
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('get', self.k1)
+        self.assertListEqual(self.r.execute_command('exec'), ['value3'])
+
+        self.assertTrue(self.r.execute_command('multi'))
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('watch', self.k1)
+        self.assertEqual(str(cm.exception), 'WATCH inside MULTI is not allowed')
+        self.assertEqual(self.r.execute_command('discard'), 'OK')
+
     def test_client(self):
         client1 = self.r
         client1_id = client1.execute_command("client id")
@@ -74,6 +178,112 @@ class GenericTest(unittest.TestCase):
         self.assertEqual(client2.execute_command("client kill id", client1_id), 1)
         self.assertEqual(client2.execute_command("client list id", client1_id), "")
 
+    def test_psubscribe(self):
+        p = self.r.pubsub()
+        p.subscribe('news.tech')
+        p.psubscribe('news.*', 'n?ws.[st]*')
+        self.assertEqual(p.get_message(timeout=1)['data'], 1)
+        self.assertEqual(p.get_message(timeout=1)['data'], 2)
+        self.assertEqual(p.get_message(timeout=1)['data'], 3)
+
+        # delivered to the channel and both patterns
+        self.assertEqual(self.r.publish('news.tech', 'hello'), 3)
+        messages = [p.get_message(timeout=1) for _ in range(3)]
+        self.assertEqual(sorted(m['type'] for m in messages), ['message', 'pmessage', 'pmessage'])
+        self.assertEqual(sorted(m['pattern'] or '' for m in messages), ['', 'n?ws.[st]*', 'news.*'])
+        self.assertTrue(all(m['channel'] == 'news.tech' and m['data'] == 'hello' for m in messages))
+        self.assertEqual(self.r.publish('news.art', 'hello'), 1)
+        self.assertEqual(p.get_message(timeout=1)['pattern'], 'news.*')
+        self.assertEqual(self.r.publish('sports', 'hello'), 0)
+
+        p.punsubscribe('news.*')
+        self.assertEqual(p.get_message(timeout=1)['data'], 2)
+        p.punsubscribe()
+        self.assertEqual(p.get_message(timeout=1)['data'], 1)
+        p.close()
+
+    def test_sharded_pubsub(self):
+        # channels of different slots
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'ssubscribe', 'a', 'b')
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'ssubscribe')
+
+        p = self.r.pubsub()
+        p.execute_command('ssubscribe', '{orders}.1', '{orders}.2')
+        self.assertEqual(p.get_message(timeout=1)['data'], 1)
+        self.assertEqual(p.get_message(timeout=1)['data'], 2)
+
+        self.assertEqual(self.r.execute_command('spublish', '{orders}.1', 'hello'), 1)
+        message = p.get_message(timeout=1)
+        self.assertEqual(message['type'], 'smessage')
+        self.assertEqual(message['channel'], '{orders}.1')
+        self.assertEqual(message['data'], 'hello')
+        # shard channels are apart from the others
+        self.assertEqual(self.r.publish('{orders}.1', 'hello'), 0)
+
+        # a bare SSUBSCRIBE is rejected while subscribed, and the connection stays usable
+        p.execute_command('ssubscribe')
+        self.assertRaises(exceptions.ResponseError, p.get_message, timeout=1)
+        self.assertEqual(self.r.execute_command('spublish', '{orders}.2', 'again'), 1)
+        self.assertEqual(p.get_message(timeout=1)['data'], 'again')
+
+        p.execute_command('sunsubscribe')
+        self.assertEqual(p.get_message(timeout=1)['data'], 1)
+        self.assertEqual(p.get_message(timeout=1)['data'], 0)
+        p.close()
+
+    def test_publishlocal_hidden(self):
+        p = self.r.pubsub()
+        p.subscribe('local.a')
+        p.get_message(timeout=1)
+
+        # only the peers know the secret of the cluster
+        with self.assertRaisesRegex(exceptions.ResponseError, "unknown command 'publishlocal'"):
+            self.r.execute_command('publishlocal', 'local.a', 'hello')
+        with self.assertRaisesRegex(exceptions.ResponseError, "unknown command 'publishlocal'"):
+            self.r.execute_command('publishlocal', 'guess', 'local.a', 'hello')
+        self.assertIsNone(p.get_message(timeout=1))
+
+        self.assertEqual(self.r.publish('local.a', 'hello'), 1)
+        self.assertEqual(p.get_message(timeout=1)['data'], 'hello')
+        p.close()
+
+    def test_pubsub_introspection(self):
+        p = self.r.pubsub()
+        p.subscribe('intro.a', 'intro.b')
+        p.psubscribe('intro.*')
+        for _ in range(3):
+            p.get_message(timeout=1)
+
+        self.assertEqual(sorted(self.r.pubsub_channels('intro.*')), ['intro.a', 'intro.b'])
+        self.assertEqual(self.r.pubsub_numsub('intro.a', 'intro.c'), [('intro.a', 1), ('intro.c', 0)])
+        self.assertEqual(self.r.pubsub_numpat(), 1)
+
+        p.unsubscribe('intro.a')
+        p.get_message(timeout=1)
+        self.assertEqual(self.r.pubsub_channels('intro.*'), ['intro.b'])
+        p.close()
+
+    def test_keyspace_notifications(self):
+        self.r.config_set('notify-keyspace-events', 'KEA')
+        p = self.r.pubsub()
+        p.psubscribe('__key*__:notify.*')
+        p.get_message(timeout=1)
+
+        self.r.set('notify.k', 'v')
+        m = p.get_message(timeout=1)
+        self.assertEqual(m['channel'], '__keyspace@0__:notify.k')
+        self.assertEqual(m['data'], 'set')
+
+        p.psubscribe('__keyevent@0__:del')
+        p.get_message(timeout=1)
+        self.r.delete('notify.k')
+        events = [p.get_message(timeout=1) for _ in range(2)]
+        self.assertIn({'type': 'pmessage', 'pattern': '__keyevent@0__:del',
+                       'channel': '__keyevent@0__:del', 'data': 'notify.k'}, events)
+
+        self.r.config_set('notify-keyspace-events', '')
+        p.close()
+
     def test_scan(self):
         # add some keys for scan test
         for i in range(0, 10):
@@ -0,0 +1,107 @@
+import unittest
+
+from rediswrap import RedisWrapper
+
+
+class GeoTest(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        cls.r = RedisWrapper.get_instance()
+
+        cls.k1 = '__geo1__'
+        cls.k2 = '__geo2__'
+
+    def setUp(self):
+        self.r.delete(self.k1)
+        self.r.delete(self.k2)
+
+    def add_sicily(self):
+        return self.r.geoadd(self.k1, 13.361389, 38.115556, 'Palermo', 15.087269, 37.502669, 'Catania')
+
+    def test_geoadd(self):
+        self.assertEqual(self.add_sicily(), 2)
+        self.assertEqual(self.r.zscore(self.k1, 'Palermo'), 3479099956230698)
+        self.assertEqual(self.r.zscore(self.k1, 'Catania'), 3479447370796909)
+        self.assertEqual(self.r.execute_command('GEOADD', self.k1, 'NX', 13, 38, 'Palermo'), 0)
+        self.assertEqual(self.r.zscore(self.k1, 'Palermo'), 3479099956230698)
+        self.assertEqual(self.r.execute_command('GEOADD', self.k1, 'XX', 'CH', 13, 38, 'Palermo', 14, 37, 'Agrigento'), 1)
+        self.assertEqual(self.r.zcard(self.k1), 2)
+        with self.assertRaises(Exception):
+            self.r.geoadd(self.k1, 200, 10, 'invalid')
+        with self.assertRaises(Exception):
+            self.r.geoadd(self.k1, 10, 86, 'invalid')
+
+    def test_geopos(self):
+        self.add_sicily()
+        pos = self.r.geopos(self.k1, 'Palermo', 'nonexisting', 'Catania')
+        self.assertAlmostEqual(pos[0][0], 13.361389, 5)
+        self.assertAlmostEqual(pos[0][1], 38.115556, 5)
+        self.assertIsNone(pos[1])
+        self.assertAlmostEqual(pos[2][0], 15.087269, 5)
+        self.assertAlmostEqual(pos[2][1], 37.502669, 5)
+        self.assertEqual(self.r.geopos(self.k2, 'Palermo'), [None])
+
+    def test_geodist(self):
+        self.add_sicily()
+        self.assertEqual(self.r.geodist(self.k1, 'Palermo', 'Catania'), 166274.1516)
+        self.assertEqual(self.r.geodist(self.k1, 'Palermo', 'Catania', 'km'), 166.2742)
+        self.assertEqual(self.r.geodist(self.k1, 'Palermo', 'Catania', 'mi'), 103.3182)
+        self.assertIsNone(self.r.geodist(self.k1, 'Palermo', 'nonexisting'))
+        self.assertIsNone(self.r.geodist(self.k2, 'Palermo', 'Catania'))
+        with self.assertRaises(Exception):
+            self.r.execute_command('GEODIST', self.k1, 'Palermo', 'Catania', 'yards')
+
+    def test_geohash(self):
+        self.add_sicily()
+        self.assertEqual(self.r.geohash(self.k1, 'Palermo', 'Catania', 'nonexisting'),
+                         ['sqc8b49rny0', 'sqdtr74hyu0', None])
+
+    def test_geosearch_radius(self):
+        self.add_sicily()
+        self.assertEqual(self.r.execute_command('GEOSEARCH', self.k1, 'FROMLONLAT', 15, 37,
+                                                'BYRADIUS', 200, 'km', 'ASC'),
+                         ['Catania', 'Palermo'])
+        self.assertEqual(self.r.execute_command('GEOSEARCH', self.k1, 'FROMLONLAT', 15, 37,
+                                                'BYRADIUS', 100, 'km'),
+                         ['Catania'])
+        self.assertEqual(self.r.execute_command('GEOSEARCH', self.k1, 'FROMMEMBER', 'Palermo',
+                                                'BYRADIUS', 200, 'km', 'DESC', 'WITHDIST'),
+                         [['Catania', '166.2742'], ['Palermo', '0.0000']])
+        self.assertEqual(self.r.execute_command('GEOSEARCH', self.k1, 'FROMLONLAT', 15, 37,
+                                                'BYRADIUS', 200, 'km', 'COUNT', 1, 'WITHHASH', 'WITHCOORD'),
+                         [['Catania', 3479447370796909, ['15.08726745843887329', '37.50266842333162032']]])
+        self.assertEqual(self.r.execute_command('GEOSEARCH', self.k2, 'FROMLONLAT', 15, 37,
+                                                'BYRADIUS', 200, 'km'), [])
+        with self.assertRaises(Exception):
+            self.r.execute_command('GEOSEARCH', self.k1, 'FROMMEMBER', 'nonexisting', 'BYRADIUS', 200, 'km')
+        with self.assertRaises(Exception):
+            self.r.execute_command('GEOSEARCH', self.k1, 'FROMLONLAT', 15, 37)
+
+    def test_geosearch_box(self):
+        self.add_sicily()
+        self.assertEqual(self.r.execute_command('GEOSEARCH', self.k1, 'FROMLONLAT', 15, 37,
+                                                'BYBOX', 400, 400, 'km', 'ASC'),
+                         ['Catania', 'Palermo'])
+        self.assertEqual(self.r.execute_command('GEOSEARCH', self.k1, 'FROMLONLAT', 15, 37,
+                                                'BYBOX', 200, 200, 'km', 'ASC'),
+                         ['Catania'])
+
+    def test_geosearchstore(self):
+        self.add_sicily()
+        self.r.set(self.k2, 'value')
+        self.assertEqual(self.r.execute_command('GEOSEARCHSTORE', self.k2, self.k1, 'FROMLONLAT', 15, 37,
+                                                'BYRADIUS', 200, 'km'), 2)
+        self.assertEqual(self.r.type(self.k2), 'zset')
+        self.assertEqual(self.r.zscore(self.k2, 'Palermo'), 3479099956230698)
+        self.assertEqual(self.r.execute_command('GEOSEARCHSTORE', self.k2, self.k1, 'FROMLONLAT', 15, 37,
+                                                'BYRADIUS', 200, 'km', 'STOREDIST'), 2)
+        self.assertAlmostEqual(self.r.zscore(self.k2, 'Catania'), 56.4413, 3)
+        self.assertEqual(self.r.execute_command('GEOSEARCHSTORE', self.k2, self.k1, 'FROMLONLAT', 0, 0,
+                                                'BYRADIUS', 1, 'km'), 0)
+        self.assertEqual(self.r.exists(self.k2), 0)
+
+    @classmethod
+    def tearDownClass(cls):
+        cls.r.execute_command('del', cls.k1)
+        cls.r.execute_command('del', cls.k2)
+        print('test data cleaned up')
@@ -61,6 +61,16 @@ class HashTest(unittest.TestCase):
         self.assertEqual(self.r.hset(self.k1, self.f1, self.v1), 1)
         self.assertEqual(self.r.hstrlen(self.k1, self.f1), len(self.v1))
 
+    def test_binary_field(self):
+        f1 = b'\xff\x00\x01'
+        f2 = b'\xff\x00\x02'
+        self.assertEqual(self.r.hset(self.k1, f1, self.v1), 1)
+        self.assertTrue(self.r.hexists(self.k1, f1))
+        self.assertFalse(self.r.hexists(self.k1, f2))
+        self.assertEqual(self.r.hget(self.k1, f1), self.v1)
+        self.assertEqual(self.r.hstrlen(self.k1, f1), len(self.v1))
+        self.assertEqual(self.r.hdel(self.k1, f1), 1)
+
     def test_hlen(self):
         prefix = '__'
         for i in range(0, 200):
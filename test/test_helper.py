@@ -1,8 +1,11 @@
 import sys
 import unittest
 
+from test_bitmap import BitmapTest
 from test_generic import GenericTest
+from test_geo import GeoTest
 from test_hash import HashTest
+from test_hyperloglog import HyperLogLogTest
 from test_invalid import InvalidTest
 from test_list import ListTest
 from test_lua import LuaTest
@@ -15,10 +18,13 @@ if __name__ == '__main__':
     suite.addTest(unittest.TestLoader().loadTestsFromTestCase(GenericTest))
     suite.addTest(unittest.TestLoader().loadTestsFromTestCase(InvalidTest))
     suite.addTest(unittest.TestLoader().loadTestsFromTestCase(StringTest))
+    suite.addTest(unittest.TestLoader().loadTestsFromTestCase(BitmapTest))
+    suite.addTest(unittest.TestLoader().loadTestsFromTestCase(HyperLogLogTest))
     suite.addTest(unittest.TestLoader().loadTestsFromTestCase(HashTest))
     suite.addTest(unittest.TestLoader().loadTestsFromTestCase(ListTest))
     suite.addTest(unittest.TestLoader().loadTestsFromTestCase(SetTest))
     suite.addTest(unittest.TestLoader().loadTestsFromTestCase(ZsetTest))
+    suite.addTest(unittest.TestLoader().loadTestsFromTestCase(GeoTest))
     suite.addTest(unittest.TestLoader().loadTestsFromTestCase(LuaTest))
 
     runner = unittest.TextTestRunner(verbosity=2)
@@ -0,0 +1,81 @@
+import unittest
+
+import redis
+
+from rediswrap import RedisWrapper
+
+
+class HyperLogLogTest(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        cls.r = RedisWrapper.get_instance()
+        kwargs = dict(cls.r.connection_pool.connection_kwargs, decode_responses=False)
+        cls.raw = redis.StrictRedis(**kwargs)
+
+        cls.k1 = '__hll1__'
+        cls.k2 = '__hll2__'
+        cls.k3 = '__hll3__'
+
+    def setUp(self):
+        self.r.delete(self.k1)
+        self.r.delete(self.k2)
+        self.r.delete(self.k3)
+
+    def test_pfadd_pfcount(self):
+        self.assertEqual(self.r.pfadd(self.k1, 'a', 'b', 'c', 'd', 'e', 'f', 'g'), 1)
+        self.assertEqual(self.r.pfcount(self.k1), 7)
+        self.assertEqual(self.r.pfadd(self.k1, 'a', 'b'), 0)
+        self.assertEqual(self.r.pfcount(self.k1), 7)
+        self.assertEqual(self.r.execute_command('PFADD', self.k2), 1)
+        self.assertEqual(self.r.execute_command('PFADD', self.k2), 0)
+        self.assertEqual(self.r.pfcount(self.k2), 0)
+        self.assertEqual(self.r.pfcount(self.k3), 0)
+        self.assertEqual(self.r.getrange(self.k1, 0, 3), 'HYLL')
+
+    def test_pfcount_multiple_keys(self):
+        self.r.pfadd(self.k1, 'a', 'b', 'c')
+        self.r.pfadd(self.k2, 'c', 'd', 'e')
+        self.assertEqual(self.r.pfcount(self.k1, self.k2), 5)
+        self.assertEqual(self.r.pfcount(self.k1, self.k2, self.k3), 5)
+        self.assertEqual(self.r.exists(self.k3), 0)
+
+    def test_pfmerge(self):
+        self.r.pfadd(self.k1, 'a', 'b', 'c')
+        self.r.pfadd(self.k2, 'c', 'd', 'e')
+        self.r.pfadd(self.k3, 'f')
+        self.assertTrue(self.r.pfmerge(self.k3, self.k1, self.k2))
+        self.assertEqual(self.r.pfcount(self.k3), 6)
+        self.r.delete(self.k3)
+        self.assertTrue(self.r.execute_command('PFMERGE', self.k3))
+        self.assertEqual(self.r.pfcount(self.k3), 0)
+
+    def test_dense(self):
+        elements = ['elem-{}'.format(i) for i in range(20000)]
+        for i in range(0, len(elements), 1000):
+            self.r.pfadd(self.k1, *elements[i:i + 1000])
+        self.assertEqual(self.r.strlen(self.k1), 12304)
+        count = self.r.pfcount(self.k1)
+        self.assertLess(abs(count - 20000), 20000 * 0.02)
+        self.r.pfadd(self.k2, 'a', 'b')
+        self.assertTrue(self.r.pfmerge(self.k2, self.k1))
+        self.assertEqual(self.r.strlen(self.k2), 12304)
+        self.assertLess(abs(self.r.pfcount(self.k2) - 20002), 20002 * 0.02)
+
+    def test_redis_compatible_value(self):
+        # an empty sparse HLL as created by redis
+        empty = b'HYLL\x01' + b'\x00' * 10 + b'\x80' + b'\x7f\xff'
+        self.assertTrue(self.raw.set(self.k1, empty))
+        self.assertEqual(self.r.pfcount(self.k1), 0)
+        self.assertEqual(self.r.pfadd(self.k1, 'a', 'b', 'c'), 1)
+        self.assertEqual(self.r.pfcount(self.k1), 3)
+        self.assertTrue(self.raw.set(self.k2, self.raw.get(self.k1)))
+        self.assertEqual(self.r.pfcount(self.k2), 3)
+
+    def test_invalid_value(self):
+        self.r.set(self.k1, 'value')
+        self.assertRaises(Exception, self.r.pfadd, self.k1, 'a')
+        self.assertRaises(Exception, self.r.pfcount, self.k1)
+        self.r.lpush(self.k2, 'a')
+        self.assertRaises(Exception, self.r.pfcount, self.k2)
+        self.raw.set(self.k3, b'HYLL\x01' + b'\x00' * 10 + b'\x80' + b'\x7f')
+        self.assertRaises(Exception, self.r.pfcount, self.k3)
@@ -1,6 +1,9 @@
+import threading
 import time
 import unittest
 
+from redis import exceptions
+
 from rediswrap import RedisWrapper
 from test_util import sec_ts_after_five_secs, msec_ts_after_five_secs, CmdType, trigger_async_del_size
 
@@ -45,6 +48,37 @@ class ListTest(unittest.TestCase):
         for i in range(200):
             self.assertEqual(self.r.lpop(self.k1), str(i))
 
+    def test_pop_count(self):
+        self.assertIsNone(self.r.execute_command('lpop', self.k1, 2))
+        self.assertTrue(self.r.rpush(self.k1, '0', '1', '2', '3'))
+        self.assertListEqual(self.r.execute_command('lpop', self.k1, 0), [])
+        self.assertListEqual(self.r.execute_command('lpop', self.k1, 2), ['0', '1'])
+        self.assertListEqual(self.r.execute_command('rpop', self.k1, 5), ['3', '2'])
+        self.assertEqual(self.r.llen(self.k1), 0)
+
+    def test_pushx(self):
+        self.assertEqual(self.r.lpushx(self.k1, '0'), 0)
+        self.assertEqual(self.r.execute_command('rpushx', self.k1, '0', '1'), 0)
+        self.assertEqual(self.r.exists(self.k1), 0)
+        self.assertTrue(self.r.rpush(self.k1, '1'))
+        self.assertEqual(self.r.lpushx(self.k1, '0'), 2)
+        self.assertEqual(self.r.execute_command('rpushx', self.k1, '2', '3'), 4)
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), ['0', '1', '2', '3'])
+
+    def test_lpos(self):
+        self.assertIsNone(self.r.execute_command('lpos', self.k1, 'a'))
+        # more elements than a single scan batch
+        self.assertTrue(self.r.rpush(self.k1, *['a' if i % 100 == 0 else str(i) for i in range(1000)]))
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'a'), 0)
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'a', 'rank', 3), 200)
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'a', 'rank', -1), 900)
+        self.assertListEqual(self.r.execute_command('lpos', self.k1, 'a', 'count', 3), [0, 100, 200])
+        self.assertListEqual(self.r.execute_command('lpos', self.k1, 'a', 'rank', -2, 'count', 2), [800, 700])
+        self.assertEqual(len(self.r.execute_command('lpos', self.k1, 'a', 'count', 0)), 10)
+        self.assertListEqual(self.r.execute_command('lpos', self.k1, 'a', 'count', 0, 'maxlen', 150), [0, 100])
+        self.assertIsNone(self.r.execute_command('lpos', self.k1, 'x'))
+        self.assertListEqual(self.r.execute_command('lpos', self.k1, 'x', 'count', 1), [])
+
     def test_type(self):
         self.assertEqual(self.r.type(self.k1), CmdType.NULL.value)
         self.assertTrue(self.r.lpush(self.k1, self.v1))
@@ -129,6 +163,176 @@ class ListTest(unittest.TestCase):
                              ['hello1', 'hello2'] + [str(i) for i in range(0, 50)] + ['hello3', '50', 'hello4'] + [
                                  str(i) for i in range(51, 99)] + ['hello5', '99', 'hello6'])
 
+    def test_large_list_insert_remove(self):
+        expected = [str(i) for i in range(1000)]
+        self.assertTrue(self.r.rpush(self.k1, *expected))
+        # insert at the same place many times, which spreads out the elements again
+        for i in range(300):
+            self.assertEqual(self.r.linsert(self.k1, 'before', '500', 'b' + str(i)), len(expected) + 1)
+            expected.insert(expected.index('500'), 'b' + str(i))
+        self.assertEqual(self.r.linsert(self.k1, 'after', '10', 'a'), len(expected) + 1)
+        expected.insert(11, 'a')
+        self.assertEqual(self.r.lrem(self.k1, 0, '20'), 1)
+        expected.remove('20')
+        self.assertEqual(self.r.lrem(self.k1, -2, '100'), 1)
+        expected.remove('100')
+
+        self.assertEqual(self.r.llen(self.k1), len(expected))
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), expected)
+        self.assertListEqual(self.r.lrange(self.k1, 495, 510), expected[495:511])
+        self.assertListEqual(self.r.lrange(self.k1, -20, -10), expected[-20:-9])
+        for idx in (0, 11, 499, 700, -1, -300):
+            self.assertEqual(self.r.lindex(self.k1, idx), expected[idx])
+        self.assertEqual(self.r.execute_command('lpos', self.k1, '500'), expected.index('500'))
+        self.assertTrue(self.r.lset(self.k1, 600, 'x'))
+        expected[600] = 'x'
+
+        self.assertEqual(self.r.lpush(self.k1, 'l'), len(expected) + 1)
+        expected.insert(0, 'l')
+        self.assertEqual(self.r.lpop(self.k1), expected.pop(0))
+        self.assertEqual(self.r.rpop(self.k1), expected.pop())
+        self.assertTrue(self.r.ltrim(self.k1, 5, -5))
+        expected = expected[5:-4]
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), expected)
+
+    def test_sparse_list_groups(self):
+        # pushing to both ends stores the elements in two groups of the index
+        left = [str(i) for i in range(300)]
+        right = [str(i) for i in range(300, 600)]
+        self.assertTrue(self.r.lpush(self.k1, *reversed(left)))
+        self.assertTrue(self.r.rpush(self.k1, *right))
+        expected = left + right
+        for v in ('100', '500'):
+            self.assertEqual(self.r.lrem(self.k1, 1, v), 1)
+            expected.remove(v)
+        for idx in (0, 150, 298, 299, 300, 450, -1):
+            self.assertEqual(self.r.lindex(self.k1, idx), expected[idx])
+        self.assertListEqual(self.r.lrange(self.k1, 290, 310), expected[290:311])
+        self.assertTrue(self.r.lset(self.k1, 299, 'x'))
+        expected[299] = 'x'
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), expected)
+
+    def test_sparse_list_shrink(self):
+        expected = [str(i) for i in range(2100)]
+        self.assertTrue(self.r.rpush(self.k1, *expected))
+        # removing from the middle leaves the list sparse across many buckets
+        for i in range(0, 2100, 3):
+            self.assertEqual(self.r.lrem(self.k1, 1, str(i)), 1)
+            expected.remove(str(i))
+        self.assertEqual(self.r.linsert(self.k1, 'after', '1501', 'a'), len(expected) + 1)
+        expected.insert(expected.index('1501') + 1, 'a')
+        for idx in (0, 682, 1000, 1300, -1):
+            self.assertEqual(self.r.lindex(self.k1, idx), expected[idx])
+        self.assertListEqual(self.r.lrange(self.k1, 1020, 1040), expected[1020:1041])
+
+        # shrinking the list makes it dense again
+        self.assertTrue(self.r.ltrim(self.k1, 1000, 1299))
+        expected = expected[1000:1300]
+        self.assertEqual(len(self.r.execute_command('lpop', self.k1, 100)), 100)
+        expected = expected[100:]
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), expected)
+        self.assertEqual(self.r.lpush(self.k1, 'l'), len(expected) + 1)
+        expected.insert(0, 'l')
+        self.assertEqual(self.r.linsert(self.k1, 'before', expected[50], 'b'), len(expected) + 1)
+        expected.insert(50, 'b')
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), expected)
+
+    def test_lmove(self):
+        self.assertIsNone(self.r.execute_command('lmove', self.k1, self.k2, 'left', 'right'))
+        self.assertTrue(self.r.rpush(self.k1, '0', '1', '2'))
+        self.assertEqual(self.r.execute_command('lmove', self.k1, self.k2, 'left', 'right'), '0')
+        self.assertEqual(self.r.rpoplpush(self.k1, self.k2), '2')
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), ['1'])
+        self.assertListEqual(self.r.lrange(self.k2, 0, -1), ['2', '0'])
+
+        # rotate the list when source and destination are the same
+        self.assertEqual(self.r.execute_command('lmove', self.k2, self.k2, 'left', 'right'), '2')
+        self.assertListEqual(self.r.lrange(self.k2, 0, -1), ['0', '2'])
+        self.assertEqual(self.r.rpoplpush(self.k1, self.k1), '1')
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), ['1'])
+
+    def test_lmove_wrong_type_in_multi(self):
+        self.assertTrue(self.r.rpush(self.k1, '0', '1'))
+        self.assertTrue(self.r.set(self.k2, 'value'))
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('lmove', self.k1, self.k2, 'left', 'right')
+        res = self.r.execute_command('exec')
+        self.assertIsInstance(res[0], exceptions.ResponseError)
+        # the source is untouched, the element is not lost
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), ['0', '1'])
+        self.assertEqual(self.r.get(self.k2), 'value')
+
+    def test_lmpop(self):
+        self.assertIsNone(self.r.execute_command('lmpop', 2, self.k1, self.k2, 'left'))
+        self.assertTrue(self.r.rpush(self.k2, '0', '1', '2'))
+        self.assertEqual(self.r.execute_command('lmpop', 2, self.k1, self.k2, 'left'), [self.k2, ['0']])
+        self.assertEqual(self.r.execute_command('lmpop', 2, self.k1, self.k2, 'right', 'count', 5),
+                         [self.k2, ['2', '1']])
+        self.assertEqual(self.r.llen(self.k2), 0)
+
+    def test_blpop(self):
+        self.assertIsNone(self.r.blpop([self.k1, self.k2], timeout=1))
+        self.assertTrue(self.r.rpush(self.k2, self.v1, self.v2))
+        self.assertEqual(self.r.blpop([self.k1, self.k2], timeout=1), (self.k2, self.v1))
+        self.assertEqual(self.r.brpop([self.k1, self.k2], timeout=1), (self.k2, self.v2))
+
+        # wake up the blocked client by a push from another connection
+        pusher = threading.Timer(0.5, self.r.rpush, args=(self.k1, self.v1))
+        pusher.start()
+        start = time.time()
+        self.assertEqual(self.r.blpop(self.k1, timeout=5), (self.k1, self.v1))
+        self.assertLess(time.time() - start, 2)
+        pusher.join()
+
+    def test_blpop_disconnect(self):
+        # a client disconnected while blocked must not pop the pushed element
+        conn = self.r.connection_pool.get_connection('blpop')
+        conn.send_command('blpop', self.k1, 0)
+        time.sleep(0.5)
+        conn.disconnect()
+        self.r.connection_pool.release(conn)
+        time.sleep(0.5)
+
+        self.assertTrue(self.r.rpush(self.k1, self.v1))
+        time.sleep(0.5)
+        self.assertListEqual(self.r.lrange(self.k1, 0, -1), [self.v1])
+
+    def test_blpop_pipeline(self):
+        # the replies pipelined before a blocking pop are sent before it blocks
+        conn = self.r.connection_pool.get_connection('blpop')
+        conn.send_packed_command(conn.pack_commands([('rpush', self.k1, self.v1), ('blpop', self.k2, 2)]))
+        start = time.time()
+        self.assertEqual(conn.read_response(), 1)
+        self.assertLess(time.time() - start, 1)
+        self.assertIsNone(conn.read_response())
+        self.r.connection_pool.release(conn)
+
+    def test_blmpop_blmove(self):
+        self.assertIsNone(self.r.execute_command('blmpop', 1, 2, self.k1, self.k2, 'left'))
+        self.assertTrue(self.r.rpush(self.k1, '0', '1', '2'))
+        self.assertEqual(self.r.execute_command('blmpop', 1, 2, self.k2, self.k1, 'right', 'count', 2),
+                         [self.k1, ['2', '1']])
+        self.assertEqual(self.r.execute_command('blmove', self.k1, self.k2, 'left', 'left', 1), '0')
+        self.assertEqual(self.r.llen(self.k1), 0)
+        self.assertListEqual(self.r.lrange(self.k2, 0, -1), ['0'])
+        self.assertIsNone(self.r.execute_command('blmove', self.k1, self.k2, 'left', 'left', 1))
+
+    def test_lmove_notifications(self):
+        self.r.config_set('notify-keyspace-events', 'El')
+        p = self.r.pubsub()
+        p.psubscribe('__keyevent@0__:*')
+        p.get_message(timeout=1)
+
+        self.assertTrue(self.r.rpush(self.k1, self.v1))
+        self.assertEqual(p.get_message(timeout=1)['channel'], '__keyevent@0__:rpush')
+        self.assertEqual(self.r.execute_command('lmove', self.k1, self.k2, 'left', 'right'), self.v1)
+        events = [p.get_message(timeout=1) for _ in range(2)]
+        self.assertEqual([(m['channel'], m['data']) for m in events],
+                         [('__keyevent@0__:lpop', self.k1), ('__keyevent@0__:rpush', self.k2)])
+
+        self.r.config_set('notify-keyspace-events', '')
+        p.close()
+
     def test_del(self):
         self.assertTrue(self.r.rpush(self.k1, self.v1))
         self.assertEqual(self.r.llen(self.k1), 1)
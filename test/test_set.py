@@ -55,6 +55,17 @@ class SetTest(unittest.TestCase):
         for i in range(100, 200):
             self.assertEqual(self.r.sismember(self.k1, str(i)), 0)
 
+    def test_binary_member(self):
+        m1 = b'\xff\x00\x01'
+        m2 = b'\xff\x00\x02'
+        self.assertEqual(self.r.sadd(self.k1, m1, m2), 2)
+        self.assertEqual(self.r.sadd(self.k1, m1), 0)
+        self.assertEqual(self.r.scard(self.k1), 2)
+        self.assertEqual(self.r.sismember(self.k1, m1), 1)
+        self.assertEqual(self.r.sismember(self.k1, b'\xff\x00\x03'), 0)
+        self.assertEqual(self.r.srem(self.k1, m2), 1)
+        self.assertEqual(self.r.scard(self.k1), 1)
+
     def test_smismember(self):
         for i in range(1, 100):
             self.assertListEqual(self.r.execute_command('smismember', self.k1, *(str(j) for j in range(i))), [0] * i)
@@ -193,6 +204,71 @@ class SetTest(unittest.TestCase):
         time.sleep(6)
         self.assertEqual(self.r.scard(self.k1), 0)
 
+    def test_sinter_sunion_sdiff(self):
+        self.r.sadd(self.k1, 'a', 'b', 'c', 'd')
+        self.r.sadd(self.k2, 'c', 'd', 'e')
+        self.assertEqual(set(self.r.sinter(self.k1, self.k2)), {'c', 'd'})
+        self.assertEqual(set(self.r.sunion(self.k1, self.k2)), {'a', 'b', 'c', 'd', 'e'})
+        self.assertEqual(set(self.r.sdiff(self.k1, self.k2)), {'a', 'b'})
+        self.assertEqual(set(self.r.sdiff(self.k2, self.k1)), {'e'})
+        self.assertEqual(self.r.sinter(self.k1, self.k3), [])
+        self.assertEqual(set(self.r.sunion(self.k1, self.k3)), {'a', 'b', 'c', 'd'})
+        self.assertEqual(self.r.sdiff(self.k3, self.k1), [])
+        self.r.set(self.k3, self.v1)
+        with self.assertRaises(Exception):
+            self.r.sinter(self.k1, self.k3)
+
+    def test_set_algebra_large(self):
+        for i in range(0, 1000, 100):
+            self.r.sadd(self.k1, *[str(j) for j in range(i, i + 100)])
+        self.r.sadd(self.k2, *[str(j) for j in range(0, 1000, 3)])
+        self.r.sadd(self.k3, *[str(j) for j in range(0, 1000, 5)])
+        self.assertEqual(set(self.r.sinter(self.k1, self.k2, self.k3)), {str(j) for j in range(0, 1000, 15)})
+        self.assertEqual(len(self.r.sunion(self.k2, self.k3)), 334 + 200 - 67)
+        self.assertEqual(len(self.r.sdiff(self.k1, self.k2, self.k3)), 1000 - 334 - 200 + 67)
+        self.assertEqual(self.r.execute_command('SINTERCARD', 2, self.k1, self.k2), 334)
+
+    def test_store(self):
+        self.r.sadd(self.k1, 'a', 'b', 'c')
+        self.r.sadd(self.k2, 'b', 'c', 'd')
+        self.r.set(self.k3, self.v1)
+        self.assertEqual(self.r.sinterstore(self.k3, self.k1, self.k2), 2)
+        self.assertEqual(self.r.smembers(self.k3), {'b', 'c'})
+        self.assertEqual(self.r.sunionstore(self.k3, self.k1, self.k2), 4)
+        self.assertEqual(self.r.smembers(self.k3), {'a', 'b', 'c', 'd'})
+        self.assertEqual(self.r.scard(self.k3), 4)
+        self.assertEqual(self.r.sdiffstore(self.k1, self.k1, self.k2), 1)
+        self.assertEqual(self.r.smembers(self.k1), {'a'})
+        self.assertEqual(self.r.scard(self.k1), 1)
+        self.assertEqual(self.r.sinterstore(self.k3, self.k1, self.k2), 0)
+        self.assertEqual(self.r.exists(self.k3), 0)
+
+    def test_sintercard(self):
+        self.r.sadd(self.k1, 'a', 'b', 'c', 'd')
+        self.r.sadd(self.k2, 'b', 'c', 'd', 'e')
+        self.assertEqual(self.r.execute_command('SINTERCARD', 2, self.k1, self.k2), 3)
+        self.assertEqual(self.r.execute_command('SINTERCARD', 2, self.k1, self.k2, 'LIMIT', 2), 2)
+        self.assertEqual(self.r.execute_command('SINTERCARD', 2, self.k1, self.k2, 'LIMIT', 0), 3)
+        self.assertEqual(self.r.execute_command('SINTERCARD', 2, self.k1, self.k3), 0)
+        with self.assertRaises(Exception):
+            self.r.execute_command('SINTERCARD', 3, self.k1, self.k2)
+
+    def test_smove(self):
+        self.r.sadd(self.k1, 'a', 'b')
+        self.assertEqual(self.r.smove(self.k1, self.k2, 'a'), 1)
+        self.assertEqual(self.r.smembers(self.k1), {'b'})
+        self.assertEqual(self.r.smembers(self.k2), {'a'})
+        self.assertEqual(self.r.smove(self.k1, self.k2, 'c'), 0)
+        self.assertEqual(self.r.smove(self.k3, self.k2, 'a'), 0)
+        self.assertEqual(self.r.smove(self.k1, self.k1, 'b'), 1)
+        self.assertEqual(self.r.smove(self.k1, self.k2, 'b'), 1)
+        self.assertEqual(self.r.exists(self.k1), 0)
+        self.assertEqual(self.r.scard(self.k2), 2)
+        self.r.set(self.k3, self.v1)
+        with self.assertRaises(Exception):
+            self.r.smove(self.k2, self.k3, 'a')
+        self.assertEqual(self.r.scard(self.k2), 2)
+
     def tearDown(self):
         pass
 
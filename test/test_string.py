@@ -156,6 +156,101 @@ class StringTest(unittest.TestCase):
         self.assertTrue(self.r.set(self.k1, self.v1))
         self.assertEqual(self.r.strlen(self.k1), len(self.v1))
 
+    def test_set_options(self):
+        self.assertIsNone(self.r.set(self.k1, self.v1, xx=True))
+        self.assertIsNone(self.r.get(self.k1))
+        self.assertTrue(self.r.set(self.k1, self.v1, ex=100))
+        self.assertEqual(self.r.execute_command('SET', self.k1, self.v2, 'XX', 'GET', 'KEEPTTL'), self.v1)
+        self.assertEqual(self.r.get(self.k1), self.v2)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.assertIsNone(self.r.execute_command('SET', self.k2, self.v2, 'NX', 'GET'))
+        self.assertEqual(self.r.execute_command('SET', self.k1, self.v1, 'GET'), self.v2)
+        self.assertEqual(self.r.ttl(self.k1), -1)
+        self.assertTrue(self.r.execute_command('SET', self.k1, self.v1, 'EXAT', sec_ts_after_five_secs()))
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.assertTrue(self.r.execute_command('SET', self.k1, self.v1, 'PXAT', msec_ts_after_five_secs()))
+        self.assertGreater(self.r.pttl(self.k1), 0)
+        self.assertTrue(self.r.rpush(self.k2, self.v1))
+        with self.assertRaises(Exception):
+            self.r.execute_command('SET', self.k2, self.v1, 'GET')
+        self.assertTrue(self.r.set(self.k2, self.v2))
+        self.assertEqual(self.r.get(self.k2), self.v2)
+
+    def test_set_overwrite_type(self):
+        fields = {str(i): self.v1 for i in range(100)}
+        self.assertTrue(self.r.hset(self.k1, mapping=fields))
+        self.assertTrue(self.r.set(self.k1, self.v2))
+        self.assertEqual(self.r.type(self.k1), 'string')
+        self.assertEqual(self.r.get(self.k1), self.v2)
+        # the fields of the overwritten hash do not come back
+        self.assertEqual(self.r.delete(self.k1), 1)
+        self.assertEqual(self.r.hset(self.k1, 'f', self.v1), 1)
+        self.assertDictEqual(self.r.hgetall(self.k1), {'f': self.v1})
+
+    def test_getset_getdel_getex(self):
+        self.assertIsNone(self.r.getset(self.k1, self.v1))
+        self.assertEqual(self.r.getset(self.k1, self.v2), self.v1)
+        self.assertEqual(self.r.execute_command('GETEX', self.k1, 'EX', 100), self.v2)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.assertEqual(self.r.execute_command('GETEX', self.k1), self.v2)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.assertEqual(self.r.execute_command('GETEX', self.k1, 'PERSIST'), self.v2)
+        self.assertEqual(self.r.ttl(self.k1), -1)
+        self.assertEqual(self.r.execute_command('GETDEL', self.k1), self.v2)
+        self.assertIsNone(self.r.execute_command('GETDEL', self.k1))
+        self.assertIsNone(self.r.execute_command('GETEX', self.k1))
+
+    def test_append_range(self):
+        self.assertEqual(self.r.append(self.k1, 'Hello'), 5)
+        self.assertEqual(self.r.append(self.k1, ' World'), 11)
+        self.assertEqual(self.r.getrange(self.k1, 0, 4), 'Hello')
+        self.assertEqual(self.r.getrange(self.k1, -5, -1), 'World')
+        self.assertEqual(self.r.getrange(self.k1, 5, 100), ' World')
+        self.assertEqual(self.r.getrange(self.k1, 3, 1), '')
+        self.assertEqual(self.r.execute_command('SUBSTR', self.k1, 0, -7), 'Hello')
+        self.assertEqual(self.r.setrange(self.k1, 6, 'Redis'), 11)
+        self.assertEqual(self.r.get(self.k1), 'Hello Redis')
+        self.assertEqual(self.r.setrange(self.k2, 3, 'abc'), 6)
+        self.assertEqual(self.r.get(self.k2), '\x00\x00\x00abc')
+        self.r.delete(self.k2)
+        self.assertEqual(self.r.setrange(self.k2, 3, ''), 0)
+        self.assertEqual(self.r.exists(self.k2), 0)
+        self.assertTrue(self.r.expire(self.k1, 100))
+        self.assertEqual(self.r.append(self.k1, '!'), 12)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+
+    def test_incrbyfloat(self):
+        self.assertEqual(self.r.incrbyfloat(self.k1, 10.5), 10.5)
+        self.assertEqual(self.r.incrbyfloat(self.k1, 0.1), 10.6)
+        self.assertEqual(self.r.incrbyfloat(self.k1, -5), 5.6)
+        self.assertTrue(self.r.expire(self.k1, 100))
+        self.assertEqual(self.r.incrbyfloat(self.k1, 1), 6.6)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.assertEqual(self.r.incr(self.k2), 1)
+        self.assertTrue(self.r.expire(self.k2, 100))
+        self.assertEqual(self.r.incr(self.k2), 2)
+        self.assertGreater(self.r.ttl(self.k2), 0)
+
+    def test_incrbyfloat_exponent(self):
+        self.assertEqual(self.r.incrbyfloat(self.k1, 1e300), 1e300)
+        self.assertEqual(self.r.get(self.k1), '1e+300')
+        self.assertEqual(self.r.incrbyfloat(self.k1, -1e300), 0)
+        self.assertEqual(self.r.get(self.k1), '0')
+        self.assertEqual(self.r.incrbyfloat(self.k1, 1.5e-7), 1.5e-7)
+        self.assertEqual(self.r.get(self.k1), '1.5e-07')
+
+    def test_msetnx(self):
+        self.assertTrue(self.r.msetnx({self.k1: self.v1, self.k2: self.v2}))
+        self.assertListEqual(self.r.mget(self.k1, self.k2), [self.v1, self.v2])
+        self.r.delete(self.k2)
+        self.assertFalse(self.r.msetnx({self.k1: self.v2, self.k2: self.v1}))
+        self.assertListEqual(self.r.mget(self.k1, self.k2), [self.v1, None])
+
+    def test_psetex(self):
+        self.assertTrue(self.r.psetex(self.k1, 5000, self.v1))
+        self.assertGreater(self.r.pttl(self.k1), 0)
+        self.assertEqual(self.r.get(self.k1), self.v1)
+
     def test_del(self):
         self.assertTrue(self.r.set(self.k1, self.v1))
         v1 = self.r.get(self.k1)
@@ -248,6 +343,60 @@ class StringTest(unittest.TestCase):
         time.sleep(6)
         self.assertIsNone(self.r.get(self.k1))
 
+    def test_active_expire(self):
+        # keys persisted or given a new ttl are kept by the background expirer
+        self.assertTrue(self.r.set(self.k1, self.v1, px=500))
+        self.assertTrue(self.r.persist(self.k1))
+        self.assertTrue(self.r.set(self.k2, self.v2, px=500))
+        self.assertTrue(self.r.pexpire(self.k2, 10000))
+        time.sleep(3)
+        self.assertEqual(self.r.get(self.k1), self.v1)
+        self.assertEqual(self.r.get(self.k2), self.v2)
+
+    def test_pipeline(self):
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        p = self.r.pipeline(transaction=False)
+        for _ in range(50):
+            p.get(self.k1)
+            p.get(self.k2)
+            p.mget(self.k1, self.k2)
+        p.set(self.k2, self.v2)
+        p.get(self.k2)
+        p.mget(self.k2, self.k1)
+        res = p.execute()
+        self.assertListEqual(res[:150], [self.v1, None, [self.v1, None]] * 50)
+        self.assertListEqual(res[150:], [True, self.v2, [self.v2, self.v1]])
+
+    def test_pipeline_expired(self):
+        self.r.config_set('notify-keyspace-events', 'Ex')
+        sub = self.r.pubsub()
+        sub.subscribe('__keyevent@0__:expired')
+        sub.get_message(timeout=1)
+
+        self.assertTrue(self.r.set(self.k1, self.v1, px=100))
+        time.sleep(0.5)
+        p = self.r.pipeline(transaction=False)
+        p.get(self.k1)
+        p.get(self.k2)
+        self.assertListEqual(p.execute(), [None, None])
+        self.assertEqual(sub.get_message(timeout=1)['data'], self.k1)
+        # the expired key is deleted from the storage, scan lists it otherwise
+        match_scan = self.r.execute_command('xscan', '', 'count', 10000, 'match', '^__string1__$')
+        self.assertListEqual(match_scan[1], [])
+
+        self.r.config_set('notify-keyspace-events', '')
+        sub.close()
+
+    def test_binary_key(self):
+        k1 = b'__string\xff\x00\xfe__'
+        k2 = b'__string\xff\x00\xfd__'
+        self.assertTrue(self.r.set(k1, self.v1))
+        self.assertIsNone(self.r.get(k2))
+        self.assertEqual(self.r.get(k1), self.v1)
+        self.assertEqual(self.r.exists(k1, k2), 1)
+        self.assertEqual(self.r.delete(k1), 1)
+        self.assertIsNone(self.r.get(k1))
+
     def tearDown(self):
         pass
 
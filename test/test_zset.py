@@ -1,3 +1,4 @@
+import threading
 import time
 import unittest
 
@@ -13,6 +14,7 @@ class ZsetTest(unittest.TestCase):
 
         cls.k1 = '__zset1__'
         cls.k2 = '__zset2__'
+        cls.k3 = '__zset3__'
 
         cls.v1 = 'value1'
         cls.v2 = 'value2'
@@ -20,6 +22,7 @@ class ZsetTest(unittest.TestCase):
     def setUp(self):
         self.r.execute_command('del', self.k1)
         self.r.execute_command('del', self.k2)
+        self.r.execute_command('del', self.k3)
         pass
 
     def test_zadd(self):
@@ -127,6 +130,15 @@ class ZsetTest(unittest.TestCase):
         for i in range(100):
             self.assertEqual(self.r.zscore(self.k1, str(i)), i)
 
+    def test_binary_member(self):
+        m1 = b'\xff\x00\x01'
+        m2 = b'\xff\x00\x02'
+        self.assertEqual(self.r.zadd(self.k1, {m1: 1, m2: 2}), 2)
+        self.assertEqual(self.r.zscore(self.k1, m1), 1)
+        self.assertEqual(self.r.zrank(self.k1, m2), 1)
+        self.assertEqual(self.r.zincrby(self.k1, 2, m1), 3)
+        self.assertEqual(self.r.zrem(self.k1, m1, m2), 2)
+
     def test_zrem(self):
         for i in range(100):
             self.assertEqual(self.r.zadd(self.k1, {str(i): i}), 1)
@@ -153,6 +165,26 @@ class ZsetTest(unittest.TestCase):
         self.assertEqual(self.r.zadd(self.k1, {self.v1: 1, self.v2: 2}), 2)
         self.assertListEqual(self.r.zpopmax(self.k1), [(self.v2, 2)])
 
+    def test_bzpopmin(self):
+        self.assertIsNone(self.r.bzpopmin([self.k1, self.k2], timeout=1))
+        self.assertEqual(self.r.zadd(self.k2, {self.v1: 1, self.v2: 2}), 2)
+        self.assertEqual(self.r.bzpopmin([self.k1, self.k2], timeout=1), (self.k2, self.v1, 1))
+        self.assertEqual(self.r.bzpopmax([self.k1, self.k2], timeout=1), (self.k2, self.v2, 2))
+
+        # wake up the blocked client by a zadd from another connection
+        adder = threading.Timer(0.5, self.r.zadd, args=(self.k1, {self.v1: 1}))
+        adder.start()
+        start = time.time()
+        self.assertEqual(self.r.bzpopmax(self.k1, timeout=5), (self.k1, self.v1, 1))
+        self.assertLess(time.time() - start, 2)
+        adder.join()
+
+    def test_bzmpop(self):
+        self.assertIsNone(self.r.execute_command('bzmpop', 1, 2, self.k1, self.k2, 'min'))
+        self.assertEqual(self.r.zadd(self.k2, {self.v1: 1, self.v2: 2}), 2)
+        self.assertEqual(self.r.execute_command('bzmpop', 1, 2, self.k1, self.k2, 'max', 'count', 5),
+                         [self.k2, [[self.v2, '2'], [self.v1, '1']]])
+
     def test_zincrby(self):
         self.assertEqual(self.r.zadd(self.k1, {self.v1: 1, self.v2: 2}), 2)
         self.assertListEqual(self.r.zrange(self.k1, 0, -1, False, True), [(self.v1, 1), (self.v2, 2)])
@@ -259,6 +291,111 @@ class ZsetTest(unittest.TestCase):
         time.sleep(6)
         self.assertEqual(self.r.zcard(self.k1), 0)
 
+    def test_zunion_zinter_zdiff(self):
+        self.r.zadd(self.k1, {'a': 1, 'b': 2, 'c': 3})
+        self.r.zadd(self.k2, {'b': 10, 'c': 20, 'd': 30})
+        self.assertEqual(self.r.execute_command('ZUNION', 2, self.k1, self.k2, 'WITHSCORES'),
+                         ['a', '1', 'b', '12', 'c', '23', 'd', '30'])
+        self.assertEqual(self.r.execute_command('ZINTER', 2, self.k1, self.k2, 'WEIGHTS', 2, 1,
+                                                'AGGREGATE', 'MIN', 'WITHSCORES'),
+                         ['b', '4', 'c', '6'])
+        self.assertEqual(self.r.execute_command('ZINTER', 2, self.k1, self.k2, 'AGGREGATE', 'MAX'), ['b', 'c'])
+        self.assertEqual(self.r.execute_command('ZDIFF', 2, self.k1, self.k2, 'WITHSCORES'), ['a', '1'])
+        self.assertEqual(self.r.execute_command('ZDIFF', 2, self.k2, self.k3), ['b', 'c', 'd'])
+        self.assertEqual(self.r.execute_command('ZINTER', 2, self.k1, self.k3), [])
+        with self.assertRaises(Exception):
+            self.r.execute_command('ZDIFF', 2, self.k1, self.k2, 'WEIGHTS', 1, 2)
+        with self.assertRaises(Exception):
+            self.r.execute_command('ZUNION', 2, self.k1, self.k2, 'WEIGHTS', 1)
+
+    def test_zunion_with_set(self):
+        self.r.zadd(self.k1, {'a': 1, 'b': 2})
+        self.r.sadd(self.k2, 'b', 'c')
+        self.assertEqual(self.r.execute_command('ZUNION', 2, self.k1, self.k2, 'WITHSCORES'),
+                         ['a', '1', 'c', '1', 'b', '3'])
+        self.r.set(self.k3, self.v1)
+        with self.assertRaises(Exception):
+            self.r.execute_command('ZUNION', 2, self.k1, self.k3)
+
+    def test_zunion_inf_scores(self):
+        self.r.zadd(self.k1, {'a': float('inf'), 'b': 1})
+        self.r.zadd(self.k2, {'a': float('-inf'), 'b': 2})
+        self.assertEqual(self.r.execute_command('ZUNION', 2, self.k1, self.k2, 'WITHSCORES'),
+                         ['a', '0', 'b', '3'])
+        self.assertEqual(self.r.execute_command('ZUNION', 2, self.k1, self.k2, 'WEIGHTS', 0, 1,
+                                                'WITHSCORES'),
+                         ['a', '-inf', 'b', '2'])
+        self.assertEqual(self.r.execute_command('ZINTER', 2, self.k1, self.k2, 'AGGREGATE', 'MAX',
+                                                'WITHSCORES'),
+                         ['b', '2', 'a', 'inf'])
+
+    def test_zunionstore_zinterstore_zdiffstore(self):
+        self.r.zadd(self.k1, {'a': 1, 'b': 2, 'c': 3})
+        self.r.zadd(self.k2, {'b': 10, 'c': 20, 'd': 30})
+        self.r.set(self.k3, self.v1)
+        self.assertEqual(self.r.zunionstore(self.k3, {self.k1: 2, self.k2: 1}), 4)
+        self.assertEqual(self.r.zrange(self.k3, 0, -1, withscores=True),
+                         [('a', 2.0), ('b', 14.0), ('c', 26.0), ('d', 30.0)])
+        self.assertEqual(self.r.zrangebyscore(self.k3, 10, 20), ['b'])
+        self.assertEqual(self.r.zinterstore(self.k3, [self.k1, self.k2], aggregate='MAX'), 2)
+        self.assertEqual(self.r.zrange(self.k3, 0, -1, withscores=True), [('b', 10.0), ('c', 20.0)])
+        self.assertEqual(self.r.zcard(self.k3), 2)
+        self.assertEqual(self.r.execute_command('ZDIFFSTORE', self.k1, 2, self.k1, self.k2), 1)
+        self.assertEqual(self.r.zrange(self.k1, 0, -1, withscores=True), [('a', 1.0)])
+        self.assertEqual(self.r.zcard(self.k1), 1)
+        self.assertEqual(self.r.zinterstore(self.k3, [self.k1, self.k2]), 0)
+        self.assertEqual(self.r.exists(self.k3), 0)
+
+    def test_zintercard(self):
+        self.r.zadd(self.k1, {str(i): i for i in range(1000)})
+        self.r.zadd(self.k2, {str(i): i for i in range(0, 1000, 3)})
+        self.assertEqual(self.r.execute_command('ZINTERCARD', 2, self.k1, self.k2), 334)
+        self.assertEqual(self.r.execute_command('ZINTERCARD', 2, self.k1, self.k2, 'LIMIT', 10), 10)
+        self.assertEqual(self.r.execute_command('ZINTERCARD', 2, self.k1, self.k3), 0)
+
+    def test_zrangebylex(self):
+        self.r.zadd(self.k1, {m: 0 for m in ['a', 'b', 'c', 'd', 'e', 'f', 'g']})
+        self.assertEqual(self.r.zrangebylex(self.k1, '-', '[c'), ['a', 'b', 'c'])
+        self.assertEqual(self.r.zrangebylex(self.k1, '-', '(c'), ['a', 'b'])
+        self.assertEqual(self.r.zrangebylex(self.k1, '[aaa', '(g'), ['b', 'c', 'd', 'e', 'f'])
+        self.assertEqual(self.r.zrangebylex(self.k1, '(e', '+'), ['f', 'g'])
+        self.assertEqual(self.r.zrangebylex(self.k1, '-', '+', 2, 3), ['c', 'd', 'e'])
+        self.assertEqual(self.r.zrangebylex(self.k1, '-', '+', 5, -1), ['f', 'g'])
+        self.assertEqual(self.r.zrangebylex(self.k1, '+', '-'), [])
+        self.assertEqual(self.r.zrangebylex(self.k2, '-', '+'), [])
+        self.assertRaises(Exception, self.r.zrangebylex, self.k1, 'a', '+')
+
+    def test_zrevrangebylex(self):
+        self.r.zadd(self.k1, {m: 0 for m in ['a', 'b', 'c', 'd', 'e', 'f', 'g']})
+        self.assertEqual(self.r.zrevrangebylex(self.k1, '[c', '-'), ['c', 'b', 'a'])
+        self.assertEqual(self.r.zrevrangebylex(self.k1, '(c', '-'), ['b', 'a'])
+        self.assertEqual(self.r.zrevrangebylex(self.k1, '(g', '[aaa'), ['f', 'e', 'd', 'c', 'b'])
+        self.assertEqual(self.r.zrevrangebylex(self.k1, '+', '-', 1, 2), ['f', 'e'])
+
+    def test_zrangebylex_autocomplete(self):
+        self.r.zadd(self.k1, {'word%04d' % i: 0 for i in range(1000)})
+        self.assertEqual(self.r.zrangebylex(self.k1, '[word05', '(word06'),
+                         ['word%04d' % i for i in range(500, 600)])
+        self.assertEqual(self.r.zrangebylex(self.k1, '[word0', '+', 290, 3),
+                         ['word0290', 'word0291', 'word0292'])
+        self.assertEqual(self.r.zrevrangebylex(self.k1, '+', '-', 0, 2), ['word0999', 'word0998'])
+        self.assertEqual(self.r.zlexcount(self.k1, '-', '+'), 1000)
+
+    def test_zlexcount(self):
+        self.r.zadd(self.k1, {m: 0 for m in ['a', 'b', 'c', 'd', 'e', 'f', 'g']})
+        self.assertEqual(self.r.zlexcount(self.k1, '-', '+'), 7)
+        self.assertEqual(self.r.zlexcount(self.k1, '[b', '(f'), 4)
+        self.assertEqual(self.r.zlexcount(self.k2, '-', '+'), 0)
+
+    def test_zremrangebylex(self):
+        self.r.zadd(self.k1, {m: 0 for m in ['aaaa', 'b', 'c', 'd', 'e', 'foo', 'zap', 'zip']})
+        self.assertEqual(self.r.zremrangebylex(self.k1, '[alpha', '[omega'), 5)
+        self.assertEqual(self.r.zrange(self.k1, 0, -1), ['aaaa', 'zap', 'zip'])
+        self.assertEqual(self.r.zcard(self.k1), 3)
+        self.assertEqual(self.r.zscore(self.k1, 'b'), None)
+        self.assertEqual(self.r.zremrangebylex(self.k1, '-', '+'), 3)
+        self.assertEqual(self.r.exists(self.k1), 0)
+
     def tearDown(self):
         pass
 
@@ -266,4 +403,5 @@ class ZsetTest(unittest.TestCase):
     def tearDownClass(cls):
         cls.r.execute_command('del', cls.k1)
         cls.r.execute_command('del', cls.k2)
+        cls.r.execute_command('del', cls.k3)
         print('test data cleaned up')